@@ -5,7 +5,7 @@ use std::path;
 pub fn main() {
   let args: Vec<String> = env::args().collect();
   let dir = &args[1];
-  compile(&dir);
+  compile(dir);
 }
 
 fn compile(dir: &str) {
@@ -1,13 +1,76 @@
 use crate::source_file::SourceFile;
+use justc_binder::modules::ImportTarget;
+use justc_binder::symbols::{SymbolId, SymbolKind};
+use justc_binder::{bind, check_injections, ModuleFile, ModuleGraph, SymbolTable};
+use justc_checker::attach::{attachable, Attachable, ExternalFn};
+use justc_checker::{check, check_with_imports, Ty, TypeCheck};
+use justc_lexer::diagnostic::Diagnostic;
+use justc_lexer::span::Span;
+use justc_parser::Parse;
+use std::collections::{BTreeMap, HashMap};
+use std::time::SystemTime;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct CompileSession {
   pub files: Vec<SourceFile>,
+  asts: HashMap<String, FileAst>,
+  modules: ModuleGraph,
+}
+
+/// Syntax tree and symbols of a source file, built by `CompileSession::build_asts`.
+#[derive(Debug)]
+pub struct FileAst {
+  /// When the file was modified when it was parsed.
+  pub modified: SystemTime,
+  pub parse: Parse,
+  pub symbols: SymbolTable,
+  pub types: TypeCheck,
+  /// Lexer, parser, binder and type diagnostics, ordered by position.
+  pub diagnostics: Vec<Diagnostic>,
+}
+
+impl FileAst {
+  pub fn new(file: &SourceFile) -> Self {
+    let parse = justc_parser::parse(&file.src);
+    let tree = parse.tree();
+    let binding = bind(&tree);
+    let mut diagnostics = parse.errors().to_vec();
+    diagnostics.extend(binding.diagnostics().iter().cloned());
+    diagnostics.extend(check_injections(&tree, binding.symbols()));
+    let types = check(&tree, binding.symbols());
+    diagnostics.extend(types.diagnostics().iter().cloned());
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    FileAst {
+      modified: file.modified,
+      symbols: binding.symbols().clone(),
+      parse,
+      types,
+      diagnostics,
+    }
+  }
+
+  /// Checks the types again, where the names imported from other modules have the types in `imports`.
+  fn check_imports(&mut self, imports: &BTreeMap<SymbolId, Ty>) {
+    let types = check_with_imports(&self.parse.tree(), &self.symbols, imports);
+    let previous = self.types.diagnostics();
+    self
+      .diagnostics
+      .retain(|diagnostic| !previous.contains(diagnostic));
+    self.diagnostics.extend(types.diagnostics().iter().cloned());
+    self
+      .diagnostics
+      .sort_by_key(|diagnostic| diagnostic.span.start);
+    self.types = types;
+  }
 }
 
 impl CompileSession {
   pub fn new() -> Self {
-    CompileSession { files: Vec::new() }
+    CompileSession {
+      files: Vec::new(),
+      asts: HashMap::new(),
+      modules: ModuleGraph::default(),
+    }
   }
 
   // pub fn add_file()
@@ -22,16 +85,292 @@ impl CompileSession {
    * This can be break off to be handled by multiple threads.
    */
   pub fn build_asts(&mut self) {
-    
-    // check each file to see if it is already parsed.
-    // parse each file to AST.
-    // capture any invalid token for error reporting
-    // error reporting can use the AST to do in-depth analysis
-    // to provide useful suggestions
-    // type analysis, merging and error reporting is done in next phrase,
-    // not here
-    // for file in &self.files {
-
-    // }
+    // Type analysis, merging and error reporting across files is done in the next phase, not here.
+    let (files, asts) = (&self.files, &mut self.asts);
+    asts.retain(|path, _| files.iter().any(|file| file.path == *path));
+    for file in files {
+      let parsed = asts
+        .get(&file.path)
+        .is_some_and(|ast| ast.modified == file.modified);
+      if !parsed {
+        asts.insert(file.path.clone(), FileAst::new(file));
+      }
+    }
+  }
+
+  /// Build the module tree of the files in `src_dir` and resolve their imports, once `build_asts` is called.
+  /// The files importing values of other modules are checked again with their types.
+  pub fn build_modules(&mut self, src_dir: &str) {
+    let files: Vec<_> = self
+      .files
+      .iter()
+      .filter_map(|file| {
+        let ast = self.asts.get(&file.path)?;
+        Some((file, ast.parse.tree(), ast))
+      })
+      .collect();
+    let files: Vec<_> = files
+      .iter()
+      .map(|(file, tree, ast)| ModuleFile {
+        path: &file.path,
+        tree,
+        symbols: &ast.symbols,
+      })
+      .collect();
+    self.modules = ModuleGraph::build(src_dir, &files);
+    let imports: Vec<_> = self
+      .files
+      .iter()
+      .map(|file| (file.path.clone(), self.imports(&file.path)))
+      .filter(|(_, imports)| !imports.is_empty())
+      .collect();
+    for (path, imports) in imports {
+      if let Some(ast) = self.asts.get_mut(&path) {
+        ast.check_imports(&imports);
+      }
+    }
+  }
+
+  /// The types of the values the file at `path` imports from other modules, by the symbols of its `use` declarations.
+  fn imports(&self, path: &str) -> BTreeMap<SymbolId, Ty> {
+    let (module, ast) = match (self.modules.module_of(path), self.ast(path)) {
+      (Some(module), Some(ast)) => (module, ast),
+      _ => return BTreeMap::new(),
+    };
+    let imports = self.modules.imports().iter();
+    let imports = imports.filter(|import| import.module == module);
+    imports
+      .filter_map(|import| {
+        let (target, symbol) = match import.target? {
+          ImportTarget::Symbol(target, symbol) => (target, symbol),
+          _ => return None,
+        };
+        let ty = self
+          .ast(&self.modules.module(target).file)?
+          .types
+          .exported_type(symbol)?;
+        Some((ast.symbols.symbol_at(import.span)?, ty))
+      })
+      .collect()
+  }
+
+  /// The functions which can be called on the value of the expression at `span` in the file at `path`,
+  /// including the functions of other modules which aren't imported, once `build_modules` is called.
+  pub fn attachable_functions(&self, path: &str, span: Span) -> Vec<Attachable> {
+    let ast = match self.ast(path) {
+      Some(ast) => ast,
+      None => return Vec::new(),
+    };
+    let mut externals = Vec::new();
+    for (_, module) in self.modules.modules() {
+      let other = match self.ast(&module.file) {
+        Some(other) if module.file != path => other,
+        _ => continue,
+      };
+      let root = other.symbols.scope(other.symbols.root());
+      for symbol in &root.symbols {
+        let info = other.symbols.symbol(*symbol);
+        if !matches!(info.kind, SymbolKind::Fn | SymbolKind::Let) {
+          continue;
+        }
+        if let Some(ty) = other.types.exported_type(*symbol) {
+          externals.push(ExternalFn {
+            name: info.name.clone(),
+            path: format!("{}::{}", module.qualified_name(), info.name),
+            ty,
+          });
+        }
+      }
+    }
+    let imports = self.imports(path);
+    attachable(
+      &ast.parse.tree(),
+      &ast.symbols,
+      &ast.types,
+      &imports,
+      span,
+      &externals,
+    )
+  }
+
+  /// The type of the innermost expression at `offset` in the file at `path`, for hover,
+  /// narrowed where it is used, e.g. `i32` for `x` after `if x == None { return 0 }`.
+  pub fn type_at(&self, path: &str, offset: usize) -> Option<&Ty> {
+    self.ast(path)?.types.type_at(offset)
+  }
+
+  pub fn modules(&self) -> &ModuleGraph {
+    &self.modules
+  }
+
+  /// Syntax tree and symbols of the file at `path`, once `build_asts` is called.
+  pub fn ast(&self, path: &str) -> Option<&FileAst> {
+    self.asts.get(path)
+  }
+
+  /// Diagnostics of all files, in the order of the files, each ordered by position.
+  pub fn diagnostics(&self) -> impl Iterator<Item = (&SourceFile, &Diagnostic)> {
+    self.files.iter().flat_map(move |file| {
+      let ast = self.ast(&file.path).map(|ast| ast.diagnostics.as_slice());
+      let module = self.modules.module_of(&file.path);
+      let modules = module
+        .into_iter()
+        .flat_map(move |id| self.modules.diagnostics(id));
+      let mut diagnostics: Vec<_> = ast.unwrap_or_default().iter().chain(modules).collect();
+      diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+      diagnostics
+        .into_iter()
+        .map(move |diagnostic| (file, diagnostic))
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::source_file::discover_source_files;
+  use path_slash::PathExt;
+  use std::path::Path;
+
+  fn file(path: &str, src: &str, modified: u64) -> SourceFile {
+    let modified = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(modified);
+    SourceFile::new(path.to_string(), src.to_string(), modified)
+  }
+
+  #[test]
+  fn build_asts_of_changed_files() {
+    let mut session = CompileSession::new();
+    session.update_files(vec![
+      file(
+        "src/main.just",
+        "fn main() { greet(name) }\nfn greet(n: str) {}",
+        1,
+      ),
+      file("src/lib.just", "let x = 1\nlet x = 2\nlet y: bool = 3", 1),
+    ]);
+    session.build_asts();
+    let main = session.ast("src/main.just").unwrap();
+    let greet = main.symbols.lookup(main.symbols.root(), "greet").unwrap();
+    assert_eq!(main.symbols.references_to(greet).count(), 1);
+    let messages: Vec<_> = session
+      .diagnostics()
+      .map(|(file, diagnostic)| format!("{}: {}", file.path, diagnostic.message))
+      .collect();
+    assert_eq!(
+      messages,
+      [
+        "src/main.just: cannot find `name` in this scope",
+        "src/lib.just: `x` is declared more than once in this scope",
+        "src/lib.just: expected `bool`, found `3`"
+      ]
+    );
+
+    session.update_files(vec![file("src/main.just", "fn main() {}", 2)]);
+    session.build_asts();
+    assert!(session.ast("src/lib.just").is_none());
+    assert_eq!(session.diagnostics().count(), 0);
+  }
+
+  #[test]
+  fn build_modules_of_sub_folder() {
+    let mut session = CompileSession::new();
+    session.update_files(discover_source_files("fixtures/binary_sub_folder").unwrap());
+    session.build_asts();
+    session.build_modules("fixtures/binary_sub_folder/src");
+    let modules = session.modules();
+    let names: Vec<_> = modules
+      .modules()
+      .map(|(_, module)| module.qualified_name())
+      .collect();
+    assert_eq!(names, ["crate", "crate::sub"]);
+    let entry_points: Vec<_> = modules
+      .entry_points()
+      .iter()
+      .map(|id| Path::new(&modules.module(*id).file).to_slash().unwrap())
+      .collect();
+    assert_eq!(entry_points, ["fixtures/binary_sub_folder/src/main.just"]);
+
+    let main = &session.files[0];
+    session.update_files(vec![
+      file(&main.path, "mod sub\nmod missing\nuse sub::f", 1),
+      file(&session.files[1].path, "pub fn f() {}", 1),
+    ]);
+    session.build_asts();
+    session.build_modules("fixtures/binary_sub_folder/src");
+    let messages: Vec<_> = session
+      .diagnostics()
+      .map(|(_, diagnostic)| &diagnostic.message)
+      .collect();
+    assert_eq!(messages, ["cannot find module `crate::missing`"]);
+  }
+
+  #[test]
+  fn attach_imported_functions() {
+    let mut session = CompileSession::new();
+    session.update_files(discover_source_files("fixtures/binary_sub_folder").unwrap());
+    let (main, sub) = (session.files[0].path.clone(), session.files[1].path.clone());
+    let src = "mod sub\nuse sub::shout\nlet loud = \"miku\".shout()\nlet quiet: i32 = \"rin\".shout()\n\"len\"";
+    session.update_files(vec![
+      file(&main, src, 1),
+      file(
+        &sub,
+        "pub fn shout(text: &str) -> str { text }\npub fn whisper(text: &str) -> str { text }",
+        1,
+      ),
+    ]);
+    session.build_asts();
+    session.build_modules("fixtures/binary_sub_folder/src");
+    let messages: Vec<_> = session
+      .diagnostics()
+      .map(|(_, diagnostic)| &diagnostic.message)
+      .collect();
+    assert_eq!(messages, ["expected `i32`, found `str`"]);
+    let start = src.rfind("\"len\"").unwrap();
+    let functions: Vec<_> = session
+      .attachable_functions(&main, Span::new(start, start + 5))
+      .into_iter()
+      .map(|function| format!("{} {:?}", function.name, function.origin))
+      .collect();
+    assert_eq!(
+      functions,
+      [
+        "isEmpty Core",
+        "len Core",
+        "shout Scope(SymbolId(1))",
+        "toLowerCase Core",
+        "toUpperCase Core",
+        "trim Core",
+        "whisper External(\"crate::sub::whisper\")"
+      ]
+    );
+  }
+
+  #[test]
+  fn hover_narrowed_types() {
+    let mut session = CompileSession::new();
+    let src =
+      "type MaybeInt = i32 | None\nfn f(x: MaybeInt) -> i32 {\n  if x == None { return 0 }\n  x\n}";
+    session.update_files(vec![file("src/main.just", src, 1)]);
+    session.build_asts();
+    let types: Vec<_> = [
+      src.find('x').unwrap(),
+      src.find("x ==").unwrap(),
+      src.rfind('x').unwrap(),
+    ]
+    .iter()
+    .map(|offset| {
+      session
+        .type_at("src/main.just", *offset)
+        .map(|ty| ty.to_string())
+    })
+    .collect();
+    assert_eq!(
+      types,
+      [
+        Some("MaybeInt".to_string()),
+        Some("MaybeInt".to_string()),
+        Some("i32".to_string())
+      ]
+    );
   }
 }
@@ -1,4 +1,4 @@
-use libunic_compile_session::SourceFile;
+use super::SourceFile;
 use std::fs;
 use std::io;
 
@@ -12,15 +12,19 @@ pub fn discover_source_files(cwd: &str) -> Result<Vec<SourceFile>, io::Error> {
 }
 
 fn discover_source_files_recur(files: &mut Vec<SourceFile>, dir: &str) -> Result<(), io::Error> {
-  for dir_result in fs::read_dir(dir)? {
-    let dir = dir_result?;
-    let modified = dir.metadata()?.modified()?;
-    let path = dir.path();
+  // `read_dir` order is platform dependent, sort for stable results.
+  let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+  entries.sort_by_key(|entry| entry.path());
+
+  for entry in entries {
+    let modified = entry.metadata()?.modified()?;
+    let path = entry.path();
     let p = path.to_str().unwrap();
     if path.is_dir() {
       discover_source_files_recur(files, p)?;
     } else {
-      files.push(SourceFile::new(p, modified));
+      let src = fs::read_to_string(&path)?;
+      files.push(SourceFile::new(p.to_string(), src, modified));
     }
   }
 
@@ -28,7 +32,6 @@ fn discover_source_files_recur(files: &mut Vec<SourceFile>, dir: &str) -> Result
 }
 
 #[cfg(test)]
-
 mod tests {
   use super::*;
   use path_slash::PathExt;
@@ -40,11 +43,11 @@ mod tests {
       Ok(files) => {
         assert_eq!(1, files.len());
         assert_eq!(
-          "fixtures/binary_single_file/src/main.uni",
+          "fixtures/binary_single_file/src/main.just",
           Path::new(&files[0].path).to_slash().unwrap()
         );
       }
-      _ => assert!(false, "failed"),
+      _ => panic!("failed"),
     }
   }
 
@@ -54,15 +57,15 @@ mod tests {
       Ok(files) => {
         assert_eq!(2, files.len());
         assert_eq!(
-          "fixtures/binary_multi_files/src/foo.uni",
+          "fixtures/binary_multi_files/src/foo.just",
           Path::new(&files[0].path).to_slash().unwrap()
         );
         assert_eq!(
-          "fixtures/binary_multi_files/src/main.uni",
+          "fixtures/binary_multi_files/src/main.just",
           Path::new(&files[1].path).to_slash().unwrap()
         );
       }
-      _ => assert!(false, "failed"),
+      _ => panic!("failed"),
     }
   }
 
@@ -73,15 +76,15 @@ mod tests {
         assert_eq!(2, files.len());
         let sorted: Vec<String> = files.iter().map(|x| x.path.to_string()).collect();
         assert_eq!(
-          "fixtures/binary_sub_folder/src/main.uni",
+          "fixtures/binary_sub_folder/src/main.just",
           Path::new(&sorted[0]).to_slash().unwrap()
         );
         assert_eq!(
-          "fixtures/binary_sub_folder/src/sub/mod.uni",
+          "fixtures/binary_sub_folder/src/sub/mod.just",
           Path::new(&sorted[1]).to_slash().unwrap()
         );
       }
-      _ => assert!(false, "failed"),
+      _ => panic!("failed"),
     }
   }
 }
@@ -0,0 +1,8 @@
+mod compile_session;
+mod discover_source_files;
+#[allow(clippy::module_inception)]
+mod source_file;
+
+pub use compile_session::CompileSession;
+pub use discover_source_files::discover_source_files;
+pub use source_file::SourceFile;
@@ -1,3 +1,7 @@
+use justc_lexer::emitter::SourceInput;
+use justc_lexer::line_index::{LineCol, LineIndex};
+use justc_lexer::span::Span;
+use justc_lexer::token_stream::TokenStream;
 use std::time::SystemTime;
 
 #[derive(Debug)]
@@ -5,14 +9,88 @@ pub struct SourceFile {
   pub path: String,
   pub modified: SystemTime,
   pub src: String,
+  line_index: LineIndex,
 }
 
 impl SourceFile {
   pub fn new(path: String, src: String, modified: SystemTime) -> Self {
+    let line_index = LineIndex::new(&src);
     SourceFile {
       path,
       modified,
       src,
+      line_index,
     }
   }
+
+  /// Replaces the content of the file, e.g. when the file is edited in an editor.
+  pub fn update(&mut self, src: String, modified: SystemTime) {
+    self.line_index = LineIndex::new(&src);
+    self.src = src;
+    self.modified = modified;
+  }
+
+  pub fn line_index(&self) -> &LineIndex {
+    &self.line_index
+  }
+
+  /// Line and column (UTF-8) of a byte offset, used by diagnostics.
+  pub fn line_col(&self, offset: usize) -> LineCol {
+    self.line_index.line_col(offset)
+  }
+
+  /// Line and column (UTF-16) of a byte offset, used by editors.
+  pub fn line_col_utf16(&self, offset: usize) -> LineCol {
+    self.line_index.line_col_utf16(offset)
+  }
+
+  /// Source code of a span.
+  pub fn text(&self, span: Span) -> &str {
+    &self.src[span.range()]
+  }
+
+  /// Source code and location information used to render diagnostics.
+  pub fn source_input(&self) -> SourceInput<'_> {
+    SourceInput {
+      path: &self.path,
+      src: &self.src,
+      line_index: &self.line_index,
+    }
+  }
+
+  /// Tokens of the file with their spans.
+  pub fn tokens(&self) -> TokenStream<'_> {
+    TokenStream::new(&self.src).with_keywords()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use justc_lexer::tokenize::TokenKind;
+
+  #[test]
+  fn locate_tokens() {
+    let file = SourceFile::new(
+      "main.just".to_string(),
+      "let s = 'ü'\nlet t = s\n".to_string(),
+      SystemTime::UNIX_EPOCH,
+    );
+    let positions: Vec<(String, LineCol, LineCol)> = file
+      .tokens()
+      .filter(|token| token.kind != TokenKind::Whitespace)
+      .map(|token| {
+        (
+          file.text(token.span).to_string(),
+          file.line_col(token.span.start),
+          file.line_col_utf16(token.span.end),
+        )
+      })
+      .collect();
+
+    let lc = |line, col| LineCol { line, col };
+    assert_eq!(positions[3], ("'ü'".to_string(), lc(0, 8), lc(0, 11)));
+    assert_eq!(positions[4], ("let".to_string(), lc(1, 0), lc(1, 3)));
+    assert_eq!(positions[7], ("s".to_string(), lc(1, 8), lc(1, 9)));
+  }
 }
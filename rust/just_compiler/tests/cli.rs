@@ -4,7 +4,7 @@ use std::process::Command;
 
 #[test]
 fn no_args_print_help() -> Result<(), Box<dyn std::error::Error>> {
-  let mut cmd = Command::cargo_bin("justc0")?;
+  let mut cmd = Command::cargo_bin("justc")?;
   cmd.assert();
 
   Ok(())
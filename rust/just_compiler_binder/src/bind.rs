@@ -0,0 +1,665 @@
+//! Builds the `SymbolTable` of a source file.
+//!
+//! Declarations of a source file are visible in the whole file, and so are functions declared in a block,
+//! other declarations of a block only after them.
+//! A name can be declared once per scope, and shadowed in an inner scope.
+
+use super::builtins::is_builtin;
+use super::codes;
+use super::symbols::{Reference, Resolution, ScopeId, ScopeKind, Symbol, SymbolKind, SymbolTable};
+use justc_lexer::diagnostic::Diagnostic;
+use justc_lexer::span::Span;
+use justc_parser::ast::{self, AstNode, HasName};
+use justc_parser::{SyntaxKind, SyntaxNode, T};
+
+/// Result of binding a source file: the symbol table and the binder diagnostics.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Binding {
+  symbols: SymbolTable,
+  diagnostics: Vec<Diagnostic>,
+}
+
+impl Binding {
+  pub fn symbols(&self) -> &SymbolTable {
+    &self.symbols
+  }
+
+  /// Duplicate declarations and unresolved names, ordered by position.
+  pub fn diagnostics(&self) -> &[Diagnostic] {
+    &self.diagnostics
+  }
+}
+
+pub fn bind(file: &ast::SourceFile) -> Binding {
+  let mut table = SymbolTable::default();
+  let root = table.add_scope(ScopeKind::Module, None, file.syntax().text_range());
+  let mut binder = Binder {
+    table,
+    diagnostics: Vec::new(),
+    scope: root,
+  };
+  binder.statements(file.syntax(), true);
+  binder.table.sort_references();
+  binder
+    .diagnostics
+    .sort_by_key(|diagnostic| diagnostic.span.start);
+  Binding {
+    symbols: binder.table,
+    diagnostics: binder.diagnostics,
+  }
+}
+
+/// The name a use tree brings in scope and its span, e.g. `env` for `std::env` and `f` for `fs as f`.
+pub fn use_tree_name(tree: &ast::UseTree) -> Option<(String, Span)> {
+  if tree.use_tree_list().is_some() || tree.star_token().is_some() {
+    return None;
+  }
+  if let Some(rename) = tree.rename() {
+    let name = rename.name()?;
+    return Some((name.text(), name.syntax().text_range()));
+  }
+  let name_ref = tree.path()?.last_segment()?.name_ref()?;
+  Some((name_ref.text(), name_ref.syntax().text_range()))
+}
+
+struct Binder {
+  table: SymbolTable,
+  diagnostics: Vec<Diagnostic>,
+  scope: ScopeId,
+}
+
+impl Binder {
+  /// Binds the statements of a source file or a block, `module` for a source file.
+  fn statements(&mut self, parent: &SyntaxNode, module: bool) {
+    let hoisted = |stmt: &SyntaxNode| module || stmt.kind() == SyntaxKind::FnDecl;
+    for stmt in parent.children().filter(hoisted) {
+      self.declare_stmt(&stmt);
+    }
+    for stmt in parent.children() {
+      self.stmt(&stmt, hoisted(&stmt));
+    }
+  }
+
+  /// Binds a statement, and declares it unless it is `hoisted`, i.e. already declared.
+  fn stmt(&mut self, stmt: &SyntaxNode, hoisted: bool) {
+    match stmt.kind() {
+      SyntaxKind::LetDecl => {
+        // Functions can call themselves, e.g. `let f = (n) => f(n - 1)`.
+        let is_fn = ast::LetDecl::cast(stmt.clone())
+          .and_then(|decl| decl.initializer())
+          .is_some_and(|init| matches!(init, ast::Expr::ArrowFn(_)));
+        if !hoisted && is_fn {
+          self.declare_stmt(stmt);
+        }
+        self.children(stmt);
+        if !hoisted && !is_fn {
+          self.declare_stmt(stmt);
+        }
+      }
+      SyntaxKind::TypeAlias => {
+        // Types can refer to themselves, e.g. `type List = { next: List | None }`.
+        if !hoisted {
+          self.declare_stmt(stmt);
+        }
+        self.scoped(ScopeKind::TypeAlias, stmt, |binder| binder.children(stmt));
+      }
+      SyntaxKind::UseDecl | SyntaxKind::ModDecl => {
+        if !hoisted {
+          self.declare_stmt(stmt);
+        }
+      }
+      SyntaxKind::FnDecl => self.function(stmt),
+      _ => self.node(stmt),
+    }
+  }
+
+  fn declare_stmt(&mut self, stmt: &SyntaxNode) {
+    match stmt.kind() {
+      SyntaxKind::LetDecl => {
+        let decl = ast::LetDecl::cast(stmt.clone()).unwrap();
+        let kind = if decl.is_mut() {
+          SymbolKind::Mut
+        } else {
+          SymbolKind::Let
+        };
+        self.declare_name(decl.name(), kind, stmt);
+      }
+      SyntaxKind::TypeAlias => {
+        let name = ast::TypeAlias::cast(stmt.clone()).and_then(|decl| decl.name());
+        self.declare_name(name, SymbolKind::Type, stmt);
+      }
+      SyntaxKind::ModDecl => {
+        let name = ast::ModDecl::cast(stmt.clone()).and_then(|decl| decl.name());
+        self.declare_name(name, SymbolKind::Mod, stmt);
+      }
+      SyntaxKind::FnDecl => {
+        let name = ast::FnDecl::cast(stmt.clone()).and_then(|decl| decl.name());
+        self.declare_name(name, SymbolKind::Fn, stmt);
+      }
+      SyntaxKind::UseDecl => {
+        for tree in stmt.descendants().filter_map(ast::UseTree::cast) {
+          if let Some((name, span)) = use_tree_name(&tree) {
+            self.declare(name, span, SymbolKind::Use, tree.syntax());
+          }
+        }
+      }
+      _ => (),
+    }
+  }
+
+  fn declare_name(&mut self, name: Option<ast::Name>, kind: SymbolKind, decl: &SyntaxNode) {
+    if let Some(name) = name {
+      self.declare(name.text(), name.syntax().text_range(), kind, decl);
+    }
+  }
+
+  fn declare(&mut self, name: String, name_span: Span, kind: SymbolKind, decl: &SyntaxNode) {
+    let scope = self.table.scope(self.scope);
+    let previous = scope
+      .symbols
+      .iter()
+      .map(|id| self.table.symbol(*id))
+      .find(|symbol| symbol.name == name);
+    if let Some(previous) = previous {
+      self.diagnostics.push(
+        Diagnostic::error(
+          codes::DUPLICATE_DECLARATION,
+          format!("`{}` is declared more than once in this scope", name),
+          name_span,
+        )
+        .with_label("declared again here")
+        .with_span_note(
+          previous.name_span,
+          format!("`{}` is first declared here", name),
+        ),
+      );
+    }
+    self.table.add_symbol(Symbol {
+      name,
+      kind,
+      scope: self.scope,
+      name_span,
+      decl_span: decl.text_range(),
+      decl_kind: decl.kind(),
+    });
+  }
+
+  fn scoped(&mut self, kind: ScopeKind, node: &SyntaxNode, f: impl FnOnce(&mut Binder)) {
+    let parent = self.scope;
+    self.scope = self.table.add_scope(kind, Some(parent), node.text_range());
+    f(self);
+    self.scope = parent;
+  }
+
+  fn children(&mut self, node: &SyntaxNode) {
+    for child in node.children() {
+      self.node(&child);
+    }
+  }
+
+  fn node(&mut self, node: &SyntaxNode) {
+    match node.kind() {
+      SyntaxKind::Block => {
+        self.scoped(ScopeKind::Block, node, |binder| {
+          binder.statements(node, false)
+        });
+      }
+      SyntaxKind::ArrowFn | SyntaxKind::FnType => self.function(node),
+      SyntaxKind::MappedType => {
+        self.scoped(ScopeKind::MappedType, node, |binder| binder.children(node));
+      }
+      SyntaxKind::GenericParam => {
+        self.children(node);
+        let name = ast::GenericParam::cast(node.clone()).and_then(|param| param.name());
+        self.declare_name(name, SymbolKind::GenericParam, node);
+      }
+      SyntaxKind::Param => {
+        self.children(node);
+        let pat = ast::Param::cast(node.clone()).and_then(|param| param.pat());
+        for name in pat.into_iter().flat_map(|pat| pat.names()) {
+          self.declare(
+            name.text(),
+            name.syntax().text_range(),
+            SymbolKind::Param,
+            node,
+          );
+        }
+      }
+      SyntaxKind::ForExpr => {
+        let for_expr = ast::ForExpr::cast(node.clone()).unwrap();
+        if let Some(iterable) = for_expr.iterable() {
+          self.node(iterable.syntax());
+        }
+        if let Some(body) = for_expr.body() {
+          self.scoped(ScopeKind::Block, body.syntax(), |binder| {
+            binder.declare_name(for_expr.name(), SymbolKind::ForBinding, node);
+            binder.node(body.syntax());
+          });
+        }
+      }
+      SyntaxKind::MatchArm => {
+        self.scoped(ScopeKind::Block, node, |binder| {
+          let pattern = ast::MatchArm::cast(node.clone()).and_then(|arm| arm.pattern());
+          for child in node.children() {
+            if pattern.as_ref().map(AstNode::syntax) == Some(&child) {
+              binder.pattern(&child);
+            } else {
+              binder.node(&child);
+            }
+          }
+        });
+      }
+      SyntaxKind::PathExpr | SyntaxKind::PathType | SyntaxKind::ObjectLiteral => {
+        for child in node.children() {
+          if child.kind() == SyntaxKind::Path {
+            self.path(&child);
+          } else {
+            self.node(&child);
+          }
+        }
+      }
+      SyntaxKind::ObjectField => {
+        let field = ast::ObjectField::cast(node.clone()).unwrap();
+        match (field.expr(), field.name_ref()) {
+          (Some(expr), _) => self.node(expr.syntax()),
+          // `{ x }` is `{ x: x }`.
+          (None, Some(name_ref)) => self.reference(&name_ref),
+          (None, None) => (),
+        }
+      }
+      SyntaxKind::FieldExpr => {
+        if let Some(receiver) = ast::FieldExpr::cast(node.clone()).and_then(|expr| expr.receiver())
+        {
+          self.node(receiver.syntax());
+        }
+      }
+      SyntaxKind::Decorator => {
+        if let Some(args) =
+          ast::Decorator::cast(node.clone()).and_then(|decorator| decorator.arg_list())
+        {
+          self.node(args.syntax());
+        }
+      }
+      SyntaxKind::LetDecl
+      | SyntaxKind::TypeAlias
+      | SyntaxKind::UseDecl
+      | SyntaxKind::ModDecl
+      | SyntaxKind::FnDecl => self.stmt(node, false),
+      // Names are declared by their parents, and references in paths are bound by `path`.
+      SyntaxKind::Name | SyntaxKind::NameRef | SyntaxKind::Path | SyntaxKind::UseTree => (),
+      _ => self.children(node),
+    }
+  }
+
+  /// Binds a function declaration, an arrow function or a function type,
+  /// its parameters are in a scope of their own.
+  fn function(&mut self, node: &SyntaxNode) {
+    self.scoped(ScopeKind::Function, node, |binder| binder.children(node));
+  }
+
+  /// The pattern of a match arm, where names that don't refer to anything are bound,
+  /// e.g. `v` in `Some(v)`, but not `Some`.
+  fn pattern(&mut self, node: &SyntaxNode) {
+    match node.kind() {
+      SyntaxKind::PathExpr => {
+        let name_ref = ast::PathExpr::cast(node.clone())
+          .and_then(|expr| expr.path())
+          .filter(|path| path.segments().count() == 1)
+          .and_then(|path| path.last_segment()?.name_ref())
+          .filter(|name_ref| name_ref.ident_token().is_some());
+        let name = match name_ref {
+          Some(name_ref) => name_ref,
+          None => return self.node(node),
+        };
+        let text = name.text();
+        if text == "_" {
+          return;
+        }
+        if self.table.lookup(self.scope, &text).is_some() || is_builtin(&text) {
+          return self.node(node);
+        }
+        self.declare(
+          text,
+          name.syntax().text_range(),
+          SymbolKind::MatchBinding,
+          node,
+        );
+      }
+      SyntaxKind::CallExpr => {
+        let call = ast::CallExpr::cast(node.clone()).unwrap();
+        if let Some(callee) = call.callee() {
+          self.node(callee.syntax());
+        }
+        for arg in call.arg_list().into_iter().flat_map(|args| args.args()) {
+          self.pattern(arg.syntax());
+        }
+      }
+      SyntaxKind::FieldExpr => self.node(node),
+      _ => {
+        for child in node.children() {
+          self.pattern(&child);
+        }
+      }
+    }
+  }
+
+  /// Binds the first segment of a path, e.g. `std` in `std::env`, and the generic arguments.
+  fn path(&mut self, path: &SyntaxNode) {
+    let path = ast::Path::cast(path.clone()).unwrap();
+    for (i, segment) in path.segments().enumerate() {
+      if i == 0 {
+        if let Some(name_ref) = segment
+          .name_ref()
+          .filter(|name_ref| name_ref.ident_token().is_some())
+        {
+          self.reference(&name_ref);
+        }
+      }
+      if let Some(args) = segment.generic_arg_list() {
+        self.node(args.syntax());
+      }
+    }
+  }
+
+  fn reference(&mut self, name_ref: &ast::NameRef) {
+    if name_ref.syntax().first_token().map(|token| token.kind()) == Some(T![self]) {
+      return;
+    }
+    let name = name_ref.text();
+    let span = name_ref.syntax().text_range();
+    let resolution = match self.table.lookup(self.scope, &name) {
+      Some(symbol) => Resolution::Symbol(symbol),
+      None if is_builtin(&name) => Resolution::Builtin,
+      None => {
+        self.diagnostics.push(
+          Diagnostic::error(
+            codes::UNRESOLVED_NAME,
+            format!("cannot find `{}` in this scope", name),
+            span,
+          )
+          .with_label("not found in this scope"),
+        );
+        Resolution::Unresolved
+      }
+    };
+    self.table.add_reference(Reference {
+      name,
+      span,
+      scope: self.scope,
+      resolution,
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expect_test::{expect, Expect};
+
+  fn check(text: &str, expect: Expect) {
+    let parse = justc_parser::parse(text);
+    assert_eq!(parse.errors(), &[], "{}", parse.debug_dump());
+    let binding = bind(&parse.tree());
+    let mut out = binding.symbols().debug_dump();
+    for diagnostic in binding.diagnostics() {
+      out.push_str(&format!(
+        "{}[{}]@{:?}: {}\n",
+        diagnostic.severity, diagnostic.code, diagnostic.span, diagnostic.message
+      ));
+    }
+    expect.assert_eq(&out);
+  }
+
+  #[test]
+  fn module_declarations_are_hoisted() {
+    check(
+      r#"
+use std::{env, fs as f}
+pub fn main() {
+  compile(env::args())
+}
+fn compile(dir: &str) -> Dir {
+  f::read_dir(dir)
+}
+type Dir = { path: String }
+mod sub
+"#,
+      expect![[r#"
+          Module@0..154
+            Use env@11..14
+            Use f@22..23
+            Fn main@32..36
+            Fn compile@69..76
+            Type Dir@123..126
+            Mod sub@150..153
+            Function@25..65
+              Block@39..65
+            Function@66..117
+              Param dir@77..80
+              Block@95..117
+            TypeAlias@118..145
+          compile@43..50 -> Fn compile@69..76
+          env@51..54 -> Use env@11..14
+          str@83..86 -> builtin
+          Dir@91..94 -> Type Dir@123..126
+          f@99..100 -> Use f@22..23
+          dir@111..114 -> Param dir@77..80
+          String@137..143 -> builtin
+      "#]],
+    );
+  }
+
+  #[test]
+  fn block_declarations_follow_statements() {
+    check(
+      r#"
+fn f(x: i32) {
+  let y = x + z
+  let mut z = g(y)
+  fn g(a: i32) { a }
+  for i in [y, z] { let x = i }
+  let rec = (n) => rec(n - 1)
+}
+"#,
+      expect![[r#"
+          Module@0..136
+            Fn f@4..5
+            Function@1..135
+              Param x@6..7
+              Block@14..135
+                Fn g@56..57
+                Let y@22..23
+                Mut z@42..43
+                Let rec@110..113
+                Function@53..71
+                  Param a@58..59
+                  Block@66..71
+                Block@90..103
+                  ForBinding i@78..79
+                  Block@90..103
+                    Let x@96..97
+                Function@116..133
+                  Param n@117..118
+          i32@9..12 -> builtin
+          x@26..27 -> Param x@6..7
+          z@30..31 -> unresolved
+          g@46..47 -> Fn g@56..57
+          y@48..49 -> Let y@22..23
+          i32@61..64 -> builtin
+          a@68..69 -> Param a@58..59
+          y@84..85 -> Let y@22..23
+          z@87..88 -> Mut z@42..43
+          i@100..101 -> ForBinding i@78..79
+          rec@123..126 -> Let rec@110..113
+          n@127..128 -> Param n@117..118
+          error[B0004]@30..31: cannot find `z` in this scope
+      "#]],
+    );
+  }
+
+  #[test]
+  fn generics_and_context_params() {
+    check(
+      r#"
+let filter = <T>(array: &Array<T>, predicate: (entry: T) -> bool) -> Array<T> => array
+let get = ({ v, w: { x } }| key: K = v) => x
+type List<T> = { value: T, next: List<T> | None }
+"#,
+      expect![[r#"
+          Module@0..183
+            Let filter@5..11
+            Let get@92..95
+            Type List@138..142
+            Function@14..87
+              GenericParam T@15..16
+              Param array@18..23
+              Param predicate@36..45
+              Function@47..65
+                Param entry@48..53
+            Function@98..132
+              Param v@101..102
+              Param x@109..110
+              Param key@116..119
+            TypeAlias@133..182
+              GenericParam T@143..144
+          Array@26..31 -> builtin
+          T@32..33 -> GenericParam T@15..16
+          T@55..56 -> GenericParam T@15..16
+          bool@61..65 -> builtin
+          Array@70..75 -> builtin
+          T@76..77 -> GenericParam T@15..16
+          array@82..87 -> Param array@18..23
+          K@121..122 -> unresolved
+          v@125..126 -> Param v@101..102
+          x@131..132 -> Param x@109..110
+          T@157..158 -> GenericParam T@143..144
+          List@166..170 -> Type List@138..142
+          T@171..172 -> GenericParam T@143..144
+          None@176..180 -> builtin
+          error[B0004]@121..122: cannot find `K` in this scope
+      "#]],
+    );
+  }
+
+  #[test]
+  fn mapped_types_bind_their_key() {
+    check(
+      r#"
+type Partial<T> = { [K in keyof T]?: T[K] }
+let key: K = 1
+"#,
+      expect![[r#"
+          Module@0..60
+            Type Partial@6..13
+            Let key@49..52
+            TypeAlias@1..44
+              GenericParam T@14..15
+              MappedType@19..44
+                GenericParam K@22..23
+          T@33..34 -> GenericParam T@14..15
+          T@38..39 -> GenericParam T@14..15
+          K@40..41 -> GenericParam K@22..23
+          K@54..55 -> unresolved
+          error[B0004]@54..55: cannot find `K` in this scope
+      "#]],
+    );
+  }
+
+  #[test]
+  fn match_arms_bind_unresolved_names() {
+    check(
+      r#"
+let limit = 10
+let f = (o) => match o {
+  Some(limit) => limit,
+  Ok(value) if value > limit => value,
+  _ => 0,
+}
+"#,
+      expect![[r#"
+          Module@0..116
+            Let limit@5..10
+            Let f@20..21
+            Function@24..115
+              Param o@25..26
+              Block@43..64
+              Block@67..103
+                MatchBinding value@70..75
+              Block@106..113
+          o@37..38 -> Param o@25..26
+          Some@43..47 -> builtin
+          limit@48..53 -> Let limit@5..10
+          limit@58..63 -> Let limit@5..10
+          Ok@67..69 -> builtin
+          value@80..85 -> MatchBinding value@70..75
+          limit@88..93 -> Let limit@5..10
+          value@97..102 -> MatchBinding value@70..75
+      "#]],
+    );
+  }
+
+  #[test]
+  fn object_fields_and_injections() {
+    check(
+      r#"
+let x = 1
+let inc = (a) => a + x
+let o = { x, y: x, z: inc{ x: 2 }(1) }
+o.x.y
+"#,
+      expect![[r#"
+          Module@0..79
+            Let x@5..6
+            Let inc@15..18
+            Let o@38..39
+            Function@21..33
+              Param a@22..23
+          a@28..29 -> Param a@22..23
+          x@32..33 -> Let x@5..6
+          x@44..45 -> Let x@5..6
+          x@50..51 -> Let x@5..6
+          inc@56..59 -> Let inc@15..18
+          o@73..74 -> Let o@38..39
+      "#]],
+    );
+  }
+
+  #[test]
+  fn duplicates_and_unresolved_names() {
+    check(
+      r#"
+let a = 1
+const a = 2
+fn f(a, b, b) {
+  let c = 1
+  let c = d
+}
+use std::env
+type env = i32
+"#,
+      expect![[r#"
+          Module@0..93
+            Let a@5..6
+            Let a@17..18
+            Fn f@26..27
+            Use env@74..77
+            Type env@83..86
+            Function@23..64
+              Param a@28..29
+              Param b@31..32
+              Param b@34..35
+              Block@37..64
+                Let c@45..46
+                Let c@57..58
+            TypeAlias@78..92
+          d@61..62 -> unresolved
+          i32@89..92 -> builtin
+          error[B0003]@17..18: `a` is declared more than once in this scope
+          error[B0003]@34..35: `b` is declared more than once in this scope
+          error[B0003]@57..58: `c` is declared more than once in this scope
+          error[B0004]@61..62: cannot find `d` in this scope
+          error[B0003]@83..86: `env` is declared more than once in this scope
+      "#]],
+    );
+  }
+}
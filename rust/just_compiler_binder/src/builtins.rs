@@ -0,0 +1,12 @@
+//! Names provided by the language, in scope in every source file without being declared.
+
+/// Primitive and core types, the constructors of `Option` and `Result`, and the root modules.
+pub const BUILTINS: &[&str] = &[
+  "bool", "char", "str", "int", "uint", "float", "i8", "i16", "i32", "i64", "i128", "isize", "u8",
+  "u16", "u32", "u64", "u128", "usize", "f32", "f64", "any", "never", "String", "Array", "Vec", "Box",
+  "Option", "Some", "None", "Result", "Ok", "Err", "Symbol", "std", "core",
+];
+
+pub fn is_builtin(name: &str) -> bool {
+  BUILTINS.contains(&name)
+}
@@ -0,0 +1,18 @@
+//! Codes of the binder diagnostics.
+
+use justc_lexer::diagnostic::Code;
+
+/// A value is injected into a function that does not capture it, e.g. `inc{ y: 1 }(2)`.
+pub const UNKNOWN_INJECTION: Code = Code("B0001");
+/// A name is declared twice in the same scope.
+pub const DUPLICATE_DECLARATION: Code = Code("B0003");
+/// A name refers to no declaration in scope.
+pub const UNRESOLVED_NAME: Code = Code("B0004");
+/// A `mod` or `use` refers to a module without a source file.
+pub const UNRESOLVED_MODULE: Code = Code("B0005");
+/// A `use` refers to no declaration of the module.
+pub const UNRESOLVED_IMPORT: Code = Code("B0006");
+/// Imports of imports lead back to the first one, e.g. `a` imports `x` from `b`, which imports it from `a`.
+pub const CYCLIC_IMPORT: Code = Code("B0007");
+/// A module is in both `a.just` and `a/mod.just`.
+pub const DUPLICATE_MODULE: Code = Code("B0008");
@@ -0,0 +1,211 @@
+//! Checks of injections, e.g. `inc{ x: 10 }(2)`.
+//!
+//! An injection replaces names captured by a function, i.e. names used in the function
+//! and declared outside of it, and the names of its context parameter, e.g. `factor` of `({ factor }| v) => v * factor`.
+//! Injecting a name the function doesn't capture is an error.
+//! The types of the injected values are checked with the other types, by the checker.
+
+use super::codes;
+use super::symbols::{Resolution, SymbolId, SymbolKind, SymbolTable};
+use justc_lexer::diagnostic::Diagnostic;
+use justc_parser::ast::{self, AstNode};
+use justc_parser::{SyntaxKind, SyntaxNode};
+use std::collections::BTreeMap;
+
+/// Checks all injections of `file`, whose symbols are `symbols`.
+pub fn check_injections(file: &ast::SourceFile, symbols: &SymbolTable) -> Vec<Diagnostic> {
+  let mut diagnostics = Vec::new();
+  for inject in file
+    .syntax()
+    .descendants()
+    .filter_map(ast::InjectExpr::cast)
+  {
+    check_injection(&inject, symbols, &mut diagnostics);
+  }
+  diagnostics
+}
+
+/// The function an injection is applied to and the names it captures.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Injected {
+  pub function: SymbolId,
+  /// The names injections can replace, with their symbol if they are declared in the file.
+  pub captures: BTreeMap<String, Option<SymbolId>>,
+}
+
+/// The function `inject` is applied to, when it is a function referred to by name,
+/// e.g. not `obj.inc{ x: 10 }(2)`.
+pub fn injected(inject: &ast::InjectExpr, symbols: &SymbolTable) -> Option<Injected> {
+  let root = inject.syntax().ancestors().last().unwrap();
+  let target = match inject.target()? {
+    ast::Expr::PathExpr(target) => target,
+    _ => return None,
+  };
+  let reference = symbols.reference_at(single_name(&target)?.syntax().text_range())?;
+  let function = reference.symbol()?;
+  let decl = function_of(&symbols.symbol(function).decl(&root)?)?;
+  Some(Injected {
+    function,
+    captures: captures(&decl, symbols),
+  })
+}
+
+fn check_injection(
+  inject: &ast::InjectExpr,
+  symbols: &SymbolTable,
+  diagnostics: &mut Vec<Diagnostic>,
+) {
+  let injected = match injected(inject, symbols) {
+    Some(injected) => injected,
+    None => return,
+  };
+  let symbol = symbols.symbol(injected.function);
+  for field in inject.fields() {
+    let key = match field.name_ref() {
+      Some(key) => key,
+      None => continue,
+    };
+    let key_name = key.text();
+    if injected.captures.contains_key(&key_name) {
+      continue;
+    }
+    diagnostics.push(
+      Diagnostic::error(
+        codes::UNKNOWN_INJECTION,
+        format!("`{}` does not capture `{}`", symbol.name, key_name),
+        key.syntax().text_range(),
+      )
+      .with_label("not used by the function")
+      .with_span_note(
+        symbol.name_span,
+        format!("`{}` is defined here", symbol.name),
+      ),
+    );
+  }
+}
+
+/// Name of a path made of a single segment, e.g. `inc`.
+fn single_name(path_expr: &ast::PathExpr) -> Option<ast::NameRef> {
+  let path = path_expr.path()?;
+  if path.segments().count() != 1 {
+    return None;
+  }
+  path.last_segment()?.name_ref()
+}
+
+/// The function defined by `decl`: a `FnDecl`, or the `ArrowFn` of `let inc = (a) => a + x`.
+fn function_of(decl: &SyntaxNode) -> Option<SyntaxNode> {
+  match decl.kind() {
+    SyntaxKind::FnDecl => Some(decl.clone()),
+    SyntaxKind::LetDecl => match ast::LetDecl::cast(decl.clone())?.initializer()? {
+      ast::Expr::ArrowFn(arrow) => Some(arrow.syntax().clone()),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+/// The names of the context parameter of `function` and the values used in it and not declared in it,
+/// with their symbol if they are declared in the file.
+fn captures(function: &SyntaxNode, symbols: &SymbolTable) -> BTreeMap<String, Option<SymbolId>> {
+  let span = function.text_range();
+  let mut captures = BTreeMap::new();
+  let context = function
+    .children()
+    .find_map(ast::ParamList::cast)
+    .and_then(|params| params.context_param()?.pat());
+  for name in context.into_iter().flat_map(|pat| pat.names()) {
+    let symbol = symbols.symbol_at(name.syntax().text_range());
+    captures.insert(name.text(), symbol);
+  }
+  for reference in symbols.references() {
+    if !span.contains_span(reference.span) {
+      continue;
+    }
+    let captured = match reference.resolution {
+      Resolution::Symbol(id) => {
+        let symbol = symbols.symbol(id);
+        let is_value = !matches!(symbol.kind, SymbolKind::Type | SymbolKind::GenericParam);
+        if span.contains_span(symbol.decl_span) || !is_value {
+          continue;
+        }
+        Some(id)
+      }
+      Resolution::Builtin => continue,
+      Resolution::Unresolved => None,
+    };
+    captures.entry(reference.name.clone()).or_insert(captured);
+  }
+  captures
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expect_test::{expect, Expect};
+
+  fn check(text: &str, expect: Expect) {
+    let parse = justc_parser::parse(text);
+    assert_eq!(parse.errors(), &[], "{}", parse.debug_dump());
+    let binding = crate::bind(&parse.tree());
+    let mut out = String::new();
+    for diagnostic in check_injections(&parse.tree(), binding.symbols()) {
+      out.push_str(&format!(
+        "{}[{}]@{:?}: {}\n",
+        diagnostic.severity, diagnostic.code, diagnostic.span, diagnostic.message
+      ));
+      if let Some(label) = &diagnostic.label {
+        out.push_str(&format!("  label: {}\n", label));
+      }
+      for note in &diagnostic.notes {
+        out.push_str(&format!("  note@{:?}: {}\n", note.span, note.message));
+      }
+    }
+    expect.assert_eq(&out);
+  }
+
+  #[test]
+  fn captured_names() {
+    check(
+      r#"
+const x = 1
+fn inc(a: i32) {
+  return x + a
+}
+inc{ x: 10 }(2)
+inc{ a: 10, y: 1 }(2)
+"#,
+      expect![[r#"
+          error[B0001]@68..69: `inc` does not capture `a`
+            label: not used by the function
+            note@Some(16..19): `inc` is defined here
+          error[B0001]@75..76: `inc` does not capture `y`
+            label: not used by the function
+            note@Some(16..19): `inc` is defined here
+      "#]],
+    );
+  }
+
+  #[test]
+  fn locals_are_not_captured() {
+    check(
+      r#"
+use std::math
+let scale = 2
+let mul = ({ factor } | v, w) => {
+  let offset = 1
+  math.round(v * scale * factor + offset + w)
+}
+mul{ math: m, scale: 3, offset: 0, factor: 2, v: 1 }(1)
+"#,
+      expect![[r#"
+          error[B0001]@153..159: `mul` does not capture `offset`
+            label: not used by the function
+            note@Some(33..36): `mul` is defined here
+          error[B0001]@175..176: `mul` does not capture `v`
+            label: not used by the function
+            note@Some(33..36): `mul` is defined here
+      "#]],
+    );
+  }
+}
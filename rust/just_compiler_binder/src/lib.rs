@@ -0,0 +1,16 @@
+//! Just compiler binder.
+//!
+//! Resolves the names used in the syntax trees of `justc_parser` to their declarations, within and across files,
+//! and reports the errors found along the way.
+
+pub mod bind;
+pub mod builtins;
+pub mod codes;
+pub mod injection;
+pub mod modules;
+pub mod symbols;
+
+pub use bind::{bind, Binding};
+pub use injection::check_injections;
+pub use modules::{ModuleFile, ModuleGraph};
+pub use symbols::SymbolTable;
@@ -0,0 +1,611 @@
+//! The module tree of a package, made of the layout of its source files, and the `use` declarations resolved across them.
+//!
+//! `src/main.just` and `src/lib.just` are the root module `crate`,
+//! `src/a.just` or `src/a/mod.just` is the module `crate::a`, `src/a/b.just` or `src/a/b/mod.just` the module `crate::a::b`.
+//!
+//! A `use` path starts with `crate`, `super`, a module under the root, or a package, e.g. `std`, which is not checked here.
+//! An import of an import is followed to the declaration, and reported when it never reaches one.
+
+use super::bind::use_tree_name;
+use super::codes;
+use super::symbols::{SymbolId, SymbolKind, SymbolTable};
+use justc_lexer::diagnostic::Diagnostic;
+use justc_lexer::span::Span;
+use justc_parser::ast::{self, AstNode, HasName};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Files of the root module, in the source directory.
+pub const ENTRY_POINTS: &[&str] = &["main.just", "lib.just"];
+
+/// Packages of the toolchain, which are not source files of the package.
+const PACKAGES: &[&str] = &["std", "core"];
+
+const EXTENSION: &str = "just";
+
+/// A parsed and bound source file, given to `ModuleGraph::build`.
+pub struct ModuleFile<'a> {
+  pub path: &'a str,
+  pub tree: &'a ast::SourceFile,
+  pub symbols: &'a SymbolTable,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModuleId(usize);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Module {
+  /// E.g. `["a", "b"]` for `crate::a::b`, empty for the root.
+  pub path: Vec<String>,
+  pub file: String,
+}
+
+impl Module {
+  /// E.g. `crate::a::b`.
+  pub fn qualified_name(&self) -> String {
+    qualified_name(&self.path)
+  }
+}
+
+/// What a `use` brings in scope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ImportTarget {
+  Module(ModuleId),
+  /// A declaration at the top of a module.
+  Symbol(ModuleId, SymbolId),
+  /// A module or a declaration of a package, e.g. `std::env`.
+  External,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Import {
+  /// The module of the `use`.
+  pub module: ModuleId,
+  pub name: String,
+  /// The name of the symbol declared by the `use`, see `use_tree_name`.
+  pub span: Span,
+  /// The declaration, after following the imports of imports, none when unresolved.
+  pub target: Option<ImportTarget>,
+}
+
+/// The modules of a package and their imports.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModuleGraph {
+  modules: Vec<Module>,
+  /// Two modules for the root when there are both `main.just` and `lib.just`.
+  by_path: BTreeMap<Vec<String>, Vec<ModuleId>>,
+  imports: Vec<Import>,
+  diagnostics: Vec<(ModuleId, Diagnostic)>,
+}
+
+impl ModuleGraph {
+  /// Builds the modules of the `files` in `src_dir`, other files are ignored.
+  pub fn build(src_dir: &str, files: &[ModuleFile]) -> ModuleGraph {
+    let src_dir = Path::new(src_dir);
+    let mut graph = ModuleGraph::default();
+    let mut sources = Vec::new();
+    for file in files {
+      if let Some(path) = module_path(src_dir, Path::new(file.path)) {
+        let id = ModuleId(graph.modules.len());
+        graph.by_path.entry(path.clone()).or_default().push(id);
+        graph.modules.push(Module {
+          path,
+          file: file.path.to_string(),
+        });
+        sources.push(file);
+      }
+    }
+    let mut resolver = Resolver {
+      graph,
+      sources,
+      src_dir,
+    };
+    resolver.duplicate_modules();
+    for id in 0..resolver.sources.len() {
+      resolver.mod_decls(ModuleId(id));
+      resolver.use_decls(ModuleId(id));
+    }
+    resolver.follow_imports();
+    let mut graph = resolver.graph;
+    graph
+      .diagnostics
+      .sort_by_key(|(module, diagnostic)| (*module, diagnostic.span.start));
+    graph
+  }
+
+  pub fn module(&self, id: ModuleId) -> &Module {
+    &self.modules[id.0]
+  }
+
+  pub fn modules(&self) -> impl Iterator<Item = (ModuleId, &Module)> {
+    self
+      .modules
+      .iter()
+      .enumerate()
+      .map(|(i, module)| (ModuleId(i), module))
+  }
+
+  /// The module of the source file at `path`.
+  pub fn module_of(&self, path: &str) -> Option<ModuleId> {
+    let position = self.modules.iter().position(|module| module.file == path);
+    position.map(ModuleId)
+  }
+
+  /// The modules of the entry points found, `main.just` and `lib.just`.
+  pub fn entry_points(&self) -> &[ModuleId] {
+    self.by_path.get(&Vec::new()).map_or(&[], Vec::as_slice)
+  }
+
+  /// The imports of all modules, in the order of the modules.
+  pub fn imports(&self) -> &[Import] {
+    &self.imports
+  }
+
+  /// The import declaring the name containing `span` in `module`.
+  pub fn import_at(&self, module: ModuleId, span: Span) -> Option<&Import> {
+    self
+      .imports
+      .iter()
+      .find(|import| import.module == module && import.span.contains_span(span))
+  }
+
+  /// Missing modules, unresolved and cyclic imports of `module`, ordered by position.
+  pub fn diagnostics(&self, module: ModuleId) -> impl Iterator<Item = &Diagnostic> {
+    self
+      .diagnostics
+      .iter()
+      .filter(move |(id, _)| *id == module)
+      .map(|(_, diagnostic)| diagnostic)
+  }
+
+  /// The modules with their imports, followed by the diagnostics, for tests and debugging.
+  pub fn debug_dump(&self) -> String {
+    let mut out = String::new();
+    for (id, module) in self.modules() {
+      out.push_str(&format!("{} {}\n", module.qualified_name(), module.file));
+      for import in self.imports.iter().filter(|import| import.module == id) {
+        let target = match import.target {
+          Some(ImportTarget::Module(module)) => self.module(module).qualified_name(),
+          Some(ImportTarget::Symbol(module, symbol)) => {
+            format!("{} {:?}", self.module(module).qualified_name(), symbol)
+          }
+          Some(ImportTarget::External) => "external".to_string(),
+          None => "unresolved".to_string(),
+        };
+        out.push_str(&format!(
+          "  {}@{:?} -> {}\n",
+          import.name, import.span, target
+        ));
+      }
+    }
+    for (id, diagnostic) in &self.diagnostics {
+      out.push_str(&format!(
+        "{}@{:?}: {}\n",
+        self.module(*id).file,
+        diagnostic.span,
+        diagnostic.message
+      ));
+      for note in &diagnostic.notes {
+        out.push_str(&format!("  note: {}\n", note.message));
+      }
+    }
+    out
+  }
+}
+
+/// The module path of the source file at `file`, e.g. `["a", "b"]` for `src/a/b.just` or `src/a/b/mod.just`,
+/// none for a file that is not a source file in `src_dir`.
+pub fn module_path(src_dir: &Path, file: &Path) -> Option<Vec<String>> {
+  let relative = file.strip_prefix(src_dir).ok()?;
+  if relative.extension()? != EXTENSION {
+    return None;
+  }
+  let mut path = relative
+    .parent()?
+    .iter()
+    .map(|segment| segment.to_str().map(str::to_string))
+    .collect::<Option<Vec<_>>>()?;
+  let file_name = relative.file_name()?.to_str()?;
+  let stem = relative.file_stem()?.to_str()?;
+  if path.is_empty() && ENTRY_POINTS.contains(&file_name) {
+    return Some(path);
+  }
+  if stem != "mod" {
+    path.push(stem.to_string());
+  } else if path.is_empty() {
+    return None;
+  }
+  Some(path)
+}
+
+/// The files the module at `path` can be in, e.g. `src/a/b.just` and `src/a/b/mod.just` for `["a", "b"]`.
+pub fn module_files(src_dir: &Path, path: &[String]) -> Vec<PathBuf> {
+  let (name, parents) = match path.split_last() {
+    Some(split) => split,
+    None => return ENTRY_POINTS.iter().map(|file| src_dir.join(file)).collect(),
+  };
+  let dir: PathBuf = parents
+    .iter()
+    .fold(src_dir.to_path_buf(), |dir, parent| dir.join(parent));
+  vec![
+    dir.join(format!("{}.{}", name, EXTENSION)),
+    dir.join(name).join(format!("mod.{}", EXTENSION)),
+  ]
+}
+
+fn qualified_name(path: &[String]) -> String {
+  let mut name = "crate".to_string();
+  for segment in path {
+    name.push_str("::");
+    name.push_str(segment);
+  }
+  name
+}
+
+struct Resolver<'a> {
+  graph: ModuleGraph,
+  /// The source file of each module.
+  sources: Vec<&'a ModuleFile<'a>>,
+  src_dir: &'a Path,
+}
+
+impl<'a> Resolver<'a> {
+  /// Reports a module in both `a.just` and `a/mod.just`, on the second one.
+  fn duplicate_modules(&mut self) {
+    let duplicates = self
+      .graph
+      .by_path
+      .iter()
+      .filter(|(path, ids)| !path.is_empty() && ids.len() > 1);
+    let mut diagnostics = Vec::new();
+    for (path, ids) in duplicates {
+      let first = &self.graph.module(ids[0]).file;
+      for id in &ids[1..] {
+        let message = format!("module `{}` is in more than one file", qualified_name(path));
+        let diagnostic = Diagnostic::error(codes::DUPLICATE_MODULE, message, Span::empty(0))
+          .with_note(format!("the module is first in `{}`", first));
+        diagnostics.push((*id, diagnostic));
+      }
+    }
+    self.graph.diagnostics.extend(diagnostics);
+  }
+
+  /// Reports the `mod` declarations of `module` without a file.
+  fn mod_decls(&mut self, module: ModuleId) {
+    let decls = self.sources[module.0]
+      .tree
+      .statements()
+      .filter_map(|stmt| match stmt {
+        ast::Stmt::ModDecl(decl) => decl.name(),
+        _ => None,
+      });
+    for name in decls {
+      let mut path = self.graph.module(module).path.clone();
+      path.push(name.text());
+      if !self.graph.by_path.contains_key(&path) {
+        let diagnostic = self.missing_module(&path, name.syntax().text_range());
+        self.graph.diagnostics.push((module, diagnostic));
+      }
+    }
+  }
+
+  fn use_decls(&mut self, module: ModuleId) {
+    let root = self.sources[module.0].tree.syntax().clone();
+    let trees = root
+      .descendants()
+      .filter_map(ast::UseDecl::cast)
+      .filter_map(|decl| decl.use_tree());
+    for tree in trees {
+      self.use_tree(module, &tree, &[]);
+    }
+  }
+
+  fn use_tree(&mut self, module: ModuleId, tree: &ast::UseTree, prefix: &[(String, Span)]) {
+    let mut path = prefix.to_vec();
+    let segments = tree.path().into_iter().flat_map(|path| path.segments());
+    for name_ref in segments.filter_map(|segment| segment.name_ref()) {
+      path.push((name_ref.text(), name_ref.syntax().text_range()));
+    }
+    if let Some(list) = tree.use_tree_list() {
+      for tree in list.use_trees() {
+        self.use_tree(module, &tree, &path);
+      }
+      return;
+    }
+    if tree.star_token().is_some() {
+      if let Err(diagnostic) = self.modules(module, &path) {
+        self.graph.diagnostics.push((module, *diagnostic));
+      }
+      return;
+    }
+    let (name, span) = match use_tree_name(tree) {
+      Some(name) if !path.is_empty() => name,
+      _ => return,
+    };
+    let target = match self.resolve(module, &path) {
+      Ok(target) => Some(target),
+      Err(diagnostic) => {
+        self.graph.diagnostics.push((module, *diagnostic));
+        None
+      }
+    };
+    self.graph.imports.push(Import {
+      module,
+      name,
+      span,
+      target,
+    });
+  }
+
+  /// Resolves a non empty `use` path to a module or a declaration at the top of one.
+  fn resolve(
+    &self,
+    module: ModuleId,
+    path: &[(String, Span)],
+  ) -> Result<ImportTarget, Box<Diagnostic>> {
+    let whole = self.modules(module, path);
+    let (prefix, (name, span)) = match path.split_last() {
+      Some((last, prefix)) if !prefix.is_empty() && whole.is_err() => (prefix, last),
+      _ => {
+        return whole.map(|path| match path {
+          Some(path) => ImportTarget::Module(self.graph.by_path[&path][0]),
+          None => ImportTarget::External,
+        })
+      }
+    };
+    let parent = match self.modules(module, prefix)? {
+      Some(parent) => parent,
+      None => return Ok(ImportTarget::External),
+    };
+    for id in self.graph.by_path.get(&parent).into_iter().flatten() {
+      if let Some(symbol) = self.item(*id, name) {
+        return Ok(ImportTarget::Symbol(*id, symbol));
+      }
+    }
+    let message = format!(
+      "cannot find `{}` in module `{}`",
+      name,
+      qualified_name(&parent)
+    );
+    let diagnostic = Diagnostic::error(codes::UNRESOLVED_IMPORT, message, *span);
+    Err(Box::new(diagnostic.with_label("not found in this module")))
+  }
+
+  /// Resolves the modules of `path` from `module`, none for a package.
+  fn modules(
+    &self,
+    module: ModuleId,
+    path: &[(String, Span)],
+  ) -> Result<Option<Vec<String>>, Box<Diagnostic>> {
+    let mut segments = path.iter().peekable();
+    let mut current = Vec::new();
+    match path.first() {
+      Some((first, _)) if PACKAGES.contains(&first.as_str()) => return Ok(None),
+      Some((first, _)) if first == "crate" => {
+        segments.next();
+      }
+      Some((first, _)) if first == "super" => {
+        current = self.graph.module(module).path.clone();
+        while let Some((_, span)) = segments.next_if(|(segment, _)| segment == "super") {
+          if current.pop().is_none() {
+            let diagnostic = Diagnostic::error(
+              codes::UNRESOLVED_MODULE,
+              "there is no module above `crate`",
+              *span,
+            );
+            return Err(Box::new(
+              diagnostic.with_label("`super` of the root module"),
+            ));
+          }
+        }
+      }
+      _ => (),
+    }
+    for (segment, span) in segments {
+      current.push(segment.clone());
+      if !self.graph.by_path.contains_key(&current) {
+        let span = path[0].1.cover(*span);
+        return Err(Box::new(self.missing_module(&current, span)));
+      }
+    }
+    Ok(Some(current))
+  }
+
+  /// The symbol `name` declared at the top of `module`, other than a module.
+  fn item(&self, module: ModuleId, name: &str) -> Option<SymbolId> {
+    let symbols = self.sources[module.0].symbols;
+    let root = symbols.scope(symbols.root());
+    root.symbols.iter().copied().find(|id| {
+      let symbol = symbols.symbol(*id);
+      symbol.name == name && symbol.kind != SymbolKind::Mod
+    })
+  }
+
+  fn missing_module(&self, path: &[String], span: Span) -> Diagnostic {
+    let files = module_files(self.src_dir, path);
+    let tried: Vec<_> = files
+      .iter()
+      .map(|file| format!("`{}`", file.display()))
+      .collect();
+    let message = format!("cannot find module `{}`", qualified_name(path));
+    Diagnostic::error(codes::UNRESOLVED_MODULE, message, span)
+      .with_label("no file for this module")
+      .with_note(format!("tried {}", tried.join(" and ")))
+  }
+
+  /// Follows the imports of imports to the declarations, and reports the imports in a cycle.
+  fn follow_imports(&mut self) {
+    let imports = &self.graph.imports;
+    let mut targets = Vec::new();
+    let mut diagnostics = Vec::new();
+    for (i, import) in imports.iter().enumerate() {
+      let mut chain = vec![i];
+      let target = loop {
+        let current = &imports[*chain.last().unwrap()];
+        let (module, symbol) = match current.target {
+          Some(ImportTarget::Symbol(module, symbol)) => (module, symbol),
+          target => break target,
+        };
+        let symbol = self.sources[module.0].symbols.symbol(symbol);
+        if symbol.kind != SymbolKind::Use {
+          break current.target;
+        }
+        let next = imports
+          .iter()
+          .position(|import| import.module == module && import.span == symbol.name_span);
+        match next {
+          Some(next) if next == i => {
+            diagnostics.push((import.module, self.cycle(&chain)));
+            break None;
+          }
+          // Unresolved, or leading to a cycle reported on the imports in it.
+          None => break None,
+          Some(next) if chain.contains(&next) => break None,
+          Some(next) => chain.push(next),
+        }
+      };
+      targets.push(target);
+    }
+    for (import, target) in self.graph.imports.iter_mut().zip(targets) {
+      import.target = target;
+    }
+    self.graph.diagnostics.extend(diagnostics);
+  }
+
+  fn cycle(&self, chain: &[usize]) -> Diagnostic {
+    let imports = &self.graph.imports;
+    let name = |i: usize| {
+      let import = &imports[i];
+      let module = self.graph.module(import.module);
+      format!("{}::{}", module.qualified_name(), import.name)
+    };
+    let first = &imports[chain[0]];
+    let message = format!("`{}` is imported in a cycle", first.name);
+    let mut diagnostic = Diagnostic::error(codes::CYCLIC_IMPORT, message, first.span)
+      .with_label("never reaches a declaration");
+    for (k, i) in chain.iter().enumerate() {
+      let next = chain.get(k + 1).unwrap_or(&chain[0]);
+      diagnostic = diagnostic.with_note(format!("`{}` imports `{}`", name(*i), name(*next)));
+    }
+    diagnostic
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bind;
+  use expect_test::{expect, Expect};
+
+  fn check(files: &[(&str, &str)], expect: Expect) {
+    let parses: Vec<_> = files
+      .iter()
+      .map(|(_, text)| justc_parser::parse(text).tree())
+      .collect();
+    let bindings: Vec<_> = parses.iter().map(bind).collect();
+    let files: Vec<_> = files
+      .iter()
+      .zip(&parses)
+      .zip(&bindings)
+      .map(|(((path, _), tree), binding)| ModuleFile {
+        path,
+        tree,
+        symbols: binding.symbols(),
+      })
+      .collect();
+    let graph = ModuleGraph::build("src", &files);
+    expect.assert_eq(&graph.debug_dump());
+  }
+
+  #[test]
+  fn module_paths_from_file_layout() {
+    let path = |file: &str| module_path(Path::new("pkg/src"), Path::new(file));
+    assert_eq!(path("pkg/src/main.just"), Some(vec![]));
+    assert_eq!(path("pkg/src/lib.just"), Some(vec![]));
+    assert_eq!(
+      path("pkg/src/tokenize.just"),
+      Some(vec!["tokenize".to_string()])
+    );
+    assert_eq!(path("pkg/src/sub/mod.just"), Some(vec!["sub".to_string()]));
+    let nested = Some(vec!["sub".to_string(), "main".to_string()]);
+    assert_eq!(path("pkg/src/sub/main.just"), nested);
+    assert_eq!(path("pkg/src/mod.just"), None);
+    assert_eq!(path("pkg/src/notes.md"), None);
+    assert_eq!(path("pkg/tests/main.just"), None);
+
+    let files = module_files(Path::new("src"), &["a".to_string(), "b".to_string()]);
+    assert_eq!(
+      files,
+      [Path::new("src/a/b.just"), Path::new("src/a/b/mod.just")]
+    );
+  }
+
+  #[test]
+  fn resolve_imports_across_files() {
+    check(
+      &[
+        (
+          "src/lib.just",
+          "pub mod tokenize\nmod parser\nuse tokenize::{tokenize, Token as T}\nuse std.str.Chars\nuse crate::sub::{deep, nope}\nfn main() { use super::x }",
+        ),
+        ("src/tokenize.just", "pub let tokenize = () => []\ntype Token = str\nuse super::sub::deep::Tree"),
+        ("src/sub/mod.just", "use crate::missing::Item\nuse super::tokenize::*"),
+        ("src/sub/deep.just", "pub type Tree = { root: Node }"),
+        ("src/notes.txt", ""),
+      ],
+      expect![[r#"
+          crate src/lib.just
+            tokenize@43..51 -> crate::tokenize SymbolId(0)
+            T@62..63 -> crate::tokenize SymbolId(1)
+            Chars@77..82 -> external
+            deep@100..104 -> crate::sub::deep
+            nope@106..110 -> unresolved
+            x@135..136 -> unresolved
+          crate::tokenize src/tokenize.just
+            Tree@67..71 -> crate::sub::deep SymbolId(0)
+          crate::sub src/sub/mod.just
+            Item@20..24 -> unresolved
+          crate::sub::deep src/sub/deep.just
+          src/lib.just@21..27: cannot find module `crate::parser`
+            note: tried `src/parser.just` and `src/parser/mod.just`
+          src/lib.just@106..110: cannot find `nope` in module `crate::sub`
+          src/lib.just@128..133: there is no module above `crate`
+          src/sub/mod.just@4..18: cannot find module `crate::missing`
+            note: tried `src/missing.just` and `src/missing/mod.just`
+      "#]],
+    );
+  }
+
+  #[test]
+  fn cyclic_imports() {
+    check(
+      &[
+        ("src/main.just", "use a::x\nuse b::y"),
+        ("src/a.just", "pub use crate::b::x"),
+        ("src/b.just", "pub use crate::a::x\npub use crate::c::y"),
+        ("src/c.just", "pub let y = 1"),
+        ("src/c/mod.just", ""),
+      ],
+      expect![[r#"
+          crate src/main.just
+            x@7..8 -> unresolved
+            y@16..17 -> crate::c SymbolId(0)
+          crate::a src/a.just
+            x@18..19 -> unresolved
+          crate::b src/b.just
+            x@18..19 -> unresolved
+            y@38..39 -> crate::c SymbolId(0)
+          crate::c src/c.just
+          crate::c src/c/mod.just
+          src/a.just@18..19: `x` is imported in a cycle
+            note: `crate::a::x` imports `crate::b::x`
+            note: `crate::b::x` imports `crate::a::x`
+          src/b.just@18..19: `x` is imported in a cycle
+            note: `crate::b::x` imports `crate::a::x`
+            note: `crate::a::x` imports `crate::b::x`
+          src/c/mod.just@0..0: module `crate::c` is in more than one file
+            note: the module is first in `src/c.just`
+      "#]],
+    );
+  }
+}
@@ -0,0 +1,324 @@
+//! Scopes, the symbols declared in them and the references to them, found by `bind`.
+//!
+//! Everything is identified by spans rather than syntax nodes,
+//! so a table can be kept and queried after the tree is dropped, e.g. by the language server.
+
+use justc_lexer::span::Span;
+use justc_parser::{SyntaxKind, SyntaxNode};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScopeId(pub(crate) usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SymbolId(pub(crate) usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScopeKind {
+  /// The declarations of a source file.
+  Module,
+  /// The generic parameters and parameters of a function.
+  Function,
+  /// The generic parameters of a type alias.
+  TypeAlias,
+  /// The key parameter of a mapped object type, e.g. `K` in `{ [K in keyof T]: T[K] }`.
+  MappedType,
+  /// A block, the body of a `for` loop or a match arm.
+  Block,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scope {
+  pub kind: ScopeKind,
+  pub parent: Option<ScopeId>,
+  /// The node the scope is made of, e.g. the block.
+  pub span: Span,
+  pub symbols: Vec<SymbolId>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+  /// `let x`, `const x`
+  Let,
+  /// `let mut x`
+  Mut,
+  /// `type T`
+  Type,
+  /// `x` in `use std::x`
+  Use,
+  /// `mod x`
+  Mod,
+  /// `fn f`
+  Fn,
+  /// `x` in `(x: i32) => x`, or in `{ x }: Point`
+  Param,
+  /// `T` in `<T>`
+  GenericParam,
+  /// `x` in `for x in xs`
+  ForBinding,
+  /// `v` in the match arm `Some(v) => v`
+  MatchBinding,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Symbol {
+  pub name: String,
+  pub kind: SymbolKind,
+  pub scope: ScopeId,
+  /// The name in the declaration.
+  pub name_span: Span,
+  /// The whole declaration, e.g. the `LetDecl` or the `Param`.
+  pub decl_span: Span,
+  pub decl_kind: SyntaxKind,
+}
+
+impl Symbol {
+  /// The declaration node in the tree rooted at `root`.
+  pub fn decl(&self, root: &SyntaxNode) -> Option<SyntaxNode> {
+    let element = root.covering_element(self.decl_span);
+    let mut nodes = element.ancestors();
+    nodes.find(|node| node.kind() == self.decl_kind && node.text_range() == self.decl_span)
+  }
+}
+
+/// What a name refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Resolution {
+  Symbol(SymbolId),
+  /// A name provided by the language, e.g. `i32` or `Some`.
+  Builtin,
+  Unresolved,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reference {
+  pub name: String,
+  pub span: Span,
+  /// The innermost scope at the reference.
+  pub scope: ScopeId,
+  pub resolution: Resolution,
+}
+
+impl Reference {
+  pub fn symbol(&self) -> Option<SymbolId> {
+    match self.resolution {
+      Resolution::Symbol(symbol) => Some(symbol),
+      _ => None,
+    }
+  }
+}
+
+/// The scopes, symbols and references of a source file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SymbolTable {
+  scopes: Vec<Scope>,
+  symbols: Vec<Symbol>,
+  /// Ordered by position.
+  references: Vec<Reference>,
+}
+
+impl SymbolTable {
+  /// The scope of the declarations of the source file.
+  pub fn root(&self) -> ScopeId {
+    ScopeId(0)
+  }
+
+  pub fn scope(&self, id: ScopeId) -> &Scope {
+    &self.scopes[id.0]
+  }
+
+  pub fn symbol(&self, id: SymbolId) -> &Symbol {
+    &self.symbols[id.0]
+  }
+
+  pub fn symbols(&self) -> impl Iterator<Item = (SymbolId, &Symbol)> {
+    self
+      .symbols
+      .iter()
+      .enumerate()
+      .map(|(i, symbol)| (SymbolId(i), symbol))
+  }
+
+  pub fn references(&self) -> &[Reference] {
+    &self.references
+  }
+
+  /// The references resolved to `symbol`.
+  pub fn references_to(&self, symbol: SymbolId) -> impl Iterator<Item = &Reference> {
+    let resolution = Resolution::Symbol(symbol);
+    self
+      .references
+      .iter()
+      .filter(move |reference| reference.resolution == resolution)
+  }
+
+  /// The innermost scope containing `offset`.
+  pub fn scope_at(&self, offset: usize) -> ScopeId {
+    // Scopes are created in tree order, so the last one containing `offset` is the innermost.
+    let innermost = self
+      .scopes
+      .iter()
+      .rposition(|scope| scope.span.contains(offset));
+    innermost.map_or(self.root(), ScopeId)
+  }
+
+  /// The reference whose name contains `span`.
+  pub fn reference_at(&self, span: Span) -> Option<&Reference> {
+    let i = self
+      .references
+      .partition_point(|reference| reference.span.end < span.end);
+    let reference = self.references.get(i)?;
+    if reference.span.contains_span(span) {
+      Some(reference)
+    } else {
+      None
+    }
+  }
+
+  /// The symbol declared or referred to by the name containing `span`, e.g. for go to definition.
+  pub fn symbol_at(&self, span: Span) -> Option<SymbolId> {
+    if let Some(reference) = self.reference_at(span) {
+      return reference.symbol();
+    }
+    self
+      .symbols()
+      .find(|(_, symbol)| symbol.name_span.contains_span(span))
+      .map(|(id, _)| id)
+  }
+
+  /// The symbol `name` refers to in `scope`, looking in the enclosing scopes from the innermost out.
+  /// Declarations of blocks are only visible after them, so it is the one visible at the end of `scope`.
+  pub fn lookup(&self, scope: ScopeId, name: &str) -> Option<SymbolId> {
+    let mut scope = Some(scope);
+    while let Some(id) = scope {
+      let current = self.scope(id);
+      let found = current
+        .symbols
+        .iter()
+        .rev()
+        .find(|symbol| self.symbol(**symbol).name == name);
+      if found.is_some() {
+        return found.copied();
+      }
+      scope = current.parent;
+    }
+    None
+  }
+
+  /// Indented dump of the scopes with their symbols, followed by the references, for tests and debugging.
+  pub fn debug_dump(&self) -> String {
+    let mut out = String::new();
+    self.dump_scope(self.root(), 0, &mut out);
+    for reference in &self.references {
+      let resolution = match reference.resolution {
+        Resolution::Symbol(id) => {
+          let symbol = self.symbol(id);
+          format!("{:?} {}@{:?}", symbol.kind, symbol.name, symbol.name_span)
+        }
+        Resolution::Builtin => "builtin".to_string(),
+        Resolution::Unresolved => "unresolved".to_string(),
+      };
+      out.push_str(&format!(
+        "{}@{:?} -> {}\n",
+        reference.name, reference.span, resolution
+      ));
+    }
+    out
+  }
+
+  fn dump_scope(&self, id: ScopeId, depth: usize, out: &mut String) {
+    let scope = self.scope(id);
+    out.push_str(&format!(
+      "{:indent$}{:?}@{:?}\n",
+      "",
+      scope.kind,
+      scope.span,
+      indent = depth * 2
+    ));
+    for symbol in scope.symbols.iter().map(|id| self.symbol(*id)) {
+      out.push_str(&format!(
+        "{:indent$}{:?} {}@{:?}\n",
+        "",
+        symbol.kind,
+        symbol.name,
+        symbol.name_span,
+        indent = depth * 2 + 2
+      ));
+    }
+    let children = self
+      .scopes
+      .iter()
+      .enumerate()
+      .filter(|(_, child)| child.parent == Some(id));
+    for (child, _) in children {
+      self.dump_scope(ScopeId(child), depth + 1, out);
+    }
+  }
+
+  pub(crate) fn add_scope(
+    &mut self,
+    kind: ScopeKind,
+    parent: Option<ScopeId>,
+    span: Span,
+  ) -> ScopeId {
+    self.scopes.push(Scope {
+      kind,
+      parent,
+      span,
+      symbols: Vec::new(),
+    });
+    ScopeId(self.scopes.len() - 1)
+  }
+
+  pub(crate) fn add_symbol(&mut self, symbol: Symbol) -> SymbolId {
+    let id = SymbolId(self.symbols.len());
+    self.scopes[symbol.scope.0].symbols.push(id);
+    self.symbols.push(symbol);
+    id
+  }
+
+  pub(crate) fn add_reference(&mut self, reference: Reference) {
+    self.references.push(reference);
+  }
+
+  pub(crate) fn sort_references(&mut self) {
+    self.references.sort_by_key(|reference| reference.span);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bind;
+
+  #[test]
+  fn query_by_span() {
+    let text = "let x = 1\nfn f(y) {\n  x + y\n}\nf(x)";
+    let parse = justc_parser::parse(text);
+    let binding = bind(&parse.tree());
+    let table = binding.symbols();
+    let span = |needle: &str, nth: usize| {
+      let start = text.match_indices(needle).nth(nth).unwrap().0;
+      Span::new(start, start + needle.len())
+    };
+
+    let x = table.symbol_at(span("x", 0)).unwrap();
+    assert_eq!(table.symbol(x).kind, SymbolKind::Let);
+    assert_eq!(table.symbol_at(span("x", 1)), Some(x));
+    assert_eq!(table.symbol_at(Span::empty(span("x", 2).start)), Some(x));
+    let uses: Vec<_> = table
+      .references_to(x)
+      .map(|reference| reference.span)
+      .collect();
+    assert_eq!(uses, [span("x", 1), span("x", 2)]);
+
+    let decl = table.symbol(x).decl(&parse.syntax()).unwrap();
+    assert_eq!(decl.text(), "let x = 1");
+    assert_eq!(table.symbol_at(span("let", 0)), None);
+
+    let scope = table.scope_at(span("y", 1).start);
+    assert_eq!(table.scope(scope).kind, ScopeKind::Block);
+    let y = table.lookup(scope, "y").unwrap();
+    assert_eq!(table.symbol(y).kind, SymbolKind::Param);
+    assert_eq!(table.lookup(table.root(), "y"), None);
+  }
+}
@@ -0,0 +1,491 @@
+//! Structural assignability: a value of type `S` can be used where `T` is expected when `S` is assignable to `T`.
+//!
+//! An object is assignable to an object type when it has all its fields, extra fields are allowed.
+//! A union is assignable when each of its types is, and a type is assignable to a union when it is to one of its types.
+//! References are transparent, the ownership of values is checked elsewhere.
+//!
+//! A nominal type is only assignable from itself, whatever its structure, but its values can be used as its structure.
+//! A nominal union, e.g. `nominal type Option<T> = Some<T> | None`, is the union of its variants.
+
+use super::check::{none, Checker};
+use super::eval::{is_computation, is_deferred};
+use super::lower::intersection;
+use super::ty::{Conditional, Field, FnTy, Lit, Mapped, Named, Ty};
+use justc_binder::symbols::SymbolId;
+use std::collections::HashMap;
+
+/// Why a type isn't assignable to another, shown in a note, or none when it is obvious.
+pub(crate) type Reason = Option<String>;
+
+/// Aliases are expanded at most this many times in a type, e.g. for `type A = A | i32`.
+const MAX_EXPANSIONS: usize = 32;
+
+impl<'a> Checker<'a> {
+  pub(crate) fn assignable(&self, source: &Ty, target: &Ty) -> Result<(), Reason> {
+    self.relate(source, target, &mut Vec::new())
+  }
+
+  /// `seen` are the pairs of aliases being compared, assumed assignable while comparing their expansions,
+  /// so recursive aliases can be compared.
+  fn relate(&self, source: &Ty, target: &Ty, seen: &mut Vec<(Ty, Ty)>) -> Result<(), Reason> {
+    if source == target {
+      return Ok(());
+    }
+    match (source, target) {
+      (Ty::Unknown, _) | (_, Ty::Unknown) | (_, Ty::Any) | (Ty::Never, _) => return Ok(()),
+      (Ty::Ref(source, _), _) => return self.relate(source, target, seen),
+      (_, Ty::Ref(target, _)) => return self.relate(source, target, seen),
+      (Ty::Union(sources), _) => {
+        for source in sources {
+          self.relate(source, target, seen)?;
+        }
+        return Ok(());
+      }
+      _ => (),
+    }
+    if let (Ty::Named(source), Ty::Named(target)) = (source, target) {
+      if source.symbol == target.symbol && self.is_nominal(target.symbol) {
+        return self.relate_args(&source.args, &target.args, seen);
+      }
+    }
+    let expand_target = is_expandable(target) && !self.is_opaque(target);
+    // A nominal type is kept while it may be one of the types of the target.
+    let keep_source = self.is_opaque(source)
+      && (expand_target || matches!(target, Ty::Union(_) | Ty::Intersection(_)));
+    let expand_source = is_expandable(source) && !keep_source;
+    if expand_source || expand_target {
+      let pair = (source.clone(), target.clone());
+      if seen.contains(&pair) || seen.len() > MAX_EXPANSIONS {
+        return Ok(());
+      }
+      seen.push(pair);
+      let source = if expand_source {
+        self.expand(source)
+      } else {
+        source.clone()
+      };
+      let target = if expand_target {
+        self.expand(target)
+      } else {
+        target.clone()
+      };
+      let result = self.relate(&source, &target, seen);
+      seen.pop();
+      return result;
+    }
+    match (source, target) {
+      (_, Ty::Union(targets)) => {
+        if targets
+          .iter()
+          .any(|target| self.relate(source, target, seen).is_ok())
+        {
+          return Ok(());
+        }
+        // The reason of the type with the same shape, e.g. of the object type for an object.
+        let similar = targets.iter().find(|target| same_kind(source, target));
+        match similar {
+          Some(similar) => self.relate(source, similar, seen),
+          None => Err(None),
+        }
+      }
+      (_, Ty::Intersection(targets)) => {
+        for target in targets {
+          self.relate(source, target, seen)?;
+        }
+        Ok(())
+      }
+      (Ty::Intersection(sources), _) => {
+        let merged = intersection(sources.clone());
+        if !matches!(merged, Ty::Intersection(_)) {
+          return self.relate(&merged, target, seen);
+        }
+        let any = sources
+          .iter()
+          .any(|source| self.relate(source, target, seen).is_ok());
+        if any {
+          Ok(())
+        } else {
+          Err(None)
+        }
+      }
+      // A value of a generic parameter is a value of its constraint.
+      (Ty::Param(param), _) if self.bounds.contains_key(&param.symbol) => {
+        self.relate(&self.bounds[&param.symbol], target, seen)
+      }
+      (_, Ty::Named(target)) => Err(Some(self.nominal_reason(target))),
+      (Ty::Literal(source), Ty::Literal(target)) => match (source, target) {
+        (Lit::Int { value: a, .. }, Lit::Int { value: b, .. }) if a == b => Ok(()),
+        _ => Err(None),
+      },
+      (Ty::Literal(Lit::Int { value, .. }), Ty::Int(ty)) => {
+        if ty.fits(*value) {
+          Ok(())
+        } else {
+          Err(Some(format!(
+            "`{}` is out of the range of `{}`",
+            value,
+            ty.name()
+          )))
+        }
+      }
+      (Ty::Literal(Lit::Str(_)), Ty::String) => Ok(()),
+      (Ty::Literal(lit), _) => self.relate(&lit.base(), target, seen),
+      (Ty::Object(sources), Ty::Object(targets)) => {
+        for field in targets {
+          let found = sources.iter().find(|source| source.name == field.name);
+          let source = match found {
+            Some(source) => source,
+            None if field.optional => continue,
+            None => return Err(Some(format!("missing field `{}`", field.name))),
+          };
+          if source.optional && !field.optional {
+            return Err(Some(format!("field `{}` is optional", field.name)));
+          }
+          if self.relate(&source.ty, &field.ty, seen).is_err() {
+            return Err(Some(format!(
+              "field `{}` is `{}`, not `{}`",
+              field.name, source.ty, field.ty
+            )));
+          }
+        }
+        Ok(())
+      }
+      (Ty::Tuple(sources), Ty::Tuple(targets)) => {
+        if sources.len() != targets.len() {
+          return Err(Some(format!(
+            "expected {} elements, found {}",
+            targets.len(),
+            sources.len()
+          )));
+        }
+        self.relate_elements(sources.iter().zip(targets), seen)
+      }
+      (Ty::Tuple(sources), Ty::Array(target, len)) => {
+        if len.is_some_and(|len| len != sources.len() as u64) {
+          return Err(Some(format!(
+            "expected {} elements, found {}",
+            len.unwrap(),
+            sources.len()
+          )));
+        }
+        self.relate_elements(sources.iter().map(|source| (source, &**target)), seen)
+      }
+      (Ty::Array(source, len), Ty::Tuple(targets)) => match len {
+        Some(len) if *len == targets.len() as u64 => {
+          self.relate_elements(targets.iter().map(|target| (&**source, target)), seen)
+        }
+        _ => Err(Some(format!("expected {} elements", targets.len()))),
+      },
+      (Ty::Array(source, source_len), Ty::Array(target, target_len)) => {
+        match (source_len, target_len) {
+          (Some(source_len), Some(target_len)) if source_len != target_len => {
+            return Err(Some(format!(
+              "expected {} elements, found {}",
+              target_len, source_len
+            )))
+          }
+          (None, Some(target_len)) => {
+            return Err(Some(format!(
+              "expected {} elements, found any number",
+              target_len
+            )))
+          }
+          _ => (),
+        }
+        self.relate(source, target, seen)
+      }
+      (Ty::Fn(source), Ty::Fn(target)) => {
+        if source.params.len() > target.params.len() {
+          return Err(Some(format!(
+            "expected a function of {} parameters, found {}",
+            target.params.len(),
+            source.params.len()
+          )));
+        }
+        // Parameters are contravariant, a function taking `i32 | None` can be used to take `i32`.
+        for (source, target) in source.params.iter().zip(&target.params) {
+          self.relate(target, source, seen)?;
+        }
+        self.relate(&source.ret, &target.ret, seen)
+      }
+      (Ty::Builtin(source, sources), Ty::Builtin(target, targets)) if source == target => {
+        self.relate_args(sources, targets, seen)
+      }
+      (Ty::Symbol(source), Ty::Symbol(target)) => match (source, target) {
+        (Some(source), Some(target)) => self.relate(source, target, seen),
+        (None, None) => Ok(()),
+        _ => Err(None),
+      },
+      _ => Err(None),
+    }
+  }
+
+  fn relate_elements<'t>(
+    &self,
+    pairs: impl Iterator<Item = (&'t Ty, &'t Ty)>,
+    seen: &mut Vec<(Ty, Ty)>,
+  ) -> Result<(), Reason> {
+    for (i, (source, target)) in pairs.enumerate() {
+      if self.relate(source, target, seen).is_err() {
+        return Err(Some(format!(
+          "element {} is `{}`, not `{}`",
+          i, source, target
+        )));
+      }
+    }
+    Ok(())
+  }
+
+  fn relate_args(
+    &self,
+    sources: &[Ty],
+    targets: &[Ty],
+    seen: &mut Vec<(Ty, Ty)>,
+  ) -> Result<(), Reason> {
+    for (source, target) in sources.iter().zip(targets) {
+      if self.relate(source, target, seen).is_err() {
+        return Err(Some(format!("`{}` is not `{}`", source, target)));
+      }
+    }
+    Ok(())
+  }
+
+  /// Why a value of another type isn't a value of the nominal type `named`, with how to build one.
+  fn nominal_reason(&self, named: &Named) -> String {
+    let build = match self.aliases.get(&named.symbol).map(|alias| &alias.ty) {
+      Some(Ty::Object(_)) => format!("`{} {{ .. }}`", named.name),
+      Some(Ty::Symbol(None)) => format!("`{}`", named.name),
+      _ => format!("`{}(..)`", named.name),
+    };
+    format!(
+      "`{}` is a nominal type, its values are built with {}",
+      named.name, build
+    )
+  }
+
+  pub(crate) fn is_nominal(&self, symbol: SymbolId) -> bool {
+    self.aliases.get(&symbol).is_some_and(|alias| alias.nominal)
+  }
+
+  /// True if `ty` is a nominal type that isn't a union, whose values are only its own.
+  pub(crate) fn is_opaque(&self, ty: &Ty) -> bool {
+    match ty {
+      Ty::Named(named) => match self.aliases.get(&named.symbol) {
+        Some(alias) => alias.nominal && !matches!(alias.ty, Ty::Union(_)),
+        None => false,
+      },
+      _ => false,
+    }
+  }
+
+  /// The structure of a value of type `ty`, e.g. the object type of a nominal object type,
+  /// or the constraint of a generic parameter.
+  pub(crate) fn structure(&self, ty: &Ty) -> Ty {
+    let mut ty = ty.clone();
+    for _ in 0..MAX_EXPANSIONS {
+      ty = match &ty {
+        Ty::Param(param) => match self.bounds.get(&param.symbol) {
+          Some(bound) => bound.clone(),
+          None => break,
+        },
+        _ if is_expandable(&ty) => self.expand(&ty),
+        _ => break,
+      };
+    }
+    ty
+  }
+
+  /// The type an alias, a builtin union or a type-level computation stands for,
+  /// e.g. `Some<T> | None` for `Option<T>`, or `"age" | "name"` for `keyof Vocaloid`.
+  pub(crate) fn expand(&self, ty: &Ty) -> Ty {
+    match ty {
+      _ if is_computation(ty) => self.evaluate(ty).unwrap_or(Ty::Unknown),
+      Ty::Named(named) => match self.aliases.get(&named.symbol) {
+        Some(alias) => {
+          let substitutions = alias
+            .params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| {
+              let arg = named.args.get(i).cloned().unwrap_or(Ty::Unknown);
+              (param.symbol, arg)
+            })
+            .collect();
+          substitute(&alias.ty, &substitutions)
+        }
+        None => Ty::Unknown,
+      },
+      Ty::Builtin(name, args) if name == "Option" => {
+        let arg = args.first().cloned().unwrap_or(Ty::Unknown);
+        Ty::union(vec![Ty::Builtin("Some".to_string(), vec![arg]), none()])
+      }
+      Ty::Builtin(name, args) if name == "Result" => {
+        let ok = args.first().cloned().unwrap_or(Ty::Unknown);
+        let err = args.get(1).cloned().unwrap_or(Ty::Unknown);
+        Ty::union(vec![
+          Ty::Builtin("Ok".to_string(), vec![ok]),
+          Ty::Builtin("Err".to_string(), vec![err]),
+        ])
+      }
+      _ => ty.clone(),
+    }
+  }
+
+  /// The types of the values of `ty`, with aliases and references expanded, e.g. `[Some<i32>, None]` for `Option<i32>`.
+  /// Nominal types other than unions are kept.
+  pub(crate) fn members(&self, ty: &Ty) -> Vec<Ty> {
+    let mut members = Vec::new();
+    self.collect_members(ty, &mut members, 0);
+    members
+  }
+
+  fn collect_members(&self, ty: &Ty, members: &mut Vec<Ty>, depth: usize) {
+    if depth > MAX_EXPANSIONS {
+      return;
+    }
+    match ty {
+      Ty::Union(types) => {
+        for ty in types {
+          self.collect_members(ty, members, depth);
+        }
+      }
+      Ty::Ref(ty, _) => self.collect_members(ty, members, depth),
+      _ if is_expandable(ty) && !self.is_opaque(ty) => {
+        self.collect_members(&self.expand(ty), members, depth + 1)
+      }
+      _ if !members.contains(ty) => members.push(ty.clone()),
+      _ => (),
+    }
+  }
+}
+
+fn is_expandable(ty: &Ty) -> bool {
+  match ty {
+    Ty::Named(_) => true,
+    Ty::Builtin(name, _) => name == "Option" || name == "Result",
+    _ => is_computation(ty) && !is_deferred(ty),
+  }
+}
+
+/// True if the types have the same shape, e.g. two objects.
+fn same_kind(a: &Ty, b: &Ty) -> bool {
+  std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+/// Replaces the generic parameters of `ty` by their types in `substitutions`.
+pub(crate) fn substitute(
+  ty: &Ty,
+  substitutions: &HashMap<justc_binder::symbols::SymbolId, Ty>,
+) -> Ty {
+  let map = |types: &[Ty]| -> Vec<Ty> {
+    types
+      .iter()
+      .map(|ty| substitute(ty, substitutions))
+      .collect()
+  };
+  match ty {
+    Ty::Param(param) => match substitutions.get(&param.symbol) {
+      Some(ty) => ty.clone(),
+      None => ty.clone(),
+    },
+    Ty::Object(fields) => Ty::Object(
+      fields
+        .iter()
+        .map(|field| Field {
+          ty: substitute(&field.ty, substitutions),
+          ..field.clone()
+        })
+        .collect(),
+    ),
+    Ty::Tuple(types) => Ty::Tuple(map(types)),
+    Ty::Array(ty, len) => Ty::Array(Box::new(substitute(ty, substitutions)), *len),
+    Ty::Fn(ty) => Ty::Fn(FnTy {
+      generics: ty.generics.clone(),
+      params: map(&ty.params),
+      ret: Box::new(substitute(&ty.ret, substitutions)),
+    }),
+    Ty::Union(types) => Ty::union(map(types)),
+    Ty::Intersection(types) => intersection(map(types)),
+    Ty::Ref(ty, mutable) => Ty::Ref(Box::new(substitute(ty, substitutions)), *mutable),
+    Ty::Named(named) => Ty::Named(Named {
+      args: map(&named.args),
+      ..named.clone()
+    }),
+    Ty::Builtin(name, args) => Ty::Builtin(name.clone(), map(args)),
+    Ty::Symbol(Some(ty)) => Ty::Symbol(Some(Box::new(substitute(ty, substitutions)))),
+    Ty::Conditional(conditional) => {
+      // The tested parameter stays in the branches, see `Conditional::param`.
+      let mut branches = substitutions.clone();
+      if let Some(param) = &conditional.param {
+        branches.remove(&param.symbol);
+      }
+      Ty::Conditional(Box::new(Conditional {
+        check: substitute(&conditional.check, substitutions),
+        extends: substitute(&conditional.extends, substitutions),
+        then: substitute(&conditional.then, &branches),
+        otherwise: substitute(&conditional.otherwise, &branches),
+        param: conditional.param.clone(),
+      }))
+    }
+    Ty::Keyof(ty) => Ty::Keyof(Box::new(substitute(ty, substitutions))),
+    Ty::Mapped(mapped) => Ty::Mapped(Box::new(Mapped {
+      key: mapped.key.clone(),
+      keys: substitute(&mapped.keys, substitutions),
+      value: substitute(&mapped.value, substitutions),
+      optional: mapped.optional,
+    })),
+    Ty::Index(ty, index) => Ty::Index(
+      Box::new(substitute(ty, substitutions)),
+      Box::new(substitute(index, substitutions)),
+    ),
+    _ => ty.clone(),
+  }
+}
+
+/// Binds the generic parameters in `param` to the parts of `arg` at the same place,
+/// e.g. `T` to `i32` for `Some<T>` and `Some<i32>`. A parameter is bound to its first known type,
+/// or to `{unknown}` when its types aren't known.
+pub(crate) fn bind_params(param: &Ty, arg: &Ty, bindings: &mut HashMap<SymbolId, Ty>) {
+  let pairs = |params: &[Ty], args: &[Ty], bindings: &mut HashMap<SymbolId, Ty>| {
+    for (param, arg) in params.iter().zip(args) {
+      bind_params(param, arg, bindings);
+    }
+  };
+  match (param, arg) {
+    (Ty::Param(param), _) => {
+      let bound = bindings.entry(param.symbol).or_insert(Ty::Unknown);
+      if bound.is_unknown() {
+        *bound = arg.clone();
+      }
+    }
+    (_, Ty::Unknown) => (),
+    (Ty::Object(params), Ty::Object(args)) => {
+      for field in params {
+        if let Some(arg) = args.iter().find(|arg| arg.name == field.name) {
+          bind_params(&field.ty, &arg.ty, bindings);
+        }
+      }
+    }
+    (Ty::Tuple(params), Ty::Tuple(args)) => pairs(params, args, bindings),
+    (Ty::Array(param, _), Ty::Array(arg, _)) | (Ty::Ref(param, _), Ty::Ref(arg, _)) => {
+      bind_params(param, arg, bindings)
+    }
+    (Ty::Array(param, _), Ty::Tuple(args)) => {
+      for arg in args {
+        bind_params(param, arg, bindings);
+      }
+    }
+    (Ty::Fn(param), Ty::Fn(arg)) => {
+      pairs(&param.params, &arg.params, bindings);
+      bind_params(&param.ret, &arg.ret, bindings);
+    }
+    (Ty::Named(param), Ty::Named(arg)) if param.symbol == arg.symbol => {
+      pairs(&param.args, &arg.args, bindings)
+    }
+    (Ty::Builtin(param, params), Ty::Builtin(arg, args)) if param == arg => {
+      pairs(params, args, bindings)
+    }
+    (Ty::Symbol(Some(param)), Ty::Symbol(Some(arg))) => bind_params(param, arg, bindings),
+    _ => (),
+  }
+}
@@ -0,0 +1,395 @@
+//! Functions attached to values: a function whose first parameter is `&T` or `&mut T`
+//! can be called on a value of type `T`, `value.f(x)` being `f(&value, x)`.
+//!
+//! The function is one of the standard core, see `core.just`, or a name in scope, declared in the file or imported
+//! with `use`. A call is ambiguous when several of them can be called on the value, e.g. `isEmpty` of the file
+//! and `isEmpty` of the core on a `str`. A field of the value with the same name is used instead.
+
+use super::assign::{bind_params, substitute};
+use super::check::{Checker, TypeCheck};
+use super::codes;
+use super::ty::{FnTy, Ty};
+use justc_binder::bind;
+use justc_binder::symbols::{SymbolId, SymbolKind, SymbolTable};
+use justc_lexer::diagnostic::Diagnostic;
+use justc_lexer::span::Span;
+use justc_parser::ast::{self, AstNode, HasName};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Where an attachable function comes from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Origin {
+  /// A function of the standard core, in scope in every source file.
+  Core,
+  /// A function declared in the file or imported with `use`.
+  Scope(SymbolId),
+  /// A function of another module which isn't imported yet, e.g. `crate::cursor::advanceToken`.
+  External(String),
+}
+
+/// A function which can be called on a value, see `attachable`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attachable {
+  pub name: String,
+  /// The type of the function, with the parameter of the value.
+  pub ty: Ty,
+  pub origin: Origin,
+}
+
+/// A function of another module, e.g. to complete `value.` with a function which isn't imported yet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalFn {
+  pub name: String,
+  /// The path to import it, e.g. `crate::cursor::advanceToken`.
+  pub path: String,
+  /// Its type as seen from another module, see `TypeCheck::exported_type`.
+  pub ty: Ty,
+}
+
+/// The functions which can be called on the value of the expression at `span` in `file`, whose types are `types`,
+/// the ones in scope and of the core, and the ones in `externals` whose name isn't in scope, ordered by name.
+/// The names declared by `use` declarations have the types in `imports`.
+pub fn attachable(
+  file: &ast::SourceFile,
+  symbols: &SymbolTable,
+  types: &TypeCheck,
+  imports: &BTreeMap<SymbolId, Ty>,
+  span: Span,
+  externals: &[ExternalFn],
+) -> Vec<Attachable> {
+  let receiver = match types.expr_type(span) {
+    Some(ty) => ty,
+    None => return Vec::new(),
+  };
+  // Only the type aliases are needed to relate the types, which are already inferred.
+  let checker = Checker::new(file.syntax().clone(), symbols);
+  let mut functions = Vec::new();
+  let mut visible = HashSet::new();
+  let mut scope = Some(symbols.scope_at(span.start));
+  while let Some(id) = scope {
+    let current = symbols.scope(id);
+    for symbol in current.symbols.iter().rev() {
+      let name = &symbols.symbol(*symbol).name;
+      if !visible.insert(name.clone()) {
+        continue;
+      }
+      let ty = match types.symbol_type(*symbol).or_else(|| imports.get(symbol)) {
+        Some(ty) => ty,
+        None => continue,
+      };
+      if checker.attach(ty, receiver).is_some() {
+        functions.push(Attachable {
+          name: name.clone(),
+          ty: ty.clone(),
+          origin: Origin::Scope(*symbol),
+        });
+      }
+    }
+    scope = current.parent;
+  }
+  for (name, ty) in core_functions() {
+    if checker.attach(ty, receiver).is_some() {
+      functions.push(Attachable {
+        name: name.clone(),
+        ty: ty.clone(),
+        origin: Origin::Core,
+      });
+    }
+  }
+  for external in externals {
+    if !visible.contains(&external.name) && checker.attach(&external.ty, receiver).is_some() {
+      functions.push(Attachable {
+        name: external.name.clone(),
+        ty: external.ty.clone(),
+        origin: Origin::External(external.path.clone()),
+      });
+    }
+  }
+  functions.sort_by(|a, b| a.name.cmp(&b.name));
+  functions
+}
+
+/// The functions of `core.just`, by name.
+fn core_functions() -> &'static [(String, Ty)] {
+  static CORE: OnceLock<Vec<(String, Ty)>> = OnceLock::new();
+  CORE.get_or_init(|| {
+    let tree = justc_parser::parse(include_str!("core.just")).tree();
+    let binding = bind(&tree);
+    let mut checker = Checker::new(tree.syntax().clone(), binding.symbols());
+    let mut functions = Vec::new();
+    for stmt in tree.statements() {
+      let name = match stmt {
+        ast::Stmt::LetDecl(decl) => decl.name(),
+        _ => None,
+      };
+      if let Some(name) = name {
+        if let Some(symbol) = checker.declared(&name) {
+          functions.push((name.text(), checker.declared_type(symbol)));
+        }
+      }
+    }
+    functions
+  })
+}
+
+impl<'a> Checker<'a> {
+  /// `value.name` called as a function: the field `name` of the value, or the function `name` attached to it.
+  pub(crate) fn method(&mut self, field: &ast::FieldExpr) -> Ty {
+    let receiver = match field.receiver() {
+      Some(receiver) => self.infer(&receiver, None),
+      None => return Ty::Unknown,
+    };
+    let name_ref = match field.name_ref() {
+      Some(name_ref) => name_ref,
+      None => return Ty::Unknown,
+    };
+    let (name, span) = (name_ref.text(), name_ref.syntax().text_range());
+    let attached = if self.has_field(&receiver, &name) {
+      None
+    } else {
+      self.attached(&receiver, &name, span)
+    };
+    let ty = attached.unwrap_or_else(|| self.field_type(&receiver, &name, span));
+    self.record(field.syntax().text_range(), &ty);
+    ty
+  }
+
+  /// True if the values of `ty` are objects with the field `name`.
+  fn has_field(&self, ty: &Ty, name: &str) -> bool {
+    self
+      .members(ty)
+      .iter()
+      .any(|member| match self.structure(member) {
+        Ty::Object(fields) => fields.iter().any(|field| field.name == name),
+        _ => false,
+      })
+  }
+
+  /// The function `name` attached to a value of type `receiver`, without its first parameter,
+  /// none when there is no such function, reported at `span` when there are several.
+  fn attached(&mut self, receiver: &Ty, name: &str, span: Span) -> Option<Ty> {
+    let mut candidates = Vec::new();
+    let scope = self.symbols.scope_at(span.start);
+    if let Some(symbol) = self.symbols.lookup(scope, name) {
+      let kind = self.symbols.symbol(symbol).kind;
+      if !matches!(
+        kind,
+        SymbolKind::Type | SymbolKind::Mod | SymbolKind::GenericParam
+      ) {
+        let ty = self.symbol_type(symbol);
+        candidates.push((Origin::Scope(symbol), ty));
+      }
+    }
+    let core = core_functions().iter().filter(|(core, _)| core == name);
+    candidates.extend(core.map(|(_, ty)| (Origin::Core, ty.clone())));
+    let mut matching: Vec<_> = candidates
+      .into_iter()
+      .filter_map(|(origin, ty)| Some((origin, self.attach(&ty, receiver)?, ty)))
+      .collect();
+    match matching.len() {
+      0 => None,
+      1 => matching.pop().map(|(_, bound, _)| Ty::Fn(bound)),
+      // Any function can be called on an unknown value.
+      _ if matches!(receiver, Ty::Unknown | Ty::Any) => Some(Ty::Unknown),
+      _ => {
+        let mut diagnostic = Diagnostic::error(
+          codes::AMBIGUOUS_FUNCTION,
+          format!(
+            "multiple functions `{}` can be called on a value of type `{}`",
+            name, receiver
+          ),
+          span,
+        )
+        .with_label(format!("multiple `{}` found", name));
+        for (origin, _, ty) in &matching {
+          diagnostic = match origin {
+            Origin::Scope(symbol) => {
+              let span = self.symbols.symbol(*symbol).name_span;
+              diagnostic.with_span_note(span, format!("candidate `{}` declared here", ty))
+            }
+            _ => diagnostic.with_note(format!("candidate `{}` of the core", ty)),
+          };
+        }
+        self.diagnostics.push(diagnostic);
+        Some(Ty::Unknown)
+      }
+    }
+  }
+
+  /// The function of type `ty` called on a value of type `receiver`, without its first parameter,
+  /// none unless it is a function whose first parameter is a reference to a type `receiver` is assignable to.
+  pub(crate) fn attach(&self, ty: &Ty, receiver: &Ty) -> Option<FnTy> {
+    let function = match ty {
+      Ty::Fn(function) => function,
+      _ => return None,
+    };
+    let param = match function.params.first() {
+      Some(Ty::Ref(param, _)) => param,
+      _ => return None,
+    };
+    let value = match receiver {
+      Ty::Ref(value, _) => value,
+      value => value,
+    };
+    // The generic parameters of the first parameter are bound to the value, e.g. `T` to `i32` for `&[T]`.
+    let mut bindings = HashMap::new();
+    bind_params(param, value, &mut bindings);
+    let unbound = function
+      .generics
+      .iter()
+      .filter(|generic| !bindings.contains_key(&generic.symbol));
+    let generics: Vec<_> = unbound.cloned().collect();
+    let mut unknowns = bindings.clone();
+    unknowns.extend(generics.iter().map(|generic| (generic.symbol, Ty::Unknown)));
+    self.assignable(value, &substitute(param, &unknowns)).ok()?;
+    Some(FnTy {
+      generics,
+      params: function.params[1..]
+        .iter()
+        .map(|param| substitute(param, &bindings))
+        .collect(),
+      ret: Box::new(substitute(&function.ret, &bindings)),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::check;
+  use expect_test::{expect, Expect};
+
+  fn check_calls(text: &str, expect: Expect) {
+    let tree = justc_parser::parse(text).tree();
+    let binding = bind(&tree);
+    let types = check(&tree, binding.symbols());
+    expect.assert_eq(&types.debug_dump(binding.symbols()));
+  }
+
+  #[test]
+  fn call_attached_functions() {
+    check_calls(
+      r#"
+type Cursor = { input: str, pos: usize }
+let advanceToken = (this: &mut Cursor) => this.pos + 1
+fn peek(this: &Cursor, n: usize) -> str { this.input }
+let mut cursor: Cursor = { input: "miku", pos: 0 }
+let next = cursor.advanceToken()
+let peeked = cursor.peek(1)
+let empty = "miku".isEmpty()
+let count = [1, 2].len()
+let input = cursor.input
+let wrong = cursor.peek("1")
+let missing = cursor.missing()
+"#,
+      expect![[r#"
+          Let advanceToken: (&mut Cursor) -> usize
+          Fn peek: (&Cursor, usize) -> str
+          Mut cursor: Cursor
+          Let next: usize
+          Let peeked: str
+          Let empty: bool
+          Let count: usize
+          Let input: str
+          Let wrong: str
+          Let missing: {unknown}
+          Param this: &mut Cursor
+          Param this: &Cursor
+          Param n: usize
+          367..370: expected `usize`, found `"1"`
+          393..400: no field `missing` on type `Cursor`
+      "#]],
+    );
+  }
+
+  #[test]
+  fn check_core_functions() {
+    let tree = justc_parser::parse(include_str!("core.just")).tree();
+    let binding = bind(&tree);
+    assert_eq!(binding.diagnostics(), &[]);
+    let types = check(&tree, binding.symbols());
+    assert_eq!(types.diagnostics(), &[]);
+    assert_eq!(core_functions().len(), tree.statements().count());
+  }
+
+  #[test]
+  fn report_ambiguous_functions() {
+    check_calls(
+      r#"
+fn isEmpty(value: &str) -> bool { true }
+fn len(values: &[i32]) -> usize { 0 }
+let empty = "miku".isEmpty()
+let count = "miku".len()
+"#,
+      expect![[r#"
+          Fn isEmpty: (&str) -> bool
+          Fn len: (&[i32]) -> usize
+          Let empty: {unknown}
+          Let count: usize
+          Param value: &str
+          Param values: &[i32]
+          99..106: multiple functions `isEmpty` can be called on a value of type `"miku"`
+            note: candidate `(&str) -> bool` declared here
+            note: candidate `(&(str | [any])) -> bool` of the core
+      "#]],
+    );
+  }
+
+  #[test]
+  fn list_attachable_functions() {
+    let text = r#"
+type Cursor = { input: str }
+fn advanceToken(this: &mut Cursor) {}
+fn peek(this: &Cursor) -> str { this.input }
+fn parse(input: str) {}
+let cursor: Cursor = { input: "miku" }
+cursor
+"#;
+    let tree = justc_parser::parse(text).tree();
+    let binding = bind(&tree);
+    let start = text.rfind("cursor").unwrap();
+    let externals = [
+      ExternalFn {
+        name: "reset".to_string(),
+        path: "crate::cursor::reset".to_string(),
+        ty: Ty::Fn(FnTy {
+          generics: Vec::new(),
+          params: vec![Ty::Ref(Box::new(Ty::Unknown), true)],
+          ret: Box::new(Ty::Unit),
+        }),
+      },
+      ExternalFn {
+        name: "trimStart".to_string(),
+        path: "crate::text::trimStart".to_string(),
+        ty: Ty::Fn(FnTy {
+          generics: Vec::new(),
+          params: vec![Ty::Ref(Box::new(Ty::Str), false)],
+          ret: Box::new(Ty::Str),
+        }),
+      },
+    ];
+    let types = check(&tree, binding.symbols());
+    let span = Span::new(start, start + "cursor".len());
+    let functions = attachable(
+      &tree,
+      binding.symbols(),
+      &types,
+      &BTreeMap::new(),
+      span,
+      &externals,
+    );
+    let functions: Vec<_> = functions
+      .iter()
+      .map(|function| format!("{} {} {:?}", function.name, function.ty, function.origin))
+      .collect();
+    expect![[r#"
+        [
+            "advanceToken (&mut Cursor) -> () Scope(SymbolId(1))",
+            "peek (&Cursor) -> str Scope(SymbolId(2))",
+            "reset (&mut {unknown}) -> () External(\"crate::cursor::reset\")",
+        ]
+    "#]]
+    .assert_debug_eq(&functions);
+  }
+}
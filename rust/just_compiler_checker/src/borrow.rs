@@ -0,0 +1,790 @@
+//! Checks moves and borrows over the lowered bodies.
+//!
+//! A borrow, `&x` or `&mut x`, creates a loan of its place, held by the locals its reference flows into.
+//! The loan is in use as long as one of them is used later, so a loan only conflicts with the accesses
+//! made before the last use of its reference: writing, moving or mutably borrowing a borrowed place,
+//! reading a mutably borrowed place, or dropping a borrowed variable at the end of its block.
+
+use super::check::Checker;
+use super::codes;
+use super::ir::{BlockId, Body, LocalId, LocalKind, Operand, Place, Rvalue, Statement, Terminator};
+use justc_lexer::diagnostic::Diagnostic;
+use justc_lexer::span::Span;
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+
+/// `&place` or `&mut place` at `span`.
+struct Loan {
+  place: Place,
+  mutable: bool,
+  span: Span,
+}
+
+/// What is known before or after a statement, on any path to it.
+#[derive(Clone, Default, PartialEq)]
+struct State {
+  /// The loans held by the value of each local.
+  holds: BTreeMap<LocalId, BTreeSet<usize>>,
+  /// The places which may have been moved, with where they were moved.
+  moved: Vec<(Place, Span)>,
+}
+
+impl State {
+  /// Adds what is known in `other`, true if it changed.
+  fn join(&mut self, other: &State) -> bool {
+    let mut changed = false;
+    for (local, loans) in &other.holds {
+      let held = self.holds.entry(*local).or_default();
+      for loan in loans {
+        changed |= held.insert(*loan);
+      }
+    }
+    for moved in &other.moved {
+      if !self.moved.contains(moved) {
+        self.moved.push(moved.clone());
+        changed = true;
+      }
+    }
+    changed
+  }
+
+  fn loans_of(&self, local: LocalId) -> BTreeSet<usize> {
+    self.holds.get(&local).cloned().unwrap_or_default()
+  }
+}
+
+/// How a place is used.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Access {
+  Read,
+  Move,
+  Write,
+  Borrow(bool),
+}
+
+/// Checks the moves and borrows of `body`.
+pub(crate) fn check_body(checker: &Checker, body: &Body) -> Vec<Diagnostic> {
+  let mut loans = Vec::new();
+  let mut loan_at = BTreeMap::new();
+  for (b, block) in body.blocks.iter().enumerate() {
+    for (i, statement) in block.statements.iter().enumerate() {
+      if let Statement::Assign(_, Rvalue::Ref(place, mutable, span), _) = statement {
+        loan_at.insert((b, i), loans.len());
+        loans.push(Loan {
+          place: place.clone(),
+          mutable: *mutable,
+          span: *span,
+        });
+      }
+    }
+  }
+  let mut analysis = Analysis {
+    checker,
+    body,
+    live_out: liveness(body),
+    loans,
+    loan_at,
+    diagnostics: Vec::new(),
+    reported: HashSet::new(),
+  };
+  let entry = analysis.fixpoint();
+  for (b, state) in entry.into_iter().enumerate() {
+    if let Some(mut state) = state {
+      analysis.block(BlockId(b), &mut state, true);
+    }
+  }
+  analysis.diagnostics
+}
+
+struct Analysis<'c, 'a, 'b> {
+  checker: &'c Checker<'a>,
+  body: &'b Body,
+  /// The locals used after each block.
+  live_out: Vec<HashSet<LocalId>>,
+  loans: Vec<Loan>,
+  /// The loan created by each borrow, by block and statement.
+  loan_at: BTreeMap<(usize, usize), usize>,
+  diagnostics: Vec<Diagnostic>,
+  /// The spans of the accesses already reported, once per access.
+  reported: HashSet<Span>,
+}
+
+impl Analysis<'_, '_, '_> {
+  /// The states at the start of the blocks, `None` for the unreachable ones.
+  fn fixpoint(&mut self) -> Vec<Option<State>> {
+    let mut entry = vec![None; self.body.blocks.len()];
+    entry[0] = Some(State::default());
+    let mut worklist = VecDeque::from(vec![BlockId(0)]);
+    while let Some(block) = worklist.pop_front() {
+      let mut state = entry[block.0].clone().unwrap();
+      self.block(block, &mut state, false);
+      for successor in self.body.successors(block) {
+        let changed = match &mut entry[successor.0] {
+          Some(entry) => entry.join(&state),
+          entry => {
+            *entry = Some(state.clone());
+            true
+          }
+        };
+        if changed && !worklist.contains(&successor) {
+          worklist.push_back(successor);
+        }
+      }
+    }
+    entry
+  }
+
+  /// Applies the statements of `block` to `state`, reporting the errors when `report` is true.
+  fn block(&mut self, block: BlockId, state: &mut State, report: bool) {
+    let body = self.body;
+    let live = live_after(body, block, &self.live_out[block.0]);
+    let statements = &body.blocks[block.0].statements;
+    for (i, statement) in statements.iter().enumerate() {
+      let at = (block, i, &live[i]);
+      match statement {
+        Statement::Assign(place, rvalue, span) => {
+          let mut held = BTreeSet::new();
+          match rvalue {
+            Rvalue::Use(operand) => held = self.operand(operand, state, at, report),
+            Rvalue::Aggregate(operands) | Rvalue::Call(operands, _) => {
+              for operand in operands {
+                held.extend(self.operand(operand, state, at, report));
+              }
+              if let Rvalue::Call(_, false) = rvalue {
+                held.clear();
+              }
+            }
+            Rvalue::Ref(borrowed, mutable, span) => {
+              let dest = Some(place.local).filter(|_| place.projection.is_empty());
+              self.access(
+                borrowed,
+                Access::Borrow(*mutable),
+                *span,
+                state,
+                at,
+                dest,
+                report,
+              );
+              // A reborrow through a reference also holds the loans of the reference.
+              if borrowed.is_deref() {
+                held = state.loans_of(borrowed.local);
+              }
+              held.insert(self.loan_at[&(block.0, i)]);
+            }
+          }
+          if !self.may_hold_loans(place.local) {
+            held.clear();
+          }
+          self.assign(place, held, *span, state, at, report);
+        }
+        Statement::Eval(operand) => {
+          self.operand(operand, state, at, report);
+        }
+        Statement::StorageDead(local, span) => {
+          self.storage_dead(*local, *span, state, at, report);
+        }
+      }
+    }
+    let live = self.live_out[block.0].clone();
+    let at = (block, statements.len(), &live);
+    match &body.blocks[block.0].terminator {
+      Terminator::Switch(Some(operand), _) => {
+        self.operand(operand, state, at, report);
+      }
+      Terminator::Return(operand, span) => {
+        let held = self.operand(operand, state, at, report);
+        if report {
+          self.returned(&held, *span);
+        }
+      }
+      Terminator::Switch(None, _) | Terminator::Goto(_) => (),
+    }
+  }
+
+  /// Uses the value of `operand`, returning the loans it holds.
+  fn operand(
+    &mut self,
+    operand: &Operand,
+    state: &mut State,
+    at: At,
+    report: bool,
+  ) -> BTreeSet<usize> {
+    match operand {
+      Operand::Copy(place, span) => {
+        self.access(place, Access::Read, *span, state, at, None, report);
+        state.loans_of(place.local)
+      }
+      Operand::Move(place, span) => {
+        self.access(place, Access::Move, *span, state, at, None, report);
+        if !state.moved.iter().any(|(moved, _)| moved == place) {
+          state.moved.push((place.clone(), *span));
+        }
+        state.loans_of(place.local)
+      }
+      Operand::Const => BTreeSet::new(),
+    }
+  }
+
+  /// Writes a value holding the loans `held` to `place`.
+  fn assign(
+    &mut self,
+    place: &Place,
+    held: BTreeSet<usize>,
+    span: Span,
+    state: &mut State,
+    at: At,
+    report: bool,
+  ) {
+    let dest = Some(place.local).filter(|_| place.projection.is_empty());
+    if !place.is_deref() {
+      self.access(place, Access::Write, span, state, at, dest, report);
+    }
+    // The assigned place holds a value again.
+    state.moved.retain(|(moved, _)| !place.contains(moved));
+    if place.projection.is_empty() {
+      state.holds.insert(place.local, held);
+    } else if !place.is_deref() {
+      state.holds.entry(place.local).or_default().extend(held);
+    }
+  }
+
+  /// Checks an access to `place`, ignoring the loans held by `dest` which is assigned by the access.
+  #[allow(clippy::too_many_arguments)]
+  fn access(
+    &mut self,
+    place: &Place,
+    access: Access,
+    span: Span,
+    state: &mut State,
+    (block, index, live): At,
+    dest: Option<LocalId>,
+    report: bool,
+  ) {
+    if access != Access::Write {
+      let moved = state.moved.iter().find(|(moved, _)| moved.overlaps(place));
+      if let Some((moved, moved_span)) = moved.cloned() {
+        if report && self.reported.insert(span) {
+          self.use_after_move(place, access, span, &moved, moved_span);
+        }
+        state.moved.retain(|(other, _)| *other != moved);
+        return;
+      }
+    }
+    let holders = live.iter().filter(|local| Some(**local) != dest);
+    let in_use: BTreeSet<usize> = holders.flat_map(|local| state.loans_of(*local)).collect();
+    let conflict = in_use.into_iter().find(|loan| {
+      let loan = &self.loans[*loan];
+      let conflicts = match access {
+        Access::Read | Access::Borrow(false) => loan.mutable,
+        Access::Move | Access::Write | Access::Borrow(true) => true,
+      };
+      conflicts && loan.place.overlaps(place) && loan.span != span
+    });
+    if let Some(loan) = conflict {
+      if report && self.reported.insert(span) {
+        let holders = self.holders(loan, state, live);
+        let later = self.later_use(block, index + 1, &holders);
+        self.conflict(place, access, span, loan, later.map(|(span, _)| span));
+      }
+    }
+  }
+
+  /// A local going out of scope, its borrows can't be used anymore.
+  fn storage_dead(
+    &mut self,
+    local: LocalId,
+    span: Span,
+    state: &mut State,
+    (block, index, live): At,
+    report: bool,
+  ) {
+    let holders: BTreeSet<usize> = live
+      .iter()
+      .flat_map(|local| state.loans_of(*local))
+      .collect();
+    let dropped = holders.into_iter().find(|loan| {
+      let place = &self.loans[*loan].place;
+      place.local == local && !place.is_deref()
+    });
+    if let Some(loan) = dropped {
+      let holders = self.holders(loan, state, live);
+      let later = self.later_use(block, index + 1, &holders);
+      let borrow = self.loans[loan].span;
+      // A returned reference is reported with the return.
+      let returned = later.is_some_and(|(_, returned)| returned);
+      if report && !returned && self.reported.insert(borrow) {
+        let name = self.body.place_name(&Place::local(local));
+        let mut diagnostic = Diagnostic::error(
+          codes::DANGLING_REFERENCE,
+          format!("`{}` does not live long enough", name),
+          borrow,
+        )
+        .with_label("borrowed value does not live long enough")
+        .with_span_note(
+          span,
+          format!("`{}` dropped here while still borrowed", name),
+        );
+        if let Some((later, _)) = later {
+          diagnostic = diagnostic.with_span_note(later, "borrow later used here");
+        }
+        self.diagnostics.push(diagnostic);
+      }
+    }
+    state.holds.remove(&local);
+    state.moved.retain(|(moved, _)| moved.local != local);
+  }
+
+  /// A value holding `held` returned at `span`, it can't borrow a value owned by the function.
+  fn returned(&mut self, held: &BTreeSet<usize>, span: Span) {
+    for loan in held {
+      let loan = &self.loans[*loan];
+      let local = self.body.local(loan.place.local);
+      if local.kind == LocalKind::Capture
+        || loan.place.is_deref()
+        || !self.reported.insert(loan.span)
+      {
+        continue;
+      }
+      let name = self.body.place_name(&Place::local(loan.place.local));
+      let (message, note) = match local.kind {
+        LocalKind::Temp => (
+          "cannot return a reference to a temporary value".to_string(),
+          "temporary value created here".to_string(),
+        ),
+        _ => (
+          format!("cannot return a reference to local `{}`", name),
+          format!("`{}` is borrowed here", name),
+        ),
+      };
+      let diagnostic = Diagnostic::error(codes::DANGLING_REFERENCE, message, span)
+        .with_label("returns a reference to data owned by the current function")
+        .with_span_note(loan.span, note);
+      self.diagnostics.push(diagnostic);
+    }
+  }
+
+  fn use_after_move(
+    &mut self,
+    place: &Place,
+    access: Access,
+    span: Span,
+    moved: &Place,
+    moved_span: Span,
+  ) {
+    let name = self.body.place_name(place);
+    let message = match access {
+      Access::Borrow(_) => format!("borrow of moved value `{}`", name),
+      _ if !moved.contains(place) => format!("use of partially moved value `{}`", name),
+      _ => format!("use of moved value `{}`", name),
+    };
+    let ty = &self.body.local(moved.local).ty;
+    let note = if moved_span == span {
+      "value moved here, in the previous iteration of the loop".to_string()
+    } else {
+      "value moved here".to_string()
+    };
+    let mut diagnostic = Diagnostic::error(codes::USE_AFTER_MOVE, message, span)
+      .with_label("value used here after move")
+      .with_span_note(moved_span, note);
+    if moved.projection.is_empty() {
+      diagnostic = diagnostic.with_note(format!(
+        "move occurs because `{}` has type `{}`, which can't be copied",
+        self.body.place_name(moved),
+        ty
+      ));
+    }
+    self.diagnostics.push(diagnostic);
+  }
+
+  fn conflict(
+    &mut self,
+    place: &Place,
+    access: Access,
+    span: Span,
+    loan: usize,
+    later: Option<Span>,
+  ) {
+    let loan = &self.loans[loan];
+    let name = self.body.place_name(place);
+    let borrowed = self.body.place_name(&loan.place);
+    let message = match (access, loan.mutable) {
+      (Access::Borrow(true), true) => format!(
+        "cannot borrow `{}` as mutable more than once at a time",
+        name
+      ),
+      (Access::Borrow(true), false) => {
+        format!(
+          "cannot borrow `{}` as mutable because it is also borrowed as immutable",
+          name
+        )
+      }
+      (Access::Borrow(false), _) => {
+        format!(
+          "cannot borrow `{}` as immutable because it is also borrowed as mutable",
+          name
+        )
+      }
+      (Access::Write, _) => format!("cannot assign to `{}` because it is borrowed", name),
+      (Access::Move, _) => format!("cannot move out of `{}` because it is borrowed", name),
+      (Access::Read, _) => format!("cannot use `{}` because it was mutably borrowed", name),
+    };
+    let mut diagnostic = Diagnostic::error(codes::CONFLICTING_BORROW, message, span)
+      .with_span_note(loan.span, format!("`{}` is borrowed here", borrowed));
+    if let Some(later) = later {
+      diagnostic = diagnostic.with_span_note(later, "borrow later used here");
+    }
+    self.diagnostics.push(diagnostic);
+  }
+
+  /// The live locals holding `loan`.
+  fn holders(&self, loan: usize, state: &State, live: &HashSet<LocalId>) -> Vec<LocalId> {
+    let holders = live
+      .iter()
+      .filter(|local| state.loans_of(**local).contains(&loan));
+    holders.copied().collect()
+  }
+
+  /// The first use of one of `locals` from the statement `index` of `block` on, true if it is returned.
+  fn later_use(&self, block: BlockId, index: usize, locals: &[LocalId]) -> Option<(Span, bool)> {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from(vec![(block, index)]);
+    while let Some((block, index)) = queue.pop_front() {
+      let data = &self.body.blocks[block.0];
+      for statement in data.statements.iter().skip(index) {
+        let used = statement_uses(statement)
+          .into_iter()
+          .find(|(local, _)| locals.contains(local));
+        if let Some((_, span)) = used {
+          return Some((span, false));
+        }
+      }
+      let used = terminator_uses(&data.terminator)
+        .into_iter()
+        .find(|(local, _)| locals.contains(local));
+      if let Some((_, span)) = used {
+        let returned = matches!(data.terminator, Terminator::Return(..));
+        return Some((span, returned));
+      }
+      for successor in self.body.successors(block) {
+        if visited.insert(successor) {
+          queue.push_back((successor, 0));
+        }
+      }
+    }
+    None
+  }
+
+  /// True if the value of `local` may hold a reference.
+  fn may_hold_loans(&self, local: LocalId) -> bool {
+    let ty = &self.body.local(local).ty;
+    ty.is_unknown() || self.checker.contains_ref(ty)
+  }
+}
+
+/// The block, the index of the statement in it, and the locals used after the statement.
+type At<'l> = (BlockId, usize, &'l HashSet<LocalId>);
+
+/// The locals `statement` uses, with where.
+fn statement_uses(statement: &Statement) -> Vec<(LocalId, Span)> {
+  let mut uses = Vec::new();
+  match statement {
+    Statement::Assign(place, rvalue, span) => {
+      match rvalue {
+        Rvalue::Use(operand) => uses.extend(operand_use(operand)),
+        Rvalue::Ref(place, _, span) => uses.push((place.local, *span)),
+        Rvalue::Aggregate(operands) | Rvalue::Call(operands, _) => {
+          uses.extend(operands.iter().filter_map(operand_use));
+        }
+      }
+      if !place.projection.is_empty() {
+        uses.push((place.local, *span));
+      }
+    }
+    Statement::Eval(operand) => uses.extend(operand_use(operand)),
+    Statement::StorageDead(..) => (),
+  }
+  uses
+}
+
+fn terminator_uses(terminator: &Terminator) -> Vec<(LocalId, Span)> {
+  match terminator {
+    Terminator::Switch(Some(operand), _) | Terminator::Return(operand, _) => {
+      operand_use(operand).into_iter().collect()
+    }
+    Terminator::Switch(None, _) | Terminator::Goto(_) => Vec::new(),
+  }
+}
+
+fn operand_use(operand: &Operand) -> Option<(LocalId, Span)> {
+  match operand {
+    Operand::Copy(place, span) | Operand::Move(place, span) => Some((place.local, *span)),
+    Operand::Const => None,
+  }
+}
+
+/// The locals used after each block.
+fn liveness(body: &Body) -> Vec<HashSet<LocalId>> {
+  let mut live_out = vec![HashSet::new(); body.blocks.len()];
+  let mut changed = true;
+  while changed {
+    changed = false;
+    for b in (0..body.blocks.len()).rev() {
+      let mut out = HashSet::new();
+      for successor in body.successors(BlockId(b)) {
+        out.extend(live_in(body, successor, &live_out[successor.0]));
+      }
+      if out != live_out[b] {
+        live_out[b] = out;
+        changed = true;
+      }
+    }
+  }
+  live_out
+}
+
+/// The locals used from the start of `block` on, given the locals used after it.
+fn live_in(body: &Body, block: BlockId, live_out: &HashSet<LocalId>) -> HashSet<LocalId> {
+  let data = &body.blocks[block.0];
+  let mut live = live_out.clone();
+  live.extend(
+    terminator_uses(&data.terminator)
+      .into_iter()
+      .map(|(local, _)| local),
+  );
+  for statement in data.statements.iter().rev() {
+    apply_backward(statement, &mut live);
+  }
+  live
+}
+
+/// The locals used after each statement of `block`, given the locals used after the block.
+fn live_after(body: &Body, block: BlockId, live_out: &HashSet<LocalId>) -> Vec<HashSet<LocalId>> {
+  let data = &body.blocks[block.0];
+  let mut live = live_out.clone();
+  live.extend(
+    terminator_uses(&data.terminator)
+      .into_iter()
+      .map(|(local, _)| local),
+  );
+  let mut after = vec![HashSet::new(); data.statements.len()];
+  for (i, statement) in data.statements.iter().enumerate().rev() {
+    after[i] = live.clone();
+    apply_backward(statement, &mut live);
+  }
+  after
+}
+
+/// Turns the locals used after `statement` into the locals used before it.
+fn apply_backward(statement: &Statement, live: &mut HashSet<LocalId>) {
+  if let Statement::Assign(place, _, _) = statement {
+    if place.projection.is_empty() {
+      live.remove(&place.local);
+    }
+  }
+  live.extend(
+    statement_uses(statement)
+      .into_iter()
+      .map(|(local, _)| local),
+  );
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::check;
+  use expect_test::{expect, Expect};
+  use justc_binder::bind;
+  use justc_parser::parse;
+
+  fn check_borrows(text: &str, expect: Expect) {
+    let tree = parse(text).tree();
+    let binding = bind(&tree);
+    let types = check(&tree, binding.symbols());
+    let mut out = String::new();
+    for diagnostic in types.diagnostics() {
+      out.push_str(&format!("{:?}: {}\n", diagnostic.span, diagnostic.message));
+      for note in &diagnostic.notes {
+        let span = note
+          .span
+          .map_or(String::new(), |span| format!("{:?}: ", span));
+        out.push_str(&format!("  note: {}{}\n", span, note.message));
+      }
+    }
+    expect.assert_eq(&out);
+  }
+
+  #[test]
+  fn use_after_move() {
+    check_borrows(
+      r#"
+type Miku = { name: String }
+fn take(miku: Miku) {}
+fn twice(miku: Miku) {
+  take(miku);
+  take(miku)
+}
+fn copied(x: i32) -> i32 {
+  let y = x;
+  x + y
+}
+fn reassigned(s: String) -> String {
+  let mut s = s;
+  let t = s;
+  s = t;
+  s
+}
+fn in_loop(s: String) {
+  loop { let t = s; }
+}
+"#,
+      expect![[r#"
+          97..101: use of moved value `miku`
+            note: 83..87: value moved here
+            note: move occurs because `miku` has type `Miku`, which can't be copied
+          278..279: use of moved value `s`
+            note: 278..279: value moved here, in the previous iteration of the loop
+            note: move occurs because `s` has type `String`, which can't be copied
+      "#]],
+    );
+  }
+
+  #[test]
+  fn conflicting_borrows() {
+    check_borrows(
+      r#"
+fn read(r: &i32) {}
+fn assign() {
+  let mut x = 1;
+  let r = &x;
+  x = 2;
+  read(r)
+}
+fn shared_then_mut() {
+  let mut x = 1;
+  let r = &x;
+  let m = &mut x;
+  read(r)
+}
+fn mut_then_shared() {
+  let mut x = 1;
+  let m = &mut x;
+  read(&x);
+  *m = 3
+}
+fn twice_mut() {
+  let mut x = 1;
+  let m = &mut x;
+  let n = &mut x;
+  *m = 2
+}
+fn ok() {
+  let mut x = 1;
+  let r = &mut x;
+  *r = 2;
+  let s = &x;
+  x
+}
+"#,
+      expect![[r#"
+          68..73: cannot assign to `x` because it is borrowed
+            note: 62..64: `x` is borrowed here
+            note: 82..83: borrow later used here
+          151..157: cannot borrow `x` as mutable because it is also borrowed as immutable
+            note: 137..139: `x` is borrowed here
+            note: 166..167: borrow later used here
+          236..238: cannot borrow `x` as immutable because it is also borrowed as mutable
+            note: 221..227: `x` is borrowed here
+            note: 243..249: borrow later used here
+          314..320: cannot borrow `x` as mutable more than once at a time
+            note: 296..302: `x` is borrowed here
+            note: 324..330: borrow later used here
+      "#]],
+    );
+  }
+
+  #[test]
+  fn dangling_references() {
+    check_borrows(
+      r#"
+fn scope() -> i32 {
+  let mut r: &i32 = &0;
+  {
+    let x = 1;
+    r = &x;
+  }
+  *r
+}
+fn local(x: i32) -> &i32 {
+  &x
+}
+fn temporary() -> &i32 {
+  &1
+}
+fn param(p: &i32) -> &i32 {
+  p
+}
+"#,
+      expect![[r#"
+          72..74: `x` does not live long enough
+            note: 78..79: `x` dropped here while still borrowed
+            note: 82..84: borrow later used here
+          116..118: cannot return a reference to local `x`
+            note: 116..118: `x` is borrowed here
+          148..150: cannot return a reference to a temporary value
+            note: 148..150: temporary value created here
+      "#]],
+    );
+  }
+
+  #[test]
+  fn closure_captures() {
+    check_borrows(
+      r#"
+fn by_ref() {
+  let mut count = 0;
+  let inc = () => { count += 1 };
+  let read = &count;
+  inc()
+}
+fn by_move(s: String) {
+  let f = move () => s;
+  s
+}
+"#,
+      expect![[r#"
+          83..89: cannot borrow `count` as immutable because it is also borrowed as mutable
+            note: 56..61: `count` is borrowed here
+            note: 93..96: borrow later used here
+          151..152: use of moved value `s`
+            note: 146..147: value moved here
+            note: move occurs because `s` has type `String`, which can't be copied
+      "#]],
+    );
+  }
+
+  #[test]
+  fn closure_bodies() {
+    check_borrows(
+      r#"
+fn outer() {
+  let take = (s: String) => {
+    let t = s;
+    s
+  };
+  let inc = (count: i32) => {
+    let mut n = count;
+    let r = &mut n;
+    let read = &n;
+    *r += 1
+  };
+}
+"#,
+      expect![[r#"
+          63..64: use of moved value `s`
+            note: 56..57: value moved here
+            note: move occurs because `s` has type `String`, which can't be copied
+          158..160: cannot borrow `n` as immutable because it is also borrowed as mutable
+            note: 135..141: `n` is borrowed here
+            note: 166..173: borrow later used here
+      "#]],
+    );
+  }
+}
@@ -0,0 +1,1088 @@
+//! Checks the types of a source file.
+//!
+//! The type of a declaration is inferred when it is first used, so declarations can be used before they are checked,
+//! e.g. a function declared after its caller. A function whose return type is needed while its body is checked,
+//! i.e. a recursive function, needs a return type annotation.
+
+use super::eval::too_deep;
+use super::ty::{Field, FnTy, Param, Ty};
+use super::{borrow, codes, ir};
+use justc_binder::symbols::{Resolution, SymbolId, SymbolKind, SymbolTable};
+use justc_lexer::diagnostic::Diagnostic;
+use justc_lexer::span::Span;
+use justc_parser::ast::{self, AstNode, HasGenericParams, HasName, HasTypeAnnotation};
+use justc_parser::SyntaxNode;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Result of checking a source file: the types of its expressions and declarations, and the type errors.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TypeCheck {
+  /// Ordered by position.
+  types: Vec<(Span, Ty)>,
+  /// The types of the names of the declarations, ordered by position.
+  declarations: Vec<(Span, Ty)>,
+  symbols: BTreeMap<SymbolId, Ty>,
+  diagnostics: Vec<Diagnostic>,
+}
+
+impl TypeCheck {
+  /// The type of the expression at `span`.
+  pub fn expr_type(&self, span: Span) -> Option<&Ty> {
+    let i = self.types.partition_point(|(expr, _)| *expr < span);
+    match self.types.get(i) {
+      Some((expr, ty)) if *expr == span => Some(ty),
+      _ => None,
+    }
+  }
+
+  /// The type of the innermost expression containing `offset`, or the declared type of the name at `offset`,
+  /// e.g. for hover.
+  pub fn type_at(&self, offset: usize) -> Option<&Ty> {
+    let containing = self
+      .types
+      .iter()
+      .chain(&self.declarations)
+      .filter(|(span, _)| span.contains(offset));
+    let innermost = containing.min_by_key(|(span, _)| span.end - span.start);
+    innermost.map(|(_, ty)| ty)
+  }
+
+  /// The type of a value declared in the source file, e.g. a `let` or a parameter.
+  pub fn symbol_type(&self, symbol: SymbolId) -> Option<&Ty> {
+    self.symbols.get(&symbol)
+  }
+
+  /// The type of `symbol` as seen from another file, which imports it with `use`.
+  /// The types declared in this file are unknown there.
+  pub fn exported_type(&self, symbol: SymbolId) -> Option<Ty> {
+    self.symbols.get(&symbol).map(exported)
+  }
+
+  /// Type errors, ordered by position.
+  pub fn diagnostics(&self) -> &[Diagnostic] {
+    &self.diagnostics
+  }
+
+  /// The types of the values declared in `table`, followed by the diagnostics, for tests and debugging.
+  pub fn debug_dump(&self, table: &SymbolTable) -> String {
+    let mut out = String::new();
+    for (id, symbol) in table.symbols() {
+      if let Some(ty) = self.symbols.get(&id) {
+        out.push_str(&format!("{:?} {}: {}\n", symbol.kind, symbol.name, ty));
+      }
+    }
+    for diagnostic in &self.diagnostics {
+      out.push_str(&format!("{:?}: {}\n", diagnostic.span, diagnostic.message));
+      for note in &diagnostic.notes {
+        out.push_str(&format!("  note: {}\n", note.message));
+      }
+    }
+    out
+  }
+}
+
+/// Checks `file`, whose symbols are `symbols`.
+pub fn check(file: &ast::SourceFile, symbols: &SymbolTable) -> TypeCheck {
+  check_with_imports(file, symbols, &BTreeMap::new())
+}
+
+/// Checks `file`, where the names declared by its `use` declarations have the types in `imports`,
+/// e.g. the types of the functions of other modules, see `TypeCheck::exported_type`.
+pub fn check_with_imports(
+  file: &ast::SourceFile,
+  symbols: &SymbolTable,
+  imports: &BTreeMap<SymbolId, Ty>,
+) -> TypeCheck {
+  let mut checker = Checker::new(file.syntax().clone(), symbols);
+  checker.imports = imports.clone();
+  for stmt in file.statements() {
+    checker.stmt(&stmt);
+  }
+  checker.types.sort_by_key(|(span, _)| *span);
+  let bodies = ir::lower_file(&checker, file);
+  let borrows: Vec<_> = bodies
+    .iter()
+    .flat_map(|body| borrow::check_body(&checker, body))
+    .collect();
+  checker.diagnostics.extend(borrows);
+  checker
+    .diagnostics
+    .sort_by_key(|diagnostic| diagnostic.span.start);
+  let mut declarations: Vec<_> = checker
+    .symbol_types
+    .iter()
+    .map(|(symbol, ty)| (symbols.symbol(*symbol).name_span, ty.clone()))
+    .collect();
+  declarations.sort_by_key(|(span, _)| *span);
+  TypeCheck {
+    types: checker.types,
+    declarations,
+    symbols: checker.symbol_types,
+    diagnostics: checker.diagnostics,
+  }
+}
+
+/// A type alias, with the generic parameters to substitute in its type.
+pub(crate) struct Alias {
+  pub(crate) params: Vec<Param>,
+  pub(crate) ty: Ty,
+  /// `nominal type`, whose values are only the values built as this type.
+  pub(crate) nominal: bool,
+}
+
+/// The function whose body is checked.
+pub(crate) struct Frame {
+  /// The annotated return type.
+  pub(crate) ret: Option<Ty>,
+  /// The types of the `return` expressions.
+  pub(crate) returns: Vec<Ty>,
+}
+
+pub(crate) struct Checker<'a> {
+  pub(crate) root: SyntaxNode,
+  pub(crate) symbols: &'a SymbolTable,
+  pub(crate) aliases: HashMap<SymbolId, Alias>,
+  /// The constraints of generic parameters, e.g. `{ name: str }` for `T: { name: str }`.
+  pub(crate) bounds: HashMap<SymbolId, Ty>,
+  pub(crate) symbol_types: BTreeMap<SymbolId, Ty>,
+  /// The types of the names declared by `use` declarations.
+  pub(crate) imports: BTreeMap<SymbolId, Ty>,
+  /// Declarations whose type is being inferred.
+  pub(crate) in_progress: HashSet<SymbolId>,
+  /// Function declarations whose body is checked.
+  pub(crate) checked: HashSet<SymbolId>,
+  /// The type of the functions whose return type is being inferred, returning `{unknown}`.
+  pub(crate) pending: HashMap<SymbolId, Ty>,
+  /// Narrower types of symbols in the current branch, the last one of a symbol applies.
+  pub(crate) narrowed: Vec<(SymbolId, Ty)>,
+  pub(crate) frames: Vec<Frame>,
+  /// How many type-level computations are being evaluated, see `eval`.
+  pub(crate) evaluating: Cell<usize>,
+  pub(crate) types: Vec<(Span, Ty)>,
+  pub(crate) diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Checker<'a> {
+  pub(crate) fn new(root: SyntaxNode, symbols: &'a SymbolTable) -> Self {
+    let mut checker = Checker {
+      root,
+      symbols,
+      aliases: HashMap::new(),
+      bounds: HashMap::new(),
+      symbol_types: BTreeMap::new(),
+      imports: BTreeMap::new(),
+      in_progress: HashSet::new(),
+      checked: HashSet::new(),
+      pending: HashMap::new(),
+      narrowed: Vec::new(),
+      frames: Vec::new(),
+      evaluating: Cell::new(0),
+      types: Vec::new(),
+      diagnostics: Vec::new(),
+    };
+    checker.lower_aliases();
+    checker
+  }
+
+  /// Lowers all type aliases first, they refer to each other by name so the order doesn't matter.
+  /// The aliases without generic parameters are then evaluated, e.g. `type Keys = keyof Vocaloid`,
+  /// and stand for `{unknown}` when their evaluation doesn't end.
+  fn lower_aliases(&mut self) {
+    let aliases: Vec<_> = self
+      .symbols
+      .symbols()
+      .filter(|(_, symbol)| symbol.kind == SymbolKind::Type)
+      .filter_map(|(id, symbol)| Some((id, symbol.decl(&self.root)?)))
+      .filter_map(|(id, decl)| Some((id, ast::TypeAlias::cast(decl)?)))
+      .collect();
+    let mut lowered = Vec::new();
+    for (id, alias) in &aliases {
+      let params = self.generic_params(alias.generic_param_list());
+      let ty = match alias.ty() {
+        Some(ty) => self.lower(&ty),
+        None => Ty::Unknown,
+      };
+      let nominal = alias.is_nominal();
+      lowered.push((
+        *id,
+        Alias {
+          params,
+          ty,
+          nominal,
+        },
+      ));
+    }
+    self.aliases.extend(lowered);
+    for (id, alias) in aliases {
+      let ty = match (&self.aliases[&id], alias.ty()) {
+        (lowered, Some(ty)) if lowered.params.is_empty() => ty,
+        _ => continue,
+      };
+      if self.normalize(&self.aliases[&id].ty).is_err() {
+        let lowered = self.aliases.get_mut(&id).unwrap();
+        let diagnostic = too_deep(&lowered.ty, ty.syntax().text_range());
+        lowered.ty = Ty::Unknown;
+        self.diagnostics.push(diagnostic);
+      }
+    }
+  }
+
+  /// The generic parameters of `list`, whose constraints are kept in `bounds`.
+  pub(crate) fn generic_params(&mut self, list: Option<ast::GenericParamList>) -> Vec<Param> {
+    let params = list.into_iter().flat_map(|list| list.generic_params());
+    let mut generics = Vec::new();
+    for param in params {
+      let name = match param.name() {
+        Some(name) => name,
+        None => continue,
+      };
+      let symbol = match self.declared(&name) {
+        Some(symbol) => symbol,
+        None => continue,
+      };
+      if let Some(bound) = param.bound() {
+        let bound = self.lower(&bound);
+        self.bounds.insert(symbol, bound);
+      }
+      generics.push(Param {
+        name: name.text(),
+        symbol,
+      });
+    }
+    generics
+  }
+
+  /// The symbol declared by `name`.
+  pub(crate) fn declared(&self, name: &ast::Name) -> Option<SymbolId> {
+    self.symbols.symbol_at(name.syntax().text_range())
+  }
+
+  /// The symbol `name_ref` refers to, `Err(true)` for a builtin, `Err(false)` when unresolved.
+  pub(crate) fn resolve_name(&self, name_ref: &ast::NameRef) -> Result<SymbolId, bool> {
+    let reference = self.symbols.reference_at(name_ref.syntax().text_range());
+    match reference.map(|reference| reference.resolution) {
+      Some(Resolution::Symbol(symbol)) => Ok(symbol),
+      Some(Resolution::Builtin) => Err(true),
+      _ => Err(false),
+    }
+  }
+
+  /// The type of the value of `symbol` at this point, narrowed by the enclosing branches.
+  pub(crate) fn symbol_type(&mut self, symbol: SymbolId) -> Ty {
+    let narrowed = self.narrowed.iter().rev().find(|(id, _)| *id == symbol);
+    match narrowed {
+      Some((_, ty)) => ty.clone(),
+      None => self.declared_type(symbol),
+    }
+  }
+
+  /// The type of the value of `symbol` as declared, inferred from its declaration the first time.
+  pub(crate) fn declared_type(&mut self, symbol: SymbolId) -> Ty {
+    if let Some(ty) = self.symbol_types.get(&symbol) {
+      return ty.clone();
+    }
+    let info = self.symbols.symbol(symbol);
+    if info.kind == SymbolKind::Use {
+      return self.imports.get(&symbol).cloned().unwrap_or(Ty::Unknown);
+    }
+    // Parameters and bindings are typed with their function, loop or match arm.
+    if !matches!(
+      info.kind,
+      SymbolKind::Let | SymbolKind::Mut | SymbolKind::Fn
+    ) {
+      return Ty::Unknown;
+    }
+    let decl = match info.decl(&self.root) {
+      Some(decl) => decl,
+      None => return Ty::Unknown,
+    };
+    if !self.in_progress.insert(symbol) {
+      // The type of a function is needed to infer its return type.
+      return self.pending.get(&symbol).cloned().unwrap_or(Ty::Unknown);
+    }
+    // The declaration is checked on its own, not in the branch that uses it.
+    let narrowed = std::mem::take(&mut self.narrowed);
+    let frames = std::mem::take(&mut self.frames);
+    let ty = match info.kind {
+      SymbolKind::Let | SymbolKind::Mut => match ast::LetDecl::cast(decl) {
+        Some(decl) => self.let_decl(&decl),
+        None => Ty::Unknown,
+      },
+      _ => match ast::FnDecl::cast(decl) {
+        Some(decl) => self.fn_decl(symbol, &decl),
+        None => Ty::Unknown,
+      },
+    };
+    self.narrowed = narrowed;
+    self.frames = frames;
+    self.in_progress.remove(&symbol);
+    self.symbol_types.insert(symbol, ty.clone());
+    ty
+  }
+
+  pub(crate) fn stmt(&mut self, stmt: &ast::Stmt) -> Ty {
+    match stmt {
+      ast::Stmt::LetDecl(decl) => {
+        match decl.name().and_then(|name| self.declared(&name)) {
+          // A `let` not used before is checked where it is, with the narrowings of the enclosing branches.
+          Some(symbol)
+            if !self.symbol_types.contains_key(&symbol) && self.in_progress.insert(symbol) =>
+          {
+            let ty = self.let_decl(decl);
+            self.in_progress.remove(&symbol);
+            self.symbol_types.insert(symbol, ty);
+          }
+          Some(symbol) => {
+            self.declared_type(symbol);
+          }
+          None => {
+            self.let_decl(decl);
+          }
+        }
+        Ty::Unit
+      }
+      ast::Stmt::FnDecl(decl) => {
+        if let Some(symbol) = decl.name().and_then(|name| self.declared(&name)) {
+          let ty = self.declared_type(symbol);
+          if let (Ty::Fn(ty), Some(body)) = (ty, decl.body()) {
+            if self.checked.insert(symbol) {
+              self.fn_body(&ast::Expr::Block(body), Some(*ty.ret));
+            }
+          }
+        }
+        Ty::Unit
+      }
+      ast::Stmt::ExprStmt(stmt) => match stmt.expr() {
+        Some(expr) => self.infer(&expr, None),
+        None => Ty::Unit,
+      },
+      ast::Stmt::TypeAlias(_) | ast::Stmt::UseDecl(_) | ast::Stmt::ModDecl(_) => Ty::Unit,
+    }
+  }
+
+  /// The type of the value of a `let`: the annotation, or the type of the initializer,
+  /// widened for a mutable value, e.g. `let a = 1` is `1` and `let mut b = 1` is `i32`.
+  fn let_decl(&mut self, decl: &ast::LetDecl) -> Ty {
+    let annotation = decl.ty().map(|ty| self.lower(&ty));
+    match (annotation, decl.initializer()) {
+      (Some(ty), Some(init)) => {
+        self.check_expr(&init, &ty);
+        ty
+      }
+      (Some(ty), None) => ty,
+      (None, Some(init)) if decl.is_mut() => self.infer(&init, None).widen(),
+      (None, Some(init)) => self.infer(&init, None),
+      (None, None) => Ty::Unknown,
+    }
+  }
+
+  /// The type of a function declaration, checking its body when the return type is inferred.
+  fn fn_decl(&mut self, symbol: SymbolId, decl: &ast::FnDecl) -> Ty {
+    let generics = self.generic_params(decl.generic_param_list());
+    let params = self.params(decl.param_list(), &[]);
+    let signature = |ret| {
+      Ty::Fn(FnTy {
+        generics: generics.clone(),
+        params: params.clone(),
+        ret: Box::new(ret),
+      })
+    };
+    if let Some(ret) = decl.ret_type() {
+      return signature(ret.ty().map_or(Ty::Unknown, |ty| self.lower(&ty)));
+    }
+    self.pending.insert(symbol, signature(Ty::Unknown));
+    self.checked.insert(symbol);
+    let ret = match decl.body() {
+      Some(body) => self.fn_body(&ast::Expr::Block(body), None),
+      None => Ty::Unknown,
+    };
+    self.pending.remove(&symbol);
+    signature(ret)
+  }
+
+  /// Types the parameters of `list`, and the names they bind.
+  /// The names of the context parameter are typed too, but it isn't a parameter of the function type.
+  pub(crate) fn params(&mut self, list: Option<ast::ParamList>, expected: &[Ty]) -> Vec<Ty> {
+    if let Some(context) = list.as_ref().and_then(|list| list.context_param()) {
+      self.param(&context, None);
+    }
+    let params = list.into_iter().flat_map(|list| list.params());
+    let mut types = Vec::new();
+    for (i, param) in params.enumerate() {
+      types.push(self.param(&param, expected.get(i)));
+    }
+    types
+  }
+
+  /// Types `param`, and the names it binds.
+  /// A parameter without annotation has the type of its default value, widened, or the type `expected`.
+  fn param(&mut self, param: &ast::Param, expected: Option<&Ty>) -> Ty {
+    let annotation = param.ty().map(|ty| self.lower(&ty));
+    let ty = match (annotation, param.default_value()) {
+      (Some(ty), Some(default)) => {
+        self.check_expr(&default, &ty);
+        ty
+      }
+      (Some(ty), None) => ty,
+      (None, Some(default)) => self.infer(&default, None).widen(),
+      (None, None) => expected.cloned().unwrap_or(Ty::Unknown),
+    };
+    if let Some(pat) = param.pat() {
+      self.bind_pat(&pat, &ty);
+    }
+    ty
+  }
+
+  /// Types the names bound by `pat` to a value of type `ty`.
+  pub(crate) fn bind_pat(&mut self, pat: &ast::Pat, ty: &Ty) {
+    match pat {
+      ast::Pat::Name(name) => {
+        if let Some(symbol) = self.declared(name) {
+          self.symbol_types.insert(symbol, ty.clone());
+        }
+      }
+      ast::Pat::ObjectPat(pat) => {
+        for field in pat.fields() {
+          let field_ty = match field.field_name() {
+            Some(name) => self.field_type(ty, &name, field.syntax().text_range()),
+            None => Ty::Unknown,
+          };
+          if let Some(pat) = field.pat() {
+            self.bind_pat(&pat, &field_ty);
+          }
+        }
+      }
+    }
+  }
+
+  /// Checks the body of a function, against `ret` when annotated, and returns the return type.
+  /// An inferred return type is widened, e.g. `() => 1` returns `i32`.
+  pub(crate) fn fn_body(&mut self, body: &ast::Expr, ret: Option<Ty>) -> Ty {
+    self.frames.push(Frame {
+      ret: ret.clone(),
+      returns: Vec::new(),
+    });
+    let narrowed = std::mem::take(&mut self.narrowed);
+    let body_ty = match &ret {
+      Some(Ty::Unit) => {
+        self.infer(body, None);
+        Ty::Unit
+      }
+      Some(ret) => self.check_expr(body, ret),
+      None => self.infer(body, None),
+    };
+    self.narrowed = narrowed;
+    let frame = self.frames.pop().unwrap();
+    match ret {
+      Some(ret) => ret,
+      None => Ty::union(frame.returns.into_iter().chain(Some(body_ty))).widen(),
+    }
+  }
+
+  /// Infers the type of `expr` and reports it unless it is assignable to `expected`.
+  pub(crate) fn check_expr(&mut self, expr: &ast::Expr, expected: &Ty) -> Ty {
+    let ty = self.infer(expr, Some(expected));
+    self.expect_assignable(&ty, expected, tail_span(expr));
+    ty
+  }
+
+  /// Checks a value whose type is part of the type of the enclosing expression, e.g. a branch of an `if`,
+  /// against `expected`, and returns its type, or `expected` when reported, so it's reported once.
+  pub(crate) fn check_part(&mut self, expr: &ast::Expr, expected: Option<&Ty>) -> Ty {
+    let expected = match expected {
+      Some(expected) => expected,
+      None => return self.infer(expr, None),
+    };
+    let ty = self.check_expr(expr, expected);
+    if self.assignable(&ty, expected).is_ok() {
+      ty
+    } else {
+      expected.clone()
+    }
+  }
+
+  /// Reports a value of type `ty` at `span` unless it is assignable to `expected`.
+  pub(crate) fn expect_assignable(&mut self, ty: &Ty, expected: &Ty, span: Span) {
+    if let Err(reason) = self.assignable(ty, expected) {
+      let (ty, expected) = (self.shown(ty), self.shown(expected));
+      let mut diagnostic = Diagnostic::error(
+        codes::MISMATCHED_TYPES,
+        format!("expected `{}`, found `{}`", expected, ty),
+        span,
+      )
+      .with_label(format!("expected `{}`", expected));
+      if let Some(reason) = reason {
+        diagnostic = diagnostic.with_note(reason);
+      }
+      self.diagnostics.push(diagnostic);
+    }
+  }
+
+  /// The type of the field `name` of a value of type `ty`, reported at `span` when the type doesn't have it.
+  /// Only object types are known to not have a field.
+  pub(crate) fn field_type(&mut self, ty: &Ty, name: &str, span: Span) -> Ty {
+    let members = self.members(ty);
+    let mut types = Vec::new();
+    for member in &members {
+      match &self.structure(member) {
+        Ty::Object(fields) => match fields.iter().find(|field| field.name == name) {
+          Some(field) => {
+            // The fields of a mapped type are computed, e.g. `Vocaloid["age"]` in `Partial<Vocaloid>`.
+            let ty = self.evaluate(&field.ty).unwrap_or(Ty::Unknown);
+            if field.optional {
+              types.push(Ty::union(vec![ty, none()]));
+            } else {
+              types.push(ty);
+            }
+          }
+          None => {
+            self.diagnostics.push(
+              Diagnostic::error(
+                codes::UNKNOWN_FIELD,
+                format!("no field `{}` on type `{}`", name, ty),
+                span,
+              )
+              .with_label("unknown field"),
+            );
+            return Ty::Unknown;
+          }
+        },
+        _ => return Ty::Unknown,
+      }
+    }
+    Ty::union(types)
+  }
+
+  pub(crate) fn record(&mut self, span: Span, ty: &Ty) {
+    self.types.push((span, ty.clone()));
+  }
+}
+
+/// `ty` with the type aliases it refers to replaced by `{unknown}`.
+fn exported(ty: &Ty) -> Ty {
+  let map = |types: &[Ty]| types.iter().map(exported).collect::<Vec<_>>();
+  match ty {
+    Ty::Named(_) => Ty::Unknown,
+    Ty::Object(fields) => Ty::Object(
+      fields
+        .iter()
+        .map(|field| Field {
+          ty: exported(&field.ty),
+          ..field.clone()
+        })
+        .collect(),
+    ),
+    Ty::Tuple(types) => Ty::Tuple(map(types)),
+    Ty::Array(ty, len) => Ty::Array(Box::new(exported(ty)), *len),
+    Ty::Fn(ty) => Ty::Fn(FnTy {
+      generics: ty.generics.clone(),
+      params: map(&ty.params),
+      ret: Box::new(exported(&ty.ret)),
+    }),
+    Ty::Union(types) => Ty::union(map(types)),
+    Ty::Intersection(types) => Ty::Intersection(map(types)),
+    Ty::Ref(ty, mutable) => Ty::Ref(Box::new(exported(ty)), *mutable),
+    Ty::Builtin(name, args) => Ty::Builtin(name.clone(), map(args)),
+    Ty::Symbol(Some(ty)) => Ty::Symbol(Some(Box::new(exported(ty)))),
+    Ty::Conditional(_) | Ty::Keyof(_) | Ty::Mapped(_) | Ty::Index(..) => Ty::Unknown,
+    _ => ty.clone(),
+  }
+}
+
+/// The value `None`.
+pub(crate) fn none() -> Ty {
+  Ty::Builtin("None".to_string(), Vec::new())
+}
+
+/// The span where a value of `expr` is reported, the last expression of a block.
+pub(crate) fn tail_span(expr: &ast::Expr) -> Span {
+  match expr {
+    ast::Expr::Block(block) => match tail(block) {
+      Some(tail) => tail_span(&tail),
+      None => block.syntax().text_range(),
+    },
+    _ => expr.syntax().text_range(),
+  }
+}
+
+/// The expression whose value is the value of `block`, the last statement when it has no `;`.
+pub(crate) fn tail(block: &ast::Block) -> Option<ast::Expr> {
+  match block.statements().last()? {
+    ast::Stmt::ExprStmt(stmt) if stmt.semicolon_token().is_none() => stmt.expr(),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expect_test::{expect, Expect};
+
+  fn check(text: &str, expect: Expect) {
+    let parse = justc_parser::parse(text);
+    assert_eq!(parse.errors(), &[], "{}", text);
+    let tree = parse.tree();
+    let binding = justc_binder::bind(&tree);
+    let types = super::check(&tree, binding.symbols());
+    expect.assert_eq(&types.debug_dump(binding.symbols()));
+  }
+
+  #[test]
+  fn infer_let_bindings() {
+    check(
+      r#"
+let a = 1
+let mut b = 1
+let c = 0xff
+let d: u8 = 200
+let e = 'a'
+let f = "foo"
+let g = a + 2
+let h = [1, 2, 3]
+let i = [1, 'a', "b"]
+let j = { name: "Miku", age: 16 }
+let k = `hello ${f}`
+let l = -1.5
+let m = 57u64
+let n = a == 1
+"#,
+      expect![[r#"
+          Let a: 1
+          Mut b: i32
+          Let c: 255
+          Let d: u8
+          Let e: 'a'
+          Let f: "foo"
+          Let g: i32
+          Let h: [i32; 3]
+          Let i: [i32, char, str]
+          Let j: { age: i32, name: str }
+          Let k: String
+          Let l: f64
+          Let m: u64
+          Let n: bool
+      "#]],
+    );
+  }
+
+  #[test]
+  fn infer_return_types() {
+    check(
+      r#"
+fn double(x: i32) { x * 2 }
+let inc = (v: i32) -> i32 => v + 1
+let greet = (name: str) => `hello ${name}`
+fn log(message: str) { print(message); }
+fn first(xs: [i32]) {
+  if xs == [] { return 0 }
+  xs[0]
+}
+let later = () => after(1)
+fn after(x: u8) { x }
+let n: u8 = double(2)
+"#,
+      expect![[r#"
+          Fn double: (i32) -> i32
+          Let inc: (i32) -> i32
+          Let greet: (str) -> String
+          Fn log: (str) -> ()
+          Fn first: ([i32]) -> i32
+          Let later: () -> u8
+          Fn after: (u8) -> u8
+          Let n: u8
+          Param x: i32
+          Param v: i32
+          Param name: str
+          Param message: str
+          Param xs: [i32]
+          Param x: u8
+          268..277: expected `u8`, found `i32`
+      "#]],
+    );
+  }
+
+  #[test]
+  fn check_object_assignability() {
+    check(
+      r#"
+type Vocaloid = { name: str, age: u8, company?: str }
+type Named = { name: str }
+let miku: Vocaloid = { name: "Miku", age: 16, extra: true }
+let rin: Named = &miku
+let len: Vocaloid = { name: "Len" }
+let luka: Vocaloid = { name: "Luka", age: 300 }
+let gumi: Vocaloid = { name: "Gumi", age: 16, company: 1 }
+let kaito = Vocaloid { name: "Kaito", age: 1 }
+let company: str = miku.company
+let height = miku.height
+"#,
+      expect![[r#"
+          Let miku: Vocaloid
+          Let rin: Named
+          Let len: Vocaloid
+          Let luka: Vocaloid
+          Let gumi: Vocaloid
+          Let kaito: Vocaloid
+          Let company: str
+          Let height: {unknown}
+          185..200: expected `Vocaloid`, found `{ name: "Len" }`
+            note: missing field `age`
+          243..246: expected `u8`, found `300`
+            note: `300` is out of the range of `u8`
+          304..305: expected `str`, found `1`
+          374..386: expected `str`, found `str | None`
+          405..411: no field `height` on type `Vocaloid`
+      "#]],
+    );
+  }
+
+  #[test]
+  fn narrow_unions() {
+    check(
+      r#"
+type Circle = { kind: "circle", radius: f64 }
+type Square = { kind: "square", side: f64 }
+type Shape = Circle | Square
+fn area(shape: Shape) -> f64 {
+  if shape.kind == "circle" {
+    shape.radius * shape.radius
+  } else {
+    shape.side * shape.side
+  }
+}
+type MaybeInt = i32 | None
+fn or_zero(x: MaybeInt) -> i32 {
+  if x != None { x } else { 0 }
+}
+fn unwrap(x: Option<i32>) -> i32 {
+  match x {
+    Some(v) => v,
+    None => 0,
+  }
+}
+type Small = 1 | 2 | str
+fn describe(x: Small) -> str {
+  match x {
+    1 => "one",
+    n if n == 2 => "two",
+    s => s,
+  }
+}
+fn wrong(x: MaybeInt) -> i32 {
+  if x == None { x } else { x }
+}
+"#,
+      expect![[r#"
+          Fn area: (Shape) -> f64
+          Fn or_zero: (MaybeInt) -> i32
+          Fn unwrap: (Option<i32>) -> i32
+          Fn describe: (Small) -> str
+          Fn wrong: (MaybeInt) -> i32
+          Param shape: Shape
+          Param x: MaybeInt
+          Param x: Option<i32>
+          MatchBinding v: i32
+          Param x: Small
+          MatchBinding n: 2 | str
+          MatchBinding s: 2 | str
+          Param x: MaybeInt
+          557..558: expected `str`, found `2 | str`
+          614..615: expected `i32`, found `None`
+      "#]],
+    );
+  }
+
+  #[test]
+  fn narrow_after_branches() {
+    check(
+      r#"
+type MaybeInt = i32 | None
+fn early(x: MaybeInt) -> i32 {
+  if x == None { return 0 }
+  let a = x
+  a
+}
+fn matched(x: Option<i32>) {
+  match x {
+    None => return,
+    _ => {}
+  }
+  let b = x
+}
+fn reassigned(input: MaybeInt) -> i32 {
+  let mut x: MaybeInt = input
+  if x == None { return 0 }
+  x += 1
+  let c = x
+  x = None
+  let d = x
+  x = 1
+  let e = x
+  e
+}
+fn joined(x: MaybeInt) {
+  let mut y = x
+  if y == None { y = 0 }
+  let f = y
+}
+fn looped(x: MaybeInt) {
+  let mut y = x
+  if y == None { return }
+  let g = y
+  while true {
+    let h = y
+    y = None
+  }
+}
+fn cast(x: MaybeInt) -> i32 {
+  let i = x as i32
+  x
+}
+"#,
+      expect![[r#"
+          Fn early: (MaybeInt) -> i32
+          Fn matched: (Option<i32>) -> ()
+          Fn reassigned: (MaybeInt) -> i32
+          Fn joined: (MaybeInt) -> ()
+          Fn looped: (MaybeInt) -> ()
+          Fn cast: (MaybeInt) -> i32
+          Param x: MaybeInt
+          Let a: i32
+          Param x: Option<i32>
+          Let b: Some<i32>
+          Param input: MaybeInt
+          Mut x: MaybeInt
+          Let c: i32
+          Let d: None
+          Let e: i32
+          Param x: MaybeInt
+          Mut y: MaybeInt
+          Let f: i32
+          Param x: MaybeInt
+          Mut y: MaybeInt
+          Let g: i32
+          Let h: MaybeInt
+          Param x: MaybeInt
+          Let i: i32
+      "#]],
+    );
+  }
+
+  #[test]
+  fn report_mismatched_types() {
+    check(
+      r#"
+let a: str = 1
+let b: [i32; 2] = [1, 2, 3]
+let c: i32 | None = "c"
+let d = 1 + "d"
+let e = a(1)
+fn f(x: i32) -> bool { x }
+let g = f(1, 2)
+let h: (x: i32) -> i32 = (x) => x == 1
+"#,
+      expect![[r#"
+          Let a: str
+          Let b: [i32; 2]
+          Let c: i32 | None
+          Let d: {unknown}
+          Let e: {unknown}
+          Fn f: (i32) -> bool
+          Let g: bool
+          Let h: (i32) -> i32
+          Param x: i32
+          Param x: i32
+          14..15: expected `str`, found `1`
+          34..43: expected `[i32; 2]`, found `[1, 2, 3]`
+            note: expected 2 elements, found 3
+          64..67: expected `i32 | None`, found `"c"`
+          76..83: cannot apply `+` to `1` and `"d"`
+          92..93: `str` is not a function
+          120..121: expected `bool`, found `i32`
+          133..139: expected 1 argument, found 2
+          172..178: expected `i32`, found `bool`
+      "#]],
+    );
+  }
+
+  #[test]
+  fn check_injected_values() {
+    check(
+      r#"
+const x: i32 = 1
+const y = 1.5
+fn f() {
+  const c = 'c'
+  let z = x + 1
+  y * 2.0
+}
+f{ x: "ten", y: 2.5 }()
+fn test() {
+  let c = 'c'
+  let y = true
+  f{ x: c, y }()
+}
+let scale = ({ factor }: { factor: f64 }| v: f64) => v * factor
+scale{ factor: 2 }(1.5)
+scale{ factor: 2.0 }(1.5)
+"#,
+      expect![[r#"
+          Let x: i32
+          Let y: f64
+          Fn f: () -> f64
+          Fn test: () -> f64
+          Let scale: (f64) -> f64
+          Let c: 'c'
+          Let z: i32
+          Let c: 'c'
+          Let y: true
+          Param factor: f64
+          Param v: f64
+          88..96: expected `i32`, found `"ten"`
+          155..159: expected `i32`, found `'c'`
+          161..162: expected `f64`, found `true`
+          240..249: expected `f64`, found `2`
+      "#]],
+    );
+  }
+
+  #[test]
+  fn check_nominal_types() {
+    check(
+      r#"
+nominal type Some<T> = Symbol<T>()
+nominal type None = Symbol()
+nominal type Option<T> = Some<T> | None
+nominal type UserId = u64
+nominal type Vocaloid = { name: str }
+type Singer = { name: str }
+nominal type Producer = { name: str }
+
+let some = Some(1)
+let none = None
+let maybe: Option<i32> = some
+let id = UserId(7)
+let miku = Vocaloid { name: "Miku" }
+let singer: Singer = &miku
+let name = miku.name
+
+let raw: UserId = 7
+let rin: Vocaloid = { name: "Rin" }
+let ryo: Producer = miku
+let wrong: Option<str> = some
+
+fn unwrap(x: Option<i32>) -> i32 {
+  match x {
+    Some(v) => v,
+    None => 0,
+  }
+}
+fn missing(x: Option<i32>) -> i32 {
+  match x {
+    Some(v) => v,
+  }
+}
+fn partial(x: Option<i32>) -> i32 {
+  match x {
+    Some(1) => 1,
+    None => 0,
+  }
+}
+"#,
+      expect![[r#"
+          Let some: Some<i32>
+          Let none: None
+          Let maybe: Option<i32>
+          Let id: UserId
+          Let miku: Vocaloid
+          Let singer: Singer
+          Let name: str
+          Let raw: UserId
+          Let rin: Vocaloid
+          Let ryo: Producer
+          Let wrong: Option<str>
+          Fn unwrap: (Option<i32>) -> i32
+          Fn missing: (Option<i32>) -> i32
+          Fn partial: (Option<i32>) -> i32
+          Param x: Option<i32>
+          MatchBinding v: i32
+          Param x: Option<i32>
+          MatchBinding v: i32
+          Param x: Option<i32>
+          424..425: expected `UserId`, found `7`
+            note: `UserId` is a nominal type, its values are built with `UserId(..)`
+          446..461: expected `Vocaloid`, found `{ name: "Rin" }`
+            note: `Vocaloid` is a nominal type, its values are built with `Vocaloid { .. }`
+          482..486: expected `Producer`, found `Vocaloid`
+            note: `Producer` is a nominal type, its values are built with `Producer { .. }`
+          512..516: expected `Option<str>`, found `Some<i32>`
+            note: `i32` is not `str`
+          648..649: match is not exhaustive, `None` is not covered
+          720..721: match is not exhaustive, `Some<i32>` is not covered
+      "#]],
+    );
+  }
+
+  #[test]
+  fn nominal_identity() {
+    check(
+      r#"
+nominal type UserId = u64
+nominal type OrderId = u64
+nominal type Vocaloid = { name: str }
+nominal type Producer = { name: str }
+type Singer = { name: str }
+
+fn greet(singer: &Singer) -> str { singer.name }
+fn order(id: OrderId) {}
+fn first<T>(values: &Array<T>) -> T { values[0] }
+fn names<T: Singer>(values: &Array<T>) -> Array<str> { [] }
+
+let user = UserId(1)
+let miku = Vocaloid { name: "Miku" }
+let ryo = Producer { name: "ryo" }
+let greeting = greet(&miku)
+let vocaloids: Array<Vocaloid> = [Vocaloid { name: "Rin" }]
+let head = first(&vocaloids)
+let all = names(&vocaloids)
+let either: Vocaloid | UserId = user
+let maybe: Vocaloid | UserId = ryo
+
+order(user)
+let producers: &Array<Producer> = &vocaloids
+let fields: &Array<Singer> = &vocaloids
+let union: OrderId | Producer = miku
+"#,
+      expect![[r#"
+          Fn greet: (&Singer) -> str
+          Fn order: (OrderId) -> ()
+          Fn first: <T>(&[T]) -> T
+          Fn names: <T>(&[T]) -> [str]
+          Let user: UserId
+          Let miku: Vocaloid
+          Let ryo: Producer
+          Let greeting: str
+          Let vocaloids: [Vocaloid]
+          Let head: Vocaloid
+          Let all: [str]
+          Let either: Vocaloid | UserId
+          Let maybe: Vocaloid | UserId
+          Let producers: &[Producer]
+          Let fields: &[Singer]
+          Let union: OrderId | Producer
+          Param singer: &Singer
+          Param id: OrderId
+          Param values: &[T]
+          Param values: &[T]
+          650..653: expected `Vocaloid | UserId`, found `Producer`
+            note: `Vocaloid` is a nominal type, its values are built with `Vocaloid { .. }`
+          661..665: expected `OrderId`, found `UserId`
+            note: `OrderId` is a nominal type, its values are built with `OrderId(..)`
+          701..711: expected `&[Producer]`, found `&[Vocaloid]`
+            note: `Producer` is a nominal type, its values are built with `Producer { .. }`
+          784..788: expected `OrderId | Producer`, found `Vocaloid`
+            note: `OrderId` is a nominal type, its values are built with `OrderId(..)`
+      "#]],
+    );
+  }
+}
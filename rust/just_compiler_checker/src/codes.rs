@@ -0,0 +1,34 @@
+//! Codes of the checker diagnostics.
+
+use justc_lexer::diagnostic::Code;
+
+/// A value is not assignable to the type expected where it is used, e.g. `let x: i32 = "a"`.
+pub const MISMATCHED_TYPES: Code = Code("T0001");
+/// A field is read from a value whose type doesn't have it.
+pub const UNKNOWN_FIELD: Code = Code("T0002");
+/// A value which is not a function is called.
+pub const NOT_CALLABLE: Code = Code("T0003");
+/// A function is called with too few or too many arguments.
+pub const WRONG_ARG_COUNT: Code = Code("T0004");
+/// An operator is applied to values of types it doesn't support, e.g. `1 + "a"`.
+pub const INVALID_OPERANDS: Code = Code("T0005");
+/// A name used as a type refers to a value, e.g. `let x: f = 1` where `f` is a function.
+pub const NOT_A_TYPE: Code = Code("T0006");
+/// A `match` doesn't cover all the values of its scrutinee, e.g. `None` of an `Option<T>`.
+pub const NON_EXHAUSTIVE_MATCH: Code = Code("T0007");
+/// A value is used after it is moved, e.g. a `String` passed to a function and then printed.
+pub const USE_AFTER_MOVE: Code = Code("T0008");
+/// A value is used in a way its borrow doesn't allow while the borrow is in use, e.g. assigned while borrowed.
+pub const CONFLICTING_BORROW: Code = Code("T0009");
+/// A reference is used after the value it borrows goes out of scope, or is returned from its function.
+pub const DANGLING_REFERENCE: Code = Code("T0010");
+/// Several functions can be called on a value with the same name, e.g. a function of the file and one of the core.
+pub const AMBIGUOUS_FUNCTION: Code = Code("T0011");
+/// A type-level computation doesn't end, or nests deeper than the checker evaluates, e.g. `type Loop<T> = Loop<[T]>`.
+pub const TYPE_TOO_DEEP: Code = Code("T0012");
+/// The generic arguments of a call can't be inferred from its arguments, e.g. `parse(text)` for `parse<T>(text: str) -> T`.
+pub const CANNOT_INFER: Code = Code("T0013");
+/// A generic argument doesn't satisfy the constraint of its parameter, e.g. `i32` for `T: { name: str }`.
+pub const UNSATISFIED_BOUND: Code = Code("T0014");
+/// A call gives more or fewer generic arguments than the function has generic parameters.
+pub const WRONG_GENERIC_ARG_COUNT: Code = Code("T0015");
@@ -0,0 +1,596 @@
+//! Type-level computations: conditional types, `keyof`, mapped object types and indexed access,
+//! e.g. `type Partial<T> = { [K in keyof T]?: T[K] }`.
+//!
+//! A computation is evaluated when its type is needed, i.e. when it is compared or its fields are read,
+//! and once the generic parameters it tests are known, e.g. `keyof T` is kept as is in a generic function.
+//! Aliases are expanded while they stand for computations, so they can be recursive,
+//! e.g. `type Flatten<T> = T extends [any] ? Flatten<T[0]> : T`, as long as they end in `MAX_DEPTH` expansions.
+
+use super::assign::substitute;
+use super::check::{none, Checker};
+use super::codes;
+use super::infer::contains_param;
+use super::lower::intersection;
+use super::ty::{Conditional, Field, FnTy, IntTy, Lit, Mapped, Named, Param, Ty};
+use justc_lexer::diagnostic::Diagnostic;
+use justc_lexer::span::Span;
+use std::convert::TryFrom;
+
+/// Evaluations nest at most this deep, e.g. the expansions of a recursive alias.
+pub(crate) const MAX_DEPTH: usize = 50;
+
+/// An evaluation nested deeper than `MAX_DEPTH`, which likely never ends.
+#[derive(Debug)]
+pub(crate) struct TooDeep;
+
+impl<'a> Checker<'a> {
+  /// The type `ty` stands for, e.g. `true` for `IsStr<"a">` or `"age" | "name"` for `keyof Vocaloid`.
+  /// Other types, and computations of generic parameters, are returned as they are.
+  pub(crate) fn evaluate(&self, ty: &Ty) -> Result<Ty, TooDeep> {
+    let depth = self.evaluating.get();
+    if depth >= MAX_DEPTH {
+      return Err(TooDeep);
+    }
+    self.evaluating.set(depth + 1);
+    let value = self.eval(ty);
+    self.evaluating.set(depth);
+    value
+  }
+
+  fn eval(&self, ty: &Ty) -> Result<Ty, TooDeep> {
+    match ty {
+      Ty::Named(_) if !self.is_opaque(ty) => {
+        let expanded = self.expand(ty);
+        if !is_computation(&expanded) && !matches!(expanded, Ty::Named(_)) {
+          return Ok(ty.clone());
+        }
+        let value = self.evaluate(&expanded)?;
+        // An alias of an alias keeps its name, e.g. `type Singer = Vocaloid`.
+        if matches!(value, Ty::Named(_)) && !is_computation(&expanded) {
+          return Ok(ty.clone());
+        }
+        Ok(value)
+      }
+      _ if is_deferred(ty) => Ok(ty.clone()),
+      Ty::Conditional(conditional) => self.conditional(conditional),
+      Ty::Keyof(operand) => {
+        let operand = self.evaluate(operand)?;
+        let keys = self.keys(&operand)?;
+        Ok(keys.map_or(Ty::Unknown, Ty::union))
+      }
+      Ty::Mapped(mapped) => self.mapped(mapped),
+      Ty::Index(indexed, index) => {
+        let indexed = self.evaluate(indexed)?;
+        let index = self.evaluate(index)?;
+        let mut types = Vec::new();
+        for key in self.members(&index) {
+          types.push(self.index(&indexed, &key)?);
+        }
+        Ok(Ty::union(types))
+      }
+      _ => Ok(ty.clone()),
+    }
+  }
+
+  /// `T extends U ? X : Y`, where a generic parameter `T` is tested for each type of the union it stands for,
+  /// e.g. `i32` for `NonNullable<i32 | None>` with `type NonNullable<T> = T extends None ? never : T`.
+  fn conditional(&self, conditional: &Conditional) -> Result<Ty, TooDeep> {
+    let check = self.evaluate(&conditional.check)?;
+    let extends = self.evaluate(&conditional.extends)?;
+    if check.is_unknown() || extends.is_unknown() {
+      return Ok(Ty::Unknown);
+    }
+    if conditional.param.is_some() {
+      let members = match check {
+        Ty::Never => Vec::new(),
+        _ => self.members(&check),
+      };
+      if members.len() != 1 {
+        let mut types = Vec::new();
+        for member in &members {
+          types.push(self.branch(conditional, member, &extends)?);
+        }
+        return Ok(Ty::union(types));
+      }
+    }
+    self.branch(conditional, &check, &extends)
+  }
+
+  /// The branch of `conditional` taken by `check`, with its generic parameter bound to `check`.
+  fn branch(&self, conditional: &Conditional, check: &Ty, extends: &Ty) -> Result<Ty, TooDeep> {
+    let branch = if self.assignable(check, extends).is_ok() {
+      &conditional.then
+    } else {
+      &conditional.otherwise
+    };
+    let branch = match &conditional.param {
+      Some(param) => bind(param, check.clone(), branch),
+      None => branch.clone(),
+    };
+    self.evaluate(&branch)
+  }
+
+  /// The keys of the values of `ty`: the names of the fields of an object or the indices of a tuple,
+  /// and the keys common to all the types of a union. None when `ty` is unknown.
+  fn keys(&self, ty: &Ty) -> Result<Option<Vec<Ty>>, TooDeep> {
+    let members = self.members(ty);
+    if members.len() > 1 {
+      let mut common: Option<Vec<Ty>> = None;
+      for member in &members {
+        let keys = match self.keys(member)? {
+          Some(keys) => keys,
+          None => return Ok(None),
+        };
+        common = Some(match common {
+          Some(common) => common
+            .into_iter()
+            .filter(|key| keys.contains(key))
+            .collect(),
+          None => keys,
+        });
+      }
+      return Ok(common);
+    }
+    let keys = match members.first().map(|ty| self.structure(ty)) {
+      Some(Ty::Object(fields)) => fields
+        .iter()
+        .map(|field| Ty::Literal(Lit::Str(field.name.clone())))
+        .collect(),
+      Some(Ty::Tuple(types)) => (0..types.len()).map(index_key).collect(),
+      Some(Ty::Intersection(types)) => {
+        let mut all = Vec::new();
+        for ty in &types {
+          let keys = match self.keys(ty)? {
+            Some(keys) => keys,
+            None => return Ok(None),
+          };
+          for key in keys {
+            if !all.contains(&key) {
+              all.push(key);
+            }
+          }
+        }
+        all
+      }
+      Some(Ty::Unknown) | Some(Ty::Any) => return Ok(None),
+      _ => Vec::new(),
+    };
+    Ok(Some(keys))
+  }
+
+  /// `{ [K in Keys]: V }`, an object with a field for each string literal of `Keys`.
+  ///
+  /// `{ [K in keyof T]: V }` maps the fields of `T`, which stay optional, the elements of a tuple or an array,
+  /// and each type of a union. Other types are mapped to themselves, e.g. `i32`.
+  fn mapped(&self, mapped: &Mapped) -> Result<Ty, TooDeep> {
+    if let Ty::Keyof(source) = &mapped.keys {
+      let source = self.evaluate(source)?;
+      let members = self.members(&source);
+      if members.len() > 1 {
+        let mut types = Vec::new();
+        for member in members {
+          let each = Mapped {
+            keys: Ty::Keyof(Box::new(member)),
+            ..mapped.clone()
+          };
+          types.push(self.evaluate(&Ty::Mapped(Box::new(each)))?);
+        }
+        return Ok(Ty::union(types));
+      }
+      let mapped = match self.structure(&source) {
+        Ty::Object(fields) => Ty::object(
+          fields
+            .iter()
+            .map(|field| Field {
+              name: field.name.clone(),
+              ty: bind_key(mapped, Ty::Literal(Lit::Str(field.name.clone()))),
+              optional: field.optional || mapped.optional,
+            })
+            .collect(),
+        ),
+        Ty::Tuple(types) => Ty::Tuple(
+          (0..types.len())
+            .map(|i| bind_key(mapped, index_key(i)))
+            .collect(),
+        ),
+        Ty::Array(_, len) => Ty::Array(Box::new(bind_key(mapped, Ty::Int(IntTy::USize))), len),
+        Ty::Unknown => Ty::Unknown,
+        Ty::Intersection(_) => return self.mapped_keys(mapped),
+        _ => source,
+      };
+      return Ok(mapped);
+    }
+    self.mapped_keys(mapped)
+  }
+
+  fn mapped_keys(&self, mapped: &Mapped) -> Result<Ty, TooDeep> {
+    let keys = self.evaluate(&mapped.keys)?;
+    let mut fields = Vec::new();
+    for key in self.members(&keys) {
+      match &key {
+        Ty::Literal(Lit::Str(name)) => fields.push(Field {
+          name: name.clone(),
+          ty: bind_key(mapped, key.clone()),
+          optional: mapped.optional,
+        }),
+        Ty::Never => (),
+        // Object types have no index signatures, e.g. for `[K in str]`.
+        _ => return Ok(Ty::Unknown),
+      }
+    }
+    Ok(Ty::object(fields))
+  }
+
+  /// The type of the values at `key` in the values of `indexed`, e.g. `u8` for `Vocaloid["age"]`,
+  /// or `{unknown}` when they don't have it.
+  fn index(&self, indexed: &Ty, key: &Ty) -> Result<Ty, TooDeep> {
+    let mut types = Vec::new();
+    for indexed in self.members(indexed) {
+      let ty = match (self.structure(&indexed), key) {
+        (Ty::Object(fields), Ty::Literal(Lit::Str(name))) => {
+          match fields.iter().find(|field| field.name == *name) {
+            Some(field) if field.optional => Ty::union(vec![field.ty.clone(), none()]),
+            Some(field) => field.ty.clone(),
+            None => Ty::Unknown,
+          }
+        }
+        (Ty::Tuple(types), Ty::Literal(Lit::Int { value, .. })) => usize::try_from(*value)
+          .ok()
+          .and_then(|i| types.get(i).cloned())
+          .unwrap_or(Ty::Unknown),
+        (Ty::Tuple(types), Ty::Int(_)) => Ty::union(types),
+        (Ty::Array(elem, _), Ty::Literal(Lit::Int { .. }) | Ty::Int(_)) => *elem,
+        _ => Ty::Unknown,
+      };
+      types.push(self.evaluate(&ty)?);
+    }
+    Ok(Ty::union(types))
+  }
+
+  /// `ty` with all its computations evaluated, e.g. `{ age?: u8, name?: str }` for `Partial<Vocaloid>`.
+  /// A recursive alias is kept where it repeats, e.g. in `{ children?: [DeepPartial<Tree>], value?: i32 }`.
+  pub(crate) fn normalize(&self, ty: &Ty) -> Result<Ty, TooDeep> {
+    self.normalize_in(ty, 0, &mut Vec::new())
+  }
+
+  /// `ty` as shown in diagnostics: a computation is evaluated, e.g. `"name"` for `keyof { name: str }`,
+  /// other types are shown as they are written, e.g. `Partial<Vocaloid>`.
+  pub(crate) fn shown(&self, ty: &Ty) -> Ty {
+    if !is_computation(ty) {
+      return ty.clone();
+    }
+    self.normalize(ty).unwrap_or_else(|_| ty.clone())
+  }
+
+  /// `seen` are the types being normalized, `depth` how many types contain `ty`.
+  fn normalize_in(&self, ty: &Ty, depth: usize, seen: &mut Vec<Ty>) -> Result<Ty, TooDeep> {
+    if depth > MAX_DEPTH {
+      return Err(TooDeep);
+    }
+    let depth = depth + 1;
+    let all = |types: &[Ty], seen: &mut Vec<Ty>| -> Result<Vec<Ty>, TooDeep> {
+      types
+        .iter()
+        .map(|ty| self.normalize_in(ty, depth, seen))
+        .collect()
+    };
+    let ty = match ty {
+      Ty::Named(named) => Ty::Named(Named {
+        args: all(&named.args, seen)?,
+        ..named.clone()
+      }),
+      _ => ty.clone(),
+    };
+    if matches!(ty, Ty::Named(_)) || is_computation(&ty) {
+      if seen.contains(&ty) {
+        return Ok(ty);
+      }
+      let value = self.evaluate(&ty)?;
+      if value != ty {
+        seen.push(ty);
+        let value = self.normalize_in(&value, depth, seen);
+        seen.pop();
+        return value;
+      }
+    }
+    let normalized = match &ty {
+      Ty::Object(fields) => {
+        let mut normalized = Vec::new();
+        for field in fields {
+          normalized.push(Field {
+            ty: self.normalize_in(&field.ty, depth, seen)?,
+            ..field.clone()
+          });
+        }
+        Ty::Object(normalized)
+      }
+      Ty::Tuple(types) => Ty::Tuple(all(types, seen)?),
+      Ty::Array(elem, len) => Ty::Array(Box::new(self.normalize_in(elem, depth, seen)?), *len),
+      Ty::Fn(function) => Ty::Fn(FnTy {
+        generics: function.generics.clone(),
+        params: all(&function.params, seen)?,
+        ret: Box::new(self.normalize_in(&function.ret, depth, seen)?),
+      }),
+      Ty::Union(types) => Ty::union(all(types, seen)?),
+      Ty::Intersection(types) => intersection(all(types, seen)?),
+      Ty::Ref(inner, mutable) => {
+        Ty::Ref(Box::new(self.normalize_in(inner, depth, seen)?), *mutable)
+      }
+      Ty::Builtin(name, args) => Ty::Builtin(name.clone(), all(args, seen)?),
+      Ty::Symbol(Some(inner)) => Ty::Symbol(Some(Box::new(self.normalize_in(inner, depth, seen)?))),
+      _ => ty,
+    };
+    Ok(normalized)
+  }
+}
+
+/// True for the types evaluated by `Checker::evaluate`, other than aliases.
+pub(crate) fn is_computation(ty: &Ty) -> bool {
+  matches!(
+    ty,
+    Ty::Conditional(_) | Ty::Keyof(_) | Ty::Mapped(_) | Ty::Index(..)
+  )
+}
+
+/// True if `ty` is a computation of generic parameters, evaluated once they are substituted, e.g. `keyof T`.
+pub(crate) fn is_deferred(ty: &Ty) -> bool {
+  match ty {
+    Ty::Conditional(conditional) => {
+      contains_param(&conditional.check) || contains_param(&conditional.extends)
+    }
+    Ty::Keyof(ty) => contains_param(ty),
+    Ty::Mapped(mapped) => contains_param(&mapped.keys),
+    Ty::Index(ty, index) => contains_param(ty) || contains_param(index),
+    _ => false,
+  }
+}
+
+/// The error of a type whose evaluation doesn't end, e.g. `Loop<i32>` for `type Loop<T> = Loop<[T]>`.
+pub(crate) fn too_deep(ty: &Ty, span: Span) -> Diagnostic {
+  Diagnostic::error(
+    codes::TYPE_TOO_DEEP,
+    format!("type `{}` is too deeply nested to be evaluated", ty),
+    span,
+  )
+  .with_label("possibly infinite")
+  .with_note(format!(
+    "type-level computations are evaluated at most {} levels deep",
+    MAX_DEPTH
+  ))
+}
+
+/// `ty` with `param` replaced by `arg`.
+fn bind(param: &Param, arg: Ty, ty: &Ty) -> Ty {
+  substitute(ty, &std::iter::once((param.symbol, arg)).collect())
+}
+
+/// The type of the field of `mapped` at `key`.
+fn bind_key(mapped: &Mapped, key: Ty) -> Ty {
+  bind(&mapped.key, key, &mapped.value)
+}
+
+/// The key of the element `i` of a tuple, e.g. `0`.
+fn index_key(i: usize) -> Ty {
+  Ty::Literal(Lit::Int {
+    value: i as i128,
+    ty: IntTy::I32,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::check::Checker;
+  use expect_test::{expect, Expect};
+  use justc_binder::symbols::SymbolKind;
+  use justc_parser::ast::AstNode;
+
+  /// The evaluated types of the aliases without generic parameters, followed by the checked declarations.
+  fn check(text: &str, expect: Expect) {
+    let parse = justc_parser::parse(text);
+    assert_eq!(parse.errors(), &[], "{}", text);
+    let tree = parse.tree();
+    let binding = justc_binder::bind(&tree);
+    let checker = Checker::new(tree.syntax().clone(), binding.symbols());
+    let mut out = String::new();
+    for (id, symbol) in binding.symbols().symbols() {
+      let alias = match checker.aliases.get(&id) {
+        Some(alias) if symbol.kind == SymbolKind::Type && alias.params.is_empty() => alias,
+        _ => continue,
+      };
+      match checker.normalize(&alias.ty) {
+        Ok(ty) => out.push_str(&format!("{} = {}\n", symbol.name, ty)),
+        Err(_) => out.push_str(&format!("{} is too deep\n", symbol.name)),
+      }
+    }
+    let types = crate::check(&tree, binding.symbols());
+    out.push_str(&types.debug_dump(binding.symbols()));
+    expect.assert_eq(&out);
+  }
+
+  #[test]
+  fn conditional_types() {
+    check(
+      r#"
+type IsStr<T> = T extends str ? true : false
+type A = IsStr<"miku">
+type B = IsStr<i32>
+type C = IsStr<str | i32>
+type NonNullable<T> = T extends None ? never : T
+type D = NonNullable<i32 | None>
+type Exclude<T, U> = T extends U ? never : T
+type Extract<T, U> = T extends U ? T : never
+type E = Exclude<"a" | "b" | "c", "a">
+type F = Extract<"a" | 1 | 'c', str>
+type G = Exclude<never, str>
+let yes: IsStr<"a"> = true
+let no: IsStr<"a"> = false
+"#,
+      expect![[r#"
+          A = true
+          B = false
+          C = true | false
+          D = i32
+          E = "b" | "c"
+          F = "a"
+          G = never
+          Let yes: IsStr<"a">
+          Let no: IsStr<"a">
+          440..445: expected `IsStr<"a">`, found `false`
+      "#]],
+    );
+  }
+
+  #[test]
+  fn keyof_and_mapped_types() {
+    check(
+      r#"
+type Vocaloid = { name: str, age: u8, company?: str }
+type Keys = keyof Vocaloid
+type Age = Vocaloid["age"]
+type Company = Vocaloid["company"]
+type Partial<T> = { [K in keyof T]?: T[K] }
+type Pick<T, K: keyof T> = { [P in K]: T[P] }
+type Record<K, V> = { [P in K]: V }
+type Draft = Partial<Vocaloid>
+type Card = Pick<Vocaloid, "name" | "age">
+type Flags = Record<"loud" | "fast", bool>
+type Pair = [i32, str]
+type PairKeys = keyof Pair
+type Second = Pair[1]
+type Wrap<T> = { [K in keyof T]: Option<T[K]> }
+type Wrapped = Wrap<Pair>
+type Same = Partial<i32>
+type Common = keyof ({ a: i32, b: i32 } | { b: str, c: str })
+let draft: Partial<Vocaloid> = { name: "Miku" }
+let flags: Flags = { loud: true }
+let age: Age = draft.age
+let company = draft.company
+"#,
+      expect![[r#"
+          Vocaloid = { age: u8, company?: str, name: str }
+          Keys = "age" | "company" | "name"
+          Age = u8
+          Company = str | None
+          Draft = { age?: u8, company?: str | None, name?: str }
+          Card = { age: u8, name: str }
+          Flags = { fast: bool, loud: bool }
+          Pair = [i32, str]
+          PairKeys = 0 | 1
+          Second = str
+          Wrapped = [Option<i32>, Option<str>]
+          Same = i32
+          Common = "b"
+          Let draft: Partial<Vocaloid>
+          Let flags: Flags
+          Let age: Age
+          Let company: str | None
+          687..701: expected `Flags`, found `{ loud: bool }`
+            note: missing field `fast`
+          717..726: expected `Age`, found `u8 | None`
+      "#]],
+    );
+  }
+
+  #[test]
+  fn recursive_aliases() {
+    check(
+      r#"
+type Flatten<T> = T extends [any] ? Flatten<T[0]> : T
+type Deep = Flatten<[[[i32]]]>
+type DeepPartial<T> = { [K in keyof T]?: DeepPartial<T[K]> }
+type Tree = { value: i32, children: [Tree] }
+type PartialTree = DeepPartial<Tree>
+type Loop<T> = Loop<[T]>
+type Forever = Loop<i32>
+let x: Loop<i32> = 1
+let leaf: DeepPartial<Tree> = { children: [{ value: 1 }] }
+"#,
+      expect![[r#"
+          Deep = i32
+          Tree = { children: [Tree], value: i32 }
+          PartialTree = { children?: [DeepPartial<Tree>], value?: i32 }
+          Forever = {unknown}
+          Let x: {unknown}
+          Let leaf: DeepPartial<Tree>
+          269..278: type `Loop<i32>` is too deeply nested to be evaluated
+            note: type-level computations are evaluated at most 50 levels deep
+          286..295: type `Loop<i32>` is too deeply nested to be evaluated
+            note: type-level computations are evaluated at most 50 levels deep
+      "#]],
+    );
+  }
+
+  #[test]
+  fn documented_types() {
+    // `Vocaloid` of `language-reference/type.just`, and the inferred and union types of `02-design/type-system.md`.
+    check(
+      r#"
+type Vocaloid = {
+  name: String,
+  age: uint
+}
+type Keys = keyof Vocaloid
+type Fields = Vocaloid[keyof Vocaloid]
+type Flags = { [K in keyof Vocaloid]: bool }
+type Id = i32 | String
+type IdKeys = keyof Id
+type Numbers = keyof i32
+type Mapped = { [K in keyof i32]: bool }
+type MappedKeys = { [K in i32]: bool }
+type Missing = Vocaloid["height"]
+let x = true
+let mut id: Id = 1
+let key: Keys = "height"
+let none: keyof i32 = "a"
+let flags: Flags = { name: true, age: 1 }
+"#,
+      expect![[r#"
+          Vocaloid = { age: uint, name: String }
+          Keys = "age" | "name"
+          Fields = uint | String
+          Flags = { age: bool, name: bool }
+          Id = i32 | String
+          IdKeys = never
+          Numbers = never
+          Mapped = i32
+          MappedKeys = {unknown}
+          Missing = {unknown}
+          Let x: true
+          Mut id: Id
+          Let key: Keys
+          Let none: keyof i32
+          Let flags: Flags
+          393..401: expected `Keys`, found `"height"`
+          424..427: expected `never`, found `"a"`
+          447..469: expected `Flags`, found `{ age: i32, name: bool }`
+            note: field `age` is `i32`, not `bool`
+      "#]],
+    );
+  }
+
+  #[test]
+  fn defer_generic_computations() {
+    check(
+      r#"
+type IsStr<T> = T extends str ? true : false
+fn keys<T>(x: T) -> keyof T { x }
+fn is_str<T>(x: T) -> IsStr<T> { true }
+let name: "name" = keys({ name: "Miku" })
+let age: "age" = keys({ name: "Miku" })
+let yes: true = is_str("a")
+"#,
+      expect![[r#"
+          Fn keys: <T>(T) -> keyof T
+          Fn is_str: <T>(T) -> IsStr<T>
+          Let name: "name"
+          Let age: "age"
+          Let yes: true
+          Param x: T
+          Param x: T
+          76..77: expected `keyof T`, found `T`
+          113..117: expected `IsStr<T>`, found `true`
+          179..201: expected `"age"`, found `"name"`
+      "#]],
+    );
+  }
+}
@@ -0,0 +1,296 @@
+//! Instantiates generic functions where they are called.
+//!
+//! The generic arguments of a call are given explicitly, e.g. `parse<i32>(text)`, or inferred from the arguments,
+//! e.g. `T` is `i32` in `filter(numbers, (n) => n > 1)` for `numbers: Array<i32>`.
+//! Arrow functions passed as arguments are typed last, with the generic arguments inferred from the other arguments,
+//! so their parameters don't need annotations, and their return types infer the remaining generic parameters,
+//! e.g. `U` of `map<T, U>(array: &Array<T>, f: (entry: T) -> U)`.
+//! A generic parameter not used by the arguments is inferred from the type expected for the result of the call,
+//! and the generic arguments must satisfy the constraints of their parameters, e.g. `T: { name: str }`.
+
+use super::assign::{bind_params, substitute};
+use super::check::Checker;
+use super::codes;
+use super::ty::{FnTy, Ty};
+use justc_binder::symbols::SymbolId;
+use justc_lexer::diagnostic::Diagnostic;
+use justc_lexer::span::Span;
+use justc_parser::ast::{self, AstNode};
+use std::collections::HashMap;
+
+impl<'a> Checker<'a> {
+  /// Infers the types of the arguments `args` of `call`, a call of `function`, with the type `expected` where it is used.
+  /// Returns `function` with its generic parameters replaced by the generic arguments, and the types of the arguments.
+  pub(crate) fn instantiate(
+    &mut self,
+    call: &ast::CallExpr,
+    function: FnTy,
+    args: &[ast::Expr],
+    expected: Option<&Ty>,
+  ) -> (FnTy, Vec<Ty>) {
+    let mut bindings = HashMap::new();
+    // Where each generic argument comes from, reported when it doesn't satisfy its constraint.
+    let mut origins = HashMap::new();
+    let explicit = call.generic_arg_list();
+    if let Some(list) = &explicit {
+      let types: Vec<_> = list.types().collect();
+      if types.len() != function.generics.len() {
+        self.diagnostics.push(
+          Diagnostic::error(
+            codes::WRONG_GENERIC_ARG_COUNT,
+            format!(
+              "expected {} generic argument{}, found {}",
+              function.generics.len(),
+              if function.generics.len() == 1 {
+                ""
+              } else {
+                "s"
+              },
+              types.len()
+            ),
+            list.syntax().text_range(),
+          )
+          .with_label(format!("`{}`", Ty::Fn(function.clone()))),
+        );
+      }
+      for (param, ty) in function.generics.iter().zip(&types) {
+        bindings.insert(param.symbol, self.lower(ty));
+        origins.insert(param.symbol, ty.syntax().text_range());
+      }
+    }
+    let mut types = vec![Ty::Unknown; args.len()];
+    let (lambdas, values): (Vec<_>, Vec<_>) =
+      (0..args.len()).partition(|i| matches!(args[*i], ast::Expr::ArrowFn(_)));
+    for i in values {
+      types[i] = self.infer_arg(&function, i, &args[i], &mut bindings, &mut origins);
+    }
+    let unbound = function
+      .generics
+      .iter()
+      .any(|param| !bindings.contains_key(&param.symbol));
+    if let Some(expected) = expected.filter(|_| unbound) {
+      bind_params(&function.ret, expected, &mut bindings);
+    }
+    // The arrow functions are typed with the generic arguments inferred from the other arguments.
+    for i in lambdas {
+      types[i] = self.infer_arg(&function, i, &args[i], &mut bindings, &mut origins);
+    }
+    let callee = call.callee().map_or(call.syntax().text_range(), |callee| {
+      callee.syntax().text_range()
+    });
+    for param in &function.generics {
+      if bindings.contains_key(&param.symbol) {
+        continue;
+      }
+      if explicit.is_none() {
+        self.diagnostics.push(
+          Diagnostic::error(
+            codes::CANNOT_INFER,
+            format!(
+              "cannot infer the type of the generic parameter `{}`",
+              param.name
+            ),
+            callee,
+          )
+          .with_label(format!("`{}`", Ty::Fn(function.clone())))
+          .with_note(format!(
+            "`{}` isn't used by the arguments, give the generic arguments of `{}` or the type of its result",
+            param.name,
+            call
+              .callee()
+              .map_or(String::new(), |callee| callee.syntax().text().to_string()),
+          )),
+        );
+      }
+      bindings.insert(param.symbol, Ty::Unknown);
+    }
+    for param in &function.generics {
+      let bound = match self.bounds.get(&param.symbol) {
+        Some(bound) => substitute(bound, &bindings),
+        None => continue,
+      };
+      let ty = &bindings[&param.symbol];
+      if let Err(reason) = self.assignable(ty, &bound) {
+        let span = origins.get(&param.symbol).copied().unwrap_or(callee);
+        let mut diagnostic = Diagnostic::error(
+          codes::UNSATISFIED_BOUND,
+          format!(
+            "`{}` doesn't satisfy the constraint `{}` of `{}`",
+            ty, bound, param.name
+          ),
+          span,
+        )
+        .with_label(format!("`{}` is `{}` here", param.name, ty));
+        if let Some(reason) = reason {
+          diagnostic = diagnostic.with_note(reason);
+        }
+        self.diagnostics.push(diagnostic);
+      }
+    }
+    let function = FnTy {
+      generics: Vec::new(),
+      params: function
+        .params
+        .iter()
+        .map(|param| substitute(param, &bindings))
+        .collect(),
+      ret: Box::new(substitute(&function.ret, &bindings)),
+    };
+    (function, types)
+  }
+
+  /// Infers the type of `arg`, the `i`th argument of a call of `function`,
+  /// and binds the generic parameters of its parameter to the parts of its type.
+  fn infer_arg(
+    &mut self,
+    function: &FnTy,
+    i: usize,
+    arg: &ast::Expr,
+    bindings: &mut HashMap<SymbolId, Ty>,
+    origins: &mut HashMap<SymbolId, Span>,
+  ) -> Ty {
+    let param = match function.params.get(i) {
+      Some(param) => param,
+      None => return self.infer(arg, None),
+    };
+    let expected = expected_arg(function, param, bindings);
+    let ty = self.infer(arg, Some(&expected));
+    bind_params(param, &ty.widen(), bindings);
+    for generic in &function.generics {
+      if bindings.contains_key(&generic.symbol) && !origins.contains_key(&generic.symbol) {
+        origins.insert(generic.symbol, arg.syntax().text_range());
+      }
+    }
+    ty
+  }
+}
+
+/// The type expected for an argument of the parameter `param` of `function`: the generic parameters already inferred
+/// are replaced by their types, the others by `{unknown}`, but in the return type of a function type,
+/// where they are inferred from the arrow function passed, e.g. `(i32) -> U` for `(entry: T) -> U` and `T: i32`.
+fn expected_arg(function: &FnTy, param: &Ty, bindings: &HashMap<SymbolId, Ty>) -> Ty {
+  let mut unknowns = bindings.clone();
+  for generic in &function.generics {
+    unknowns.entry(generic.symbol).or_insert(Ty::Unknown);
+  }
+  match param {
+    Ty::Fn(param) => Ty::Fn(FnTy {
+      generics: param.generics.clone(),
+      params: param
+        .params
+        .iter()
+        .map(|param| substitute(param, &unknowns))
+        .collect(),
+      ret: Box::new(substitute(&param.ret, bindings)),
+    }),
+    param => substitute(param, &unknowns),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expect_test::{expect, Expect};
+
+  fn check(text: &str, expect: Expect) {
+    let parse = justc_parser::parse(text);
+    assert_eq!(parse.errors(), &[], "{}", text);
+    let tree = parse.tree();
+    let binding = justc_binder::bind(&tree);
+    let types = crate::check(&tree, binding.symbols());
+    expect.assert_eq(&types.debug_dump(binding.symbols()));
+  }
+
+  #[test]
+  fn infer_generic_arguments() {
+    check(
+      r#"
+type Named = { name: str }
+fn filter<T>(array: &Array<T>, predicate: (entry: T) -> bool) -> Array<T> { [] }
+fn map<T, U>(array: &Array<T>, f: (entry: T) -> U) -> Array<U> { [] }
+fn names<T: Named>(values: &Array<T>) -> Array<str> { map(values, (value) => value.name) }
+fn parse<T>(text: str) -> Array<T> { [] }
+let numbers: Array<i32> = [1, 2, 3]
+let big = filter(&numbers, (n) => n > 1)
+let labels = map(&numbers, (n) => `${n}`)
+let singers = names(&[{ name: "Miku", age: 16 }])
+let explicit = parse<i32>("1")
+let expected: Array<u8> = parse("1")
+pub let keep = <T>(array: &Array<T>, predicate: (entry: T) -> bool) -> Array<T> => filter(array, predicate)
+let kept = keep(&numbers, (n) => n > 1)
+"#,
+      expect![[r#"
+          Fn filter: <T>(&[T], (T) -> bool) -> [T]
+          Fn map: <T, U>(&[T], (T) -> U) -> [U]
+          Fn names: <T>(&[T]) -> [str]
+          Fn parse: <T>(str) -> [T]
+          Let numbers: [i32]
+          Let big: [i32]
+          Let labels: [String]
+          Let singers: [str]
+          Let explicit: [i32]
+          Let expected: [u8]
+          Let keep: <T>(&[T], (T) -> bool) -> [T]
+          Let kept: [i32]
+          Param array: &[T]
+          Param predicate: (T) -> bool
+          Param array: &[T]
+          Param f: (T) -> U
+          Param values: &[T]
+          Param value: T
+          Param text: str
+          Param n: i32
+          Param n: i32
+          Param array: &[T]
+          Param predicate: (T) -> bool
+          Param n: i32
+      "#]],
+    );
+  }
+
+  #[test]
+  fn report_generic_arguments() {
+    check(
+      r#"
+type Named = { name: str }
+fn names<T: Named>(values: &Array<T>) -> Array<str> { [] }
+fn parse<T>(text: str) -> Array<T> { [] }
+let numbers: Array<i32> = [1, 2, 3]
+let wrong = names(&numbers)
+let unknown = parse("1")
+let count = parse<i32, str>("1")
+let explicit = names<i32>(&numbers)
+fn first<T>(a: T, b: T) -> T { a }
+let conflicting = first(1, "a")
+fn pick<T, U: Named>(array: &Array<T>, f: (entry: T) -> U) -> Array<U> { [] }
+let picked = pick(&numbers, (n) => n)
+"#,
+      expect![[r#"
+          Fn names: <T>(&[T]) -> [str]
+          Fn parse: <T>(str) -> [T]
+          Let numbers: [i32]
+          Let wrong: [str]
+          Let unknown: [{unknown}]
+          Let count: [i32]
+          Let explicit: [str]
+          Fn first: <T>(T, T) -> T
+          Let conflicting: i32
+          Fn pick: <T, U>(&[T], (T) -> U) -> [U]
+          Let picked: [i32]
+          Param values: &[T]
+          Param text: str
+          Param a: T
+          Param b: T
+          Param array: &[T]
+          Param f: (T) -> U
+          Param n: i32
+          183..191: `i32` doesn't satisfy the constraint `Named` of `T`
+          207..212: cannot infer the type of the generic parameter `T`
+            note: `T` isn't used by the arguments, give the generic arguments of `parse` or the type of its result
+          235..245: expected 1 generic argument, found 2
+          272..275: `i32` doesn't satisfy the constraint `Named` of `T`
+          349..352: expected `i32`, found `"a"`
+          460..468: `i32` doesn't satisfy the constraint `Named` of `U`
+      "#]],
+    );
+  }
+}
@@ -0,0 +1,939 @@
+//! Infers the types of expressions.
+//!
+//! An expression is inferred with the type expected where it is used, if any, e.g. the annotation of a `let`,
+//! which types the parameters of arrow functions and keeps literal types in arrays and objects.
+//! Without it, the elements of arrays and fields of objects are widened, e.g. `{ x: 1 }` is `{ x: i32 }`.
+
+use super::assign::bind_params;
+use super::check::{none, tail, tail_span, Checker};
+use super::codes;
+use super::lower::builtin_type;
+use super::ty::{Field, FloatTy, FnTy, IntTy, Lit, Named, Ty};
+use justc_binder::injection::injected;
+use justc_binder::symbols::{SymbolId, SymbolKind};
+use justc_lexer::diagnostic::Diagnostic;
+use justc_lexer::span::Span;
+use justc_parser::ast::{self, AstNode, BinOp, HasGenericParams, HasName, LiteralKind, PrefixOp};
+use justc_parser::SyntaxKind;
+use std::collections::HashMap;
+
+/// Builtin constructors, e.g. `Some(1)` is `Some<i32>`.
+const CONSTRUCTORS: &[&str] = &["Some", "Ok", "Err"];
+
+impl<'a> Checker<'a> {
+  /// Infers the type of `expr`, with the type `expected` where it is used.
+  pub(crate) fn infer(&mut self, expr: &ast::Expr, expected: Option<&Ty>) -> Ty {
+    let ty = self.infer_inner(expr, expected);
+    self.record(expr.syntax().text_range(), &ty);
+    ty
+  }
+
+  fn infer_inner(&mut self, expr: &ast::Expr, expected: Option<&Ty>) -> Ty {
+    match expr {
+      ast::Expr::Literal(literal) => literal_type(literal),
+      ast::Expr::PathExpr(path) => self.path_expr(path),
+      ast::Expr::ParenExpr(paren) => match paren.expr() {
+        Some(expr) => self.infer(&expr, expected),
+        None => Ty::Unknown,
+      },
+      ast::Expr::ArrayExpr(array) => self.array(array, expected),
+      ast::Expr::ObjectLiteral(object) => self.object(object, expected),
+      ast::Expr::TemplateLiteral(template) => {
+        for expr in template.substitutions() {
+          self.infer(&expr, None);
+        }
+        Ty::String
+      }
+      ast::Expr::ArrowFn(function) => self.arrow_fn(function, expected),
+      ast::Expr::InjectExpr(inject) => {
+        self.inject(inject);
+        match inject.target() {
+          Some(target) => self.infer(&target, expected),
+          None => Ty::Unknown,
+        }
+      }
+      ast::Expr::CallExpr(call) => self.call(call, expected),
+      ast::Expr::FieldExpr(field) => {
+        let receiver = match field.receiver() {
+          Some(receiver) => self.infer(&receiver, None),
+          None => return Ty::Unknown,
+        };
+        match field.name_ref() {
+          Some(name) => self.field_type(&receiver, &name.text(), name.syntax().text_range()),
+          None => Ty::Unknown,
+        }
+      }
+      ast::Expr::IndexExpr(index) => {
+        let base = index.base().map(|base| self.infer(&base, None));
+        let index_ty = index.index().map(|index| self.infer(&index, None));
+        match (base, index_ty) {
+          (Some(base), index_ty) => self.index_type(&base, index_ty.as_ref()),
+          (None, _) => Ty::Unknown,
+        }
+      }
+      ast::Expr::PrefixExpr(prefix) => self.prefix(prefix),
+      ast::Expr::RefExpr(reference) => {
+        let expected = expected.map(|expected| match expected {
+          Ty::Ref(ty, _) => &**ty,
+          ty => ty,
+        });
+        match reference.expr() {
+          Some(expr) => Ty::Ref(Box::new(self.infer(&expr, expected)), reference.is_mut()),
+          None => Ty::Unknown,
+        }
+      }
+      ast::Expr::BinExpr(bin) => self.bin(bin),
+      ast::Expr::CastExpr(cast) => {
+        let ty = match cast.expr() {
+          Some(expr) => self.infer(&expr, None),
+          None => Ty::Unknown,
+        };
+        if cast.literal_token().is_some() {
+          return ty;
+        }
+        let target = match cast.ty() {
+          Some(target) => self.lower(&target),
+          None => Ty::Unknown,
+        };
+        if let Some(expr) = cast.expr() {
+          self.cast(&expr, &ty, &target);
+        }
+        target
+      }
+      ast::Expr::IfExpr(if_expr) => self.if_expr(if_expr, expected),
+      ast::Expr::MatchExpr(match_expr) => self.match_expr(match_expr, expected),
+      ast::Expr::ReturnExpr(ret) => {
+        let expected = self.frames.last().and_then(|frame| frame.ret.clone());
+        let ty = match (ret.expr(), &expected) {
+          (Some(expr), Some(expected)) => self.check_expr(&expr, expected),
+          (Some(expr), None) => self.infer(&expr, None),
+          (None, Some(expected)) => {
+            self.expect_assignable(&Ty::Unit, expected, ret.syntax().text_range());
+            Ty::Unit
+          }
+          (None, None) => Ty::Unit,
+        };
+        if let Some(frame) = self.frames.last_mut() {
+          frame.returns.push(ty);
+        }
+        Ty::Never
+      }
+      ast::Expr::BreakExpr(_) | ast::Expr::ContinueExpr(_) => Ty::Never,
+      ast::Expr::WhileExpr(while_expr) => {
+        self.invalidate(while_expr.syntax());
+        let narrowing = match while_expr.condition() {
+          Some(condition) => {
+            self.check_expr(&condition, &Ty::Bool);
+            self.narrowing(&condition).0
+          }
+          None => Vec::new(),
+        };
+        if let Some(body) = while_expr.body() {
+          self.with_narrowing(narrowing, |checker| {
+            checker.infer(&ast::Expr::Block(body), None)
+          });
+        }
+        Ty::Unit
+      }
+      ast::Expr::LoopExpr(loop_expr) => {
+        self.invalidate(loop_expr.syntax());
+        if let Some(body) = loop_expr.body() {
+          self.with_narrowing(Vec::new(), |checker| {
+            checker.infer(&ast::Expr::Block(body), None)
+          });
+        }
+        Ty::Unit
+      }
+      ast::Expr::ForExpr(for_expr) => {
+        let iterable = for_expr
+          .iterable()
+          .map(|iterable| self.infer(&iterable, None));
+        let elem = match iterable {
+          Some(iterable) => self.element_type(&iterable),
+          None => Ty::Unknown,
+        };
+        if let Some(symbol) = for_expr.name().and_then(|name| self.declared(&name)) {
+          self.symbol_types.insert(symbol, elem);
+        }
+        self.invalidate(for_expr.syntax());
+        if let Some(body) = for_expr.body() {
+          self.with_narrowing(Vec::new(), |checker| {
+            checker.infer(&ast::Expr::Block(body), None)
+          });
+        }
+        Ty::Unit
+      }
+      ast::Expr::Block(block) => self.block(block, expected),
+    }
+  }
+
+  /// The value of a block is the value of its last statement without `;`,
+  /// a block ending with a statement that never ends, e.g. `return`, never ends either.
+  pub(crate) fn block(&mut self, block: &ast::Block, expected: Option<&Ty>) -> Ty {
+    let tail = tail(block);
+    let mut diverges = false;
+    let mut ty = Ty::Unit;
+    for stmt in block.statements() {
+      let is_tail = match (&stmt, &tail) {
+        (ast::Stmt::ExprStmt(stmt), Some(tail)) => stmt.expr().as_ref() == Some(tail),
+        _ => false,
+      };
+      let stmt_ty = match (&stmt, is_tail) {
+        (ast::Stmt::ExprStmt(_), true) => self.infer(tail.as_ref().unwrap(), expected),
+        _ => self.stmt(&stmt),
+      };
+      diverges |= stmt_ty == Ty::Never;
+      if is_tail {
+        ty = stmt_ty;
+      }
+    }
+    if diverges {
+      Ty::Never
+    } else {
+      ty
+    }
+  }
+
+  fn path_expr(&mut self, path: &ast::PathExpr) -> Ty {
+    let name_ref = path
+      .path()
+      .filter(|path| path.segments().count() == 1)
+      .and_then(|path| path.last_segment()?.name_ref());
+    let name_ref = match name_ref {
+      Some(name_ref) => name_ref,
+      None => return Ty::Unknown,
+    };
+    match self.resolve_name(&name_ref) {
+      Ok(symbol) if self.symbols.symbol(symbol).kind == SymbolKind::Type => {
+        self.nominal_value(symbol, name_ref.text())
+      }
+      Ok(symbol) => self.symbol_type(symbol),
+      Err(true) if name_ref.text() == "None" => none(),
+      Err(_) => Ty::Unknown,
+    }
+  }
+
+  /// The value of the name of a nominal type: its value for a `Symbol()`, or the function building its values,
+  /// e.g. `None` is `None`, `Some` is `<T>(T) -> Some<T>` and `UserId` is `(u64) -> UserId`.
+  pub(crate) fn nominal_value(&self, symbol: SymbolId, name: String) -> Ty {
+    let alias = match self.aliases.get(&symbol) {
+      Some(alias) if alias.nominal => alias,
+      _ => return Ty::Unknown,
+    };
+    let args = alias.params.iter().cloned().map(Ty::Param).collect();
+    let ty = Ty::Named(Named { name, symbol, args });
+    let param = match &alias.ty {
+      Ty::Symbol(None) => return ty,
+      Ty::Symbol(Some(param)) => (**param).clone(),
+      // A nominal union is built from its variants.
+      Ty::Union(_) => return Ty::Unknown,
+      param => param.clone(),
+    };
+    Ty::Fn(FnTy {
+      generics: alias.params.clone(),
+      params: vec![param],
+      ret: Box::new(ty),
+    })
+  }
+
+  /// `[1, 2, 3]` is `[i32; 3]`, and `[1, 'a']` is `[i32, char]`, an array when all elements have the same type.
+  fn array(&mut self, array: &ast::ArrayExpr, expected: Option<&Ty>) -> Ty {
+    let expected = expected.map(|expected| self.expand(expected));
+    let mut types = Vec::new();
+    let mut spread = false;
+    for (i, element) in array.elements().enumerate() {
+      let expected = match &expected {
+        Some(Ty::Array(elem, _)) => Some(&**elem),
+        Some(Ty::Tuple(types)) => types.get(i),
+        _ => None,
+      };
+      match element {
+        ast::ArrayElement::Expr(expr) => {
+          let ty = self.infer(&expr, expected);
+          types.push(if expected.is_some() { ty } else { ty.widen() });
+        }
+        ast::ArrayElement::Spread(spread_expr) => {
+          spread = true;
+          let ty = match spread_expr.expr() {
+            Some(expr) => self.infer(&expr, None),
+            None => Ty::Unknown,
+          };
+          types.push(self.element_type(&ty));
+        }
+      }
+    }
+    if spread {
+      return Ty::Array(Box::new(Ty::union(types)), None);
+    }
+    if let Some(Ty::Tuple(_)) = expected {
+      return Ty::Tuple(types);
+    }
+    match types.first() {
+      None => Ty::Array(Box::new(Ty::Never), Some(0)),
+      Some(first) if types.iter().all(|ty| ty == first) => {
+        Ty::Array(Box::new(first.clone()), Some(types.len() as u64))
+      }
+      Some(_) => Ty::Tuple(types),
+    }
+  }
+
+  /// Checks the values of an injection against the types of the names the function captures,
+  /// e.g. `x: "ten"` in `inc{ x: "ten" }(2)` where `inc` captures `const x = 1`,
+  /// or against the type of its context parameter, e.g. `factor: f64` of `({ factor }: { factor: f64 }| v) => ..`.
+  fn inject(&mut self, inject: &ast::InjectExpr) {
+    let injected = injected(inject, self.symbols);
+    if let Some(injected) = &injected {
+      // Types the names of the context parameter of the function.
+      self.declared_type(injected.function);
+    }
+    for field in inject.fields() {
+      let name = match field.name_ref() {
+        Some(name) => name,
+        None => continue,
+      };
+      let captured = injected
+        .as_ref()
+        .and_then(|injected| *injected.captures.get(&name.text())?);
+      let expected = captured
+        .map(|symbol| self.declared_type(symbol).widen())
+        .filter(|ty| !ty.is_unknown());
+      let ty = match field.expr() {
+        Some(expr) => self.infer(&expr, expected.as_ref()),
+        // `{ x }` injects `x`.
+        None => match self.resolve_name(&name) {
+          Ok(symbol) => {
+            let ty = self.symbol_type(symbol);
+            self.record(name.syntax().text_range(), &ty);
+            ty
+          }
+          Err(_) => Ty::Unknown,
+        },
+      };
+      let (injected, expected) = match (&injected, expected) {
+        (Some(injected), Some(expected)) => (injected, expected),
+        _ => continue,
+      };
+      if self.assignable(&ty, &expected).is_err() {
+        let function = &self.symbols.symbol(injected.function).name;
+        let context =
+          captured.is_some_and(|symbol| self.symbols.symbol(symbol).kind == SymbolKind::Param);
+        let label = if context {
+          format!(
+            "`{}` takes `{}` in its context as `{}`",
+            function,
+            name.text(),
+            expected
+          )
+        } else {
+          format!(
+            "`{}` captures `{}` as `{}`",
+            function,
+            name.text(),
+            expected
+          )
+        };
+        self.diagnostics.push(
+          Diagnostic::error(
+            codes::MISMATCHED_TYPES,
+            format!("expected `{}`, found `{}`", expected, ty),
+            field.syntax().text_range(),
+          )
+          .with_label(label),
+        );
+      }
+    }
+  }
+
+  /// `{ name, age: 16 }`, or `Vocaloid { name, age: 16 }` whose type is `Vocaloid`.
+  fn object(&mut self, object: &ast::ObjectLiteral, expected: Option<&Ty>) -> Ty {
+    let typed = object.path().map(|path| {
+      let ty = ast::PathType::cast(path.syntax().parent().unwrap());
+      match ty {
+        // The path of a typed object literal is a `Path`, lowered as the path of a type.
+        Some(ty) => self.lower(&ast::Type::PathType(ty)),
+        None => self.named_path(&path),
+      }
+    });
+    let expected = typed
+      .as_ref()
+      .or(expected)
+      .map(|expected| self.expand(expected));
+    let expected_fields: &[Field] = match &expected {
+      Some(Ty::Object(fields)) => fields,
+      _ => &[],
+    };
+    let mut fields: Vec<Field> = Vec::new();
+    for member in object.members() {
+      match member {
+        ast::ObjectMember::Field(field) => {
+          let name = match field.name_ref() {
+            Some(name) => name,
+            None => continue,
+          };
+          let expected = expected_fields
+            .iter()
+            .find(|field| field.name == name.text())
+            .map(|field| field.ty.clone());
+          let ty = match (field.expr(), &expected) {
+            (Some(expr), Some(expected)) => self.check_part(&expr, Some(expected)),
+            (Some(expr), None) => self.infer(&expr, None).widen(),
+            // `{ x }` is `{ x: x }`.
+            (None, _) => match self.resolve_name(&name) {
+              Ok(symbol) => {
+                let ty = self.symbol_type(symbol);
+                self.record(name.syntax().text_range(), &ty);
+                match &expected {
+                  Some(expected) if self.assignable(&ty, expected).is_ok() => ty,
+                  Some(expected) => {
+                    self.expect_assignable(&ty, expected, name.syntax().text_range());
+                    expected.clone()
+                  }
+                  None => ty.widen(),
+                }
+              }
+              Err(_) => Ty::Unknown,
+            },
+          };
+          fields.retain(|field| field.name != name.text());
+          fields.push(Field {
+            name: name.text(),
+            ty,
+            optional: false,
+          });
+        }
+        ast::ObjectMember::Spread(spread) => {
+          let ty = match spread.expr() {
+            Some(expr) => self.infer(&expr, None),
+            None => Ty::Unknown,
+          };
+          for member in self.members(&ty) {
+            if let Ty::Object(more) = member {
+              for field in more {
+                fields.retain(|merged| merged.name != field.name);
+                fields.push(field);
+              }
+            }
+          }
+        }
+      }
+    }
+    let ty = Ty::object(fields);
+    match typed {
+      // The object is built as its type, even a nominal one, with the generic arguments it has.
+      Some(typed) => {
+        let typed = self.infer_args(typed, &ty);
+        let structure = self.structure(&typed);
+        self.expect_assignable(&ty, &structure, object.syntax().text_range());
+        typed
+      }
+      None => ty,
+    }
+  }
+
+  /// `named` with the generic arguments it doesn't have bound to the parts of `value`,
+  /// e.g. `Pair<i32>` for `Pair { first: 1, second: 2 }`.
+  fn infer_args(&self, named: Ty, value: &Ty) -> Ty {
+    let mut named = match named {
+      Ty::Named(named) if named.args.is_empty() => named,
+      ty => return ty,
+    };
+    let alias = match self.aliases.get(&named.symbol) {
+      Some(alias) if !alias.params.is_empty() => alias,
+      _ => return Ty::Named(named),
+    };
+    let mut bindings = HashMap::new();
+    bind_params(&alias.ty, value, &mut bindings);
+    named.args = alias
+      .params
+      .iter()
+      .map(|param| {
+        bindings
+          .remove(&param.symbol)
+          .map_or(Ty::Unknown, |ty| ty.widen())
+      })
+      .collect();
+    Ty::Named(named)
+  }
+
+  /// The type named by the path of a typed object literal.
+  fn named_path(&mut self, path: &ast::Path) -> Ty {
+    let name_ref = match path.last_segment().and_then(|segment| segment.name_ref()) {
+      Some(name_ref) if path.segments().count() == 1 => name_ref,
+      _ => return Ty::Unknown,
+    };
+    match self.resolve_name(&name_ref) {
+      Ok(symbol) if self.aliases.contains_key(&symbol) => Ty::Named(Named {
+        name: name_ref.text(),
+        symbol,
+        args: Vec::new(),
+      }),
+      Err(true) => builtin_type(&name_ref.text(), Vec::new()),
+      _ => Ty::Unknown,
+    }
+  }
+
+  /// `(v: i32) => v + 1`, whose parameters without annotation have the types of the expected function type, if any.
+  fn arrow_fn(&mut self, function: &ast::ArrowFn, expected: Option<&Ty>) -> Ty {
+    let expected = expected.map(|expected| self.expand(expected));
+    let (expected_params, expected_ret) = match &expected {
+      Some(Ty::Fn(ty)) => (ty.params.clone(), Some((*ty.ret).clone())),
+      _ => (Vec::new(), None),
+    };
+    let generics = self.generic_params(function.generic_param_list());
+    let params = self.params(function.param_list(), &expected_params);
+    let annotated = function.ret_type().map(|ret| match ret.ty() {
+      Some(ty) => self.lower(&ty),
+      None => Ty::Unknown,
+    });
+    // A function returning nothing can be used where a function returning a value is expected, not the opposite.
+    let ret = match (annotated, expected_ret) {
+      (Some(ret), _) => Some(ret),
+      (None, Some(Ty::Unit)) | (None, None) => None,
+      (None, Some(expected)) if contains_param(&expected) => None,
+      (None, Some(expected)) => Some(expected),
+    };
+    let ret = match function.body() {
+      Some(body) => self.fn_body(&body, ret),
+      None => ret.unwrap_or(Ty::Unknown),
+    };
+    Ty::Fn(FnTy {
+      generics,
+      params,
+      ret: Box::new(ret),
+    })
+  }
+
+  /// `f(a)`, `Some(a)`, `f<i32>(a)`
+  fn call(&mut self, call: &ast::CallExpr, expected: Option<&Ty>) -> Ty {
+    let args: Vec<_> = call
+      .arg_list()
+      .into_iter()
+      .flat_map(|list| list.args())
+      .collect();
+    let callee = match call.callee() {
+      Some(callee) => callee,
+      None => return Ty::Unknown,
+    };
+    if let Some(name) = self.constructor(&callee) {
+      self.record(callee.syntax().text_range(), &Ty::Unknown);
+      let types: Vec<_> = args
+        .iter()
+        .map(|arg| self.infer(arg, None).widen())
+        .collect();
+      return Ty::Builtin(name, types);
+    }
+    let callee_ty = match &callee {
+      ast::Expr::FieldExpr(field) => self.method(field),
+      callee => self.infer(callee, None),
+    };
+    let function = self
+      .members(&callee_ty)
+      .into_iter()
+      .find_map(|ty| match ty {
+        Ty::Fn(ty) => Some(ty),
+        _ => None,
+      });
+    let function = match function {
+      Some(function) => function,
+      None => {
+        let unknown = matches!(
+          self.expand(&callee_ty),
+          Ty::Unknown | Ty::Any | Ty::Param(_) | Ty::Builtin(..)
+        );
+        if !unknown {
+          self.diagnostics.push(
+            Diagnostic::error(
+              codes::NOT_CALLABLE,
+              format!("`{}` is not a function", callee_ty),
+              callee.syntax().text_range(),
+            )
+            .with_label("called here"),
+          );
+        }
+        for arg in &args {
+          self.infer(arg, None);
+        }
+        return Ty::Unknown;
+      }
+    };
+    let (function, types) = self.instantiate(call, function, &args, expected);
+    if args.len() != function.params.len() {
+      let span = call.arg_list().map_or(call.syntax().text_range(), |list| {
+        list.syntax().text_range()
+      });
+      self.diagnostics.push(
+        Diagnostic::error(
+          codes::WRONG_ARG_COUNT,
+          format!(
+            "expected {} argument{}, found {}",
+            function.params.len(),
+            if function.params.len() == 1 { "" } else { "s" },
+            args.len()
+          ),
+          span,
+        )
+        .with_label(format!("`{}`", Ty::Fn(function.clone()))),
+      );
+    }
+    for ((arg, ty), param) in args.iter().zip(&types).zip(&function.params) {
+      self.expect_assignable(ty, param, tail_span(arg));
+    }
+    *function.ret
+  }
+
+  /// The name of the builtin constructor called by `callee`, e.g. `Some`.
+  fn constructor(&self, callee: &ast::Expr) -> Option<String> {
+    let path = match callee {
+      ast::Expr::PathExpr(path) => path.path()?,
+      _ => return None,
+    };
+    let name_ref = path.last_segment()?.name_ref()?;
+    let name = name_ref.text();
+    let builtin = self.resolve_name(&name_ref) == Err(true);
+    if builtin && path.segments().count() == 1 && CONSTRUCTORS.contains(&name.as_str()) {
+      Some(name)
+    } else {
+      None
+    }
+  }
+
+  /// The type of the elements of an array, a tuple or a `Vec`.
+  pub(crate) fn element_type(&self, ty: &Ty) -> Ty {
+    let types = self
+      .members(ty)
+      .into_iter()
+      .map(|ty| match self.structure(&ty) {
+        Ty::Array(elem, _) => *elem,
+        Ty::Tuple(types) => Ty::union(types),
+        Ty::Builtin(name, args) if name == "Vec" => args.into_iter().next().unwrap_or(Ty::Unknown),
+        _ => Ty::Unknown,
+      });
+    Ty::union(types.collect::<Vec<_>>())
+  }
+
+  /// `xs[0]`, a literal index of a tuple is its element.
+  fn index_type(&self, base: &Ty, index: Option<&Ty>) -> Ty {
+    if let (Ty::Tuple(types), Some(Ty::Literal(Lit::Int { value, .. }))) =
+      (self.expand(base), index)
+    {
+      if let Some(ty) = types.get(*value as usize) {
+        return ty.clone();
+      }
+    }
+    self.element_type(base)
+  }
+
+  fn prefix(&mut self, prefix: &ast::PrefixExpr) -> Ty {
+    let ty = match prefix.expr() {
+      Some(expr) => self.infer(&expr, None),
+      None => return Ty::Unknown,
+    };
+    match prefix.op() {
+      Some(PrefixOp::Neg) => match ty {
+        Ty::Literal(Lit::Int { value, ty }) => Ty::Literal(Lit::Int { value: -value, ty }),
+        _ if self.is_numeric(&ty) => ty,
+        _ => {
+          self.invalid_operand(prefix.syntax().text_range(), "-", &ty);
+          Ty::Unknown
+        }
+      },
+      Some(PrefixOp::Not) => match self.expand(&ty) {
+        Ty::Literal(Lit::Bool(value)) => Ty::Literal(Lit::Bool(!value)),
+        Ty::Int(_) => ty,
+        _ => {
+          if let Some(expr) = prefix.expr() {
+            self.expect_assignable(&ty, &Ty::Bool, expr.syntax().text_range());
+          }
+          Ty::Bool
+        }
+      },
+      Some(PrefixOp::Deref) => match ty {
+        Ty::Ref(ty, _) => *ty,
+        _ => Ty::Unknown,
+      },
+      None => Ty::Unknown,
+    }
+  }
+
+  fn bin(&mut self, bin: &ast::BinExpr) -> Ty {
+    let op = match bin.op() {
+      Some(op) => op,
+      None => return Ty::Unknown,
+    };
+    let text = bin
+      .op_token()
+      .map_or(String::new(), |token| token.text().to_string());
+    let span = bin.syntax().text_range();
+    if op == BinOp::Assign {
+      let target = bin.lhs().map(|lhs| self.place(&lhs));
+      let ty = match (bin.rhs(), target) {
+        (Some(rhs), Some(target)) => self.check_expr(&rhs, &target),
+        (Some(rhs), None) => self.infer(&rhs, None),
+        _ => Ty::Unknown,
+      };
+      if let Some(lhs) = bin.lhs() {
+        self.assign(&lhs, &ty);
+      }
+      return Ty::Unit;
+    }
+    let lhs = match bin.lhs() {
+      Some(lhs) => self.infer(&lhs, None),
+      None => Ty::Unknown,
+    };
+    let rhs = match bin.rhs() {
+      Some(rhs) if op.is_logical() => self.narrowed_rhs(bin, &rhs),
+      Some(rhs) => self.infer(&rhs, None),
+      None => Ty::Unknown,
+    };
+    match op {
+      // `x += 1` reads the narrowed value of `x`, and stores a value of its declared type.
+      _ if op.is_assignment() => {
+        let ty = self.arithmetic(span, text.trim_end_matches('='), &lhs, &rhs);
+        if let Some(target) = bin.lhs() {
+          let declared = match self.place_symbol(&target) {
+            Some(symbol) => self.declared_type(symbol),
+            None => lhs,
+          };
+          self.expect_assignable(&ty, &declared, span);
+          self.assign(&target, &ty);
+        }
+        Ty::Unit
+      }
+      BinOp::Or | BinOp::And => {
+        for (side, ty) in [(bin.lhs(), &lhs), (bin.rhs(), &rhs)] {
+          if let Some(side) = side {
+            self.expect_assignable(ty, &Ty::Bool, side.syntax().text_range());
+          }
+        }
+        Ty::Bool
+      }
+      BinOp::Eq | BinOp::NotEq => {
+        let comparable = lhs.is_unknown()
+          || rhs.is_unknown()
+          || self.assignable(&lhs, &rhs).is_ok()
+          || self.assignable(&rhs, &lhs).is_ok();
+        if !comparable {
+          self.invalid_operands(span, &text, &lhs, &rhs);
+        }
+        Ty::Bool
+      }
+      BinOp::Lt | BinOp::Gt | BinOp::LtEq | BinOp::GtEq => {
+        self.arithmetic(span, &text, &lhs, &rhs);
+        Ty::Bool
+      }
+      _ => self.arithmetic(span, &text, &lhs, &rhs),
+    }
+  }
+
+  /// The type of the right side of `&&` or `||`, with the narrowing of the left side,
+  /// e.g. `x != None && x > 1`.
+  fn narrowed_rhs(&mut self, bin: &ast::BinExpr, rhs: &ast::Expr) -> Ty {
+    let narrowing = match bin.lhs() {
+      Some(lhs) => {
+        let (then, otherwise) = self.narrowing(&lhs);
+        if bin.op() == Some(BinOp::And) {
+          then
+        } else {
+          otherwise
+        }
+      }
+      None => Vec::new(),
+    };
+    self.with_narrowing(narrowing, |checker| checker.infer(rhs, None))
+  }
+
+  /// The type of a value assigned to `place`, its declared type rather than its narrowed one.
+  fn place(&mut self, place: &ast::Expr) -> Ty {
+    if let Some(symbol) = self.place_symbol(place) {
+      let ty = self.declared_type(symbol);
+      self.record(place.syntax().text_range(), &ty);
+      return ty;
+    }
+    self.infer(place, None)
+  }
+
+  /// The symbol named by `place`, when it is a name.
+  fn place_symbol(&self, place: &ast::Expr) -> Option<SymbolId> {
+    let path = match place {
+      ast::Expr::PathExpr(path) => path.path()?,
+      _ => return None,
+    };
+    let name_ref = path
+      .last_segment()
+      .filter(|_| path.segments().count() == 1)?
+      .name_ref()?;
+    self.resolve_name(&name_ref).ok()
+  }
+
+  /// The type of an arithmetic operation, a literal takes the type of the other side, e.g. `x + 1` is `u8` for `x: u8`.
+  fn arithmetic(&mut self, span: Span, op: &str, lhs: &Ty, rhs: &Ty) -> Ty {
+    let (lhs_ty, rhs_ty) = (self.expand(lhs).widen(), self.expand(rhs).widen());
+    let (lhs_ty, rhs_ty) = (strip_ref(lhs_ty), strip_ref(rhs_ty));
+    match (&lhs_ty, &rhs_ty) {
+      (Ty::Unknown, _) | (_, Ty::Unknown) | (Ty::Any, _) | (_, Ty::Any) => Ty::Unknown,
+      _ if op == "+" && is_string(&lhs_ty) && is_string(&rhs_ty) => Ty::String,
+      (Ty::Int(_), Ty::Int(_)) | (Ty::Float(_), Ty::Float(_)) if lhs_ty == rhs_ty => lhs_ty,
+      (Ty::Int(ty), _) if is_int_literal(rhs) && self.assignable(rhs, &lhs_ty).is_ok() => {
+        Ty::Int(*ty)
+      }
+      (_, Ty::Int(ty)) if is_int_literal(lhs) && self.assignable(lhs, &rhs_ty).is_ok() => {
+        Ty::Int(*ty)
+      }
+      _ => {
+        self.invalid_operands(span, op, lhs, rhs);
+        Ty::Unknown
+      }
+    }
+  }
+
+  fn is_numeric(&self, ty: &Ty) -> bool {
+    matches!(
+      strip_ref(self.expand(ty).widen()),
+      Ty::Int(_) | Ty::Float(_) | Ty::Unknown | Ty::Any
+    )
+  }
+
+  fn invalid_operands(&mut self, span: Span, op: &str, lhs: &Ty, rhs: &Ty) {
+    self.diagnostics.push(
+      Diagnostic::error(
+        codes::INVALID_OPERANDS,
+        format!("cannot apply `{}` to `{}` and `{}`", op, lhs, rhs),
+        span,
+      )
+      .with_label(format!("`{}` {} `{}`", lhs.widen(), op, rhs.widen())),
+    );
+  }
+
+  fn invalid_operand(&mut self, span: Span, op: &str, ty: &Ty) {
+    self.diagnostics.push(
+      Diagnostic::error(
+        codes::INVALID_OPERANDS,
+        format!("cannot apply `{}` to `{}`", op, ty),
+        span,
+      )
+      .with_label(format!("`{}` is not a number", ty)),
+    );
+  }
+}
+
+/// The type of a literal, a literal type for booleans, characters, strings and integers without suffix.
+pub(crate) fn literal_type(literal: &ast::Literal) -> Ty {
+  let token = match literal.token() {
+    Some(token) => token,
+    None => return Ty::Unknown,
+  };
+  let text = token.text();
+  match literal.kind() {
+    Some(LiteralKind::Bool(value)) => Ty::Literal(Lit::Bool(value)),
+    Some(LiteralKind::Int) => int_literal(text),
+    Some(LiteralKind::Float) => {
+      let ty = [FloatTy::F32, FloatTy::F64]
+        .iter()
+        .copied()
+        .find(|ty| text.ends_with(ty.name()));
+      Ty::Float(ty.unwrap_or(FloatTy::F64))
+    }
+    Some(LiteralKind::Char) => match unquote(text).chars().next() {
+      Some(value) => Ty::Literal(Lit::Char(value)),
+      None => Ty::Char,
+    },
+    Some(LiteralKind::Byte) => Ty::Int(IntTy::U8),
+    Some(LiteralKind::String) => match token.kind() {
+      SyntaxKind::String => Ty::Literal(Lit::Str(unquote(text))),
+      _ => Ty::Str,
+    },
+    Some(LiteralKind::ByteString) => Ty::Ref(
+      Box::new(Ty::Array(Box::new(Ty::Int(IntTy::U8)), None)),
+      false,
+    ),
+    None => Ty::Unknown,
+  }
+}
+
+/// `1` is `1` widening to `i32`, `0xff` is `255` widening to `u32`, and `57u64` is `u64`.
+fn int_literal(text: &str) -> Ty {
+  let text = text.replace('_', "");
+  let (radix, digits) = match text.get(..2) {
+    Some("0x") => (16, &text[2..]),
+    Some("0o") => (8, &text[2..]),
+    Some("0b") => (2, &text[2..]),
+    _ => (10, &text[..]),
+  };
+  let suffix_start = digits.find(['i', 'u']).unwrap_or(digits.len());
+  let (digits, suffix) = digits.split_at(suffix_start);
+  if let Some(ty) = IntTy::from_name(suffix) {
+    return Ty::Int(ty);
+  }
+  let value = match i128::from_str_radix(digits, radix) {
+    Ok(value) => value,
+    Err(_) => return Ty::Unknown,
+  };
+  let ty = match (radix, value) {
+    (10, value) if IntTy::I32.fits(value) => IntTy::I32,
+    (10, _) => IntTy::I64,
+    (_, value) if IntTy::U32.fits(value) => IntTy::U32,
+    _ => IntTy::U64,
+  };
+  Ty::Literal(Lit::Int { value, ty })
+}
+
+/// The value of a character or string literal, e.g. `a\n` for `"a\n"`.
+fn unquote(text: &str) -> String {
+  let inner = &text[1..text.len().saturating_sub(1).max(1)];
+  let mut value = String::new();
+  let mut chars = inner.chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      value.push(c);
+      continue;
+    }
+    match chars.next() {
+      Some('n') => value.push('\n'),
+      Some('t') => value.push('\t'),
+      Some('r') => value.push('\r'),
+      Some('0') => value.push('\0'),
+      Some(c) => value.push(c),
+      None => (),
+    }
+  }
+  value
+}
+
+pub(crate) fn contains_param(ty: &Ty) -> bool {
+  match ty {
+    Ty::Param(_) => true,
+    Ty::Object(fields) => fields.iter().any(|field| contains_param(&field.ty)),
+    Ty::Tuple(types) | Ty::Union(types) | Ty::Intersection(types) => {
+      types.iter().any(contains_param)
+    }
+    Ty::Array(ty, _) | Ty::Ref(ty, _) => contains_param(ty),
+    Ty::Fn(ty) => ty.params.iter().any(contains_param) || contains_param(&ty.ret),
+    Ty::Named(named) => named.args.iter().any(contains_param),
+    Ty::Builtin(_, args) => args.iter().any(contains_param),
+    Ty::Keyof(ty) => contains_param(ty),
+    Ty::Index(ty, index) => contains_param(ty) || contains_param(index),
+    Ty::Conditional(ty) => {
+      contains_param(&ty.check)
+        || contains_param(&ty.extends)
+        || contains_param(&ty.then)
+        || contains_param(&ty.otherwise)
+    }
+    Ty::Mapped(ty) => contains_param(&ty.keys) || contains_param(&ty.value),
+    _ => false,
+  }
+}
+
+fn strip_ref(ty: Ty) -> Ty {
+  match ty {
+    Ty::Ref(ty, _) => strip_ref(*ty),
+    ty => ty,
+  }
+}
+
+fn is_string(ty: &Ty) -> bool {
+  matches!(ty, Ty::Str | Ty::String)
+}
+
+fn is_int_literal(ty: &Ty) -> bool {
+  matches!(ty, Ty::Literal(Lit::Int { .. }))
+}
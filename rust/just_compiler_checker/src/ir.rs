@@ -0,0 +1,1184 @@
+//! Bodies of functions lowered to control-flow graphs of assignments to places, for the ownership analysis.
+//!
+//! Every value is held by a local: a variable, a parameter, a value captured by a closure,
+//! or a temporary holding the value of an expression. Using a place by value copies it,
+//! or moves it when its type can't be copied, e.g. an object or a `String`.
+//! Operators, conditions and comparisons only read their operands.
+
+use super::check::{tail, tail_span, Checker};
+use super::ty::Ty;
+use justc_binder::symbols::{SymbolId, SymbolKind};
+use justc_lexer::span::Span;
+use justc_parser::ast::{self, AstNode, HasName};
+use justc_parser::SyntaxNode;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Types are expanded at most this many times to know if they can be copied.
+const MAX_DEPTH: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct LocalId(pub(crate) usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct BlockId(pub(crate) usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LocalKind {
+  Var,
+  Param,
+  /// A value of the enclosing function captured by a closure, which the closure doesn't own.
+  Capture,
+  /// The value of an expression.
+  Temp,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Local {
+  pub(crate) name: String,
+  pub(crate) kind: LocalKind,
+  pub(crate) ty: Ty,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Projection {
+  Field(String),
+  /// The value a reference points to, `*r`, or `r.x` for a reference `r`.
+  Deref,
+  /// An element of an array, any of them.
+  Index,
+}
+
+/// A local or a part of it, e.g. `miku.name`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Place {
+  pub(crate) local: LocalId,
+  pub(crate) projection: Vec<Projection>,
+}
+
+impl Place {
+  pub(crate) fn local(local: LocalId) -> Place {
+    Place {
+      local,
+      projection: Vec::new(),
+    }
+  }
+
+  fn project(mut self, projection: Projection) -> Place {
+    self.projection.push(projection);
+    self
+  }
+
+  /// True if `self` contains `other`, e.g. `miku` contains `miku.name`.
+  pub(crate) fn contains(&self, other: &Place) -> bool {
+    self.local == other.local && other.projection.starts_with(&self.projection)
+  }
+
+  /// True if the places share a value, one contains the other.
+  pub(crate) fn overlaps(&self, other: &Place) -> bool {
+    self.contains(other) || other.contains(self)
+  }
+
+  /// True if the place is reached through a reference, so its value isn't owned by its local.
+  pub(crate) fn is_deref(&self) -> bool {
+    self.projection.contains(&Projection::Deref)
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Operand {
+  Copy(Place, Span),
+  Move(Place, Span),
+  /// A value which isn't held by a local of the body, e.g. a literal or a function.
+  Const,
+}
+
+impl Operand {
+  pub(crate) fn place(&self) -> Option<&Place> {
+    match self {
+      Operand::Copy(place, _) | Operand::Move(place, _) => Some(place),
+      Operand::Const => None,
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Rvalue {
+  Use(Operand),
+  /// `&place`, or `&mut place` when mutable.
+  Ref(Place, bool, Span),
+  /// An object, an array or a closure, holding the values of the operands.
+  Aggregate(Vec<Operand>),
+  /// A call of the first operand with the others, which may return references to them when `true`.
+  Call(Vec<Operand>, bool),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Statement {
+  Assign(Place, Rvalue, Span),
+  /// Reads a value without using it, e.g. a condition.
+  Eval(Operand),
+  /// The local goes out of scope at the end of its block.
+  StorageDead(LocalId, Span),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Terminator {
+  Goto(BlockId),
+  /// Goes to one of the blocks, depending on the operand if any.
+  Switch(Option<Operand>, Vec<BlockId>),
+  Return(Operand, Span),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Block {
+  pub(crate) statements: Vec<Statement>,
+  pub(crate) terminator: Terminator,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Body {
+  pub(crate) locals: Vec<Local>,
+  pub(crate) blocks: Vec<Block>,
+}
+
+impl Body {
+  pub(crate) fn local(&self, local: LocalId) -> &Local {
+    &self.locals[local.0]
+  }
+
+  /// The blocks the terminator of `block` goes to.
+  pub(crate) fn successors(&self, block: BlockId) -> Vec<BlockId> {
+    match &self.blocks[block.0].terminator {
+      Terminator::Goto(target) => vec![*target],
+      Terminator::Switch(_, targets) => targets.clone(),
+      Terminator::Return(..) => Vec::new(),
+    }
+  }
+
+  /// `place` as written in the source, e.g. `miku.name`, or `*r`.
+  pub(crate) fn place_name(&self, place: &Place) -> String {
+    let local = self.local(place.local);
+    if local.kind == LocalKind::Temp {
+      return "temporary value".to_string();
+    }
+    let mut name = local.name.clone();
+    let mut deref = false;
+    for projection in &place.projection {
+      match projection {
+        Projection::Field(field) => {
+          name.push('.');
+          name.push_str(field);
+          deref = false;
+        }
+        Projection::Deref => deref = true,
+        Projection::Index => {
+          name.push_str("[..]");
+          deref = false;
+        }
+      }
+    }
+    if deref {
+      format!("*{}", name)
+    } else {
+      name
+    }
+  }
+
+  /// The body in a readable form, for tests.
+  #[cfg(test)]
+  pub(crate) fn debug_dump(&self) -> String {
+    let mut out = String::new();
+    for (i, local) in self.locals.iter().enumerate() {
+      let kind = match local.kind {
+        LocalKind::Var => "let",
+        LocalKind::Param => "param",
+        LocalKind::Capture => "capture",
+        LocalKind::Temp => "temp",
+      };
+      out.push_str(&format!("{} _{} {}: {}\n", kind, i, local.name, local.ty));
+    }
+    for (i, block) in self.blocks.iter().enumerate() {
+      out.push_str(&format!("bb{}:\n", i));
+      for statement in &block.statements {
+        out.push_str(&format!("  {}\n", DisplayStatement(statement)));
+      }
+      let terminator = match &block.terminator {
+        Terminator::Goto(target) => format!("goto bb{}", target.0),
+        Terminator::Switch(operand, targets) => {
+          let targets: Vec<_> = targets
+            .iter()
+            .map(|target| format!("bb{}", target.0))
+            .collect();
+          match operand {
+            Some(operand) => format!("switch {} -> [{}]", operand, targets.join(", ")),
+            None => format!("switch -> [{}]", targets.join(", ")),
+          }
+        }
+        Terminator::Return(operand, _) => format!("return {}", operand),
+      };
+      out.push_str(&format!("  {}\n", terminator));
+    }
+    out
+  }
+}
+
+impl fmt::Display for Place {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mut text = format!("_{}", self.local.0);
+    for projection in &self.projection {
+      match projection {
+        Projection::Field(field) => text = format!("{}.{}", text, field),
+        Projection::Deref => text = format!("(*{})", text),
+        Projection::Index => text = format!("{}[..]", text),
+      }
+    }
+    f.write_str(&text)
+  }
+}
+
+impl fmt::Display for Operand {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Operand::Copy(place, _) => write!(f, "copy {}", place),
+      Operand::Move(place, _) => write!(f, "move {}", place),
+      Operand::Const => f.write_str("const"),
+    }
+  }
+}
+
+#[cfg(test)]
+struct DisplayStatement<'s>(&'s Statement);
+
+#[cfg(test)]
+impl fmt::Display for DisplayStatement<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let list = |operands: &[Operand]| {
+      let operands: Vec<_> = operands.iter().map(Operand::to_string).collect();
+      operands.join(", ")
+    };
+    match self.0 {
+      Statement::Assign(place, rvalue, _) => match rvalue {
+        Rvalue::Use(operand) => write!(f, "{} = {}", place, operand),
+        Rvalue::Ref(borrowed, false, _) => write!(f, "{} = &{}", place, borrowed),
+        Rvalue::Ref(borrowed, true, _) => write!(f, "{} = &mut {}", place, borrowed),
+        Rvalue::Aggregate(operands) => write!(f, "{} = {{ {} }}", place, list(operands)),
+        Rvalue::Call(operands, _) => {
+          write!(
+            f,
+            "{} = call {}({})",
+            place,
+            operands[0],
+            list(&operands[1..])
+          )
+        }
+      },
+      Statement::Eval(operand) => write!(f, "eval {}", operand),
+      Statement::StorageDead(local, _) => write!(f, "dead _{}", local.0),
+    }
+  }
+}
+
+/// Lowers the functions and closures of `file`, and its top-level statements as a body of their own.
+pub(crate) fn lower_file(checker: &Checker, file: &ast::SourceFile) -> Vec<Body> {
+  let mut bodies = Vec::new();
+  let mut builder = Builder::new(checker, &mut bodies);
+  for stmt in file.statements() {
+    builder.stmt(&stmt);
+  }
+  let end = Span::empty(file.syntax().text_range().end);
+  builder.terminate(Terminator::Return(Operand::Const, end));
+  let body = builder.finish();
+  bodies.insert(0, body);
+  bodies
+}
+
+struct Loop {
+  continue_target: BlockId,
+  break_target: BlockId,
+  /// The number of scopes outside of the loop.
+  depth: usize,
+}
+
+struct Builder<'c, 'a, 'b> {
+  checker: &'c Checker<'a>,
+  locals: Vec<Local>,
+  /// Blocks whose terminator is `None` while they are built.
+  blocks: Vec<(Vec<Statement>, Option<Terminator>)>,
+  current: BlockId,
+  /// The locals of the variables of the body.
+  symbols: HashMap<SymbolId, LocalId>,
+  /// The variables declared in each enclosing block, which go out of scope at its end.
+  scopes: Vec<Vec<LocalId>>,
+  loops: Vec<Loop>,
+  /// The bodies of the functions and closures declared in the body.
+  bodies: &'b mut Vec<Body>,
+}
+
+impl<'c, 'a, 'b> Builder<'c, 'a, 'b> {
+  fn new(checker: &'c Checker<'a>, bodies: &'b mut Vec<Body>) -> Self {
+    Builder {
+      checker,
+      locals: Vec::new(),
+      blocks: vec![(Vec::new(), None)],
+      current: BlockId(0),
+      symbols: HashMap::new(),
+      scopes: vec![Vec::new()],
+      loops: Vec::new(),
+      bodies,
+    }
+  }
+
+  fn finish(self) -> Body {
+    let blocks = self
+      .blocks
+      .into_iter()
+      .map(|(statements, terminator)| Block {
+        statements,
+        // Blocks after a `return` or a `break` aren't reachable.
+        terminator: terminator.unwrap_or(Terminator::Switch(None, Vec::new())),
+      });
+    Body {
+      locals: self.locals,
+      blocks: blocks.collect(),
+    }
+  }
+
+  fn new_local(&mut self, name: String, kind: LocalKind, ty: Ty) -> LocalId {
+    self.locals.push(Local { name, kind, ty });
+    LocalId(self.locals.len() - 1)
+  }
+
+  /// Declares the variable `symbol` in the current scope.
+  fn declare(&mut self, symbol: SymbolId, kind: LocalKind) -> LocalId {
+    let name = self.checker.symbols.symbol(symbol).name.clone();
+    let ty = self.symbol_type(symbol);
+    let local = self.new_local(name, kind, ty);
+    self.symbols.insert(symbol, local);
+    if kind == LocalKind::Var {
+      self.scopes.last_mut().unwrap().push(local);
+    }
+    local
+  }
+
+  fn temp(&mut self, ty: Ty) -> LocalId {
+    self.new_local(String::new(), LocalKind::Temp, ty)
+  }
+
+  fn new_block(&mut self) -> BlockId {
+    self.blocks.push((Vec::new(), None));
+    BlockId(self.blocks.len() - 1)
+  }
+
+  fn push(&mut self, statement: Statement) {
+    self.blocks[self.current.0].0.push(statement);
+  }
+
+  fn assign(&mut self, place: Place, rvalue: Rvalue, span: Span) {
+    self.push(Statement::Assign(place, rvalue, span));
+  }
+
+  fn terminate(&mut self, terminator: Terminator) {
+    let block = &mut self.blocks[self.current.0];
+    if block.1.is_none() {
+      block.1 = Some(terminator);
+    }
+  }
+
+  fn goto(&mut self, target: BlockId) {
+    self.terminate(Terminator::Goto(target));
+    self.current = target;
+  }
+
+  fn symbol_type(&self, symbol: SymbolId) -> Ty {
+    let ty = self.checker.symbol_types.get(&symbol);
+    ty.cloned().unwrap_or(Ty::Unknown)
+  }
+
+  fn expr_type(&self, expr: &ast::Expr) -> Ty {
+    let span = expr.syntax().text_range();
+    let types = &self.checker.types;
+    let i = types.partition_point(|(expr, _)| *expr < span);
+    match types.get(i) {
+      Some((expr, ty)) if *expr == span => ty.clone(),
+      _ => Ty::Unknown,
+    }
+  }
+
+  /// Uses the value of `place` of type `ty`, moving it unless it can be copied.
+  /// A value behind a reference is copied, it can't be moved out of it.
+  fn operand(&self, place: Place, span: Span, ty: &Ty) -> Operand {
+    if place.is_deref() || self.checker.is_copy(ty) {
+      Operand::Copy(place, span)
+    } else {
+      Operand::Move(place, span)
+    }
+  }
+
+  /// Holds `rvalue` in a new temporary.
+  fn temp_operand(&mut self, rvalue: Rvalue, ty: Ty, span: Span) -> Operand {
+    let copy = self.checker.is_copy(&ty);
+    let temp = self.temp(ty);
+    self.assign(Place::local(temp), rvalue, span);
+    if copy {
+      Operand::Copy(Place::local(temp), span)
+    } else {
+      Operand::Move(Place::local(temp), span)
+    }
+  }
+
+  fn stmt(&mut self, stmt: &ast::Stmt) {
+    match stmt {
+      ast::Stmt::LetDecl(decl) => {
+        let init = decl.initializer().map(|init| self.expr(&init));
+        let symbol = decl.name().and_then(|name| self.checker.declared(&name));
+        if let Some(symbol) = symbol {
+          let local = self.declare(symbol, LocalKind::Var);
+          if let Some(init) = init {
+            self.assign(
+              Place::local(local),
+              Rvalue::Use(init),
+              decl.syntax().text_range(),
+            );
+          }
+        }
+      }
+      ast::Stmt::FnDecl(decl) => {
+        let mut bodies = Vec::new();
+        let mut builder = Builder::new(self.checker, &mut bodies);
+        builder.params(decl.param_list());
+        if let Some(body) = decl.body() {
+          let value = builder.block(&body);
+          let span = tail_span(&ast::Expr::Block(body));
+          builder.terminate(Terminator::Return(value, span));
+        }
+        let body = builder.finish();
+        self.bodies.push(body);
+        self.bodies.extend(bodies);
+      }
+      ast::Stmt::ExprStmt(stmt) => {
+        if let Some(expr) = stmt.expr() {
+          let value = self.read(&expr);
+          self.push(Statement::Eval(value));
+        }
+      }
+      ast::Stmt::TypeAlias(_) | ast::Stmt::UseDecl(_) | ast::Stmt::ModDecl(_) => (),
+    }
+  }
+
+  fn params(&mut self, list: Option<ast::ParamList>) {
+    // The names of the context parameter are locals of the body too.
+    let params = list
+      .into_iter()
+      .flat_map(|list| list.context_param().into_iter().chain(list.params()));
+    for param in params {
+      let names: Vec<_> = match param.pat() {
+        Some(pat) => pat
+          .syntax()
+          .descendants()
+          .filter_map(ast::Name::cast)
+          .collect(),
+        None => Vec::new(),
+      };
+      for name in names {
+        if let Some(symbol) = self.checker.declared(&name) {
+          self.declare(symbol, LocalKind::Param);
+        }
+      }
+    }
+  }
+
+  /// The value of `block`, its locals go out of scope at its end.
+  fn block(&mut self, block: &ast::Block) -> Operand {
+    self.scopes.push(Vec::new());
+    let tail = tail(block);
+    let mut value = Operand::Const;
+    for stmt in block.statements() {
+      match (&stmt, &tail) {
+        (ast::Stmt::ExprStmt(stmt), Some(tail)) if stmt.expr().as_ref() == Some(tail) => {
+          value = self.expr(tail);
+        }
+        _ => self.stmt(&stmt),
+      }
+    }
+    let span = block.syntax().text_range();
+    let scope = self.scopes.pop().unwrap();
+    // The value outlives the locals of the block.
+    if let (Some(_), Some(tail)) = (value.place(), &tail) {
+      if !scope.is_empty() {
+        let ty = self.expr_type(tail);
+        let value_span = place_span(&value, span);
+        value = self.temp_operand(Rvalue::Use(value), ty, value_span);
+      }
+    }
+    let end = Span::new(span.end.saturating_sub(1), span.end);
+    for local in scope.into_iter().rev() {
+      self.push(Statement::StorageDead(local, end));
+    }
+    value
+  }
+
+  /// Reads the value of `expr` without moving it.
+  fn read(&mut self, expr: &ast::Expr) -> Operand {
+    match self.place(expr) {
+      Some(place) => Operand::Copy(place, expr.syntax().text_range()),
+      None => self.expr(expr),
+    }
+  }
+
+  /// The place `expr` refers to, if it is a variable of the body or a part of a value.
+  fn place(&mut self, expr: &ast::Expr) -> Option<Place> {
+    match expr {
+      ast::Expr::PathExpr(path) => {
+        let name_ref = path
+          .path()
+          .filter(|path| path.segments().count() == 1)
+          .and_then(|path| path.last_segment()?.name_ref())?;
+        let symbol = self.checker.resolve_name(&name_ref).ok()?;
+        self.symbols.get(&symbol).map(|local| Place::local(*local))
+      }
+      ast::Expr::ParenExpr(paren) => self.place(&paren.expr()?),
+      ast::Expr::FieldExpr(field) => {
+        let receiver = field.receiver()?;
+        let place = self.place_or_temp(&receiver);
+        let place = match self.expr_type(&receiver) {
+          Ty::Ref(..) => place.project(Projection::Deref),
+          _ => place,
+        };
+        Some(place.project(Projection::Field(field.name_ref()?.text())))
+      }
+      ast::Expr::IndexExpr(index) => {
+        let base = index.base()?;
+        let place = self.place_or_temp(&base);
+        if let Some(index) = index.index() {
+          let value = self.read(&index);
+          self.push(Statement::Eval(value));
+        }
+        let place = match self.expr_type(&base) {
+          Ty::Ref(..) => place.project(Projection::Deref),
+          _ => place,
+        };
+        Some(place.project(Projection::Index))
+      }
+      ast::Expr::PrefixExpr(prefix) if prefix.op() == Some(ast::PrefixOp::Deref) => {
+        let place = self.place_or_temp(&prefix.expr()?);
+        Some(place.project(Projection::Deref))
+      }
+      _ => None,
+    }
+  }
+
+  /// The place `expr` refers to, or a temporary holding its value.
+  fn place_or_temp(&mut self, expr: &ast::Expr) -> Place {
+    if let Some(place) = self.place(expr) {
+      return place;
+    }
+    let value = self.expr(expr);
+    let ty = self.expr_type(expr);
+    let temp = self.temp(ty);
+    self.assign(
+      Place::local(temp),
+      Rvalue::Use(value),
+      expr.syntax().text_range(),
+    );
+    Place::local(temp)
+  }
+
+  /// Uses the value of `expr`.
+  fn expr(&mut self, expr: &ast::Expr) -> Operand {
+    let span = expr.syntax().text_range();
+    if let Some(place) = self.place(expr) {
+      let ty = self.expr_type(expr);
+      return self.operand(place, span, &ty);
+    }
+    match expr {
+      ast::Expr::Literal(_) | ast::Expr::PathExpr(_) => Operand::Const,
+      ast::Expr::ParenExpr(paren) => match paren.expr() {
+        Some(expr) => self.expr(&expr),
+        None => Operand::Const,
+      },
+      ast::Expr::TemplateLiteral(template) => {
+        for expr in template.substitutions() {
+          let value = self.read(&expr);
+          self.push(Statement::Eval(value));
+        }
+        Operand::Const
+      }
+      ast::Expr::ArrayExpr(array) => {
+        let values = array
+          .elements()
+          .filter_map(|element| match element {
+            ast::ArrayElement::Expr(expr) => Some(expr),
+            ast::ArrayElement::Spread(spread) => spread.expr(),
+          })
+          .map(|expr| self.expr(&expr))
+          .collect();
+        self.temp_operand(Rvalue::Aggregate(values), self.expr_type(expr), span)
+      }
+      ast::Expr::ObjectLiteral(object) => {
+        let mut values = Vec::new();
+        for member in object.members() {
+          let value = match member {
+            ast::ObjectMember::Field(field) => match (field.expr(), field.name_ref()) {
+              (Some(expr), _) => self.expr(&expr),
+              // `{ name }` uses the variable `name`.
+              (None, Some(name_ref)) => self.name_value(&name_ref),
+              (None, None) => Operand::Const,
+            },
+            ast::ObjectMember::Spread(spread) => match spread.expr() {
+              Some(expr) => self.expr(&expr),
+              None => Operand::Const,
+            },
+          };
+          values.push(value);
+        }
+        self.temp_operand(Rvalue::Aggregate(values), self.expr_type(expr), span)
+      }
+      ast::Expr::InjectExpr(inject) => {
+        let mut values = Vec::new();
+        for field in inject.fields() {
+          if let Some(expr) = field.expr() {
+            values.push(self.expr(&expr));
+          }
+        }
+        if let Some(target) = inject.target() {
+          values.push(self.expr(&target));
+        }
+        self.temp_operand(Rvalue::Aggregate(values), self.expr_type(expr), span)
+      }
+      ast::Expr::ArrowFn(function) => self.closure(function, span),
+      ast::Expr::CallExpr(call) => {
+        let mut values = vec![match call.callee() {
+          Some(callee) => self.read(&callee),
+          None => Operand::Const,
+        }];
+        for arg in call.arg_list().into_iter().flat_map(|list| list.args()) {
+          values.push(self.expr(&arg));
+        }
+        let ty = self.expr_type(expr);
+        let returns_ref = self.checker.contains_ref(&ty);
+        self.temp_operand(Rvalue::Call(values, returns_ref), ty, span)
+      }
+      ast::Expr::PrefixExpr(prefix) => {
+        if let Some(expr) = prefix.expr() {
+          let value = self.read(&expr);
+          self.push(Statement::Eval(value));
+        }
+        Operand::Const
+      }
+      ast::Expr::RefExpr(reference) => {
+        let place = match reference.expr() {
+          Some(expr) => self.place_or_temp(&expr),
+          None => return Operand::Const,
+        };
+        let rvalue = Rvalue::Ref(place, reference.is_mut(), span);
+        self.temp_operand(rvalue, self.expr_type(expr), span)
+      }
+      ast::Expr::BinExpr(bin) => {
+        let op = match bin.op() {
+          Some(op) => op,
+          None => return Operand::Const,
+        };
+        if op.is_assignment() {
+          let value = match bin.rhs() {
+            Some(rhs) if op == ast::BinOp::Assign => self.expr(&rhs),
+            Some(rhs) => {
+              let value = self.read(&rhs);
+              self.push(Statement::Eval(value));
+              Operand::Const
+            }
+            None => Operand::Const,
+          };
+          if let Some(place) = bin.lhs().and_then(|lhs| self.place(&lhs)) {
+            if op != ast::BinOp::Assign {
+              self.push(Statement::Eval(Operand::Copy(place.clone(), span)));
+            }
+            self.assign(place, Rvalue::Use(value), span);
+          }
+          return Operand::Const;
+        }
+        for side in [bin.lhs(), bin.rhs()].iter().flatten() {
+          let value = self.read(side);
+          self.push(Statement::Eval(value));
+        }
+        Operand::Const
+      }
+      ast::Expr::CastExpr(cast) => match cast.expr() {
+        Some(expr) => self.expr(&expr),
+        None => Operand::Const,
+      },
+      ast::Expr::IfExpr(if_expr) => self.if_expr(if_expr, span),
+      ast::Expr::MatchExpr(match_expr) => self.match_expr(match_expr, span),
+      ast::Expr::ReturnExpr(ret) => {
+        let value = match ret.expr() {
+          Some(expr) => self.expr(&expr),
+          None => Operand::Const,
+        };
+        self.terminate(Terminator::Return(value, span));
+        self.current = self.new_block();
+        Operand::Const
+      }
+      ast::Expr::BreakExpr(_) | ast::Expr::ContinueExpr(_) => {
+        if let Some(target) = self.loops.last() {
+          let (depth, target) = match expr {
+            ast::Expr::BreakExpr(_) => (target.depth, target.break_target),
+            _ => (target.depth, target.continue_target),
+          };
+          let exited: Vec<_> = self.scopes[depth..].iter().flatten().copied().collect();
+          for local in exited.into_iter().rev() {
+            self.push(Statement::StorageDead(local, span));
+          }
+          self.terminate(Terminator::Goto(target));
+        }
+        self.current = self.new_block();
+        Operand::Const
+      }
+      ast::Expr::WhileExpr(while_expr) => {
+        let header = self.new_block();
+        let body = self.new_block();
+        let exit = self.new_block();
+        self.goto(header);
+        let condition = match while_expr.condition() {
+          Some(condition) => self.read(&condition),
+          None => Operand::Const,
+        };
+        self.terminate(Terminator::Switch(Some(condition), vec![body, exit]));
+        self.current = body;
+        let depth = self.scopes.len();
+        self.loop_body(while_expr.body(), header, exit, depth);
+        self.goto(header);
+        self.current = exit;
+        Operand::Const
+      }
+      ast::Expr::LoopExpr(loop_expr) => {
+        let header = self.new_block();
+        let exit = self.new_block();
+        self.goto(header);
+        let depth = self.scopes.len();
+        self.loop_body(loop_expr.body(), header, exit, depth);
+        self.goto(header);
+        self.current = exit;
+        Operand::Const
+      }
+      ast::Expr::ForExpr(for_expr) => {
+        if let Some(iterable) = for_expr.iterable() {
+          let value = self.read(&iterable);
+          self.push(Statement::Eval(value));
+        }
+        let header = self.new_block();
+        let body = self.new_block();
+        let exit = self.new_block();
+        self.goto(header);
+        self.terminate(Terminator::Switch(None, vec![body, exit]));
+        self.current = body;
+        // The binding goes out of scope at the end of each iteration.
+        let depth = self.scopes.len();
+        self.scopes.push(Vec::new());
+        let symbol = for_expr
+          .name()
+          .and_then(|name| self.checker.declared(&name));
+        if let Some(symbol) = symbol {
+          let local = self.declare(symbol, LocalKind::Var);
+          self.assign(Place::local(local), Rvalue::Use(Operand::Const), span);
+        }
+        let body = for_expr.body();
+        let end = body
+          .as_ref()
+          .map_or(span, |body| body.syntax().text_range());
+        self.loop_body(body, header, exit, depth);
+        for local in self.scopes.pop().unwrap() {
+          self.push(Statement::StorageDead(local, Span::empty(end.end)));
+        }
+        self.goto(header);
+        self.current = exit;
+        Operand::Const
+      }
+      ast::Expr::Block(block) => self.block(block),
+      ast::Expr::FieldExpr(_) | ast::Expr::IndexExpr(_) => Operand::Const,
+    }
+  }
+
+  /// The body of a loop going back to `header`, the loop ends at `exit`.
+  /// `depth` is the number of scopes outside of the loop.
+  fn loop_body(&mut self, body: Option<ast::Block>, header: BlockId, exit: BlockId, depth: usize) {
+    self.loops.push(Loop {
+      continue_target: header,
+      break_target: exit,
+      depth,
+    });
+    if let Some(body) = body {
+      let value = self.block(&body);
+      self.push(Statement::Eval(value));
+    }
+    self.loops.pop();
+  }
+
+  /// The value of the variable `name_ref` refers to.
+  fn name_value(&self, name_ref: &ast::NameRef) -> Operand {
+    let symbol = match self.checker.resolve_name(name_ref) {
+      Ok(symbol) => symbol,
+      Err(_) => return Operand::Const,
+    };
+    match self.symbols.get(&symbol) {
+      Some(local) => {
+        let ty = self.symbol_type(symbol);
+        self.operand(Place::local(*local), name_ref.syntax().text_range(), &ty)
+      }
+      None => Operand::Const,
+    }
+  }
+
+  fn if_expr(&mut self, if_expr: &ast::IfExpr, span: Span) -> Operand {
+    let condition = match if_expr.condition() {
+      Some(condition) => self.read(&condition),
+      None => Operand::Const,
+    };
+    let then_block = self.new_block();
+    let else_block = self.new_block();
+    let join = self.new_block();
+    self.terminate(Terminator::Switch(
+      Some(condition),
+      vec![then_block, else_block],
+    ));
+    let else_branch = if_expr.else_branch();
+    let result = else_branch.as_ref().map(|_| {
+      let ty = self.expr_type(&ast::Expr::IfExpr(if_expr.clone()));
+      (self.temp(ty.clone()), ty)
+    });
+    let branches = [
+      (then_block, if_expr.then_branch()),
+      (else_block, else_branch),
+    ];
+    for (block, branch) in branches.iter() {
+      self.current = *block;
+      if let Some(branch) = branch {
+        let value = self.expr(branch);
+        match &result {
+          Some((result, _)) => self.assign(Place::local(*result), Rvalue::Use(value), span),
+          None => self.push(Statement::Eval(value)),
+        }
+      }
+      self.goto(join);
+    }
+    match result {
+      Some((result, ty)) => self.operand(Place::local(result), span, &ty),
+      None => Operand::Const,
+    }
+  }
+
+  fn match_expr(&mut self, match_expr: &ast::MatchExpr, span: Span) -> Operand {
+    let scrutinee = match match_expr.expr() {
+      Some(expr) => self.place_or_temp(&expr),
+      None => return Operand::Const,
+    };
+    self.push(Statement::Eval(Operand::Copy(scrutinee.clone(), span)));
+    let ty = self.expr_type(&ast::Expr::MatchExpr(match_expr.clone()));
+    let result = self.temp(ty.clone());
+    let arms: Vec<_> = match_expr.arms().collect();
+    let blocks: Vec<_> = arms.iter().map(|_| self.new_block()).collect();
+    let join = self.new_block();
+    self.terminate(Terminator::Switch(None, blocks.clone()));
+    for (arm, block) in arms.iter().zip(blocks) {
+      self.current = block;
+      self.scopes.push(Vec::new());
+      if let Some(pattern) = arm.pattern() {
+        self.bind_pattern(&pattern, &scrutinee);
+      }
+      if let Some(condition) = arm.guard().and_then(|guard| guard.condition()) {
+        let value = self.read(&condition);
+        self.push(Statement::Eval(value));
+      }
+      if let Some(body) = arm.body() {
+        let value = self.expr(&body);
+        self.assign(Place::local(result), Rvalue::Use(value), span);
+      }
+      let end = Span::empty(arm.syntax().text_range().end);
+      let scope = self.scopes.pop().unwrap();
+      for local in scope.into_iter().rev() {
+        self.push(Statement::StorageDead(local, end));
+      }
+      self.goto(join);
+    }
+    self.operand(Place::local(result), span, &ty)
+  }
+
+  /// Binds the names of `pattern` to the value of `scrutinee`, moving it unless they can be copied.
+  fn bind_pattern(&mut self, pattern: &ast::Expr, scrutinee: &Place) {
+    let bindings: Vec<_> = pattern
+      .syntax()
+      .descendants()
+      .filter_map(ast::NameRef::cast)
+      .filter_map(|name_ref| {
+        self
+          .checker
+          .symbols
+          .symbol_at(name_ref.syntax().text_range())
+      })
+      .filter(|symbol| self.checker.symbols.symbol(*symbol).kind == SymbolKind::MatchBinding)
+      .collect();
+    let span = pattern.syntax().text_range();
+    for symbol in bindings {
+      let local = self.declare(symbol, LocalKind::Var);
+      let ty = self.symbol_type(symbol);
+      let value = self.operand(scrutinee.clone(), span, &ty);
+      self.assign(Place::local(local), Rvalue::Use(value), span);
+    }
+  }
+
+  /// An arrow function, holding references to the variables it uses, or their values for a `move` function.
+  fn closure(&mut self, function: &ast::ArrowFn, span: Span) -> Operand {
+    let captures = self.captures(function.syntax());
+    let mut values = Vec::new();
+    for (symbol, mutable, use_span) in &captures {
+      let local = self.symbols[symbol];
+      let ty = self.symbol_type(*symbol);
+      let value = if function.is_move() {
+        self.operand(Place::local(local), *use_span, &ty)
+      } else {
+        let rvalue = Rvalue::Ref(Place::local(local), *mutable, *use_span);
+        self.temp_operand(rvalue, Ty::Ref(Box::new(ty), *mutable), *use_span)
+      };
+      values.push(value);
+    }
+    let mut bodies = Vec::new();
+    let mut builder = Builder::new(self.checker, &mut bodies);
+    for (symbol, _, _) in &captures {
+      builder.declare(*symbol, LocalKind::Capture);
+    }
+    builder.params(function.param_list());
+    if let Some(body) = function.body() {
+      let value = builder.expr(&body);
+      let span = tail_span(&body);
+      builder.terminate(Terminator::Return(value, span));
+    }
+    let body = builder.finish();
+    self.bodies.push(body);
+    self.bodies.extend(bodies);
+    self.temp_operand(Rvalue::Aggregate(values), Ty::Unknown, span)
+  }
+
+  /// The variables of the body used in `function`, in order, true for those it assigns or borrows mutably,
+  /// with the span of their first use.
+  fn captures(&self, function: &SyntaxNode) -> Vec<(SymbolId, bool, Span)> {
+    let mut captures: Vec<(SymbolId, bool, Span)> = Vec::new();
+    for name_ref in function.descendants().filter_map(ast::NameRef::cast) {
+      let symbol = match self.checker.resolve_name(&name_ref) {
+        Ok(symbol) if self.symbols.contains_key(&symbol) => symbol,
+        _ => continue,
+      };
+      let mutable = is_mutated(name_ref.syntax());
+      match captures.iter_mut().find(|capture| capture.0 == symbol) {
+        Some(capture) => capture.1 |= mutable,
+        None => captures.push((symbol, mutable, name_ref.syntax().text_range())),
+      }
+    }
+    captures
+  }
+}
+
+/// The span of the value of `operand`, or `default`.
+fn place_span(operand: &Operand, default: Span) -> Span {
+  match operand {
+    Operand::Copy(_, span) | Operand::Move(_, span) => *span,
+    Operand::Const => default,
+  }
+}
+
+/// True if the variable of `name_ref` is assigned or borrowed mutably where it is used, e.g. `x.count += 1`.
+fn is_mutated(name_ref: &SyntaxNode) -> bool {
+  let mut node = match name_ref.ancestors().find_map(ast::PathExpr::cast) {
+    Some(path) => path.syntax().clone(),
+    None => return false,
+  };
+  // The place is the receiver of fields and indices, e.g. `x` in `x.items[0]`.
+  while let Some(parent) = node.parent() {
+    let is_base = match ast::Expr::cast(parent.clone()) {
+      Some(ast::Expr::FieldExpr(field)) => {
+        field.receiver().map(|e| e.syntax().clone()) == Some(node.clone())
+      }
+      Some(ast::Expr::IndexExpr(index)) => {
+        index.base().map(|e| e.syntax().clone()) == Some(node.clone())
+      }
+      Some(ast::Expr::ParenExpr(_)) => true,
+      _ => false,
+    };
+    if !is_base {
+      break;
+    }
+    node = parent;
+  }
+  match node.parent().and_then(ast::Expr::cast) {
+    Some(ast::Expr::BinExpr(bin)) => {
+      bin.op().is_some_and(|op| op.is_assignment())
+        && bin.lhs().map(|lhs| lhs.syntax().clone()) == Some(node)
+    }
+    Some(ast::Expr::RefExpr(reference)) => reference.is_mut(),
+    _ => false,
+  }
+}
+
+impl<'a> Checker<'a> {
+  /// True if a value of type `ty` is copied when used, rather than moved,
+  /// e.g. numbers, and arrays and tuples of them. A reference is reborrowed, which copies it.
+  pub(crate) fn is_copy(&self, ty: &Ty) -> bool {
+    self.is_copy_at(ty, 0)
+  }
+
+  fn is_copy_at(&self, ty: &Ty, depth: usize) -> bool {
+    if depth > MAX_DEPTH {
+      return true;
+    }
+    let copy = |ty: &Ty| self.is_copy_at(ty, depth + 1);
+    // Members are the values references point to, so references are matched first.
+    ty.members().iter().all(|ty| match ty {
+      Ty::Ref(..) => true,
+      ty => self.members(ty).iter().all(|member| match member {
+        Ty::Unknown | Ty::Any | Ty::Never | Ty::Unit => true,
+        Ty::Bool | Ty::Char | Ty::Str | Ty::Int(_) | Ty::Float(_) | Ty::Literal(_) => true,
+        Ty::Fn(_) | Ty::Ref(..) => true,
+        Ty::Tuple(types) => types.iter().all(copy),
+        Ty::Array(ty, _) => copy(ty),
+        Ty::Builtin(name, args) => match name.as_str() {
+          "Some" | "Ok" | "Err" | "None" => args.iter().all(copy),
+          _ => false,
+        },
+        Ty::Symbol(payload) => payload.as_deref().is_none_or(copy),
+        Ty::Named(_) => copy(&self.structure(member)),
+        Ty::Union(types) => types.iter().all(copy),
+        // Keys are literals, the other computations are those of generic parameters.
+        Ty::Keyof(_) => true,
+        Ty::String | Ty::Object(_) | Ty::Param(_) | Ty::Intersection(_) => false,
+        Ty::Conditional(_) | Ty::Mapped(_) | Ty::Index(..) => false,
+      }),
+    })
+  }
+
+  /// True if a value of type `ty` may hold a reference, a function may be a closure holding some.
+  pub(crate) fn contains_ref(&self, ty: &Ty) -> bool {
+    self.contains_ref_at(ty, 0)
+  }
+
+  fn contains_ref_at(&self, ty: &Ty, depth: usize) -> bool {
+    if depth > MAX_DEPTH {
+      return false;
+    }
+    let contains = |ty: &Ty| self.contains_ref_at(ty, depth + 1);
+    ty.members().iter().any(|ty| match ty {
+      Ty::Ref(..) => true,
+      ty => self.members(ty).iter().any(|member| match member {
+        Ty::Ref(..) | Ty::Fn(_) => true,
+        Ty::Object(fields) => fields.iter().any(|field| contains(&field.ty)),
+        Ty::Tuple(types) | Ty::Builtin(_, types) => types.iter().any(contains),
+        Ty::Array(ty, _) => contains(ty),
+        Ty::Symbol(payload) => payload.as_deref().is_some_and(contains),
+        Ty::Named(_) => contains(&self.structure(member)),
+        _ => false,
+      }),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expect_test::{expect, Expect};
+  use justc_binder::bind;
+  use justc_parser::parse;
+
+  fn check(text: &str, expect: Expect) {
+    let tree = parse(text).tree();
+    let binding = bind(&tree);
+    let mut checker = Checker::new(tree.syntax().clone(), binding.symbols());
+    for stmt in tree.statements() {
+      checker.stmt(&stmt);
+    }
+    checker.types.sort_by_key(|(span, _)| *span);
+    let bodies = lower_file(&checker, &tree);
+    let dumps: Vec<_> = bodies.iter().map(Body::debug_dump).collect();
+    expect.assert_eq(&dumps.join("\n"));
+  }
+
+  #[test]
+  fn lower_bodies() {
+    check(
+      r#"
+fn greet(name: String, n: i32) -> String {
+  let s = name;
+  if n > 0 { s } else { "none" }
+}
+fn counter() {
+  let mut count = 0;
+  let inc = () => { count += 1 };
+  let r = &mut count;
+  for i in [1, 2] { *r += i }
+}
+"#,
+      expect![[r#"
+            bb0:
+              return const
+
+            param _0 name: String
+            param _1 n: i32
+            let _2 s: String
+            temp _3 : String | "none"
+            temp _4 : String | "none"
+            bb0:
+              _2 = move _0
+              eval copy _1
+              eval const
+              switch const -> [bb1, bb2]
+            bb1:
+              _3 = move _2
+              goto bb3
+            bb2:
+              _3 = const
+              goto bb3
+            bb3:
+              _4 = move _3
+              dead _2
+              return move _4
+
+            let _0 count: i32
+            temp _1 : &mut i32
+            temp _2 : {unknown}
+            let _3 inc: () -> ()
+            temp _4 : &mut i32
+            let _5 r: &mut i32
+            temp _6 : [i32; 2]
+            let _7 i: i32
+            bb0:
+              _0 = const
+              _1 = &mut _0
+              _2 = { copy _1 }
+              _3 = copy _2
+              _4 = &mut _0
+              _5 = copy _4
+              _6 = { const, const }
+              eval copy _6
+              goto bb1
+            bb1:
+              switch -> [bb2, bb3]
+            bb2:
+              _7 = const
+              eval copy _7
+              eval copy (*_5)
+              (*_5) = const
+              eval const
+              dead _7
+              goto bb1
+            bb3:
+              dead _5
+              dead _3
+              dead _0
+              return const
+
+            capture _0 count: i32
+            bb0:
+              eval const
+              eval copy _0
+              _0 = const
+              return const
+      "#]],
+    );
+  }
+}
@@ -0,0 +1,24 @@
+//! Just compiler checker.
+//!
+//! Infers the types of the values of a bound source file, where only function parameters need annotations,
+//! and checks that values are used as their types allow. Types can be computed from other types,
+//! e.g. with conditional and mapped types, see `eval`, and generic functions are instantiated where they are called,
+//! see `generics`.
+//! The function bodies are then lowered to control-flow graphs to check that values aren't used after they are moved,
+//! and that references don't outlive or conflict with the values they borrow.
+
+mod assign;
+pub mod attach;
+mod borrow;
+pub mod check;
+pub mod codes;
+mod eval;
+mod generics;
+mod infer;
+mod ir;
+mod lower;
+mod narrow;
+pub mod ty;
+
+pub use check::{check, check_with_imports, TypeCheck};
+pub use ty::Ty;
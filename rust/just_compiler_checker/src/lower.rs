@@ -0,0 +1,233 @@
+//! Lowers type expressions of the syntax tree to `Ty`.
+
+use super::check::Checker;
+use super::codes;
+use super::eval::too_deep;
+use super::infer::contains_param;
+use super::infer::literal_type;
+use super::ty::{Conditional, Field, FloatTy, FnTy, IntTy, Lit, Mapped, Named, Param, Ty};
+use justc_binder::symbols::SymbolKind;
+use justc_lexer::diagnostic::Diagnostic;
+use justc_parser::ast::{self, AstNode, HasGenericParams, HasName, HasTypeAnnotation};
+
+/// Generic types provided by the language.
+const BUILTIN_GENERICS: &[&str] = &[
+  "Vec", "Box", "Option", "Result", "Some", "None", "Ok", "Err",
+];
+
+impl<'a> Checker<'a> {
+  pub(crate) fn lower(&mut self, ty: &ast::Type) -> Ty {
+    match ty {
+      ast::Type::PathType(ty) => self.lower_path(ty),
+      ast::Type::RefType(ty) => {
+        let inner = self.lower_opt(ty.ty());
+        Ty::Ref(Box::new(inner), ty.is_mut())
+      }
+      ast::Type::ParenType(ty) => self.lower_opt(ty.ty()),
+      ast::Type::UnionType(ty) => {
+        let types: Vec<_> = ty.types().map(|ty| self.lower(&ty)).collect();
+        Ty::union(types)
+      }
+      ast::Type::IntersectionType(ty) => {
+        let types: Vec<_> = ty.types().map(|ty| self.lower(&ty)).collect();
+        intersection(types)
+      }
+      ast::Type::ObjectType(ty) => {
+        let fields = ty
+          .fields()
+          .filter_map(|field| {
+            let name = field.name()?.text();
+            Some(Field {
+              name,
+              ty: self.lower_opt(field.ty()),
+              optional: field.is_optional(),
+            })
+          })
+          .collect();
+        Ty::object(fields)
+      }
+      ast::Type::TupleType(ty) => Ty::Tuple(ty.types().map(|ty| self.lower(&ty)).collect()),
+      ast::Type::ArrayType(ty) => {
+        let elem = self.lower_opt(ty.ty());
+        let len = match ty.len() {
+          Some(ast::Expr::Literal(literal)) => match literal_type(&literal) {
+            Ty::Literal(Lit::Int { value, .. }) => Some(value as u64),
+            _ => None,
+          },
+          _ => None,
+        };
+        Ty::Array(Box::new(elem), len)
+      }
+      ast::Type::FnType(ty) => {
+        let generics = self.generic_params(ty.generic_param_list());
+        let params = ty.param_list().into_iter().flat_map(|list| list.params());
+        let params = params.map(|param| self.lower_opt(param.ty())).collect();
+        let ret = match ty.ret_type() {
+          Some(ret) => self.lower_opt(ret.ty()),
+          None => Ty::Unit,
+        };
+        Ty::Fn(FnTy {
+          generics,
+          params,
+          ret: Box::new(ret),
+        })
+      }
+      ast::Type::LiteralType(ty) => match ty.literal() {
+        Some(literal) => literal_type(&literal),
+        None => Ty::Unknown,
+      },
+      ast::Type::SymbolType(ty) => Ty::Symbol(ty.ty().map(|ty| Box::new(self.lower(&ty)))),
+      ast::Type::KeyofType(ty) => Ty::Keyof(Box::new(self.lower_opt(ty.ty()))),
+      ast::Type::IndexedAccessType(ty) => {
+        let indexed = self.lower_opt(ty.ty());
+        let index = self.lower_opt(ty.index());
+        Ty::Index(Box::new(indexed), Box::new(index))
+      }
+      ast::Type::MappedType(ty) => {
+        let name = ty.generic_param().and_then(|param| param.name());
+        let key = match name.and_then(|name| Some((self.declared(&name)?, name.text()))) {
+          Some((symbol, name)) => Param { name, symbol },
+          None => return Ty::Unknown,
+        };
+        Ty::Mapped(Box::new(Mapped {
+          key,
+          keys: self.lower_opt(ty.keys()),
+          value: self.lower_opt(ty.ty()),
+          optional: ty.is_optional(),
+        }))
+      }
+      ast::Type::ConditionalType(ty) => {
+        let check = self.lower_opt(ty.check_type());
+        let param = match &check {
+          Ty::Param(param) => Some(param.clone()),
+          _ => None,
+        };
+        Ty::Conditional(Box::new(Conditional {
+          check,
+          extends: self.lower_opt(ty.extends_type()),
+          then: self.lower_opt(ty.true_type()),
+          otherwise: self.lower_opt(ty.false_type()),
+          param,
+        }))
+      }
+    }
+  }
+
+  fn lower_opt(&mut self, ty: Option<ast::Type>) -> Ty {
+    match ty {
+      Some(ty) => self.lower(&ty),
+      None => Ty::Unknown,
+    }
+  }
+
+  /// `i32`, `Vocaloid`, `Vec<T>`, a path of more than one segment is a type of another module, not checked yet.
+  fn lower_path(&mut self, ty: &ast::PathType) -> Ty {
+    let path = match ty.path() {
+      Some(path) if path.segments().count() == 1 => path,
+      _ => return Ty::Unknown,
+    };
+    let segment = match path.last_segment() {
+      Some(segment) => segment,
+      None => return Ty::Unknown,
+    };
+    let name_ref = match segment.name_ref() {
+      Some(name_ref) => name_ref,
+      None => return Ty::Unknown,
+    };
+    let args: Vec<_> = segment
+      .generic_arg_list()
+      .into_iter()
+      .flat_map(|args| args.types())
+      .collect();
+    let args: Vec<_> = args.iter().map(|arg| self.lower(arg)).collect();
+    let name = name_ref.text();
+    let symbol = match self.resolve_name(&name_ref) {
+      Ok(symbol) => symbol,
+      Err(true) => return builtin_type(&name, args),
+      Err(false) => return Ty::Unknown,
+    };
+    match self.symbols.symbol(symbol).kind {
+      SymbolKind::Type => {
+        let named = Ty::Named(Named { name, symbol, args });
+        // Aliases are only evaluated once they are all lowered, not in the types of other aliases.
+        if !self.aliases.contains_key(&symbol) || contains_param(&named) {
+          return named;
+        }
+        if self.normalize(&named).is_err() {
+          self
+            .diagnostics
+            .push(too_deep(&named, ty.syntax().text_range()));
+          return Ty::Unknown;
+        }
+        named
+      }
+      SymbolKind::GenericParam => Ty::Param(Param { name, symbol }),
+      SymbolKind::Use | SymbolKind::Mod => Ty::Unknown,
+      _ => {
+        self.diagnostics.push(
+          Diagnostic::error(
+            codes::NOT_A_TYPE,
+            format!("`{}` is a value, not a type", name),
+            name_ref.syntax().text_range(),
+          )
+          .with_label("not a type"),
+        );
+        Ty::Unknown
+      }
+    }
+  }
+}
+
+/// The type of a builtin name used as a type, e.g. `i32` or `Vec<T>`.
+pub(crate) fn builtin_type(name: &str, args: Vec<Ty>) -> Ty {
+  if let Some(ty) = IntTy::from_name(name) {
+    return Ty::Int(ty);
+  }
+  if let Some(ty) = FloatTy::from_name(name) {
+    return Ty::Float(ty);
+  }
+  match name {
+    "bool" => Ty::Bool,
+    "char" => Ty::Char,
+    "str" => Ty::Str,
+    "String" => Ty::String,
+    "any" => Ty::Any,
+    "never" => Ty::Never,
+    "Array" => Ty::Array(
+      Box::new(args.into_iter().next().unwrap_or(Ty::Unknown)),
+      None,
+    ),
+    _ if BUILTIN_GENERICS.contains(&name) => Ty::Builtin(name.to_string(), args),
+    _ => Ty::Unknown,
+  }
+}
+
+/// `A & B`, with the fields of object types merged, e.g. `{ a: i32 } & { b: str }` is `{ a: i32, b: str }`.
+pub(crate) fn intersection(types: Vec<Ty>) -> Ty {
+  if types.iter().any(Ty::is_unknown) {
+    return Ty::Unknown;
+  }
+  let mut types = types;
+  types.dedup();
+  if types.len() == 1 {
+    return types.pop().unwrap();
+  }
+  if !types.iter().all(|ty| matches!(ty, Ty::Object(_))) {
+    return Ty::Intersection(types);
+  }
+  let mut fields: Vec<Field> = Vec::new();
+  for ty in types {
+    if let Ty::Object(more) = ty {
+      for field in more {
+        match fields.iter_mut().find(|merged| merged.name == field.name) {
+          Some(merged) => {
+            merged.ty = intersection(vec![merged.ty.clone(), field.ty]);
+            merged.optional &= field.optional;
+          }
+          None => fields.push(field),
+        }
+      }
+    }
+  }
+  Ty::object(fields)
+}
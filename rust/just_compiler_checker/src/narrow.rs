@@ -0,0 +1,827 @@
+//! Narrows unions in the branches of `if` and `match`, and after them.
+//!
+//! In `if x != None { x }`, `x` is `i32` rather than `i32 | None`, and in the `else` branch it is `None`.
+//! A value can be narrowed by comparing it, or one of its fields, to a value of a single value type,
+//! e.g. a literal or `None`, so `if shape.kind == "circle"` picks the circles of a union of shapes.
+//!
+//! The narrowings at the end of the branches that don't return hold after them, so `x` is `i32`
+//! after `if x == None { return 0 }`. Assigning a variable narrows it to the assigned value, a loop
+//! restores the declared types of the variables it assigns, and `x as T` narrows `x` to `T`.
+
+use super::check::{none, Checker};
+use super::codes;
+use super::infer::literal_type;
+use super::lower::builtin_type;
+use super::ty::{Lit, Named, Ty};
+use justc_binder::symbols::{SymbolId, SymbolKind};
+use justc_lexer::diagnostic::Diagnostic;
+use justc_parser::ast::{self, AstNode, BinOp, PrefixOp};
+use justc_parser::SyntaxNode;
+
+/// Builtin constructors of values matched by patterns, e.g. `Some(v)`.
+const CONSTRUCTORS: &[&str] = &["Some", "Ok", "Err"];
+
+/// Narrower types of symbols.
+pub(crate) type Narrowing = Vec<(SymbolId, Ty)>;
+
+/// A value compared in a condition, a variable or a field of a variable.
+struct Place {
+  symbol: SymbolId,
+  field: Option<String>,
+}
+
+/// What a match arm pattern matches.
+enum Pattern {
+  /// `_`, or a name binding the value.
+  All(Option<SymbolId>),
+  /// A value of this type, e.g. `1`, `None` or `Vocaloid`.
+  Type(Ty),
+  /// `Some(v)`, `UserId(1)`, with the pattern of the argument.
+  Constructor(Constructor, Box<Pattern>),
+  /// A pattern that isn't understood, matching some values.
+  Unknown,
+}
+
+/// What builds the value matched by a pattern like `Some(v)`.
+enum Constructor {
+  /// `Some`, `Ok` or `Err`.
+  Builtin(String),
+  /// A nominal type.
+  Nominal(SymbolId),
+}
+
+impl<'a> Checker<'a> {
+  /// Runs `f` with the symbols of `narrowing` narrowed.
+  pub(crate) fn with_narrowing<T>(
+    &mut self,
+    narrowing: Narrowing,
+    f: impl FnOnce(&mut Self) -> T,
+  ) -> T {
+    let len = self.narrowed.len();
+    self.narrowed.extend(narrowing);
+    let result = f(self);
+    self.narrowed.truncate(len);
+    result
+  }
+
+  /// Runs `f` in a branch starting with the symbols of `narrowing` narrowed,
+  /// and returns the narrowings at the end of the branch.
+  fn in_branch<T>(
+    &mut self,
+    narrowing: Narrowing,
+    f: impl FnOnce(&mut Self) -> T,
+  ) -> (T, Narrowing) {
+    let len = self.narrowed.len();
+    self.narrowed.extend(narrowing);
+    let result = f(self);
+    let end = self.narrowed.split_off(len);
+    (result, end)
+  }
+
+  /// Narrows the symbols narrowed at the end of `paths`, the branches continuing after an `if` or a `match`,
+  /// to the union of their types at the end of each path.
+  fn join(&mut self, paths: Vec<Narrowing>) {
+    if paths.is_empty() {
+      return;
+    }
+    let mut symbols: Vec<SymbolId> = Vec::new();
+    for (symbol, _) in paths.iter().flatten() {
+      if !symbols.contains(symbol) {
+        symbols.push(*symbol);
+      }
+    }
+    for symbol in symbols {
+      let current = self.symbol_type(symbol);
+      let types = paths.iter().map(|path| {
+        let end = path.iter().rev().find(|(id, _)| *id == symbol);
+        end.map_or_else(|| current.clone(), |(_, ty)| ty.clone())
+      });
+      let joined = Ty::union(types.collect::<Vec<_>>());
+      let same =
+        self.assignable(&current, &joined).is_ok() && self.assignable(&joined, &current).is_ok();
+      if !same {
+        self.narrowed.push((symbol, joined));
+      }
+    }
+  }
+
+  pub(crate) fn if_expr(&mut self, if_expr: &ast::IfExpr, expected: Option<&Ty>) -> Ty {
+    let (then, otherwise) = match if_expr.condition() {
+      Some(condition) => {
+        self.check_expr(&condition, &Ty::Bool);
+        self.narrowing(&condition)
+      }
+      None => (Vec::new(), Vec::new()),
+    };
+    let else_branch = if_expr.else_branch();
+    // Without `else`, the value of the `then` branch is not used.
+    let expected = expected.filter(|_| else_branch.is_some());
+    let mut paths = Vec::new();
+    let then_ty = match if_expr.then_branch() {
+      Some(branch) => {
+        let (ty, end) = self.in_branch(then, |checker| checker.check_part(&branch, expected));
+        if ty != Ty::Never {
+          paths.push(end);
+        }
+        ty
+      }
+      None => Ty::Unknown,
+    };
+    let ty = match else_branch {
+      Some(branch) => {
+        let (else_ty, end) =
+          self.in_branch(otherwise, |checker| checker.check_part(&branch, expected));
+        if else_ty != Ty::Never {
+          paths.push(end);
+        }
+        Ty::union(vec![then_ty, else_ty])
+      }
+      None => {
+        paths.push(otherwise);
+        Ty::Unit
+      }
+    };
+    self.join(paths);
+    ty
+  }
+
+  /// Narrows the variable of `target = value`, or `target += value`, to the type `ty` of the value,
+  /// and restores the declared type of a variable whose field is assigned.
+  pub(crate) fn assign(&mut self, target: &ast::Expr, ty: &Ty) {
+    let place = match self.place_of(target) {
+      Some(place) => place,
+      None => return,
+    };
+    let declared = self.declared_type(place.symbol);
+    let ty = match place.field {
+      None if !ty.is_unknown() => self.narrow_to(&declared, &ty.widen()),
+      _ => declared.clone(),
+    };
+    let ty = if ty == Ty::Never { declared } else { ty };
+    self.narrowed.push((place.symbol, ty));
+  }
+
+  /// Restores the declared types of the narrowed variables assigned in `node`, a loop,
+  /// whose narrowings don't hold when the loop repeats.
+  pub(crate) fn invalidate(&mut self, node: &SyntaxNode) {
+    for bin in node.descendants().filter_map(ast::BinExpr::cast) {
+      if !bin.op().is_some_and(BinOp::is_assignment) {
+        continue;
+      }
+      let place = match bin.lhs().and_then(|lhs| self.place_of(&lhs)) {
+        Some(place) => place,
+        None => continue,
+      };
+      if self.narrowed.iter().any(|(id, _)| *id == place.symbol) {
+        let ty = self.declared_type(place.symbol);
+        self.narrowed.push((place.symbol, ty));
+      }
+    }
+  }
+
+  /// `x as T`, where `x` is a variable whose type is a union, narrows `x` to the members of its type which are `T`.
+  pub(crate) fn cast(&mut self, expr: &ast::Expr, ty: &Ty, target: &Ty) {
+    let symbol = match self.place_of(expr) {
+      Some(Place {
+        symbol,
+        field: None,
+      }) => symbol,
+      _ => return,
+    };
+    if self.members(ty).len() < 2 || target.is_unknown() {
+      return;
+    }
+    let narrowed = self.narrow_to(ty, target);
+    if narrowed != Ty::Never {
+      self.narrowed.push((symbol, narrowed));
+    }
+  }
+
+  /// The narrowings when `condition` holds and when it doesn't.
+  pub(crate) fn narrowing(&mut self, condition: &ast::Expr) -> (Narrowing, Narrowing) {
+    match condition {
+      ast::Expr::ParenExpr(paren) => match paren.expr() {
+        Some(expr) => self.narrowing(&expr),
+        None => (Vec::new(), Vec::new()),
+      },
+      ast::Expr::PrefixExpr(prefix) if prefix.op() == Some(PrefixOp::Not) => match prefix.expr() {
+        Some(expr) => {
+          let (then, otherwise) = self.narrowing(&expr);
+          (otherwise, then)
+        }
+        None => (Vec::new(), Vec::new()),
+      },
+      ast::Expr::BinExpr(bin) => {
+        let (lhs, rhs) = match (bin.lhs(), bin.rhs()) {
+          (Some(lhs), Some(rhs)) => (lhs, rhs),
+          _ => return (Vec::new(), Vec::new()),
+        };
+        match bin.op() {
+          // Both hold in `a && b`, and neither holds when `a || b` doesn't.
+          Some(BinOp::And) => {
+            let (mut then, _) = self.narrowing(&lhs);
+            let (more, _) = self.with_narrowing(then.clone(), |checker| checker.narrowing(&rhs));
+            then.extend(more);
+            (then, Vec::new())
+          }
+          Some(BinOp::Or) => {
+            let (_, mut otherwise) = self.narrowing(&lhs);
+            let (_, more) =
+              self.with_narrowing(otherwise.clone(), |checker| checker.narrowing(&rhs));
+            otherwise.extend(more);
+            (Vec::new(), otherwise)
+          }
+          Some(BinOp::Eq) => self.compare(&lhs, &rhs),
+          Some(BinOp::NotEq) => {
+            let (then, otherwise) = self.compare(&lhs, &rhs);
+            (otherwise, then)
+          }
+          _ => (Vec::new(), Vec::new()),
+        }
+      }
+      _ => (Vec::new(), Vec::new()),
+    }
+  }
+
+  /// The narrowings of `lhs == rhs`, where one side is a place and the other a value of a single value type.
+  fn compare(&mut self, lhs: &ast::Expr, rhs: &ast::Expr) -> (Narrowing, Narrowing) {
+    let (place, value) = match (self.place_of(lhs), self.place_of(rhs)) {
+      (Some(place), _) => (place, self.value_type(rhs)),
+      (None, Some(place)) => (place, self.value_type(lhs)),
+      (None, None) => return (Vec::new(), Vec::new()),
+    };
+    let value = match value {
+      Some(value) => value,
+      None => return (Vec::new(), Vec::new()),
+    };
+    let ty = self.symbol_type(place.symbol);
+    match &place.field {
+      None => {
+        let then = self.narrow_to(&ty, &value);
+        let otherwise = self.exclude(&ty, &value);
+        (vec![(place.symbol, then)], vec![(place.symbol, otherwise)])
+      }
+      Some(field) => {
+        let members = self.members(&ty);
+        let mut then = Vec::new();
+        let mut otherwise = Vec::new();
+        for member in members {
+          let field_ty = match &self.structure(&member) {
+            Ty::Object(fields) => fields
+              .iter()
+              .find(|f| f.name == *field)
+              .map(|f| f.ty.clone()),
+            _ => None,
+          };
+          match field_ty {
+            Some(field_ty) => {
+              if self.overlaps(&field_ty, &value) {
+                then.push(member.clone());
+              }
+              if self.assignable(&field_ty, &value).is_err() {
+                otherwise.push(member);
+              }
+            }
+            None => {
+              then.push(member.clone());
+              otherwise.push(member);
+            }
+          }
+        }
+        (
+          vec![(place.symbol, Ty::union(then))],
+          vec![(place.symbol, Ty::union(otherwise))],
+        )
+      }
+    }
+  }
+
+  /// The place `expr` is, `x` or `x.field`.
+  fn place_of(&self, expr: &ast::Expr) -> Option<Place> {
+    match expr {
+      ast::Expr::PathExpr(path) => Some(Place {
+        symbol: self.path_symbol(path)?,
+        field: None,
+      }),
+      ast::Expr::FieldExpr(field) => match field.receiver()? {
+        ast::Expr::PathExpr(path) => Some(Place {
+          symbol: self.path_symbol(&path)?,
+          field: Some(field.name_ref()?.text()),
+        }),
+        _ => None,
+      },
+      ast::Expr::ParenExpr(paren) => self.place_of(&paren.expr()?),
+      _ => None,
+    }
+  }
+
+  /// The symbol of a variable, not of a function or a type.
+  fn path_symbol(&self, path: &ast::PathExpr) -> Option<SymbolId> {
+    let path = path.path().filter(|path| path.segments().count() == 1)?;
+    let symbol = self.resolve_name(&path.last_segment()?.name_ref()?).ok()?;
+    match self.symbols.symbol(symbol).kind {
+      SymbolKind::Fn | SymbolKind::Type | SymbolKind::Use | SymbolKind::Mod => None,
+      _ => Some(symbol),
+    }
+  }
+
+  /// The type of `expr` when it is a value of a single value type, e.g. `1` or `None`.
+  fn value_type(&mut self, expr: &ast::Expr) -> Option<Ty> {
+    let ty = match expr {
+      ast::Expr::Literal(literal) => literal_type(literal),
+      ast::Expr::PrefixExpr(prefix) => match (prefix.op(), prefix.expr()) {
+        (Some(PrefixOp::Neg), Some(ast::Expr::Literal(literal))) => match literal_type(&literal) {
+          Ty::Literal(Lit::Int { value, ty }) => Ty::Literal(Lit::Int { value: -value, ty }),
+          _ => return None,
+        },
+        _ => return None,
+      },
+      ast::Expr::PathExpr(path) => {
+        let name_ref = path.path()?.last_segment()?.name_ref()?;
+        match self.resolve_name(&name_ref) {
+          Err(true) if name_ref.text() == "None" => none(),
+          Ok(symbol) if self.symbols.symbol(symbol).kind == SymbolKind::Type => {
+            match self.nominal_value(symbol, name_ref.text()) {
+              ty @ Ty::Named(_) => return Some(ty),
+              _ => return None,
+            }
+          }
+          Ok(symbol) if self.symbols.symbol(symbol).kind == SymbolKind::Let => {
+            self.symbol_type(symbol)
+          }
+          _ => return None,
+        }
+      }
+      ast::Expr::ParenExpr(paren) => return self.value_type(&paren.expr()?),
+      _ => return None,
+    };
+    if is_unit(&ty) {
+      Some(ty)
+    } else {
+      None
+    }
+  }
+
+  /// The values of `ty` that are also values of `pattern`, e.g. `1` for `i32` and `1`.
+  pub(crate) fn narrow_to(&self, ty: &Ty, pattern: &Ty) -> Ty {
+    if ty.is_unknown() || pattern.is_unknown() {
+      return pattern.clone();
+    }
+    let types = self.members(ty).into_iter().filter_map(|member| {
+      if self.assignable(&member, pattern).is_ok() {
+        Some(member)
+      } else if self.assignable(pattern, &member).is_ok() {
+        Some(pattern.clone())
+      } else {
+        None
+      }
+    });
+    Ty::union(types.collect::<Vec<_>>())
+  }
+
+  /// The values of `ty` that are not values of `pattern`, e.g. `i32` for `i32 | None` and `None`.
+  pub(crate) fn exclude(&self, ty: &Ty, pattern: &Ty) -> Ty {
+    if ty.is_unknown() || pattern.is_unknown() {
+      return ty.clone();
+    }
+    let types = self
+      .members(ty)
+      .into_iter()
+      .filter(|member| self.assignable(member, pattern).is_err());
+    Ty::union(types.collect::<Vec<_>>())
+  }
+
+  /// True if some values have both types.
+  fn overlaps(&self, a: &Ty, b: &Ty) -> bool {
+    self.narrow_to(a, b) != Ty::Never
+  }
+
+  pub(crate) fn match_expr(&mut self, match_expr: &ast::MatchExpr, expected: Option<&Ty>) -> Ty {
+    let scrutinee = match_expr.expr();
+    let mut remaining = match &scrutinee {
+      Some(expr) => self.infer(expr, None),
+      None => Ty::Unknown,
+    };
+    let place = scrutinee.as_ref().and_then(|expr| self.place_of(expr));
+    let place = place
+      .filter(|place| place.field.is_none())
+      .map(|place| place.symbol);
+    let mut types = Vec::new();
+    let mut paths = Vec::new();
+    // False when a pattern isn't understood, which may match the remaining values.
+    let mut exhaustive = true;
+    for arm in match_expr.arms() {
+      let pattern = arm.pattern();
+      let pattern_kind = match &pattern {
+        Some(pattern) => self.pattern(pattern),
+        None => Pattern::Unknown,
+      };
+      let matched = self.matched(&remaining, &pattern_kind);
+      if let Some(pattern) = &pattern {
+        self.record(pattern.syntax().text_range(), &matched);
+      }
+      self.bind_pattern(&pattern_kind, &matched);
+      let narrowing = place
+        .map(|symbol| (symbol, matched.clone()))
+        .into_iter()
+        .collect();
+      let guarded = arm.guard().is_some();
+      let (ty, end) = self.in_branch(narrowing, |checker| {
+        if let Some(condition) = arm.guard().and_then(|guard| guard.condition()) {
+          checker.check_expr(&condition, &Ty::Bool);
+          let narrowing = checker.narrowing(&condition).0;
+          checker.narrowed.extend(narrowing);
+        }
+        match arm.body() {
+          Some(body) => checker.check_part(&body, expected),
+          None => Ty::Unknown,
+        }
+      });
+      if ty != Ty::Never {
+        paths.push(end);
+      }
+      types.push(ty);
+      exhaustive &= !matches!(pattern_kind, Pattern::Unknown);
+      if !guarded {
+        remaining = self.unmatched(&remaining, &pattern_kind);
+      }
+    }
+    if exhaustive && remaining != Ty::Never && !remaining.is_unknown() {
+      let span = scrutinee.map_or(match_expr.syntax().text_range(), |expr| {
+        expr.syntax().text_range()
+      });
+      self.diagnostics.push(
+        Diagnostic::error(
+          codes::NON_EXHAUSTIVE_MATCH,
+          format!("match is not exhaustive, `{}` is not covered", remaining),
+          span,
+        )
+        .with_label(format!("`{}` not covered", remaining)),
+      );
+    }
+    // The values no arm matches go on after the match.
+    if remaining != Ty::Never {
+      paths.push(
+        place
+          .map(|symbol| (symbol, remaining))
+          .into_iter()
+          .collect(),
+      );
+    }
+    self.join(paths);
+    Ty::union(types)
+  }
+
+  /// What `pattern` matches.
+  fn pattern(&mut self, pattern: &ast::Expr) -> Pattern {
+    match pattern {
+      ast::Expr::Literal(literal) => Pattern::Type(literal_type(literal)),
+      ast::Expr::ParenExpr(paren) => match paren.expr() {
+        Some(expr) => self.pattern(&expr),
+        None => Pattern::Unknown,
+      },
+      ast::Expr::PathExpr(path) => {
+        let name_ref = path
+          .path()
+          .filter(|path| path.segments().count() == 1)
+          .and_then(|path| path.last_segment()?.name_ref());
+        let name_ref = match name_ref {
+          Some(name_ref) => name_ref,
+          None => return Pattern::Unknown,
+        };
+        let name = name_ref.text();
+        if name == "_" {
+          return Pattern::All(None);
+        }
+        let binding = self.symbols.symbol_at(name_ref.syntax().text_range());
+        if let Some(symbol) = binding {
+          if self.symbols.symbol(symbol).kind == SymbolKind::MatchBinding {
+            return Pattern::All(Some(symbol));
+          }
+        }
+        match self.resolve_name(&name_ref) {
+          Ok(symbol) => match self.symbols.symbol(symbol).kind {
+            SymbolKind::Type => Pattern::Type(Ty::Named(Named {
+              name,
+              symbol,
+              args: Vec::new(),
+            })),
+            _ => match self.value_type(pattern) {
+              Some(ty) => Pattern::Type(ty),
+              None => Pattern::Unknown,
+            },
+          },
+          Err(true) => match builtin_type(&name, Vec::new()) {
+            Ty::Unknown => Pattern::Unknown,
+            ty => Pattern::Type(ty),
+          },
+          Err(false) => Pattern::Unknown,
+        }
+      }
+      ast::Expr::CallExpr(call) => {
+        let name = match call.callee() {
+          Some(ast::Expr::PathExpr(path)) => path
+            .path()
+            .filter(|path| path.segments().count() == 1)
+            .and_then(|path| path.last_segment()?.name_ref()),
+          _ => None,
+        };
+        let constructor = match name {
+          Some(name_ref) => match self.resolve_name(&name_ref) {
+            Ok(symbol) if self.is_nominal(symbol) => Constructor::Nominal(symbol),
+            Err(true) if CONSTRUCTORS.contains(&name_ref.text().as_str()) => {
+              Constructor::Builtin(name_ref.text())
+            }
+            _ => return Pattern::Unknown,
+          },
+          None => return Pattern::Unknown,
+        };
+        let mut args = call.arg_list().into_iter().flat_map(|list| list.args());
+        match (args.next(), args.next()) {
+          (Some(arg), None) => Pattern::Constructor(constructor, Box::new(self.pattern(&arg))),
+          _ => Pattern::Unknown,
+        }
+      }
+      ast::Expr::PrefixExpr(_) => match self.value_type(pattern) {
+        Some(ty) => Pattern::Type(ty),
+        None => Pattern::Unknown,
+      },
+      _ => Pattern::Unknown,
+    }
+  }
+
+  /// The values of `ty` matched by `pattern`.
+  fn matched(&self, ty: &Ty, pattern: &Pattern) -> Ty {
+    match pattern {
+      Pattern::All(_) | Pattern::Unknown => ty.clone(),
+      Pattern::Type(pattern) => self.narrow_to(ty, pattern),
+      Pattern::Constructor(constructor, arg) => {
+        let types = self.members(ty).into_iter().filter_map(|member| {
+          if let Ty::Unknown | Ty::Any = member {
+            return Some(member);
+          }
+          let inner = self.matched(&self.payload(&member, constructor)?, arg);
+          if inner == Ty::Never {
+            None
+          } else {
+            Some(rebuild(member, inner))
+          }
+        });
+        Ty::union(types.collect::<Vec<_>>())
+      }
+    }
+  }
+
+  /// The values of `ty` not matched by `pattern`.
+  fn unmatched(&self, ty: &Ty, pattern: &Pattern) -> Ty {
+    match pattern {
+      Pattern::All(_) => Ty::Never,
+      Pattern::Unknown => ty.clone(),
+      Pattern::Type(pattern) => self.exclude(ty, pattern),
+      Pattern::Constructor(constructor, arg) => {
+        let types = self.members(ty).into_iter().filter_map(|member| {
+          let payload = match self.payload(&member, constructor) {
+            Some(payload) => payload,
+            None => return Some(member),
+          };
+          let inner = self.unmatched(&payload, arg);
+          if inner == Ty::Never {
+            None
+          } else {
+            Some(rebuild(member, inner))
+          }
+        });
+        Ty::union(types.collect::<Vec<_>>())
+      }
+    }
+  }
+
+  /// The type of the value a value of type `member` is built from, if it is built by `constructor`,
+  /// e.g. `i32` for `Some<i32>`.
+  fn payload(&self, member: &Ty, constructor: &Constructor) -> Option<Ty> {
+    match (member, constructor) {
+      (Ty::Builtin(name, args), Constructor::Builtin(constructor)) if name == constructor => {
+        Some(args.first().cloned().unwrap_or(Ty::Unknown))
+      }
+      (Ty::Named(named), Constructor::Nominal(symbol)) if named.symbol == *symbol => {
+        match self.expand(member) {
+          Ty::Symbol(Some(payload)) => Some(*payload),
+          Ty::Symbol(None) => None,
+          structure => Some(structure),
+        }
+      }
+      _ => None,
+    }
+  }
+
+  /// The types of the names bound by `pattern` to the values of `matched`.
+  fn bind_pattern(&mut self, pattern: &Pattern, matched: &Ty) {
+    match pattern {
+      Pattern::All(Some(symbol)) => {
+        self.symbol_types.insert(*symbol, matched.clone());
+      }
+      Pattern::Constructor(constructor, arg) => {
+        let inner = self
+          .members(matched)
+          .into_iter()
+          .filter_map(|member| self.payload(&member, constructor));
+        let inner = Ty::union(inner.collect::<Vec<_>>());
+        let inner = if inner == Ty::Never {
+          Ty::Unknown
+        } else {
+          inner
+        };
+        self.bind_pattern(arg, &inner)
+      }
+      _ => (),
+    }
+  }
+}
+
+/// `member` built from a value of type `payload`, e.g. `Some<1>` for `Some<i32>` and `1`.
+/// A nominal type is kept as it is.
+fn rebuild(member: Ty, payload: Ty) -> Ty {
+  match member {
+    Ty::Builtin(name, _) => Ty::Builtin(name, vec![payload]),
+    member => member,
+  }
+}
+
+/// True if `ty` has a single value, e.g. `1` or `None`.
+fn is_unit(ty: &Ty) -> bool {
+  match ty {
+    Ty::Literal(_) | Ty::Unit => true,
+    Ty::Builtin(name, args) => name == "None" && args.is_empty(),
+    _ => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::check;
+  use expect_test::{expect, Expect};
+  use justc_binder::bind;
+  use justc_binder::symbols::SymbolKind;
+  use justc_parser::parse;
+
+  /// The types of the uses of the variables, followed by the diagnostics.
+  fn check_narrowing(text: &str, expect: Expect) {
+    let parse = parse(text);
+    assert_eq!(parse.errors(), &[], "{}", text);
+    let tree = parse.tree();
+    let binding = bind(&tree);
+    let symbols = binding.symbols();
+    let types = check(&tree, symbols);
+    let mut out = String::new();
+    for reference in symbols.references() {
+      let variable = reference.symbol().is_some_and(|symbol| {
+        !matches!(
+          symbols.symbol(symbol).kind,
+          SymbolKind::Fn | SymbolKind::Type | SymbolKind::Use | SymbolKind::Mod
+        )
+      });
+      if let Some(ty) = types.expr_type(reference.span).filter(|_| variable) {
+        out.push_str(&format!(
+          "{:?} {}: {}\n",
+          reference.span, reference.name, ty
+        ));
+      }
+    }
+    for diagnostic in types.diagnostics() {
+      out.push_str(&format!("{:?}: {}\n", diagnostic.span, diagnostic.message));
+    }
+    expect.assert_eq(&out);
+  }
+
+  #[test]
+  fn narrow_conditions() {
+    check_narrowing(
+      r#"
+type MaybeInt = i32 | None
+type Circle = { kind: "circle", radius: f64 }
+type Square = { kind: "square", side: f64 }
+type Shape = Circle | Square
+fn compare(x: MaybeInt) {
+  if x != None { x } else { x };
+  if !(x == None) { x };
+  if x == 1 { x } else { x };
+}
+fn combine(x: MaybeInt, y: MaybeInt) {
+  if x != None && y != None { x; y };
+  if x == None || y == None {} else { x; y };
+}
+fn fields(shape: Shape) {
+  if shape.kind == "circle" { shape } else { shape };
+}
+"#,
+      expect![[r#"
+          178..179 x: MaybeInt
+          190..191 x: i32
+          201..202 x: None
+          213..214 x: MaybeInt
+          226..227 x: i32
+          236..237 x: MaybeInt
+          245..246 x: 1
+          256..257 x: i32 | None
+          307..308 x: MaybeInt
+          320..321 y: MaybeInt
+          332..333 x: i32
+          335..336 y: i32
+          345..346 x: MaybeInt
+          358..359 y: MaybeInt
+          378..379 x: i32
+          381..382 y: i32
+          419..424 shape: Shape
+          444..449 shape: { kind: "circle", radius: f64 }
+          459..464 shape: { kind: "square", side: f64 }
+      "#]],
+    );
+  }
+
+  #[test]
+  fn narrow_match_arms() {
+    check_narrowing(
+      r#"
+nominal type Some<T> = Symbol<T>()
+nominal type None = Symbol()
+nominal type Option<T> = Some<T> | None
+fn arms(x: Option<i32>) {
+  match x {
+    Some(1) => x,
+    Some(v) => v,
+    None => x,
+  };
+}
+type Value = "miku" | "rin" | i32
+fn remaining(x: Value) {
+  match x {
+    "miku" => return,
+    _ if x == "rin" => x,
+    i32 => return,
+    _ => x,
+  };
+  x;
+}
+"#,
+      expect![[r#"
+          139..140 x: Option<i32>
+          158..159 x: Some<i32>
+          176..177 v: i32
+          191..192 x: None
+          268..269 x: Value
+          303..304 x: "rin" | i32
+          317..318 x: "rin"
+          348..349 x: "rin"
+          358..359 x: "rin"
+      "#]],
+    );
+  }
+
+  #[test]
+  fn restore_declared_types() {
+    check_narrowing(
+      r#"
+type MaybeInt = i32 | None
+type Circle = { kind: "circle", radius: f64 }
+type Square = { kind: "square", side: f64 }
+type Shape = Circle | Square
+fn loops(input: MaybeInt) {
+  let mut x = input;
+  if x == None { return }
+  x;
+  for i in [1, 2] {
+    x;
+    x = None
+  }
+  x;
+}
+fn fields(input: Shape) {
+  let mut shape = input;
+  if shape.kind == "square" { return }
+  shape;
+  shape.radius = 2.0;
+  shape;
+}
+fn casts(x: MaybeInt) {
+  x as None;
+  x;
+}
+"#,
+      expect![[r#"
+          189..194 input: MaybeInt
+          201..202 x: MaybeInt
+          224..225 x: i32
+          251..252 x: MaybeInt
+          258..259 x: MaybeInt
+          273..274 x: MaybeInt
+          322..327 input: Shape
+          334..339 shape: Shape
+          370..375 shape: { kind: "circle", radius: f64 }
+          379..384 shape: { kind: "circle", radius: f64 }
+          401..406 shape: Shape
+          436..437 x: MaybeInt
+          449..450 x: None
+      "#]],
+    );
+  }
+}
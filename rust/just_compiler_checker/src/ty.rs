@@ -0,0 +1,420 @@
+//! Types of values, as inferred and checked by the checker.
+//!
+//! Types are structural: two object types with the same fields are the same type, whatever their names.
+//! Type aliases are kept as `Named` for messages, and expanded when types are compared,
+//! except nominal types, which are only the values built as them, e.g. `Vocaloid { name }` for `nominal type Vocaloid`.
+
+use justc_binder::symbols::SymbolId;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IntTy {
+  I8,
+  I16,
+  I32,
+  I64,
+  I128,
+  ISize,
+  U8,
+  U16,
+  U32,
+  U64,
+  U128,
+  USize,
+  /// `int`, a signed integer of 64 bits.
+  Int,
+  /// `uint`, an unsigned integer of 64 bits.
+  UInt,
+}
+
+impl IntTy {
+  pub fn from_name(name: &str) -> Option<IntTy> {
+    let ty = match name {
+      "i8" => IntTy::I8,
+      "i16" => IntTy::I16,
+      "i32" => IntTy::I32,
+      "i64" => IntTy::I64,
+      "i128" => IntTy::I128,
+      "isize" => IntTy::ISize,
+      "u8" => IntTy::U8,
+      "u16" => IntTy::U16,
+      "u32" => IntTy::U32,
+      "u64" => IntTy::U64,
+      "u128" => IntTy::U128,
+      "usize" => IntTy::USize,
+      "int" => IntTy::Int,
+      "uint" => IntTy::UInt,
+      _ => return None,
+    };
+    Some(ty)
+  }
+
+  pub fn name(self) -> &'static str {
+    match self {
+      IntTy::I8 => "i8",
+      IntTy::I16 => "i16",
+      IntTy::I32 => "i32",
+      IntTy::I64 => "i64",
+      IntTy::I128 => "i128",
+      IntTy::ISize => "isize",
+      IntTy::U8 => "u8",
+      IntTy::U16 => "u16",
+      IntTy::U32 => "u32",
+      IntTy::U64 => "u64",
+      IntTy::U128 => "u128",
+      IntTy::USize => "usize",
+      IntTy::Int => "int",
+      IntTy::UInt => "uint",
+    }
+  }
+
+  /// True if `value` is in the range of the type.
+  pub fn fits(self, value: i128) -> bool {
+    let (min, max) = match self {
+      IntTy::I8 => (i8::MIN as i128, i8::MAX as i128),
+      IntTy::I16 => (i16::MIN as i128, i16::MAX as i128),
+      IntTy::I32 => (i32::MIN as i128, i32::MAX as i128),
+      IntTy::I64 | IntTy::ISize | IntTy::Int => (i64::MIN as i128, i64::MAX as i128),
+      IntTy::I128 => (i128::MIN, i128::MAX),
+      IntTy::U8 => (0, u8::MAX as i128),
+      IntTy::U16 => (0, u16::MAX as i128),
+      IntTy::U32 => (0, u32::MAX as i128),
+      IntTy::U64 | IntTy::USize | IntTy::UInt => (0, u64::MAX as i128),
+      IntTy::U128 => (0, i128::MAX),
+    };
+    min <= value && value <= max
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FloatTy {
+  F32,
+  F64,
+  /// `float`, a floating-point of 64 bits.
+  Float,
+}
+
+impl FloatTy {
+  pub fn from_name(name: &str) -> Option<FloatTy> {
+    match name {
+      "f32" => Some(FloatTy::F32),
+      "f64" => Some(FloatTy::F64),
+      "float" => Some(FloatTy::Float),
+      _ => None,
+    }
+  }
+
+  pub fn name(self) -> &'static str {
+    match self {
+      FloatTy::F32 => "f32",
+      FloatTy::F64 => "f64",
+      FloatTy::Float => "float",
+    }
+  }
+}
+
+/// The value of a literal type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Lit {
+  Bool(bool),
+  /// An integer without suffix, `ty` is the type it widens to, e.g. `i32` for `1` and `u32` for `0xff`.
+  Int {
+    value: i128,
+    ty: IntTy,
+  },
+  Char(char),
+  Str(String),
+}
+
+impl Lit {
+  /// The type of the values of the same kind, e.g. `i32` for `1`.
+  pub fn base(&self) -> Ty {
+    match self {
+      Lit::Bool(_) => Ty::Bool,
+      Lit::Int { ty, .. } => Ty::Int(*ty),
+      Lit::Char(_) => Ty::Char,
+      Lit::Str(_) => Ty::Str,
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Ty {
+  /// The type of an expression that could not be typed, e.g. after an error.
+  /// It is compatible with any type, so one error is reported once.
+  Unknown,
+  /// `any`
+  Any,
+  /// The type of expressions that never produce a value, e.g. `return x`.
+  Never,
+  /// The type of statements and of functions without a value, `()`.
+  Unit,
+  Bool,
+  Char,
+  Str,
+  String,
+  Int(IntTy),
+  Float(FloatTy),
+  /// `true`, `1`, `'a'`, `"foo"`
+  Literal(Lit),
+  /// `{ name: String, age?: uint }`, fields are sorted by name.
+  Object(Vec<Field>),
+  /// `[i32, char]`
+  Tuple(Vec<Ty>),
+  /// `[i32; 3]`, or `[i32]` of any length.
+  Array(Box<Ty>, Option<u64>),
+  Fn(FnTy),
+  /// `i32 | None`, never nested, and with at least two types.
+  Union(Vec<Ty>),
+  /// `A & B`, of types that are not all objects, which are merged instead.
+  Intersection(Vec<Ty>),
+  /// `&T`, `&mut T`
+  Ref(Box<Ty>, bool),
+  /// A type alias, e.g. `Vocaloid` or `Pair<i32>`.
+  Named(Named),
+  /// A generic parameter, e.g. `T` in `<T>(x: T) -> T`.
+  Param(Param),
+  /// A type provided by the language, e.g. `Vec<T>`, `Some<T>` or `None`.
+  Builtin(String, Vec<Ty>),
+  /// `Symbol()`, or `Symbol<T>()` carrying a value of type `T`, the type of a nominal type without structure,
+  /// e.g. `nominal type None = Symbol()`.
+  Symbol(Option<Box<Ty>>),
+  /// `T extends str ? true : false`, evaluated when its type is needed, see `eval`.
+  Conditional(Box<Conditional>),
+  /// `keyof T`, the union of the names of the fields of `T`.
+  Keyof(Box<Ty>),
+  /// `{ [K in keyof T]?: T[K] }`
+  Mapped(Box<Mapped>),
+  /// `T["name"]`, `T[0]`
+  Index(Box<Ty>, Box<Ty>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Field {
+  pub name: String,
+  pub ty: Ty,
+  pub optional: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FnTy {
+  pub generics: Vec<Param>,
+  pub params: Vec<Ty>,
+  pub ret: Box<Ty>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Named {
+  pub name: String,
+  /// The `type` declaration.
+  pub symbol: SymbolId,
+  pub args: Vec<Ty>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Param {
+  pub name: String,
+  pub symbol: SymbolId,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Conditional {
+  pub check: Ty,
+  pub extends: Ty,
+  pub then: Ty,
+  pub otherwise: Ty,
+  /// The generic parameter tested, when `check` is one, e.g. `T` in `T extends None ? never : T`.
+  /// It stays in the branches when it is substituted, and is bound to each type of the union it stands for,
+  /// e.g. `i32` for `i32 | None`.
+  pub param: Option<Param>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Mapped {
+  /// `K`, bound to each key in `value`.
+  pub key: Param,
+  pub keys: Ty,
+  pub value: Ty,
+  pub optional: bool,
+}
+
+impl Ty {
+  /// The union of `types`, flattened and without duplicates, e.g. `never` for no types.
+  pub fn union(types: impl IntoIterator<Item = Ty>) -> Ty {
+    let mut members: Vec<Ty> = Vec::new();
+    for ty in types {
+      let flattened = match ty {
+        Ty::Union(types) => types,
+        Ty::Never => continue,
+        Ty::Unknown | Ty::Any => return ty,
+        ty => vec![ty],
+      };
+      for ty in flattened {
+        if !members.contains(&ty) {
+          members.push(ty);
+        }
+      }
+    }
+    match members.len() {
+      0 => Ty::Never,
+      1 => members.pop().unwrap(),
+      _ => Ty::Union(members),
+    }
+  }
+
+  /// An object type of `fields`, in any order.
+  pub fn object(mut fields: Vec<Field>) -> Ty {
+    fields.sort_by(|a, b| a.name.cmp(&b.name));
+    Ty::Object(fields)
+  }
+
+  /// The type of a mutable value initialized with a value of this type, e.g. `i32` for `1`.
+  pub fn widen(&self) -> Ty {
+    match self {
+      Ty::Literal(lit) => lit.base(),
+      Ty::Union(types) => Ty::union(types.iter().map(Ty::widen)),
+      _ => self.clone(),
+    }
+  }
+
+  pub fn is_unknown(&self) -> bool {
+    *self == Ty::Unknown
+  }
+
+  /// The types of a union, or the type itself.
+  pub fn members(&self) -> &[Ty] {
+    match self {
+      Ty::Union(types) => types,
+      _ => std::slice::from_ref(self),
+    }
+  }
+}
+
+impl fmt::Display for Ty {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Ty::Unknown => f.write_str("{unknown}"),
+      Ty::Any => f.write_str("any"),
+      Ty::Never => f.write_str("never"),
+      Ty::Unit => f.write_str("()"),
+      Ty::Bool => f.write_str("bool"),
+      Ty::Char => f.write_str("char"),
+      Ty::Str => f.write_str("str"),
+      Ty::String => f.write_str("String"),
+      Ty::Int(ty) => f.write_str(ty.name()),
+      Ty::Float(ty) => f.write_str(ty.name()),
+      Ty::Literal(Lit::Bool(value)) => write!(f, "{}", value),
+      Ty::Literal(Lit::Int { value, .. }) => write!(f, "{}", value),
+      Ty::Literal(Lit::Char(value)) => write!(f, "{:?}", value),
+      Ty::Literal(Lit::Str(value)) => write!(f, "{:?}", value),
+      Ty::Object(fields) if fields.is_empty() => f.write_str("{}"),
+      Ty::Object(fields) => {
+        f.write_str("{ ")?;
+        for (i, field) in fields.iter().enumerate() {
+          if i > 0 {
+            f.write_str(", ")?;
+          }
+          let optional = if field.optional { "?" } else { "" };
+          write!(f, "{}{}: {}", field.name, optional, field.ty)?;
+        }
+        f.write_str(" }")
+      }
+      Ty::Tuple(types) => {
+        f.write_str("[")?;
+        list(f, types, ", ")?;
+        f.write_str("]")
+      }
+      Ty::Array(ty, Some(len)) => write!(f, "[{}; {}]", ty, len),
+      Ty::Array(ty, None) => write!(f, "[{}]", ty),
+      Ty::Fn(ty) => {
+        if !ty.generics.is_empty() {
+          f.write_str("<")?;
+          for (i, param) in ty.generics.iter().enumerate() {
+            if i > 0 {
+              f.write_str(", ")?;
+            }
+            f.write_str(&param.name)?;
+          }
+          f.write_str(">")?;
+        }
+        f.write_str("(")?;
+        list(f, &ty.params, ", ")?;
+        write!(f, ") -> {}", ty.ret)
+      }
+      Ty::Union(types) => list(f, types, " | "),
+      Ty::Intersection(types) => list(f, types, " & "),
+      Ty::Ref(ty, false) => {
+        f.write_str("&")?;
+        operand(f, ty)
+      }
+      Ty::Ref(ty, true) => {
+        f.write_str("&mut ")?;
+        operand(f, ty)
+      }
+      Ty::Named(named) => generic(f, &named.name, &named.args),
+      Ty::Param(param) => f.write_str(&param.name),
+      Ty::Builtin(name, args) => generic(f, name, args),
+      Ty::Symbol(None) => f.write_str("Symbol()"),
+      Ty::Symbol(Some(ty)) => write!(f, "Symbol<{}>()", ty),
+      Ty::Conditional(ty) => write!(
+        f,
+        "{} extends {} ? {} : {}",
+        ty.check, ty.extends, ty.then, ty.otherwise
+      ),
+      Ty::Keyof(ty) => {
+        f.write_str("keyof ")?;
+        operand(f, ty)
+      }
+      Ty::Mapped(ty) => {
+        let optional = if ty.optional { "?" } else { "" };
+        write!(
+          f,
+          "{{ [{} in {}]{}: {} }}",
+          ty.key.name, ty.keys, optional, ty.value
+        )
+      }
+      Ty::Index(ty, index) => {
+        operand(f, ty)?;
+        write!(f, "[{}]", index)
+      }
+    }
+  }
+}
+
+/// Writes `types` separated by `separator`, in parentheses when they would bind to the separator,
+/// e.g. `(() -> i32) | None`.
+fn list(f: &mut fmt::Formatter<'_>, types: &[Ty], separator: &str) -> fmt::Result {
+  for (i, ty) in types.iter().enumerate() {
+    if i > 0 {
+      f.write_str(separator)?;
+    }
+    match ty {
+      Ty::Fn(_) | Ty::Union(_) | Ty::Intersection(_) | Ty::Conditional(_) if separator != ", " => {
+        write!(f, "({})", ty)?
+      }
+      _ => write!(f, "{}", ty)?,
+    }
+  }
+  Ok(())
+}
+
+/// Writes the operand of `keyof`, of an index or of a reference, in parentheses when it would bind to the operator,
+/// e.g. `keyof (A | B)`.
+fn operand(f: &mut fmt::Formatter<'_>, ty: &Ty) -> fmt::Result {
+  match ty {
+    Ty::Fn(_) | Ty::Union(_) | Ty::Intersection(_) | Ty::Conditional(_) | Ty::Keyof(_) => {
+      write!(f, "({})", ty)
+    }
+    _ => write!(f, "{}", ty),
+  }
+}
+
+fn generic(f: &mut fmt::Formatter<'_>, name: &str, args: &[Ty]) -> fmt::Result {
+  f.write_str(name)?;
+  if !args.is_empty() {
+    f.write_str("<")?;
+    list(f, args, ", ")?;
+    f.write_str(">")?;
+  }
+  Ok(())
+}
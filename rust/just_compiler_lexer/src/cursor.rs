@@ -1,5 +1,9 @@
 use std::str::Chars;
 
+/// Peekable iterator over a char sequence.
+///
+/// Next characters can be peeked via `first` and `second` methods,
+/// and position can be shifted forward via `bump` method.
 pub struct Cursor<'a> {
   initial_len: usize,
   chars: Chars<'a>,
@@ -19,6 +23,7 @@ impl<'a> Cursor<'a> {
     }
   }
 
+  /// Moves to the next character.
   pub fn bump(&mut self) -> Option<char> {
     let c = self.chars.next()?;
 
@@ -30,11 +35,30 @@ impl<'a> Cursor<'a> {
     Some(c)
   }
 
+  /// Returns the last eaten symbol (or `'\0'` in release builds).
+  /// (For debug assertions only.)
+  pub fn prev(&self) -> char {
+    #[cfg(debug_assertions)]
+    {
+      self.prev
+    }
+
+    #[cfg(not(debug_assertions))]
+    {
+      EOF_CHAR
+    }
+  }
+
   /// Peeks the next symbol from the input stream without consuming it.
   pub fn first(&self) -> char {
     self.nth_char(0)
   }
 
+  /// Peeks the second symbol from the input stream without consuming it.
+  pub fn second(&self) -> char {
+    self.nth_char(1)
+  }
+
   /// Returns nth character relative to the current cursor position.
   /// If requested position doesn't exist, `EOF_CHAR` is returned.
   /// However, getting `EOF_CHAR` doesn't always mean actual end of file,
@@ -0,0 +1,453 @@
+//! Lexer diagnostics.
+//!
+//! The lexer never fails, errors are recorded in the token kinds
+//! (e.g. `BlockComment { terminated: false }`).
+//! This module walks the token stream and reports them as `Diagnostic`s.
+
+use super::diagnostic::{Code, Diagnostic};
+use super::literal::{eval_number, NumberError, NumberErrorKind};
+use super::span::Span;
+use super::token_stream::{tokenize_with_spans, SpannedToken};
+use super::tokenize::{LiteralKind, RawStrError, TokenKind};
+
+pub const UNTERMINATED_BLOCK_COMMENT: Code = Code("L0001");
+pub const UNTERMINATED_CHAR: Code = Code("L0002");
+pub const UNTERMINATED_STRING: Code = Code("L0003");
+pub const UNTERMINATED_RAW_STRING: Code = Code("L0004");
+pub const INVALID_RAW_STRING_STARTER: Code = Code("L0005");
+pub const TOO_MANY_RAW_STRING_DELIMITERS: Code = Code("L0006");
+pub const UNTERMINATED_TEMPLATE: Code = Code("L0007");
+pub const UNTERMINATED_SUBSTITUTION: Code = Code("L0008");
+pub const EMPTY_INT: Code = Code("L0009");
+pub const EMPTY_EXPONENT: Code = Code("L0010");
+pub const UNKNOWN_TOKEN: Code = Code("L0011");
+pub const INVALID_DIGIT: Code = Code("L0012");
+pub const INVALID_NUMBER_SUFFIX: Code = Code("L0013");
+pub const NUMBER_OUT_OF_RANGE: Code = Code("L0014");
+pub const NON_DECIMAL_FLOAT: Code = Code("L0015");
+
+/// Tokenizes the input and returns the diagnostics of all invalid tokens.
+pub fn diagnose(input: &str) -> Vec<Diagnostic> {
+  diagnose_tokens(input, tokenize_with_spans(input))
+}
+
+/// Returns the diagnostics of all invalid tokens in `tokens`, which are tokens of `input`.
+pub fn diagnose_tokens(
+  input: &str,
+  tokens: impl IntoIterator<Item = SpannedToken>,
+) -> Vec<Diagnostic> {
+  let mut diagnostics = Vec::new();
+  // Template heads whose substitution is not closed yet.
+  let mut open_templates = Vec::new();
+  for token in tokens {
+    match token.kind {
+      TokenKind::Literal {
+        kind: LiteralKind::TemplateHead,
+        ..
+      } => open_templates.push(token.span),
+      TokenKind::Literal {
+        kind: LiteralKind::TemplateTail { .. },
+        ..
+      } => {
+        open_templates.pop();
+      }
+      _ => (),
+    }
+    diagnostics.extend(diagnose_token(input, &token));
+  }
+
+  let end = Span::empty(input.len());
+  for head in open_templates.into_iter().rev() {
+    diagnostics.push(
+      Diagnostic::error(
+        UNTERMINATED_SUBSTITUTION,
+        "unterminated template substitution",
+        head,
+      )
+      .with_label("substitution starts here")
+      .with_fix("close the substitution and the template string", end, "}`"),
+    );
+  }
+  diagnostics
+}
+
+/// Returns the diagnostic of an invalid token, `None` if the token is valid.
+pub fn diagnose_token(input: &str, token: &SpannedToken) -> Option<Diagnostic> {
+  let span = token.span;
+  let end = Span::empty(span.end);
+  let diagnostic = match token.kind {
+    TokenKind::BlockComment {
+      terminated: false, ..
+    } => {
+      let closing = "*/".repeat(unclosed_block_comments(token.text(input)));
+      Diagnostic::error(
+        UNTERMINATED_BLOCK_COMMENT,
+        "unterminated block comment",
+        span,
+      )
+      .with_label("comment starts here")
+      .with_fix(
+        format!("close the comment with `{}`", closing),
+        end,
+        closing,
+      )
+    }
+    TokenKind::Literal {
+      kind: LiteralKind::Int { .. },
+      ..
+    }
+    | TokenKind::Literal {
+      kind: LiteralKind::Float { .. },
+      ..
+    } => return eval_number(input, token).err().map(number_diagnostic),
+    TokenKind::Literal { kind, .. } => return diagnose_literal(kind, span),
+    TokenKind::Unknown => {
+      let c = token.text(input).chars().next().unwrap_or_default();
+      Diagnostic::error(
+        UNKNOWN_TOKEN,
+        format!("unknown start of token: {}", c.escape_debug()),
+        span,
+      )
+    }
+    _ => return None,
+  };
+  Some(diagnostic)
+}
+
+fn diagnose_literal(kind: LiteralKind, span: Span) -> Option<Diagnostic> {
+  let end = Span::empty(span.end);
+  let unterminated = |code, what: &str, quote: &str, label: &str| {
+    Diagnostic::error(code, format!("unterminated {}", what), span)
+      .with_label(label)
+      .with_fix(
+        format!("close the {} with {}", what, code_span(quote)),
+        end,
+        quote,
+      )
+  };
+  let diagnostic = match kind {
+    LiteralKind::Char { terminated: false } => unterminated(
+      UNTERMINATED_CHAR,
+      "character literal",
+      "'",
+      "character literal starts here",
+    ),
+    LiteralKind::Byte { terminated: false } => unterminated(
+      UNTERMINATED_CHAR,
+      "byte literal",
+      "'",
+      "byte literal starts here",
+    ),
+    LiteralKind::Str { terminated: false } => {
+      unterminated(UNTERMINATED_STRING, "string", "\"", "string starts here")
+    }
+    LiteralKind::ByteStr { terminated: false } => unterminated(
+      UNTERMINATED_STRING,
+      "byte string",
+      "\"",
+      "byte string starts here",
+    ),
+    LiteralKind::Template { terminated: false } => unterminated(
+      UNTERMINATED_TEMPLATE,
+      "template string",
+      "`",
+      "template string starts here",
+    ),
+    LiteralKind::TemplateTail { terminated: false } => unterminated(
+      UNTERMINATED_TEMPLATE,
+      "template string",
+      "`",
+      "template string continues here",
+    ),
+    LiteralKind::RawStr { err: Some(err), .. } | LiteralKind::RawByteStr { err: Some(err), .. } => {
+      diagnose_raw_str(err, span)
+    }
+    _ => return None,
+  };
+  Some(diagnostic)
+}
+
+fn number_diagnostic(err: NumberError) -> Diagnostic {
+  let code = match err.kind {
+    NumberErrorKind::EmptyInt => EMPTY_INT,
+    NumberErrorKind::EmptyExponent => EMPTY_EXPONENT,
+    NumberErrorKind::InvalidDigit { .. } => INVALID_DIGIT,
+    NumberErrorKind::InvalidSuffix { .. } => INVALID_NUMBER_SUFFIX,
+    NumberErrorKind::IntOverflow { .. } | NumberErrorKind::FloatOverflow { .. } => {
+      NUMBER_OUT_OF_RANGE
+    }
+    NumberErrorKind::NonDecimalFloat { .. } => NON_DECIMAL_FLOAT,
+  };
+  let diagnostic = Diagnostic::error(code, err.to_string(), err.span);
+  match err.kind {
+    NumberErrorKind::InvalidSuffix { .. } => diagnostic.with_note(
+      "valid suffixes are `i8` to `i128`, `isize`, `u8` to `u128`, `usize`, `f32` and `f64`",
+    ),
+    _ => diagnostic,
+  }
+}
+
+fn diagnose_raw_str(err: RawStrError, span: Span) -> Diagnostic {
+  match err {
+    RawStrError::InvalidStarter { bad_char } => Diagnostic::error(
+      INVALID_RAW_STRING_STARTER,
+      format!(
+        "found invalid character; only `#` is allowed in raw string delimitation: {}",
+        bad_char.escape_debug()
+      ),
+      span,
+    ),
+    RawStrError::NoTerminator {
+      expected,
+      found,
+      possible_terminator_offset,
+    } => {
+      let terminator = format!("\"{}", "#".repeat(expected));
+      let diagnostic = Diagnostic::error(
+        UNTERMINATED_RAW_STRING,
+        "unterminated raw string",
+        span,
+      )
+      .with_label("unterminated raw string");
+      let help = format!("close the raw string with `{}`", terminator);
+      match possible_terminator_offset {
+        // `possible_terminator_offset` points right after the quote of the closest terminator,
+        // complete its `#`s.
+        Some(offset) => {
+          let start = span.start + offset;
+          diagnostic
+            .with_span_note(
+              Span::new(start - 1, start + found),
+              format!(
+                "this terminator has {} `#`s, {} are expected",
+                found, expected
+              ),
+            )
+            .with_fix(
+              help,
+              Span::new(start, start + found),
+              "#".repeat(expected),
+            )
+        }
+        None => diagnostic.with_fix(help, Span::empty(span.end), terminator),
+      }
+    }
+    RawStrError::TooManyDelimiters { found } => Diagnostic::error(
+      TOO_MANY_RAW_STRING_DELIMITERS,
+      format!(
+        "too many `#` symbols: raw strings may be delimited by up to 65535 `#` symbols, but found {}",
+        found
+      ),
+      span,
+    ),
+  }
+}
+
+/// Formats `code` as inline code, `` ` `` when it contains a backtick.
+fn code_span(code: &str) -> String {
+  if code.contains('`') {
+    format!("`` {} ``", code)
+  } else {
+    format!("`{}`", code)
+  }
+}
+
+/// Number of block comments still open at the end of the (nested) block comment `text`.
+fn unclosed_block_comments(text: &str) -> usize {
+  let bytes = text.as_bytes();
+  let mut depth = 0;
+  let mut i = 0;
+  while i + 1 < bytes.len() {
+    match (bytes[i], bytes[i + 1]) {
+      (b'/', b'*') => {
+        depth += 1;
+        i += 2;
+      }
+      (b'*', b'/') => {
+        depth -= 1;
+        i += 2;
+      }
+      _ => i += 1,
+    }
+  }
+  depth.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::emitter::{render_all, SourceInput};
+  use crate::line_index::LineIndex;
+  use expect_test::{expect, Expect};
+
+  fn check(src: &str, expect: Expect) {
+    let line_index = LineIndex::new(src);
+    let input = SourceInput {
+      path: "main.just",
+      src,
+      line_index: &line_index,
+    };
+    expect.assert_eq(&render_all(&diagnose(src), &input));
+  }
+
+  fn check_fix(src: &str, fixed: &str) {
+    let diagnostics = diagnose(src);
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    let fixed_src = diagnostics[0].fix.as_ref().unwrap().apply(src);
+    assert_eq!(fixed_src, fixed);
+    assert!(diagnose(&fixed_src).is_empty());
+  }
+
+  #[test]
+  fn valid_tokens() {
+    check("let s = r#\"a\"# + `b${c}` // ok", expect![[r#""#]]);
+  }
+
+  #[test]
+  fn unterminated_block_comment() {
+    check(
+      "let a = 1\n/* outer /* inner */\nlet b = 2",
+      expect![[r#"
+          error[L0001]: unterminated block comment
+           --> main.just:2:1
+            |
+          2 | /* outer /* inner */
+            | ^^^^^^^^^^^^^^^^^^^^ comment starts here
+            |
+            = help: close the comment with `*/`
+      "#]],
+    );
+    check_fix("/* a /* b", "/* a /* b*/*/");
+  }
+
+  #[test]
+  fn unterminated_strings() {
+    check(
+      "let c = 'a\nlet s = \"abc",
+      expect![[r#"
+        error[L0002]: unterminated character literal
+         --> main.just:1:9
+          |
+        1 | let c = 'a
+          |         ^^ character literal starts here
+          |
+          = help: close the character literal with `'`
+
+        error[L0003]: unterminated string
+         --> main.just:2:9
+          |
+        2 | let s = "abc
+          |         ^^^^ string starts here
+          |
+          = help: close the string with `"`
+    "#]],
+    );
+    check_fix("\"abc", "\"abc\"");
+    check_fix("b'a", "b'a'");
+  }
+
+  #[test]
+  fn raw_strings() {
+    check(
+      "let s = r###\"abc\"##\nlet t = 1",
+      expect![[r####"
+          error[L0004]: unterminated raw string
+           --> main.just:1:9
+            |
+          1 | let s = r###"abc"##
+            |         ^^^^^^^^^^^ unterminated raw string
+            |                 --- this terminator has 2 `#`s, 3 are expected
+            |
+            = help: close the raw string with `"###`
+      "####]],
+    );
+    check_fix("r###\"abc\"## + 1", "r###\"abc\"### + 1");
+    check_fix("r#\"abc", "r#\"abc\"#");
+    check(
+      "r#~\"x\"#",
+      expect![[r##"
+        error[L0005]: found invalid character; only `#` is allowed in raw string delimitation: ~
+         --> main.just:1:1
+          |
+        1 | r#~"x"#
+          | ^^^
+    "##]],
+    );
+  }
+
+  #[test]
+  fn templates() {
+    check(
+      "let t = `${u",
+      expect![[r#"
+        error[L0008]: unterminated template substitution
+         --> main.just:1:9
+          |
+        1 | let t = `${u
+          |         ^^^ substitution starts here
+          |
+          = help: close the substitution and the template string
+    "#]],
+    );
+    check(
+      "let s = `a ${b} c",
+      expect![[r#"
+          error[L0007]: unterminated template string
+           --> main.just:1:15
+            |
+          1 | let s = `a ${b} c
+            |               ^^^ template string continues here
+            |
+            = help: close the template string with `` ` ``
+      "#]],
+    );
+    check_fix("`abc", "`abc`");
+    check_fix("`a ${b", "`a ${b}`");
+  }
+
+  #[test]
+  fn numbers_and_unknown() {
+    check(
+      "let a = 0x + 1e\nlet b = №\nlet c = 0b12 + 300u8 + 1u3",
+      expect![[r#"
+          error[L0009]: no valid digits found for number
+           --> main.just:1:9
+            |
+          1 | let a = 0x + 1e
+            |         ^^
+
+          error[L0010]: expected at least one digit in exponent
+           --> main.just:1:14
+            |
+          1 | let a = 0x + 1e
+            |              ^^
+
+          error[L0011]: unknown start of token: №
+           --> main.just:2:9
+            |
+          2 | let b = №
+            |         ^
+
+          error[L0012]: invalid digit `2` in binary literal
+           --> main.just:3:12
+            |
+          3 | let c = 0b12 + 300u8 + 1u3
+            |            ^
+
+          error[L0014]: literal out of range for `u8`, the maximum is `255`
+           --> main.just:3:16
+            |
+          3 | let c = 0b12 + 300u8 + 1u3
+            |                ^^^^^
+
+          error[L0013]: invalid suffix `u3` for number literal
+           --> main.just:3:25
+            |
+          3 | let c = 0b12 + 300u8 + 1u3
+            |                         ^^
+            |
+            = note: valid suffixes are `i8` to `i128`, `isize`, `u8` to `u128`, `usize`, `f32` and `f64`
+      "#]],
+    );
+  }
+}
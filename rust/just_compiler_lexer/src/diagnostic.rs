@@ -0,0 +1,135 @@
+//! Structured diagnostics.
+//!
+//! Diagnostics only carry byte spans,
+//! they are turned into lines and columns when rendered (see `emitter`).
+
+use super::span::Span;
+use std::fmt;
+
+/// Stable identifier of a kind of diagnostic, e.g. `L0001`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Code(pub &'static str);
+
+impl fmt::Display for Code {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.0)
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+  Error,
+  Warning,
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Severity::Error => "error",
+      Severity::Warning => "warning",
+    })
+  }
+}
+
+/// Additional information attached to a diagnostic,
+/// optionally pointing at another location.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Note {
+  pub message: String,
+  pub span: Option<Span>,
+}
+
+/// Suggested change to the source code, `span` is replaced by `replacement`.
+/// An empty `span` inserts the replacement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fix {
+  pub message: String,
+  pub span: Span,
+  pub replacement: String,
+}
+
+impl Fix {
+  /// Applies the fix to the source code.
+  pub fn apply(&self, src: &str) -> String {
+    let mut res = String::with_capacity(src.len() + self.replacement.len());
+    res.push_str(&src[..self.span.start]);
+    res.push_str(&self.replacement);
+    res.push_str(&src[self.span.end..]);
+    res
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+  pub code: Code,
+  pub severity: Severity,
+  pub message: String,
+  /// Location the diagnostic is about.
+  pub span: Span,
+  /// Message shown next to the primary span.
+  pub label: Option<String>,
+  pub notes: Vec<Note>,
+  pub fix: Option<Fix>,
+}
+
+impl Diagnostic {
+  pub fn error(code: Code, message: impl Into<String>, span: Span) -> Diagnostic {
+    Diagnostic::new(code, Severity::Error, message, span)
+  }
+
+  pub fn warning(code: Code, message: impl Into<String>, span: Span) -> Diagnostic {
+    Diagnostic::new(code, Severity::Warning, message, span)
+  }
+
+  fn new(code: Code, severity: Severity, message: impl Into<String>, span: Span) -> Diagnostic {
+    Diagnostic {
+      code,
+      severity,
+      message: message.into(),
+      span,
+      label: None,
+      notes: Vec::new(),
+      fix: None,
+    }
+  }
+
+  pub fn with_label(mut self, label: impl Into<String>) -> Diagnostic {
+    self.label = Some(label.into());
+    self
+  }
+
+  pub fn with_note(mut self, message: impl Into<String>) -> Diagnostic {
+    self.notes.push(Note {
+      message: message.into(),
+      span: None,
+    });
+    self
+  }
+
+  /// Adds a note pointing at another location, e.g. a previous definition.
+  pub fn with_span_note(mut self, span: Span, message: impl Into<String>) -> Diagnostic {
+    self.notes.push(Note {
+      message: message.into(),
+      span: Some(span),
+    });
+    self
+  }
+
+  pub fn with_fix(
+    mut self,
+    message: impl Into<String>,
+    span: Span,
+    replacement: impl Into<String>,
+  ) -> Diagnostic {
+    self.fix = Some(Fix {
+      message: message.into(),
+      span,
+      replacement: replacement.into(),
+    });
+    self
+  }
+
+  pub fn is_error(&self) -> bool {
+    self.severity == Severity::Error
+  }
+}
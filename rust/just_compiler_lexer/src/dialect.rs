@@ -0,0 +1,36 @@
+/// Language the lexer produces tokens for.
+///
+/// Both dialects share the same lexer core and `TokenKind`,
+/// they only differ in a few token kinds each of them supports.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Dialect {
+  /// Tokens of Rust, as produced by `rustc_lexer`.
+  Rust,
+  /// Tokens of Just.
+  #[default]
+  Just,
+}
+
+impl Dialect {
+  /// Multi-char punctuation such as `=>` or `::` is lexed as one token.
+  /// `rustc_lexer` leaves gluing punctuation to the parser.
+  pub fn glues_punctuation(self) -> bool {
+    matches!(self, Dialect::Just)
+  }
+
+  /// `'a` is a lifetime.
+  pub fn has_lifetimes(self) -> bool {
+    matches!(self, Dialect::Rust)
+  }
+
+  /// `r#ident` is a raw identifier.
+  /// In Just, `r#` always starts a raw string.
+  pub fn has_raw_identifiers(self) -> bool {
+    matches!(self, Dialect::Rust)
+  }
+
+  /// `` `a${b}c` `` is a template string.
+  pub fn has_template_strings(self) -> bool {
+    matches!(self, Dialect::Just)
+  }
+}
@@ -0,0 +1,157 @@
+//! Renders diagnostics for the terminal, with source snippets and carets:
+//!
+//! ```text
+//! error[L0004]: unterminated raw string
+//!  --> main.just:1:9
+//!   |
+//! 1 | let s = r##"abc"#
+//!   |         ^^^^^^^^^ unterminated raw string
+//!   |
+//!   = note: expected 2 `#`s, found 1
+//!   = help: close the raw string with `"##`
+//! ```
+
+use super::diagnostic::Diagnostic;
+use super::line_index::LineIndex;
+use super::span::Span;
+use std::fmt::Write;
+
+/// Source code a diagnostic refers to.
+pub struct SourceInput<'a> {
+  pub path: &'a str,
+  pub src: &'a str,
+  pub line_index: &'a LineIndex,
+}
+
+/// Renders the diagnostic as plain text.
+pub fn render(diagnostic: &Diagnostic, input: &SourceInput<'_>) -> String {
+  let mut out = String::new();
+  writeln!(
+    out,
+    "{}[{}]: {}",
+    diagnostic.severity, diagnostic.code, diagnostic.message
+  )
+  .unwrap();
+
+  // Width of the line number gutter, large enough for every line shown.
+  let last_line = std::iter::once(diagnostic.span)
+    .chain(diagnostic.notes.iter().filter_map(|note| note.span))
+    .map(|span| input.line_index.line_col(span.start).line + 1)
+    .max()
+    .unwrap_or(1);
+  let gutter = last_line.to_string().len();
+
+  let start = input.line_index.line_col(diagnostic.span.start);
+  writeln!(
+    out,
+    "{:gutter$}--> {}:{}:{}",
+    "",
+    input.path,
+    start.line + 1,
+    start.col + 1,
+    gutter = gutter
+  )
+  .unwrap();
+  writeln!(out, "{:gutter$} |", "", gutter = gutter).unwrap();
+  let mut line = render_snippet(
+    &mut out,
+    input,
+    diagnostic.span,
+    '^',
+    diagnostic.label.as_deref(),
+    None,
+    gutter,
+  );
+  for note in &diagnostic.notes {
+    if let Some(span) = note.span {
+      line = render_snippet(
+        &mut out,
+        input,
+        span,
+        '-',
+        Some(&note.message),
+        Some(line),
+        gutter,
+      );
+    }
+  }
+
+  let mut footer = diagnostic
+    .notes
+    .iter()
+    .filter(|note| note.span.is_none())
+    .map(|note| format!("note: {}", note.message))
+    .collect::<Vec<_>>();
+  if let Some(fix) = &diagnostic.fix {
+    footer.push(format!("help: {}", fix.message));
+  }
+  if !footer.is_empty() {
+    writeln!(out, "{:gutter$} |", "", gutter = gutter).unwrap();
+    for line in footer {
+      writeln!(out, "{:gutter$} = {}", "", line, gutter = gutter).unwrap();
+    }
+  }
+  out
+}
+
+/// Renders all diagnostics, separated by empty lines.
+pub fn render_all(diagnostics: &[Diagnostic], input: &SourceInput<'_>) -> String {
+  diagnostics
+    .iter()
+    .map(|diagnostic| render(diagnostic, input))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Renders the first line of `span` and marks the spanned part of it,
+/// the line itself is omitted if it is `prev_line`, the line rendered just before.
+/// Spans over multiple lines are marked up to the end of their first line.
+/// Returns the rendered line.
+fn render_snippet(
+  out: &mut String,
+  input: &SourceInput<'_>,
+  span: Span,
+  marker: char,
+  label: Option<&str>,
+  prev_line: Option<u32>,
+  gutter: usize,
+) -> u32 {
+  let start = input.line_index.line_col(span.start);
+  let line_start = span.start - start.col as usize;
+  let line_end = input.src[line_start..]
+    .find('\n')
+    .map_or(input.src.len(), |i| line_start + i);
+  let line = input.src[line_start..line_end].trim_end_matches('\r');
+
+  // Columns are counted in characters so that carets line up with the text.
+  let col = input.src[line_start..span.start].chars().count();
+  let len = input.src[span.start..span.end.min(line_end).max(span.start)]
+    .chars()
+    .count()
+    .max(1);
+
+  if prev_line != Some(start.line) {
+    writeln!(
+      out,
+      "{:>gutter$} | {}",
+      start.line + 1,
+      line,
+      gutter = gutter
+    )
+    .unwrap();
+  }
+  let mut marks = format!(
+    "{:gutter$} | {:col$}{}",
+    "",
+    "",
+    marker.to_string().repeat(len),
+    gutter = gutter,
+    col = col
+  );
+  if let Some(label) = label {
+    marks.push(' ');
+    marks.push_str(label);
+  }
+  writeln!(out, "{}", marks).unwrap();
+  start.line
+}
@@ -0,0 +1,282 @@
+//! Incremental re-lexing.
+//!
+//! When a file is edited, only the tokens around the edit are lexed again:
+//! lexing restarts at the last token boundary before the edit that is not affected by it,
+//! and stops as soon as a new token starts where an old token (after the edit) started,
+//! with the same lexer state. From there, the old tokens are still valid, only shifted.
+
+use super::span::Span;
+use super::token_stream::{SpannedToken, TokenStream};
+use super::tokenize::LexerState;
+use std::ops::Range;
+
+/// Number of bytes the lexer may look at past the end of a token to decide where it ends,
+/// e.g. `1.` followed by `.` or `'a` followed by a newline.
+/// This is 3 characters of up to 4 bytes.
+const LOOKAHEAD: usize = 12;
+
+/// Replacement of `span` in the text by `replacement`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+  pub span: Span,
+  pub replacement: String,
+}
+
+impl TextEdit {
+  pub fn new(span: Span, replacement: impl Into<String>) -> TextEdit {
+    TextEdit {
+      span,
+      replacement: replacement.into(),
+    }
+  }
+
+  pub fn apply(&self, text: &str) -> String {
+    let mut res = String::with_capacity(text.len() + self.replacement.len());
+    res.push_str(&text[..self.span.start]);
+    res.push_str(&self.replacement);
+    res.push_str(&text[self.span.end..]);
+    res
+  }
+}
+
+/// Tokens changed by `relex`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Relexed {
+  /// Indices of the replaced tokens in the old token vector.
+  pub old: Range<usize>,
+  /// Indices of the replacing tokens in the new token vector.
+  pub new: Range<usize>,
+}
+
+/// Updates `tokens`, the tokens of a text before `edit`, to the tokens of `text`, the text after `edit`.
+///
+/// Tokens are expected to be classified with keywords,
+/// as produced by `tokenize_with_spans(text).with_keywords()`.
+/// Tokens after the re-lexed range are kept and only have their spans shifted.
+pub fn relex(tokens: &mut Vec<SpannedToken>, text: &str, edit: &TextEdit) -> Relexed {
+  let inserted_end = edit.span.start + edit.replacement.len();
+  debug_assert_eq!(
+    text.len() + edit.span.len(),
+    tokens.last().map_or(0, |token| token.span.end) + edit.replacement.len()
+  );
+
+  // The first token that may be affected by the edit.
+  let start = tokens
+    .iter()
+    .position(|token| token.span.end + LOOKAHEAD > edit.span.start)
+    .unwrap_or(tokens.len());
+
+  let mut state = LexerState::default();
+  for token in &tokens[..start] {
+    state.advance(token.kind);
+  }
+
+  let offset = tokens.get(start).map_or(0, |token| token.span.start);
+  let mut stream = TokenStream::resume(text, offset, state.clone()).with_keywords();
+  let mut new_tokens = Vec::new();
+
+  // Old tokens starting after the edit are candidates to re-synchronize with,
+  // `old_state` is the lexer state before `tokens[old]`.
+  let mut old = start;
+  let mut old_state = state;
+  let end = loop {
+    let new_offset = stream.offset();
+    if new_offset >= inserted_end {
+      // Skip old tokens starting before the new offset, or in the edited range.
+      while old < tokens.len()
+        && (tokens[old].span.start < edit.span.end
+          || shift(tokens[old].span.start, edit) < new_offset)
+      {
+        old_state.advance(tokens[old].kind);
+        old += 1;
+      }
+      if old == tokens.len() && new_offset == text.len() {
+        break old;
+      }
+      if old < tokens.len()
+        && shift(tokens[old].span.start, edit) == new_offset
+        && &old_state == stream.state()
+      {
+        break old;
+      }
+    }
+    match stream.next() {
+      Some(token) => new_tokens.push(token),
+      None => break tokens.len(),
+    }
+  };
+
+  // Tokens at the start of the re-lexed range, before the edit, may not have changed.
+  let unchanged = tokens[start..end]
+    .iter()
+    .zip(&new_tokens)
+    .take_while(|(old, new)| old.span.end <= edit.span.start && old == new)
+    .count();
+  let start = start + unchanged;
+  new_tokens.drain(..unchanged);
+
+  for token in &mut tokens[end..] {
+    token.span = Span::new(shift(token.span.start, edit), shift(token.span.end, edit));
+  }
+  let inserted = new_tokens.len();
+  tokens.splice(start..end, new_tokens);
+
+  Relexed {
+    old: start..end,
+    new: start..start + inserted,
+  }
+}
+
+/// Offset in the text after `edit` of `offset`, an offset after the edit in the text before it.
+fn shift(offset: usize, edit: &TextEdit) -> usize {
+  offset - edit.span.end + edit.span.start + edit.replacement.len()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::token_stream::tokenize_with_spans;
+
+  fn lex(text: &str) -> Vec<SpannedToken> {
+    tokenize_with_spans(text).with_keywords().collect()
+  }
+
+  fn check(text: &str, edit: TextEdit) -> Relexed {
+    let mut tokens = lex(text);
+    let new_text = edit.apply(text);
+    let relexed = relex(&mut tokens, &new_text, &edit);
+    assert_eq!(
+      tokens,
+      lex(&new_text),
+      "{:?} with {:?} gives {:?}",
+      text,
+      edit,
+      new_text
+    );
+    relexed
+  }
+
+  #[test]
+  fn relex_identifier() {
+    let text = "let a = b + c;\nlet d = e;";
+    let relexed = check(text, TextEdit::new(Span::new(12, 13), "cc"));
+    assert_eq!(
+      relexed,
+      Relexed {
+        old: 10..11,
+        new: 10..11
+      }
+    );
+  }
+
+  #[test]
+  fn relex_unchanged_tail() {
+    let text = "let a = 1;\nlet b = 2;\nlet c = 3;";
+    let relexed = check(text, TextEdit::new(Span::new(4, 5), "x y"));
+    assert_eq!(
+      relexed,
+      Relexed {
+        old: 2..3,
+        new: 2..5
+      }
+    );
+  }
+
+  #[test]
+  fn relex_opening_comment() {
+    let text = "a b c d";
+    let relexed = check(text, TextEdit::new(Span::new(2, 2), "/*"));
+    assert_eq!(
+      relexed,
+      Relexed {
+        old: 2..7,
+        new: 2..3
+      }
+    );
+  }
+
+  #[test]
+  fn relex_template() {
+    check("`a${b}c` + d", TextEdit::new(Span::new(4, 5), "{x}"));
+    check("`a${b}c` + d", TextEdit::new(Span::new(6, 6), "${"));
+    check("x } `a` }", TextEdit::new(Span::new(0, 0), "`${"));
+    check("`${ a } b ${ c }`", TextEdit::new(Span::new(6, 7), ""));
+  }
+
+  #[test]
+  fn relex_empty() {
+    check("", TextEdit::new(Span::new(0, 0), "let a"));
+    check("let a", TextEdit::new(Span::new(0, 5), ""));
+  }
+
+  /// Deterministic xorshift generator, so failures can be reproduced.
+  struct Rng(u64);
+
+  impl Rng {
+    fn next(&mut self) -> u64 {
+      self.0 ^= self.0 << 13;
+      self.0 ^= self.0 >> 7;
+      self.0 ^= self.0 << 17;
+      self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+      (self.next() % n as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+      items[self.below(items.len())]
+    }
+  }
+
+  const FRAGMENTS: &[&str] = &[
+    " ", "\n", "let", "a", "b1", "1", "2.5", "0x", "e", ".", "..", "=", "=>", "+", "-", "*", "/",
+    "/*", "*/", "//", "{", "}", "(", ")", ";", "'", "\"", "r#\"", "\"#", "#", "`", "${", "\\", "ü",
+    "🦀",
+  ];
+
+  fn random_text(rng: &mut Rng, fragments: usize) -> String {
+    (0..fragments).map(|_| rng.pick(FRAGMENTS)).collect()
+  }
+
+  fn random_offset(rng: &mut Rng, text: &str) -> usize {
+    let mut offset = rng.below(text.len() + 1);
+    while !text.is_char_boundary(offset) {
+      offset -= 1;
+    }
+    offset
+  }
+
+  #[test]
+  fn relex_matches_full_lex() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..500 {
+      let mut text = random_text(&mut rng, 30);
+      let mut tokens = lex(&text);
+      for _ in 0..10 {
+        let a = random_offset(&mut rng, &text);
+        let b = random_offset(&mut rng, &text);
+        let fragments = rng.below(4);
+        let replacement = random_text(&mut rng, fragments);
+        let edit = TextEdit::new(Span::new(a.min(b), a.max(b)), replacement);
+        let new_text = edit.apply(&text);
+
+        let old_tokens = tokens.clone();
+        let relexed = relex(&mut tokens, &new_text, &edit);
+        let expected = lex(&new_text);
+        assert_eq!(
+          tokens, expected,
+          "{:?} with {:?} gives {:?}",
+          text, edit, new_text
+        );
+        // Tokens outside of the reported range are unchanged.
+        assert_eq!(old_tokens[..relexed.old.start], tokens[..relexed.new.start]);
+        assert_eq!(
+          old_tokens.len() - relexed.old.end,
+          tokens.len() - relexed.new.end
+        );
+        text = new_text;
+      }
+    }
+  }
+}
@@ -0,0 +1,208 @@
+//! Keywords of *Just*.
+//!
+//! `tokenize` reports every word as `Identifier`.
+//! This module holds the keyword table shared by the parser and the highlighter,
+//! and turns identifiers into keywords after lexing.
+
+use super::tokenize::{Token, TokenKind};
+
+macro_rules! keywords {
+  (
+    $(#[$meta:meta])*
+    pub enum $name:ident {
+      $($variant:ident = ($text:literal, $symbol:literal),)*
+    }
+  ) => {
+    $(#[$meta])*
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub enum $name {
+      $(
+        #[doc = concat!("\"", $text, "\"")]
+        $variant,
+      )*
+    }
+
+    impl $name {
+      /// All keywords of this kind, in table order.
+      pub const ALL: &'static [$name] = &[$($name::$variant,)*];
+
+      /// Looks up the keyword spelled as `text`.
+      pub fn from_ident(text: &str) -> Option<$name> {
+        match text {
+          $($text => Some($name::$variant),)*
+          _ => None,
+        }
+      }
+
+      /// The keyword as written in source code, e.g. "let".
+      pub fn as_str(self) -> &'static str {
+        match self {
+          $($name::$variant => $text,)*
+        }
+      }
+
+      /// The symbol name of the keyword, e.g. "KW_LET".
+      pub fn symbol(self) -> &'static str {
+        match self {
+          $($name::$variant => $symbol,)*
+        }
+      }
+    }
+  };
+}
+
+keywords! {
+  /// Reserved keyword.
+  /// It is never an identifier, regardless of where it is used.
+  ///
+  /// See `docs/just-programming-language/language-reference/keywords.md`.
+  pub enum Keyword {
+    As = ("as", "KW_AS"),
+    Async = ("async", "KW_ASYNC"),
+    Await = ("await", "KW_AWAIT"),
+    Break = ("break", "KW_BREAK"),
+    Const = ("const", "KW_CONST"),
+    Continue = ("continue", "KW_CONTINUE"),
+    Else = ("else", "KW_ELSE"),
+    Export = ("export", "KW_EXPORT"),
+    Fn = ("fn", "KW_FN"),
+    For = ("for", "KW_FOR"),
+    If = ("if", "KW_IF"),
+    Import = ("import", "KW_IMPORT"),
+    In = ("in", "KW_IN"),
+    Let = ("let", "KW_LET"),
+    Literal = ("literal", "KW_LITERAL"),
+    Loop = ("loop", "KW_LOOP"),
+    Move = ("move", "KW_MOVE"),
+    Ns = ("ns", "KW_NS"),
+    Pub = ("pub", "KW_PUB"),
+    Return = ("return", "KW_RETURN"),
+    SelfValue = ("self", "KW_SELF"),
+    Static = ("static", "KW_STATIC"),
+    Type = ("type", "KW_TYPE"),
+    Unsafe = ("unsafe", "KW_UNSAFE"),
+    While = ("while", "KW_WHILE"),
+    Yield = ("yield", "KW_YIELD"),
+  }
+}
+
+keywords! {
+  /// Contextual keyword.
+  /// It is a keyword only in specific positions, e.g. `mut` before a binding,
+  /// and an ordinary identifier everywhere else.
+  /// The parser decides which one it is, so it is not produced by `classify`.
+  pub enum ContextualKeyword {
+    Mut = ("mut", "KW_MUT"),
+    Use = ("use", "KW_USE"),
+    Mod = ("mod", "KW_MOD"),
+    Match = ("match", "KW_MATCH"),
+    Nominal = ("nominal", "KW_NOMINAL"),
+    True = ("true", "KW_TRUE"),
+    False = ("false", "KW_FALSE"),
+    Symbol = ("Symbol", "KW_SYMBOL"),
+    Keyof = ("keyof", "KW_KEYOF"),
+    Extends = ("extends", "KW_EXTENDS"),
+  }
+}
+
+/// Turns the kind of an `Identifier` spelled as a reserved keyword into `Keyword`.
+/// Any other kind is returned as is.
+pub fn classify(kind: TokenKind, text: &str) -> TokenKind {
+  match kind {
+    TokenKind::Identifier => Keyword::from_ident(text).map_or(kind, TokenKind::Keyword),
+    _ => kind,
+  }
+}
+
+/// Classifies the keywords in a token stream produced by `tokenize(input)`.
+pub fn classify_keywords<'a>(
+  input: &'a str,
+  tokens: impl Iterator<Item = Token> + 'a,
+) -> impl Iterator<Item = Token> + 'a {
+  let mut offset = 0;
+  tokens.map(move |token| {
+    let text = &input[offset..offset + token.len];
+    offset += token.len;
+    Token {
+      kind: classify(token.kind, text),
+      len: token.len,
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tokenize::tokenize;
+  use expect_test::{expect, Expect};
+
+  fn check_classify(src: &str, expect: Expect) {
+    let actual: String = classify_keywords(src, tokenize(src))
+      .map(|token| format!("{:?}\n", token))
+      .collect();
+    expect.assert_eq(&actual)
+  }
+
+  #[test]
+  fn keyword_table_round_trip() {
+    for keyword in Keyword::ALL {
+      assert_eq!(Keyword::from_ident(keyword.as_str()), Some(*keyword));
+      assert_eq!(
+        keyword.symbol(),
+        format!("KW_{}", keyword.as_str().to_uppercase())
+      );
+    }
+    for keyword in ContextualKeyword::ALL {
+      assert_eq!(
+        ContextualKeyword::from_ident(keyword.as_str()),
+        Some(*keyword)
+      );
+      assert_eq!(Keyword::from_ident(keyword.as_str()), None);
+    }
+  }
+
+  #[test]
+  fn not_keywords() {
+    assert_eq!(Keyword::from_ident("Let"), None);
+    assert_eq!(Keyword::from_ident("letter"), None);
+    assert_eq!(ContextualKeyword::from_ident("mutable"), None);
+  }
+
+  #[test]
+  fn classify_let() {
+    check_classify(
+      "pub let mut x = y as literal",
+      expect![[r#"
+          Token { kind: Keyword(Pub), len: 3 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Keyword(Let), len: 3 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Identifier, len: 3 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Identifier, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Eq, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Identifier, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Keyword(As), len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Keyword(Literal), len: 7 }
+      "#]],
+    );
+  }
+
+  #[test]
+  fn classify_only_identifiers() {
+    check_classify(
+      r#""let" `type` // return"#,
+      expect![[r#"
+        Token { kind: Literal { kind: Str { terminated: true }, suffix_start: 5 }, len: 5 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: Template { terminated: true }, suffix_start: 6 }, len: 6 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: LineComment { doc_style: None }, len: 9 }
+    "#]],
+    );
+  }
+}
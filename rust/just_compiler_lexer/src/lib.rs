@@ -2,4 +2,15 @@
 //!
 
 mod cursor;
+pub mod dialect;
+pub mod diagnose;
+pub mod diagnostic;
+pub mod emitter;
+pub mod incremental;
+pub mod keyword;
+pub mod line_index;
+pub mod literal;
+pub mod span;
+pub mod token_stream;
 pub mod tokenize;
+pub mod unescape;
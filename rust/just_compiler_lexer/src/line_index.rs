@@ -0,0 +1,194 @@
+//! Maps byte offsets to line and column, and back.
+//!
+//! Diagnostics are reported in UTF-8 columns,
+//! while editors (e.g. the language server protocol) count columns in UTF-16 code units.
+
+use std::collections::BTreeMap;
+
+/// Zero-based line and column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineCol {
+  pub line: u32,
+  pub col: u32,
+}
+
+/// Index of line starts of a source text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineIndex {
+  /// Byte offset of the start of each line.
+  line_starts: Vec<usize>,
+  /// Characters that are more than one byte long, per line.
+  wide_chars: BTreeMap<u32, Vec<WideChar>>,
+  len: usize,
+}
+
+/// A character that is more than one byte long in UTF-8.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct WideChar {
+  /// Byte offset of the character within its line.
+  start: usize,
+  len_utf8: usize,
+  len_utf16: usize,
+}
+
+impl LineIndex {
+  pub fn new(text: &str) -> LineIndex {
+    let mut line_starts = vec![0];
+    let mut wide_chars = BTreeMap::new();
+    let mut line = 0;
+    for (offset, c) in text.char_indices() {
+      if c == '\n' {
+        line_starts.push(offset + 1);
+        line += 1;
+        continue;
+      }
+      if !c.is_ascii() {
+        wide_chars
+          .entry(line)
+          .or_insert_with(Vec::new)
+          .push(WideChar {
+            start: offset - line_starts[line as usize],
+            len_utf8: c.len_utf8(),
+            len_utf16: c.len_utf16(),
+          });
+      }
+    }
+    LineIndex {
+      line_starts,
+      wide_chars,
+      len: text.len(),
+    }
+  }
+
+  /// Number of lines. A text always has at least one (maybe empty) line.
+  pub fn line_count(&self) -> usize {
+    self.line_starts.len()
+  }
+
+  /// Byte offset of the start of `line`.
+  pub fn line_start(&self, line: u32) -> Option<usize> {
+    self.line_starts.get(line as usize).copied()
+  }
+
+  /// Line and UTF-8 column of `offset`.
+  ///
+  /// # Panics
+  ///
+  /// If `offset` is past the end of the text.
+  pub fn line_col(&self, offset: usize) -> LineCol {
+    assert!(offset <= self.len, "offset {} out of bounds", offset);
+    let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+    LineCol {
+      line: line as u32,
+      col: (offset - self.line_starts[line]) as u32,
+    }
+  }
+
+  /// Line and UTF-16 column of `offset`.
+  pub fn line_col_utf16(&self, offset: usize) -> LineCol {
+    let LineCol { line, col } = self.line_col(offset);
+    LineCol {
+      line,
+      col: self.utf8_to_utf16_col(line, col as usize) as u32,
+    }
+  }
+
+  /// Byte offset of a line and UTF-8 column.
+  /// Returns `None` if the position is outside of the text.
+  pub fn offset(&self, line_col: LineCol) -> Option<usize> {
+    let start = self.line_start(line_col.line)?;
+    let offset = start + line_col.col as usize;
+    let line_end = self
+      .line_start(line_col.line + 1)
+      .map_or(self.len, |next| next - 1);
+    if offset > line_end {
+      return None;
+    }
+    Some(offset)
+  }
+
+  /// Byte offset of a line and UTF-16 column.
+  pub fn offset_utf16(&self, line_col: LineCol) -> Option<usize> {
+    let col = self.utf16_to_utf8_col(line_col.line, line_col.col as usize);
+    self.offset(LineCol {
+      line: line_col.line,
+      col: col as u32,
+    })
+  }
+
+  fn utf8_to_utf16_col(&self, line: u32, col: usize) -> usize {
+    let mut res = col;
+    if let Some(wide_chars) = self.wide_chars.get(&line) {
+      for c in wide_chars {
+        if c.start >= col {
+          break;
+        }
+        res -= c.len_utf8 - c.len_utf16;
+      }
+    }
+    res
+  }
+
+  fn utf16_to_utf8_col(&self, line: u32, mut col: usize) -> usize {
+    if let Some(wide_chars) = self.wide_chars.get(&line) {
+      for c in wide_chars {
+        if c.start >= col {
+          break;
+        }
+        col += c.len_utf8 - c.len_utf16;
+      }
+    }
+    col
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn lc(line: u32, col: u32) -> LineCol {
+    LineCol { line, col }
+  }
+
+  #[test]
+  fn empty_text() {
+    let index = LineIndex::new("");
+    assert_eq!(index.line_count(), 1);
+    assert_eq!(index.line_col(0), lc(0, 0));
+    assert_eq!(index.offset(lc(0, 0)), Some(0));
+    assert_eq!(index.offset(lc(1, 0)), None);
+  }
+
+  #[test]
+  fn lines() {
+    let text = "let a = 1\nlet b = 2\n\nc";
+    let index = LineIndex::new(text);
+    assert_eq!(index.line_count(), 4);
+    assert_eq!(index.line_col(0), lc(0, 0));
+    assert_eq!(index.line_col(9), lc(0, 9));
+    assert_eq!(index.line_col(10), lc(1, 0));
+    assert_eq!(index.line_col(20), lc(2, 0));
+    assert_eq!(index.line_col(21), lc(3, 0));
+    assert_eq!(index.line_col(22), lc(3, 1));
+    for offset in 0..=text.len() {
+      assert_eq!(index.offset(index.line_col(offset)), Some(offset));
+    }
+    assert_eq!(index.offset(lc(0, 10)), None);
+  }
+
+  #[test]
+  fn utf16_columns() {
+    // `ü` is 2 bytes in UTF-8 and 1 unit in UTF-16,
+    // `🦀` is 4 bytes in UTF-8 and 2 units in UTF-16.
+    let text = "a\nü🦀 = `🦀`";
+    let index = LineIndex::new(text);
+    let eq = text.find('=').unwrap();
+    assert_eq!(index.line_col(eq), lc(1, 7));
+    assert_eq!(index.line_col_utf16(eq), lc(1, 4));
+    assert_eq!(index.offset_utf16(lc(1, 4)), Some(eq));
+
+    let end = text.len();
+    assert_eq!(index.line_col_utf16(end), lc(1, 10));
+    assert_eq!(index.offset_utf16(lc(1, 10)), Some(end));
+  }
+}
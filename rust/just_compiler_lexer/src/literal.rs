@@ -0,0 +1,472 @@
+//! Evaluation of numeric literals.
+//!
+//! The lexer only classifies the shape of a number (`LiteralKind::Int` / `Float`),
+//! this module computes its value and type:
+//! - `_` separators are ignored, e.g. `1_000`.
+//! - A suffix sets the type, e.g. `57u64`, `0.1f32`.
+//! - Without suffix, decimal integers are `i32` and hex, octal and binary integers are `u32`.
+//!   If the value does not fit in 32 bits, the type is widened to 64 and then 128 bits.
+//! - Without suffix, floats are `f64`.
+
+use super::span::Span;
+use super::token_stream::SpannedToken;
+use super::tokenize::{Base, LiteralKind, TokenKind};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IntType {
+  I8,
+  I16,
+  I32,
+  I64,
+  I128,
+  Isize,
+  U8,
+  U16,
+  U32,
+  U64,
+  U128,
+  Usize,
+}
+
+impl IntType {
+  pub const ALL: [IntType; 12] = [
+    IntType::I8,
+    IntType::I16,
+    IntType::I32,
+    IntType::I64,
+    IntType::I128,
+    IntType::Isize,
+    IntType::U8,
+    IntType::U16,
+    IntType::U32,
+    IntType::U64,
+    IntType::U128,
+    IntType::Usize,
+  ];
+
+  pub fn from_suffix(suffix: &str) -> Option<IntType> {
+    IntType::ALL
+      .iter()
+      .copied()
+      .find(|ty| ty.as_str() == suffix)
+  }
+
+  pub fn as_str(self) -> &'static str {
+    match self {
+      IntType::I8 => "i8",
+      IntType::I16 => "i16",
+      IntType::I32 => "i32",
+      IntType::I64 => "i64",
+      IntType::I128 => "i128",
+      IntType::Isize => "isize",
+      IntType::U8 => "u8",
+      IntType::U16 => "u16",
+      IntType::U32 => "u32",
+      IntType::U64 => "u64",
+      IntType::U128 => "u128",
+      IntType::Usize => "usize",
+    }
+  }
+
+  pub fn is_signed(self) -> bool {
+    matches!(
+      self,
+      IntType::I8 | IntType::I16 | IntType::I32 | IntType::I64 | IntType::I128 | IntType::Isize
+    )
+  }
+
+  /// Size of the type in bits.
+  /// `isize` and `usize` are 64 bits, only 64-bit targets are supported for now.
+  pub fn bits(self) -> u32 {
+    match self {
+      IntType::I8 | IntType::U8 => 8,
+      IntType::I16 | IntType::U16 => 16,
+      IntType::I32 | IntType::U32 => 32,
+      IntType::I64 | IntType::U64 | IntType::Isize | IntType::Usize => 64,
+      IntType::I128 | IntType::U128 => 128,
+    }
+  }
+
+  /// Largest value of the type.
+  pub fn max_value(self) -> u128 {
+    let value_bits = if self.is_signed() {
+      self.bits() - 1
+    } else {
+      self.bits()
+    };
+    u128::MAX >> (128 - value_bits)
+  }
+}
+
+impl fmt::Display for IntType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FloatType {
+  F32,
+  F64,
+}
+
+impl FloatType {
+  pub fn from_suffix(suffix: &str) -> Option<FloatType> {
+    match suffix {
+      "f32" => Some(FloatType::F32),
+      "f64" => Some(FloatType::F64),
+      _ => None,
+    }
+  }
+
+  pub fn as_str(self) -> &'static str {
+    match self {
+      FloatType::F32 => "f32",
+      FloatType::F64 => "f64",
+    }
+  }
+}
+
+impl fmt::Display for FloatType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// Value of a numeric literal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Number {
+  /// Literals are never negative, `-1` is the negation of the literal `1`.
+  Int {
+    value: u128,
+    ty: IntType,
+  },
+  Float {
+    value: f64,
+    ty: FloatType,
+  },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NumberErrorKind {
+  /// "0x", a base prefix without digits.
+  EmptyInt,
+  /// "1e", an exponent without digits.
+  EmptyExponent,
+  /// "0b102", a digit not valid in the base of the literal.
+  InvalidDigit { digit: char, base: Base },
+  /// "1u7", "1.0u8"
+  InvalidSuffix { suffix: String },
+  /// "256u8", the value does not fit in the type.
+  IntOverflow { ty: IntType },
+  /// "1e40f32", the value is infinite in the type.
+  FloatOverflow { ty: FloatType },
+  /// "0b1.0", "0x1.0", only decimal literals can be floats.
+  NonDecimalFloat { base: Base },
+}
+
+/// Error evaluating a numeric literal, `span` is the location of the invalid part.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NumberError {
+  pub kind: NumberErrorKind,
+  pub span: Span,
+}
+
+impl fmt::Display for NumberError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.kind {
+      NumberErrorKind::EmptyInt => write!(f, "no valid digits found for number"),
+      NumberErrorKind::EmptyExponent => write!(f, "expected at least one digit in exponent"),
+      NumberErrorKind::InvalidDigit { digit, base } => write!(
+        f,
+        "invalid digit `{}` in {} literal",
+        digit,
+        base_name(*base)
+      ),
+      NumberErrorKind::InvalidSuffix { suffix } => {
+        write!(f, "invalid suffix `{}` for number literal", suffix)
+      }
+      NumberErrorKind::IntOverflow { ty } => write!(
+        f,
+        "literal out of range for `{}`, the maximum is `{}`",
+        ty,
+        ty.max_value()
+      ),
+      NumberErrorKind::FloatOverflow { ty } => write!(f, "literal out of range for `{}`", ty),
+      NumberErrorKind::NonDecimalFloat { base } => {
+        write!(f, "{} float literal is not supported", base_name(*base))
+      }
+    }
+  }
+}
+
+fn base_name(base: Base) -> &'static str {
+  match base {
+    Base::Binary => "binary",
+    Base::Octal => "octal",
+    Base::Hexadecimal => "hexadecimal",
+    Base::Decimal => "decimal",
+  }
+}
+
+/// Evaluates a numeric literal token of `input`.
+///
+/// # Panics
+///
+/// If the token is not an `Int` or `Float` literal.
+pub fn eval_number(input: &str, token: &SpannedToken) -> Result<Number, NumberError> {
+  let (kind, suffix_start) = match token.kind {
+    TokenKind::Literal { kind, suffix_start } => (kind, suffix_start),
+    kind => panic!("not a number: {:?}", kind),
+  };
+  let text = token.text(input);
+  let start = token.span.start;
+  let suffix = &text[suffix_start..];
+  let suffix_span = Span::new(start + suffix_start, token.span.end);
+  let error = |kind, span| Err(NumberError { kind, span });
+
+  match kind {
+    LiteralKind::Int {
+      empty_int: true, ..
+    } => error(NumberErrorKind::EmptyInt, token.span),
+    LiteralKind::Int { base, .. } => {
+      if let Some(ty) = FloatType::from_suffix(suffix) {
+        if base != Base::Decimal {
+          return error(NumberErrorKind::NonDecimalFloat { base }, token.span);
+        }
+        return eval_float(&text[..suffix_start], ty, token.span);
+      }
+      let ty = match suffix {
+        "" => None,
+        _ => match IntType::from_suffix(suffix) {
+          Some(ty) => Some(ty),
+          None => {
+            let suffix = suffix.to_string();
+            return error(NumberErrorKind::InvalidSuffix { suffix }, suffix_span);
+          }
+        },
+      };
+      eval_int(&text[..suffix_start], base, ty, start, token.span)
+    }
+    LiteralKind::Float {
+      empty_exponent: true,
+      ..
+    } => error(NumberErrorKind::EmptyExponent, token.span),
+    LiteralKind::Float { base, .. } => {
+      if base != Base::Decimal {
+        return error(NumberErrorKind::NonDecimalFloat { base }, token.span);
+      }
+      let ty = match suffix {
+        "" => FloatType::F64,
+        _ => match FloatType::from_suffix(suffix) {
+          Some(ty) => ty,
+          None => {
+            let suffix = suffix.to_string();
+            return error(NumberErrorKind::InvalidSuffix { suffix }, suffix_span);
+          }
+        },
+      };
+      eval_float(&text[..suffix_start], ty, token.span)
+    }
+    kind => panic!("not a number: {:?}", kind),
+  }
+}
+
+/// Evaluates the digits of an integer, `start` is the offset of `digits` in the source code.
+fn eval_int(
+  digits: &str,
+  base: Base,
+  ty: Option<IntType>,
+  start: usize,
+  span: Span,
+) -> Result<Number, NumberError> {
+  let (radix, prefix_len) = match base {
+    Base::Binary => (2, 2),
+    Base::Octal => (8, 2),
+    Base::Hexadecimal => (16, 2),
+    Base::Decimal => (10, 0),
+  };
+  // Type reported when the value does not even fit in 128 bits.
+  let widest = ty.unwrap_or(match base {
+    Base::Decimal => IntType::I128,
+    _ => IntType::U128,
+  });
+
+  let mut value: u128 = 0;
+  let mut overflow = false;
+  for (i, c) in digits.char_indices().skip(prefix_len) {
+    if c == '_' {
+      continue;
+    }
+    let digit = match c.to_digit(radix) {
+      Some(digit) => digit,
+      None => {
+        let offset = start + i;
+        return Err(NumberError {
+          kind: NumberErrorKind::InvalidDigit { digit: c, base },
+          span: Span::new(offset, offset + c.len_utf8()),
+        });
+      }
+    };
+    // Keep validating the digits after an overflow.
+    match value
+      .checked_mul(radix as u128)
+      .and_then(|value| value.checked_add(digit as u128))
+    {
+      Some(next) => value = next,
+      None => overflow = true,
+    }
+  }
+  let overflow_error = |ty| {
+    Err(NumberError {
+      kind: NumberErrorKind::IntOverflow { ty },
+      span,
+    })
+  };
+  if overflow {
+    return overflow_error(widest);
+  }
+
+  let candidates: &[IntType] = match (ty, base) {
+    (Some(ty), _) => &[ty][..],
+    (None, Base::Decimal) => &[IntType::I32, IntType::I64, IntType::I128],
+    (None, _) => &[IntType::U32, IntType::U64, IntType::U128],
+  };
+  match candidates.iter().find(|ty| value <= ty.max_value()) {
+    Some(&ty) => Ok(Number::Int { value, ty }),
+    None => overflow_error(widest),
+  }
+}
+
+fn eval_float(digits: &str, ty: FloatType, span: Span) -> Result<Number, NumberError> {
+  let digits: String = digits.chars().filter(|&c| c != '_').collect();
+  // The lexer only produces valid float syntax, `2.` included.
+  let value: f64 = digits.parse().expect("invalid float literal");
+  let infinite = match ty {
+    FloatType::F32 => (value as f32).is_infinite(),
+    FloatType::F64 => value.is_infinite(),
+  };
+  if infinite {
+    return Err(NumberError {
+      kind: NumberErrorKind::FloatOverflow { ty },
+      span,
+    });
+  }
+  Ok(Number::Float { value, ty })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::token_stream::tokenize_with_spans;
+  use expect_test::{expect, Expect};
+
+  fn check(src: &str, expect: Expect) {
+    let actual: String = tokenize_with_spans(src)
+      .filter(|token| matches!(token.kind, TokenKind::Literal { .. }))
+      .map(|token| {
+        let res = match eval_number(src, &token) {
+          Ok(Number::Int { value, ty }) => format!("{} {}", value, ty),
+          Ok(Number::Float { value, ty }) => format!("{:?} {}", value, ty),
+          Err(err) => format!("error {:?}: {}", err.span, err),
+        };
+        format!("{} => {}\n", token.text(src), res)
+      })
+      .collect();
+    expect.assert_eq(&actual)
+  }
+
+  #[test]
+  fn int_types() {
+    assert_eq!(IntType::I8.max_value(), 127);
+    assert_eq!(IntType::U8.max_value(), 255);
+    assert_eq!(IntType::I128.max_value(), i128::MAX as u128);
+    assert_eq!(IntType::U128.max_value(), u128::MAX);
+    assert_eq!(IntType::Usize.max_value(), u64::MAX as u128);
+    for ty in IntType::ALL.iter() {
+      assert_eq!(IntType::from_suffix(ty.as_str()), Some(*ty));
+    }
+  }
+
+  #[test]
+  fn default_types() {
+    check(
+      "98_222 0xff 0o77 0b1111_0000 2147483647 2147483648 0xffff_ffff 0x1_0000_0000",
+      expect![[r#"
+          98_222 => 98222 i32
+          0xff => 255 u32
+          0o77 => 63 u32
+          0b1111_0000 => 240 u32
+          2147483647 => 2147483647 i32
+          2147483648 => 2147483648 i64
+          0xffff_ffff => 4294967295 u32
+          0x1_0000_0000 => 4294967296 u64
+      "#]],
+    );
+  }
+
+  #[test]
+  fn widening() {
+    check(
+      "9223372036854775807 9223372036854775808 170141183460469231731687303715884105728 \
+       0xffff_ffff_ffff_ffff_ffff_ffff_ffff_ffff 0x1_0000_0000_0000_0000_0000_0000_0000_0000",
+      expect![[r#"
+          9223372036854775807 => 9223372036854775807 i64
+          9223372036854775808 => 9223372036854775808 i128
+          170141183460469231731687303715884105728 => error 40..79: literal out of range for `i128`, the maximum is `170141183460469231731687303715884105727`
+          0xffff_ffff_ffff_ffff_ffff_ffff_ffff_ffff => 340282366920938463463374607431768211455 u128
+          0x1_0000_0000_0000_0000_0000_0000_0000_0000 => error 122..165: literal out of range for `u128`, the maximum is `340282366920938463463374607431768211455`
+      "#]],
+    );
+  }
+
+  #[test]
+  fn suffixes() {
+    check(
+      "57u64 0x01u8 255u8 256u8 128i8 1usize 1isize 1u7 1foo",
+      expect![[r#"
+          57u64 => 57 u64
+          0x01u8 => 1 u8
+          255u8 => 255 u8
+          256u8 => error 19..24: literal out of range for `u8`, the maximum is `255`
+          128i8 => error 25..30: literal out of range for `i8`, the maximum is `127`
+          1usize => 1 usize
+          1isize => 1 isize
+          1u7 => error 46..48: invalid suffix `u7` for number literal
+          1foo => error 50..53: invalid suffix `foo` for number literal
+      "#]],
+    );
+  }
+
+  #[test]
+  fn invalid_digits() {
+    check("0b102 0o78 0x 0b_", expect![[r#"
+        0b102 => error 4..5: invalid digit `2` in binary literal
+        0o78 => error 9..10: invalid digit `8` in octal literal
+        0x => error 11..13: no valid digits found for number
+        0b_ => error 14..17: no valid digits found for number
+    "#]]);
+  }
+
+  #[test]
+  fn floats() {
+    check(
+      "1_000.123_456 0.1f64 1.23e-8_f64 0.1 2. 1e10 1f32 1e40f32 1e400 0b1f32 1.0u8 0x1.0",
+      expect![[r#"
+          1_000.123_456 => 1000.123456 f64
+          0.1f64 => 0.1 f64
+          1.23e-8_f64 => 1.23e-8 f64
+          0.1 => 0.1 f64
+          2. => 2.0 f64
+          1e10 => 10000000000.0 f64
+          1f32 => 1.0 f32
+          1e40f32 => error 50..57: literal out of range for `f32`
+          1e400 => error 58..63: literal out of range for `f64`
+          0b1f32 => error 64..70: binary float literal is not supported
+          1.0u8 => error 74..76: invalid suffix `u8` for number literal
+          0x1.0 => error 77..82: hexadecimal float literal is not supported
+      "#]],
+    );
+  }
+}
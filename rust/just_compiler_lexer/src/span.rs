@@ -0,0 +1,61 @@
+use std::fmt;
+use std::ops::Range;
+
+/// Byte range of a piece of source code, `start..end`.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+  pub start: usize,
+  pub end: usize,
+}
+
+impl Span {
+  pub fn new(start: usize, end: usize) -> Span {
+    debug_assert!(start <= end);
+    Span { start, end }
+  }
+
+  /// Empty span at `offset`, e.g. for a missing token.
+  pub fn empty(offset: usize) -> Span {
+    Span::new(offset, offset)
+  }
+
+  pub fn len(self) -> usize {
+    self.end - self.start
+  }
+
+  pub fn is_empty(self) -> bool {
+    self.start == self.end
+  }
+
+  /// True if `offset` is within the span.
+  /// The end of the span is included, so a cursor right after a token is within it.
+  pub fn contains(self, offset: usize) -> bool {
+    self.start <= offset && offset <= self.end
+  }
+
+  /// True if `other` is entirely within the span.
+  pub fn contains_span(self, other: Span) -> bool {
+    self.start <= other.start && other.end <= self.end
+  }
+
+  /// The smallest span covering both spans.
+  pub fn cover(self, other: Span) -> Span {
+    Span::new(self.start.min(other.start), self.end.max(other.end))
+  }
+
+  pub fn range(self) -> Range<usize> {
+    self.start..self.end
+  }
+}
+
+impl From<Range<usize>> for Span {
+  fn from(range: Range<usize>) -> Span {
+    Span::new(range.start, range.end)
+  }
+}
+
+impl fmt::Debug for Span {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}..{}", self.start, self.end)
+  }
+}
@@ -0,0 +1,133 @@
+//! Token stream with byte spans.
+//!
+//! `tokenize` only reports the length of each token,
+//! `TokenStream` keeps track of the offsets so consumers don't have to.
+
+use super::dialect::Dialect;
+use super::keyword;
+use super::span::Span;
+use super::tokenize::{first_token, LexerState, TokenKind};
+
+/// Token with its location in the source code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpannedToken {
+  pub kind: TokenKind,
+  pub span: Span,
+}
+
+impl SpannedToken {
+  /// The source code of the token.
+  pub fn text<'a>(&self, input: &'a str) -> &'a str {
+    &input[self.span.range()]
+  }
+}
+
+/// Iterator of `SpannedToken` over the input string.
+pub struct TokenStream<'a> {
+  input: &'a str,
+  offset: usize,
+  state: LexerState,
+  dialect: Dialect,
+  classify_keywords: bool,
+}
+
+impl<'a> TokenStream<'a> {
+  pub fn new(input: &'a str) -> TokenStream<'a> {
+    TokenStream {
+      input,
+      offset: 0,
+      state: LexerState::default(),
+      dialect: Dialect::Just,
+      classify_keywords: false,
+    }
+  }
+
+  /// Continues lexing `input` at `offset`, with the lexer state of the token stream at that offset.
+  pub(crate) fn resume(input: &'a str, offset: usize, state: LexerState) -> TokenStream<'a> {
+    TokenStream {
+      input,
+      offset,
+      state,
+      dialect: Dialect::Just,
+      classify_keywords: false,
+    }
+  }
+
+  pub(crate) fn state(&self) -> &LexerState {
+    &self.state
+  }
+
+  /// Produces tokens of `dialect` instead of Just tokens.
+  pub fn with_dialect(mut self, dialect: Dialect) -> TokenStream<'a> {
+    self.dialect = dialect;
+    self
+  }
+
+  /// Reports reserved keywords as `TokenKind::Keyword` instead of `Identifier`.
+  pub fn with_keywords(mut self) -> TokenStream<'a> {
+    self.classify_keywords = true;
+    self
+  }
+
+  /// Byte offset of the next token.
+  pub fn offset(&self) -> usize {
+    self.offset
+  }
+}
+
+impl Iterator for TokenStream<'_> {
+  type Item = SpannedToken;
+
+  fn next(&mut self) -> Option<SpannedToken> {
+    let rest = &self.input[self.offset..];
+    let token = first_token(rest, self.dialect, &mut self.state)?;
+    let span = Span::new(self.offset, self.offset + token.len);
+    self.offset = span.end;
+
+    let kind = if self.classify_keywords {
+      keyword::classify(token.kind, &self.input[span.range()])
+    } else {
+      token.kind
+    };
+    Some(SpannedToken { kind, span })
+  }
+}
+
+/// Creates an iterator that produces tokens with their spans from the input string.
+pub fn tokenize_with_spans(input: &str) -> TokenStream<'_> {
+  TokenStream::new(input)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use expect_test::{expect, Expect};
+
+  fn check_spans(src: &str, expect: Expect) {
+    let actual: String = tokenize_with_spans(src)
+      .with_keywords()
+      .map(|token| format!("{:?} {:?} {:?}\n", token.span, token.kind, token.text(src)))
+      .collect();
+    expect.assert_eq(&actual)
+  }
+
+  #[test]
+  fn spans() {
+    check_spans(
+      "let s = `a${b}c`\n// ü",
+      expect![[r#"
+          0..3 Keyword(Let) "let"
+          3..4 Whitespace " "
+          4..5 Identifier "s"
+          5..6 Whitespace " "
+          6..7 Eq "="
+          7..8 Whitespace " "
+          8..12 Literal { kind: TemplateHead, suffix_start: 4 } "`a${"
+          12..13 Identifier "b"
+          13..16 Literal { kind: TemplateTail { terminated: true }, suffix_start: 3 } "}c`"
+          16..17 Whitespace "\n"
+          17..22 LineComment { doc_style: None } "// ü"
+      "#]],
+    );
+  }
+}
@@ -1,4 +1,7 @@
-use super::cursor::Cursor;
+use super::cursor::{Cursor, EOF_CHAR};
+use super::dialect::Dialect;
+use super::keyword::Keyword;
+use std::convert::TryFrom;
 
 /// Parsed token.
 /// It doesn't contain information about data that has been parsed,
@@ -19,48 +22,999 @@ impl Token {
 // perf note: Changing all `usize` to `u32` doesn't change performance. See #77629
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TokenKind {
+  // Multi-char tokens:
+  /// "// comment"
+  LineComment { doc_style: Option<DocStyle> },
+  /// `/* block comment */`
+  ///
+  /// Block comments can be recursive, so the sequence like `/* /* */`
+  /// will not be considered terminated and will result in a parsing error.
+  BlockComment {
+    doc_style: Option<DocStyle>,
+    terminated: bool,
+  },
   /// Any whitespace characters sequence.
   Whitespace,
   /// Identifier, including keywords
   Identifier,
+  /// "r#ident", Rust dialect only.
+  RawIdent,
+  /// Reserved keyword, e.g. "let".
+  /// `tokenize` reports keywords as `Identifier`, see `keyword::classify`.
+  Keyword(Keyword),
+  /// "12_u8", "1.0e-40", "b"123"", "`hello`". See `LiteralKind` for more details.
+  Literal {
+    kind: LiteralKind,
+    suffix_start: usize,
+  },
+  /// "'a", Rust dialect only.
+  Lifetime { starts_with_number: bool },
+
+  // Multi-char punctuation:
+  /// "=>"
+  FatArrow,
+  /// "->"
+  ThinArrow,
+  /// ".."
+  DotDot,
+  /// "..."
+  DotDotDot,
+  /// "::"
+  ColonColon,
+  /// "=="
+  EqEq,
+  /// "!="
+  NotEq,
+  /// "<="
+  LtEq,
+  /// ">="
+  GtEq,
+  /// "&&"
+  AndAnd,
+  /// "||"
+  OrOr,
+  /// "+="
+  PlusEq,
+  /// "-="
+  MinusEq,
+  /// "*="
+  StarEq,
+  /// "/="
+  SlashEq,
+  /// "%="
+  PercentEq,
+
+  // One-char tokens:
+  /// ";"
+  Semi,
+  /// ","
+  Comma,
+  /// "."
+  Dot,
+  /// "("
+  OpenParen,
+  /// ")"
+  CloseParen,
+  /// "{"
+  OpenBrace,
+  /// "}"
+  CloseBrace,
+  /// "["
+  OpenBracket,
+  /// "]"
+  CloseBracket,
+  /// "@"
+  At,
+  /// "#"
+  Pound,
+  /// "~"
+  Tilde,
+  /// "?"
+  Question,
+  /// ":"
+  Colon,
+  /// "$"
+  Dollar,
+  /// "="
+  Eq,
+  /// "!"
+  Bang,
+  /// "<"
+  Lt,
+  /// ">"
+  Gt,
+  /// "-"
+  Minus,
+  /// "&"
+  And,
+  /// "|"
+  Or,
+  /// "+"
+  Plus,
+  /// "*"
+  Star,
+  /// "/"
+  Slash,
+  /// "^"
+  Caret,
+  /// "%"
+  Percent,
+
   /// Unknown token, not expected by the lexer, e.g. "№"
   Unknown,
 }
 
-use TokenKind::*;
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DocStyle {
+  Outer,
+  Inner,
+}
 
-pub fn tokenize(mut input: &str) -> impl Iterator<Item = Token> + '_ {
-  std::iter::from_fn(move || {
-    if input.is_empty() {
-      return None;
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LiteralKind {
+  /// "12_u8", "0o100", "0b120i99"
+  Int { base: Base, empty_int: bool },
+  /// "12.34f32", "0b100.100", "2."
+  Float { base: Base, empty_exponent: bool },
+  /// "'a'", "'\\'", "'''", "';"
+  Char { terminated: bool },
+  /// "b'a'", "b'\\'", "b'''", "b';"
+  Byte { terminated: bool },
+  /// ""abc"", ""abc"
+  Str { terminated: bool },
+  /// "b"abc"", "b"abc"
+  ByteStr { terminated: bool },
+  /// "r"abc"", "r#"abc"#", "r####"ab"###"c"####", "r#"a"
+  RawStr {
+    n_hashes: u16,
+    err: Option<RawStrError>,
+  },
+  /// "br"abc"", "br#"abc"#", "br####"ab"###"c"####", "br#"a"
+  RawByteStr {
+    n_hashes: u16,
+    err: Option<RawStrError>,
+  },
+  /// "`hello`", "`abc"
+  Template { terminated: bool },
+  /// "`hello ${", the start of a template string with substitutions.
+  TemplateHead,
+  /// "} and ${", the part of a template string between two substitutions.
+  TemplateMiddle,
+  /// "}!`", "}!", the end of a template string with substitutions.
+  TemplateTail { terminated: bool },
+}
+
+/// Error produced validating a raw string. Represents cases like:
+/// - `r##~"abcde"##`: `InvalidStarter`
+/// - `r###"abcde"##`: `NoTerminator { expected: 3, found: 2, possible_terminator_offset: Some(11)`
+/// - Too many `#`s (>65535): `TooManyDelimiters`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RawStrError {
+  /// Non `#` characters exist between `r` and `"` eg. `r#~"..`
+  InvalidStarter { bad_char: char },
+  /// The string was never terminated. `possible_terminator_offset` is the number of characters after `r` or `br` where they
+  /// may have intended to terminate it.
+  NoTerminator {
+    expected: usize,
+    found: usize,
+    possible_terminator_offset: Option<usize>,
+  },
+  /// More than 65535 `#`s exist.
+  TooManyDelimiters { found: usize },
+}
+
+/// Base of numeric literal encoding according to its prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Base {
+  /// Literal starts with "0b".
+  Binary,
+  /// Literal starts with "0o".
+  Octal,
+  /// Literal starts with "0x".
+  Hexadecimal,
+  /// Literal doesn't contain a prefix.
+  Decimal,
+}
+
+/// Lexer state carried from one token to the next.
+///
+/// Template strings re-enter normal tokenization inside `${ ... }`,
+/// so the lexer needs to know which `}` closes a substitution
+/// (and resumes the template string) and which one closes a nested block.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct LexerState {
+  /// Number of unclosed `{` within each open substitution, innermost last.
+  substitutions: Vec<usize>,
+}
+
+impl LexerState {
+  /// Replays the effect of lexing a token of `kind` on the state,
+  /// to restore the state from already lexed tokens without lexing them again.
+  pub(crate) fn advance(&mut self, kind: TokenKind) {
+    match kind {
+      OpenBrace => {
+        if let Some(depth) = self.substitutions.last_mut() {
+          *depth += 1;
+        }
+      }
+      CloseBrace => {
+        if let Some(depth) = self.substitutions.last_mut() {
+          *depth -= 1;
+        }
+      }
+      Literal {
+        kind: TemplateHead, ..
+      } => self.substitutions.push(0),
+      Literal {
+        kind: TemplateTail { .. },
+        ..
+      } => {
+        self.substitutions.pop();
+      }
+      _ => (),
     }
+  }
+}
+
+use LiteralKind::*;
+use TokenKind::*;
+
+/// Creates an iterator that produces tokens from the input string.
+pub fn tokenize(input: &str) -> impl Iterator<Item = Token> + '_ {
+  tokenize_with(input, Dialect::Just)
+}
 
-    let token = first_token(input);
+/// Creates an iterator that produces tokens of the given dialect from the input string.
+pub fn tokenize_with(mut input: &str, dialect: Dialect) -> impl Iterator<Item = Token> + '_ {
+  let mut state = LexerState::default();
+  std::iter::from_fn(move || {
+    let token = first_token(input, dialect, &mut state)?;
     input = &input[token.len..];
-    return Some(token);
+    Some(token)
   })
 }
 
-fn first_token(input: &str) -> Token {
-  Cursor::new(input).advance_token()
+/// Parses the first token of the input string, `None` if the input is empty.
+pub(crate) fn first_token(input: &str, dialect: Dialect, state: &mut LexerState) -> Option<Token> {
+  Cursor::new(input).advance_token(dialect, state)
+}
+
+/// `rustc` allows files to have a shebang, e.g. "#!/usr/bin/rustrun",
+/// but shebang isn't a part of rust syntax.
+/// Returns the length of the shebang, if any.
+pub fn strip_shebang(input: &str) -> Option<usize> {
+  // Shebang must start with `#!` literally, without any preceding whitespace.
+  // For simplicity we consider any line starting with `#!` a shebang,
+  // regardless of restrictions put on shebangs by specific platforms.
+  if let Some(input_tail) = input.strip_prefix("#!") {
+    // Ok, this is a shebang but if the next non-whitespace token is `[`,
+    // then it may be valid Rust code, so consider it Rust code.
+    let next_non_whitespace_token = tokenize_with(input_tail, Dialect::Rust)
+      .map(|tok| tok.kind)
+      .find(|tok| {
+        !matches!(
+          tok,
+          TokenKind::Whitespace
+            | TokenKind::LineComment { doc_style: None }
+            | TokenKind::BlockComment {
+              doc_style: None,
+              ..
+            }
+        )
+      });
+    if next_non_whitespace_token != Some(TokenKind::OpenBracket) {
+      // No other choice than to consider this a shebang.
+      return Some(2 + input_tail.lines().next().unwrap_or_default().len());
+    }
+  }
+  None
+}
+
+/// How a piece of template string ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TemplateEnd {
+  /// "`", the template string is terminated.
+  Backtick,
+  /// "${", a substitution starts.
+  Substitution,
+  /// End of file reached.
+  Eof,
 }
 
 impl Cursor<'_> {
-  fn advance_token(&mut self) -> Token {
-    let first_char = self.bump().unwrap();
+  /// Parses a token from the input string, `None` at the end of the input.
+  fn advance_token(&mut self, dialect: Dialect, state: &mut LexerState) -> Option<Token> {
+    let first_char = self.bump()?;
+    let glue = dialect.glues_punctuation();
     let token_kind = match first_char {
-      c if is_whitespace(c) => Whitespace,
+      // Slash, comment or block comment.
+      '/' => match self.first() {
+        '/' => self.line_comment(),
+        '*' => self.block_comment(),
+        '=' if glue => self.glued(SlashEq),
+        _ => Slash,
+      },
+
+      // Whitespace sequence.
+      c if is_whitespace(c) => self.whitespace(),
+
+      // Raw identifier, raw string literal or identifier.
+      'r' => match (self.first(), self.second()) {
+        ('#', c1) if dialect.has_raw_identifiers() && is_id_start(c1) => self.raw_ident(),
+        ('#', _) | ('"', _) => {
+          let (n_hashes, err) = self.raw_double_quoted_string(1);
+          let suffix_start = self.len_consumed();
+          if err.is_none() {
+            self.eat_literal_suffix();
+          }
+          let kind = RawStr { n_hashes, err };
+          Literal { kind, suffix_start }
+        }
+        _ => self.identifier(),
+      },
+
+      // Byte literal, byte string literal, raw byte string literal or identifier.
+      'b' => match (self.first(), self.second()) {
+        ('\'', _) => {
+          self.bump();
+          let terminated = self.single_quoted_string();
+          let suffix_start = self.len_consumed();
+          if terminated {
+            self.eat_literal_suffix();
+          }
+          let kind = Byte { terminated };
+          Literal { kind, suffix_start }
+        }
+        ('"', _) => {
+          self.bump();
+          let terminated = self.double_quoted_string();
+          let suffix_start = self.len_consumed();
+          if terminated {
+            self.eat_literal_suffix();
+          }
+          let kind = ByteStr { terminated };
+          Literal { kind, suffix_start }
+        }
+        ('r', '"') | ('r', '#') => {
+          self.bump();
+          let (n_hashes, err) = self.raw_double_quoted_string(2);
+          let suffix_start = self.len_consumed();
+          if err.is_none() {
+            self.eat_literal_suffix();
+          }
+          let kind = RawByteStr { n_hashes, err };
+          Literal { kind, suffix_start }
+        }
+        _ => self.identifier(),
+      },
+
+      // Identifier (this should be checked after other variant that can
+      // start as identifier).
       c if is_id_start(c) => self.identifier(),
+
+      // Numeric literal.
+      c @ '0'..='9' => {
+        let literal_kind = self.number(c);
+        let suffix_start = self.len_consumed();
+        self.eat_literal_suffix();
+        TokenKind::Literal {
+          kind: literal_kind,
+          suffix_start,
+        }
+      }
+
+      // Punctuation that may be glued with the following symbol.
+      '.' if glue => match (self.first(), self.second()) {
+        ('.', '.') => {
+          self.bump();
+          self.glued(DotDotDot)
+        }
+        ('.', _) => self.glued(DotDot),
+        _ => Dot,
+      },
+      ':' if glue => match self.first() {
+        ':' => self.glued(ColonColon),
+        _ => Colon,
+      },
+      '=' if glue => match self.first() {
+        '>' => self.glued(FatArrow),
+        '=' => self.glued(EqEq),
+        _ => Eq,
+      },
+      '!' if glue => match self.first() {
+        '=' => self.glued(NotEq),
+        _ => Bang,
+      },
+      '<' if glue => match self.first() {
+        '=' => self.glued(LtEq),
+        _ => Lt,
+      },
+      '>' if glue => match self.first() {
+        '=' => self.glued(GtEq),
+        _ => Gt,
+      },
+      '-' if glue => match self.first() {
+        '>' => self.glued(ThinArrow),
+        '=' => self.glued(MinusEq),
+        _ => Minus,
+      },
+      '&' if glue => match self.first() {
+        '&' => self.glued(AndAnd),
+        _ => And,
+      },
+      '|' if glue => match self.first() {
+        '|' => self.glued(OrOr),
+        _ => Or,
+      },
+      '+' if glue => match self.first() {
+        '=' => self.glued(PlusEq),
+        _ => Plus,
+      },
+      '*' if glue => match self.first() {
+        '=' => self.glued(StarEq),
+        _ => Star,
+      },
+      '%' if glue => match self.first() {
+        '=' => self.glued(PercentEq),
+        _ => Percent,
+      },
+
+      // One-symbol tokens.
+      ';' => Semi,
+      ',' => Comma,
+      '.' => Dot,
+      ':' => Colon,
+      '=' => Eq,
+      '!' => Bang,
+      '<' => Lt,
+      '>' => Gt,
+      '-' => Minus,
+      '&' => And,
+      '|' => Or,
+      '+' => Plus,
+      '*' => Star,
+      '%' => Percent,
+      '(' => OpenParen,
+      ')' => CloseParen,
+      '{' => {
+        if let Some(depth) = state.substitutions.last_mut() {
+          *depth += 1;
+        }
+        OpenBrace
+      }
+      '}' => match state.substitutions.last_mut() {
+        // Closing a substitution, the template string continues.
+        Some(0) => {
+          let kind = self.template_continuation(state);
+          Literal {
+            kind,
+            suffix_start: self.len_consumed(),
+          }
+        }
+        Some(depth) => {
+          *depth -= 1;
+          CloseBrace
+        }
+        None => CloseBrace,
+      },
+      '[' => OpenBracket,
+      ']' => CloseBracket,
+      '@' => At,
+      '#' => Pound,
+      '~' => Tilde,
+      '?' => Question,
+      '$' => Dollar,
+      '^' => Caret,
+
+      // Lifetime or character literal.
+      '\'' if dialect.has_lifetimes() => self.lifetime_or_char(),
+
+      // Character literal.
+      '\'' => {
+        let terminated = self.single_quoted_string();
+        let suffix_start = self.len_consumed();
+        if terminated {
+          self.eat_literal_suffix();
+        }
+        let kind = Char { terminated };
+        Literal { kind, suffix_start }
+      }
+
+      // String literal.
+      '"' => {
+        let terminated = self.double_quoted_string();
+        let suffix_start = self.len_consumed();
+        if terminated {
+          self.eat_literal_suffix();
+        }
+        let kind = Str { terminated };
+        Literal { kind, suffix_start }
+      }
+
+      // Template string literal.
+      '`' if dialect.has_template_strings() => {
+        let kind = match self.template_string() {
+          TemplateEnd::Substitution => {
+            state.substitutions.push(0);
+            TemplateHead
+          }
+          TemplateEnd::Backtick => Template { terminated: true },
+          TemplateEnd::Eof => Template { terminated: false },
+        };
+        Literal {
+          kind,
+          suffix_start: self.len_consumed(),
+        }
+      }
       _ => Unknown,
     };
 
-    Token::new(token_kind, self.len_consumed())
+    Some(Token::new(token_kind, self.len_consumed()))
+  }
+
+  /// Eats the second symbol of a glued punctuation, e.g. the `>` of `=>`.
+  fn glued(&mut self, kind: TokenKind) -> TokenKind {
+    self.bump();
+    kind
+  }
+
+  fn line_comment(&mut self) -> TokenKind {
+    debug_assert!(self.prev() == '/' && self.first() == '/');
+    self.bump();
+
+    let doc_style = match self.first() {
+      // `//!` is an inner line doc comment.
+      '!' => Some(DocStyle::Inner),
+      // `////` (more than 3 slashes) is not considered a doc comment.
+      '/' if self.second() != '/' => Some(DocStyle::Outer),
+      _ => None,
+    };
+
+    self.eat_while(|c| c != '\n');
+    LineComment { doc_style }
   }
+
+  fn block_comment(&mut self) -> TokenKind {
+    debug_assert!(self.prev() == '/' && self.first() == '*');
+    self.bump();
+
+    let doc_style = match self.first() {
+      // `/*!` is an inner block doc comment.
+      '!' => Some(DocStyle::Inner),
+      // `/***` (more than 2 stars) is not considered a doc comment.
+      // `/**/` is not considered a doc comment.
+      '*' if !matches!(self.second(), '*' | '/') => Some(DocStyle::Outer),
+      _ => None,
+    };
+
+    let mut depth = 1usize;
+    while let Some(c) = self.bump() {
+      match c {
+        '/' if self.first() == '*' => {
+          self.bump();
+          depth += 1;
+        }
+        '*' if self.first() == '/' => {
+          self.bump();
+          depth -= 1;
+          if depth == 0 {
+            // This block comment is closed, so for a construction like "/* */ */"
+            // there will be a successfully parsed block comment "/* */"
+            // and " */" will be processed separately.
+            break;
+          }
+        }
+        _ => (),
+      }
+    }
+
+    BlockComment {
+      doc_style,
+      terminated: depth == 0,
+    }
+  }
+
+  fn whitespace(&mut self) -> TokenKind {
+    debug_assert!(is_whitespace(self.prev()));
+    self.eat_while(is_whitespace);
+    Whitespace
+  }
+
+  fn raw_ident(&mut self) -> TokenKind {
+    debug_assert!(self.prev() == 'r' && self.first() == '#' && is_id_start(self.second()));
+    // Eat "#" symbol.
+    self.bump();
+    // Eat the identifier part of RawIdent.
+    self.bump();
+    self.eat_while(is_id_continue);
+    RawIdent
+  }
+
   fn identifier(&mut self) -> TokenKind {
+    debug_assert!(is_id_start(self.prev()));
+    // Start is already eaten, eat the rest of identifier.
     self.eat_while(is_id_continue);
     Identifier
   }
 
+  fn number(&mut self, first_digit: char) -> LiteralKind {
+    debug_assert!(self.prev().is_ascii_digit());
+    let mut base = Base::Decimal;
+    if first_digit == '0' {
+      // Attempt to parse encoding base.
+      let has_digits = match self.first() {
+        'b' => {
+          base = Base::Binary;
+          self.bump();
+          self.eat_decimal_digits()
+        }
+        'o' => {
+          base = Base::Octal;
+          self.bump();
+          self.eat_decimal_digits()
+        }
+        'x' => {
+          base = Base::Hexadecimal;
+          self.bump();
+          self.eat_hexadecimal_digits()
+        }
+        // Not a base prefix.
+        '0'..='9' | '_' | '.' | 'e' | 'E' => {
+          self.eat_decimal_digits();
+          true
+        }
+        // Just a 0.
+        _ => {
+          return Int {
+            base,
+            empty_int: false,
+          }
+        }
+      };
+      // Base prefix was provided, but there were no digits
+      // after it, e.g. "0x".
+      if !has_digits {
+        return Int {
+          base,
+          empty_int: true,
+        };
+      }
+    } else {
+      // No base prefix, parse number in the usual way.
+      self.eat_decimal_digits();
+    };
+
+    match self.first() {
+      // Don't be greedy if this is actually an
+      // integer literal followed by field/method access or a range
+      // (`0..2` and `12.foo()`)
+      '.' if self.second() != '.' && !is_id_start(self.second()) => {
+        // might have stuff after the ., and if it does, it needs to start
+        // with a number
+        self.bump();
+        let mut empty_exponent = false;
+        if self.first().is_ascii_digit() {
+          self.eat_decimal_digits();
+          match self.first() {
+            'e' | 'E' => {
+              self.bump();
+              empty_exponent = !self.eat_float_exponent();
+            }
+            _ => (),
+          }
+        }
+        Float {
+          base,
+          empty_exponent,
+        }
+      }
+      'e' | 'E' => {
+        self.bump();
+        let empty_exponent = !self.eat_float_exponent();
+        Float {
+          base,
+          empty_exponent,
+        }
+      }
+      _ => Int {
+        base,
+        empty_int: false,
+      },
+    }
+  }
+
+  fn lifetime_or_char(&mut self) -> TokenKind {
+    debug_assert!(self.prev() == '\'');
+
+    let can_be_a_lifetime = if self.second() == '\'' {
+      // It's surely not a lifetime.
+      false
+    } else {
+      // If the first symbol is valid for identifier, it can be a lifetime.
+      // Also check if it's a number for a better error reporting (so '0 will
+      // be reported as invalid lifetime and not as unterminated char literal).
+      is_id_start(self.first()) || self.first().is_ascii_digit()
+    };
+
+    if !can_be_a_lifetime {
+      let terminated = self.single_quoted_string();
+      let suffix_start = self.len_consumed();
+      if terminated {
+        self.eat_literal_suffix();
+      }
+      let kind = Char { terminated };
+      return Literal { kind, suffix_start };
+    }
+
+    // Either a lifetime or a character literal with
+    // length greater than 1.
+
+    let starts_with_number = self.first().is_ascii_digit();
+
+    // Skip the literal contents.
+    // First symbol can be a number (which isn't a valid identifier start),
+    // so skip it without any checks.
+    self.bump();
+    self.eat_while(is_id_continue);
+
+    // Check if after skipping literal contents we've met a closing
+    // single quote (which means that user attempted to create a
+    // string with single quotes).
+    if self.first() == '\'' {
+      self.bump();
+      let kind = Char { terminated: true };
+      Literal {
+        kind,
+        suffix_start: self.len_consumed(),
+      }
+    } else {
+      Lifetime { starts_with_number }
+    }
+  }
+
+  fn single_quoted_string(&mut self) -> bool {
+    debug_assert!(self.prev() == '\'');
+    // Check if it's a one-symbol literal.
+    if self.second() == '\'' && self.first() != '\\' {
+      self.bump();
+      self.bump();
+      return true;
+    }
+
+    // Literal has more than one symbol.
+
+    // Parse until either quotes are terminated or error is detected.
+    loop {
+      match self.first() {
+        // Quotes are terminated, finish parsing.
+        '\'' => {
+          self.bump();
+          return true;
+        }
+        // Probably beginning of the comment, which we don't want to include
+        // to the error report.
+        '/' => break,
+        // Newline without following '\'' means unclosed quote, stop parsing.
+        '\n' if self.second() != '\'' => break,
+        // End of file, stop parsing.
+        EOF_CHAR if self.is_eof() => break,
+        // Escaped slash is considered one character, so bump twice.
+        '\\' => {
+          self.bump();
+          self.bump();
+        }
+        // Skip the character.
+        _ => {
+          self.bump();
+        }
+      }
+    }
+    // String was not terminated.
+    false
+  }
+
+  /// Eats double-quoted string and returns true
+  /// if string is terminated.
+  fn double_quoted_string(&mut self) -> bool {
+    debug_assert!(self.prev() == '"');
+    while let Some(c) = self.bump() {
+      match c {
+        '"' => {
+          return true;
+        }
+        '\\' if self.first() == '\\' || self.first() == '"' => {
+          // Bump again to skip escaped character.
+          self.bump();
+        }
+        _ => (),
+      }
+    }
+    // End of file reached.
+    false
+  }
+
+  /// Eats the part of a template string after the opening backtick,
+  /// up to and including the closing backtick or the next `${`.
+  fn template_string(&mut self) -> TemplateEnd {
+    debug_assert!(self.prev() == '`');
+    self.template_chars()
+  }
+
+  /// Eats the part of a template string after the `}` closing a substitution,
+  /// and returns either `TemplateMiddle` or `TemplateTail`.
+  fn template_continuation(&mut self, state: &mut LexerState) -> LiteralKind {
+    debug_assert!(self.prev() == '}');
+    match self.template_chars() {
+      TemplateEnd::Substitution => TemplateMiddle,
+      end => {
+        state.substitutions.pop();
+        TemplateTail {
+          terminated: end == TemplateEnd::Backtick,
+        }
+      }
+    }
+  }
+
+  fn template_chars(&mut self) -> TemplateEnd {
+    while let Some(c) = self.bump() {
+      match c {
+        '`' => return TemplateEnd::Backtick,
+        '$' if self.first() == '{' => {
+          self.bump();
+          return TemplateEnd::Substitution;
+        }
+        '\\' if matches!(self.first(), '\\' | '`' | '$') => {
+          // Bump again to skip escaped character.
+          self.bump();
+        }
+        _ => (),
+      }
+    }
+    // End of file reached.
+    TemplateEnd::Eof
+  }
+
+  /// Eats the double-quoted string and returns `n_hashes` and an error if encountered.
+  fn raw_double_quoted_string(&mut self, prefix_len: usize) -> (u16, Option<RawStrError>) {
+    // Wrap the actual function to handle the error with too many hashes.
+    // This way, it eats the whole raw string.
+    let (n_hashes, err) = self.raw_string_unvalidated(prefix_len);
+    // Only up to 65535 `#`s are allowed in raw strings
+    match u16::try_from(n_hashes) {
+      Ok(num) => (num, err),
+      // We lie about the number of hashes here :P
+      Err(_) => (0, Some(RawStrError::TooManyDelimiters { found: n_hashes })),
+    }
+  }
+
+  fn raw_string_unvalidated(&mut self, prefix_len: usize) -> (usize, Option<RawStrError>) {
+    debug_assert!(self.prev() == 'r');
+    let start_pos = self.len_consumed();
+    let mut possible_terminator_offset = None;
+    let mut max_hashes = 0;
+
+    // Count opening '#' symbols.
+    let mut eaten = 0;
+    while self.first() == '#' {
+      eaten += 1;
+      self.bump();
+    }
+    let n_start_hashes = eaten;
+
+    // Check that string is started.
+    match self.bump() {
+      Some('"') => (),
+      c => {
+        let c = c.unwrap_or(EOF_CHAR);
+        return (
+          n_start_hashes,
+          Some(RawStrError::InvalidStarter { bad_char: c }),
+        );
+      }
+    }
+
+    // Skip the string contents and on each '#' character met, check if this is
+    // a raw string termination.
+    loop {
+      self.eat_while(|c| c != '"');
+
+      if self.is_eof() {
+        return (
+          n_start_hashes,
+          Some(RawStrError::NoTerminator {
+            expected: n_start_hashes,
+            found: max_hashes,
+            possible_terminator_offset,
+          }),
+        );
+      }
+
+      // Eat closing double quote.
+      self.bump();
+
+      // Check that amount of closing '#' symbols
+      // is equal to the amount of opening ones.
+      // Note that this will not consume extra trailing `#` characters:
+      // `r###"abcde"####` is lexed as a `RawStr { n_hashes: 3 }`
+      // followed by a `#` token.
+      let mut n_end_hashes = 0;
+      while self.first() == '#' && n_end_hashes < n_start_hashes {
+        n_end_hashes += 1;
+        self.bump();
+      }
+
+      if n_end_hashes == n_start_hashes {
+        return (n_start_hashes, None);
+      } else if n_end_hashes > max_hashes {
+        // Keep track of possible terminators to give a hint about
+        // where there might be a missing terminator
+        possible_terminator_offset =
+          Some(self.len_consumed() - start_pos - n_end_hashes + prefix_len);
+        max_hashes = n_end_hashes;
+      }
+    }
+  }
+
+  fn eat_decimal_digits(&mut self) -> bool {
+    let mut has_digits = false;
+    loop {
+      match self.first() {
+        '_' => {
+          self.bump();
+        }
+        '0'..='9' => {
+          has_digits = true;
+          self.bump();
+        }
+        _ => break,
+      }
+    }
+    has_digits
+  }
+
+  fn eat_hexadecimal_digits(&mut self) -> bool {
+    let mut has_digits = false;
+    loop {
+      match self.first() {
+        '_' => {
+          self.bump();
+        }
+        '0'..='9' | 'a'..='f' | 'A'..='F' => {
+          has_digits = true;
+          self.bump();
+        }
+        _ => break,
+      }
+    }
+    has_digits
+  }
+
+  /// Eats the float exponent. Returns true if at least one digit was met,
+  /// and returns false otherwise.
+  fn eat_float_exponent(&mut self) -> bool {
+    debug_assert!(self.prev() == 'e' || self.prev() == 'E');
+    if self.first() == '-' || self.first() == '+' {
+      self.bump();
+    }
+    self.eat_decimal_digits()
+  }
+
+  // Eats the suffix of the literal, e.g. "u8".
+  fn eat_literal_suffix(&mut self) {
+    self.eat_identifier();
+  }
+
+  // Eats the identifier.
+  fn eat_identifier(&mut self) {
+    if !is_id_start(self.first()) {
+      return;
+    }
+    self.bump();
+
+    self.eat_while(is_id_continue);
+  }
+
+  /// Eats symbols while predicate returns true or until the end of file is reached.
   fn eat_while(&mut self, mut predicate: impl FnMut(char) -> bool) {
     while predicate(self.first()) && !self.is_eof() {
       self.bump();
@@ -106,10 +1060,7 @@ pub fn is_whitespace(c: char) -> bool {
 pub fn is_id_start(c: char) -> bool {
   // This is XID_Start OR '_' (which formally is not a XID_Start).
   // We also add fast-path for ascii idents
-  ('a' <= c && c <= 'z')
-    || ('A' <= c && c <= 'Z')
-    || c == '_'
-    || (c > '\x7f' && unicode_xid::UnicodeXID::is_xid_start(c))
+  c.is_ascii_alphabetic() || c == '_' || (c > '\x7f' && unicode_xid::UnicodeXID::is_xid_start(c))
 }
 
 /// True if `c` is valid as a non-first character of an identifier.
@@ -118,13 +1069,24 @@ pub fn is_id_start(c: char) -> bool {
 pub fn is_id_continue(c: char) -> bool {
   // This is exactly XID_Continue.
   // We also add fast-path for ascii idents
-  ('a' <= c && c <= 'z')
-    || ('A' <= c && c <= 'Z')
-    || ('0' <= c && c <= '9')
+  c.is_ascii_alphanumeric()
     || c == '_'
     || (c > '\x7f' && unicode_xid::UnicodeXID::is_xid_continue(c))
 }
 
+/// The passed string is lexically an identifier.
+pub fn is_ident(string: &str) -> bool {
+  let mut chars = string.chars();
+  if let Some(start) = chars.next() {
+    is_id_start(start) && chars.all(is_id_continue)
+  } else {
+    false
+  }
+}
+
+#[cfg(test)]
+mod rust_tests;
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -160,4 +1122,475 @@ mod tests {
     "#]],
     );
   }
+
+  #[test]
+  fn smoke_test() {
+    check_lexing(
+      "pub let greet = (name: &str) => `hello ${name}`\n",
+      expect![[r#"
+          Token { kind: Identifier, len: 3 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Identifier, len: 3 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Identifier, len: 5 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Eq, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: OpenParen, len: 1 }
+          Token { kind: Identifier, len: 4 }
+          Token { kind: Colon, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: And, len: 1 }
+          Token { kind: Identifier, len: 3 }
+          Token { kind: CloseParen, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: FatArrow, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: TemplateHead, suffix_start: 9 }, len: 9 }
+          Token { kind: Identifier, len: 4 }
+          Token { kind: Literal { kind: TemplateTail { terminated: true }, suffix_start: 2 }, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+      "#]],
+    )
+  }
+
+  #[test]
+  fn comment_flavors() {
+    check_lexing(
+      r"
+// line
+//// line as well
+/// outer doc line
+//! inner doc line
+/* block */
+/**/
+/*** also block */
+/** outer doc block */
+/*! inner doc block */
+",
+      expect![[r#"
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: LineComment { doc_style: None }, len: 7 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: LineComment { doc_style: None }, len: 17 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: LineComment { doc_style: Some(Outer) }, len: 18 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: LineComment { doc_style: Some(Inner) }, len: 18 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: BlockComment { doc_style: None, terminated: true }, len: 11 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: BlockComment { doc_style: None, terminated: true }, len: 4 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: BlockComment { doc_style: None, terminated: true }, len: 18 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: BlockComment { doc_style: Some(Outer), terminated: true }, len: 22 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: BlockComment { doc_style: Some(Inner), terminated: true }, len: 22 }
+          Token { kind: Whitespace, len: 1 }
+      "#]],
+    )
+  }
+
+  #[test]
+  fn nested_block_comments() {
+    check_lexing(
+      "/* /* */ */'a'",
+      expect![[r#"
+        Token { kind: BlockComment { doc_style: None, terminated: true }, len: 11 }
+        Token { kind: Literal { kind: Char { terminated: true }, suffix_start: 3 }, len: 3 }
+    "#]],
+    )
+  }
+
+  #[test]
+  fn unterminated_block_comment() {
+    check_lexing(
+      "/* /* */",
+      expect![[r#"
+        Token { kind: BlockComment { doc_style: None, terminated: false }, len: 8 }
+    "#]],
+    )
+  }
+
+  #[test]
+  fn characters() {
+    check_lexing(
+      "'a' ' ' '\\n' 'ab",
+      expect![[r#"
+        Token { kind: Literal { kind: Char { terminated: true }, suffix_start: 3 }, len: 3 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: Char { terminated: true }, suffix_start: 3 }, len: 3 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: Char { terminated: true }, suffix_start: 4 }, len: 4 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: Char { terminated: false }, suffix_start: 3 }, len: 3 }
+    "#]],
+    );
+  }
+
+  #[test]
+  fn strings() {
+    check_lexing(
+      r#""abc" "a\"b" b"bytes" "open"#,
+      expect![[r#"
+        Token { kind: Literal { kind: Str { terminated: true }, suffix_start: 5 }, len: 5 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: Str { terminated: true }, suffix_start: 6 }, len: 6 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: ByteStr { terminated: true }, suffix_start: 8 }, len: 8 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: Str { terminated: false }, suffix_start: 5 }, len: 5 }
+    "#]],
+    );
+  }
+
+  #[test]
+  fn raw_string() {
+    check_lexing(
+      "r###\"\"#a\\b\x00c\"\"###",
+      expect![[r#"
+        Token { kind: Literal { kind: RawStr { n_hashes: 3, err: None }, suffix_start: 17 }, len: 17 }
+    "#]],
+    )
+  }
+
+  #[test]
+  fn unterminated_raw_string() {
+    check_lexing(
+      "r##\"abc\"#",
+      expect![[r#"
+        Token { kind: Literal { kind: RawStr { n_hashes: 2, err: Some(NoTerminator { expected: 2, found: 1, possible_terminator_offset: Some(8) }) }, suffix_start: 9 }, len: 9 }
+    "#]],
+    )
+  }
+
+  #[test]
+  fn template_strings() {
+    check_lexing(
+      r"`hello` `a\`b` `open",
+      expect![[r#"
+        Token { kind: Literal { kind: Template { terminated: true }, suffix_start: 7 }, len: 7 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: Template { terminated: true }, suffix_start: 6 }, len: 6 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: Template { terminated: false }, suffix_start: 5 }, len: 5 }
+    "#]],
+    )
+  }
+
+  #[test]
+  fn template_substitutions() {
+    check_lexing(
+      "`a${b}c${d}e`",
+      expect![[r#"
+          Token { kind: Literal { kind: TemplateHead, suffix_start: 4 }, len: 4 }
+          Token { kind: Identifier, len: 1 }
+          Token { kind: Literal { kind: TemplateMiddle, suffix_start: 4 }, len: 4 }
+          Token { kind: Identifier, len: 1 }
+          Token { kind: Literal { kind: TemplateTail { terminated: true }, suffix_start: 3 }, len: 3 }
+      "#]],
+    )
+  }
+
+  #[test]
+  fn template_substitution_with_braces() {
+    check_lexing(
+      "`${ { x: 1 }.x }`",
+      expect![[r#"
+        Token { kind: Literal { kind: TemplateHead, suffix_start: 3 }, len: 3 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: OpenBrace, len: 1 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Identifier, len: 1 }
+        Token { kind: Colon, len: 1 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: Int { base: Decimal, empty_int: false }, suffix_start: 1 }, len: 1 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: CloseBrace, len: 1 }
+        Token { kind: Dot, len: 1 }
+        Token { kind: Identifier, len: 1 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: TemplateTail { terminated: true }, suffix_start: 2 }, len: 2 }
+    "#]],
+    )
+  }
+
+  #[test]
+  fn nested_templates() {
+    check_lexing(
+      "`a ${ `b ${c}` } d`",
+      expect![[r#"
+        Token { kind: Literal { kind: TemplateHead, suffix_start: 5 }, len: 5 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: TemplateHead, suffix_start: 5 }, len: 5 }
+        Token { kind: Identifier, len: 1 }
+        Token { kind: Literal { kind: TemplateTail { terminated: true }, suffix_start: 2 }, len: 2 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: TemplateTail { terminated: true }, suffix_start: 4 }, len: 4 }
+    "#]],
+    )
+  }
+
+  #[test]
+  fn escaped_substitution() {
+    check_lexing(
+      r"`\${a}` `\\${a}`",
+      expect![[r#"
+        Token { kind: Literal { kind: Template { terminated: true }, suffix_start: 7 }, len: 7 }
+        Token { kind: Whitespace, len: 1 }
+        Token { kind: Literal { kind: TemplateHead, suffix_start: 5 }, len: 5 }
+        Token { kind: Identifier, len: 1 }
+        Token { kind: Literal { kind: TemplateTail { terminated: true }, suffix_start: 2 }, len: 2 }
+    "#]],
+    )
+  }
+
+  #[test]
+  fn unterminated_templates() {
+    check_lexing(
+      "`a ${b",
+      expect![[r#"
+        Token { kind: Literal { kind: TemplateHead, suffix_start: 5 }, len: 5 }
+        Token { kind: Identifier, len: 1 }
+    "#]],
+    );
+    check_lexing(
+      "`a ${b} c",
+      expect![[r#"
+        Token { kind: Literal { kind: TemplateHead, suffix_start: 5 }, len: 5 }
+        Token { kind: Identifier, len: 1 }
+        Token { kind: Literal { kind: TemplateTail { terminated: false }, suffix_start: 3 }, len: 3 }
+    "#]],
+    );
+    check_lexing(
+      "`a ${b} c ${d",
+      expect![[r#"
+        Token { kind: Literal { kind: TemplateHead, suffix_start: 5 }, len: 5 }
+        Token { kind: Identifier, len: 1 }
+        Token { kind: Literal { kind: TemplateMiddle, suffix_start: 6 }, len: 6 }
+        Token { kind: Identifier, len: 1 }
+    "#]],
+    );
+  }
+
+  #[test]
+  fn literal_suffixes() {
+    check_lexing(
+      r####"
+'a'
+b'a'
+"a"
+b"a"
+1234
+0b101
+0xABC
+1.0
+1.0e10
+2us
+r###"raw"###suffix
+br###"raw"###suffix
+"####,
+      expect![[r#"
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Char { terminated: true }, suffix_start: 3 }, len: 3 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Byte { terminated: true }, suffix_start: 4 }, len: 4 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Str { terminated: true }, suffix_start: 3 }, len: 3 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: ByteStr { terminated: true }, suffix_start: 4 }, len: 4 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Int { base: Decimal, empty_int: false }, suffix_start: 4 }, len: 4 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Int { base: Binary, empty_int: false }, suffix_start: 5 }, len: 5 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Int { base: Hexadecimal, empty_int: false }, suffix_start: 5 }, len: 5 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Float { base: Decimal, empty_exponent: false }, suffix_start: 3 }, len: 3 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Float { base: Decimal, empty_exponent: false }, suffix_start: 6 }, len: 6 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Int { base: Decimal, empty_int: false }, suffix_start: 1 }, len: 3 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: RawStr { n_hashes: 3, err: None }, suffix_start: 12 }, len: 18 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: RawByteStr { n_hashes: 3, err: None }, suffix_start: 13 }, len: 19 }
+          Token { kind: Whitespace, len: 1 }
+      "#]],
+    )
+  }
+
+  #[test]
+  fn numbers() {
+    check_lexing(
+      "1_000.123_456 0.1f64 1.23e-8_f64 2. 0x01u8 57u64 0b 0..2 1.foo",
+      expect![[r#"
+          Token { kind: Literal { kind: Float { base: Decimal, empty_exponent: false }, suffix_start: 13 }, len: 13 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Float { base: Decimal, empty_exponent: false }, suffix_start: 3 }, len: 6 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Float { base: Decimal, empty_exponent: false }, suffix_start: 8 }, len: 11 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Float { base: Decimal, empty_exponent: false }, suffix_start: 2 }, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Int { base: Hexadecimal, empty_int: false }, suffix_start: 4 }, len: 6 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Int { base: Decimal, empty_int: false }, suffix_start: 2 }, len: 5 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Int { base: Binary, empty_int: true }, suffix_start: 2 }, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Int { base: Decimal, empty_int: false }, suffix_start: 1 }, len: 1 }
+          Token { kind: DotDot, len: 2 }
+          Token { kind: Literal { kind: Int { base: Decimal, empty_int: false }, suffix_start: 1 }, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Literal { kind: Int { base: Decimal, empty_int: false }, suffix_start: 1 }, len: 1 }
+          Token { kind: Dot, len: 1 }
+          Token { kind: Identifier, len: 3 }
+      "#]],
+    )
+  }
+
+  #[test]
+  fn punctuation() {
+    check_lexing(
+      "=> -> .. ... :: == != <= >= && || += -= *= /= %= ; , . ( ) { } [ ] @ # ~ ? : $ = ! < > - & | + * / ^ %",
+      expect![[r#"
+          Token { kind: FatArrow, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: ThinArrow, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: DotDot, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: DotDotDot, len: 3 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: ColonColon, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: EqEq, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: NotEq, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: LtEq, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: GtEq, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: AndAnd, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: OrOr, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: PlusEq, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: MinusEq, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: StarEq, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: SlashEq, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: PercentEq, len: 2 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Semi, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Comma, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Dot, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: OpenParen, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: CloseParen, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: OpenBrace, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: CloseBrace, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: OpenBracket, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: CloseBracket, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: At, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Pound, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Tilde, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Question, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Colon, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Dollar, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Eq, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Bang, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Lt, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Gt, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Minus, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: And, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Or, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Plus, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Star, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Slash, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Caret, len: 1 }
+          Token { kind: Whitespace, len: 1 }
+          Token { kind: Percent, len: 1 }
+      "#]],
+    )
+  }
+
+  #[test]
+  fn unknown() {
+    check_lexing(
+      "№\\",
+      expect![[r#"
+        Token { kind: Unknown, len: 3 }
+        Token { kind: Unknown, len: 1 }
+    "#]],
+    )
+  }
+
+  #[test]
+  fn dialects() {
+    let src = "a::b => r#c `d`";
+    let kinds = |dialect| {
+      tokenize_with(src, dialect)
+        .map(|token| format!("{:?}\n", token.kind))
+        .collect::<String>()
+    };
+    expect![[r#"
+        Identifier
+        ColonColon
+        Identifier
+        Whitespace
+        FatArrow
+        Whitespace
+        Literal { kind: RawStr { n_hashes: 1, err: Some(InvalidStarter { bad_char: 'c' }) }, suffix_start: 3 }
+        Whitespace
+        Literal { kind: Template { terminated: true }, suffix_start: 3 }
+    "#]]
+    .assert_eq(&kinds(Dialect::Just));
+    expect![[r#"
+        Identifier
+        Colon
+        Colon
+        Identifier
+        Whitespace
+        Eq
+        Gt
+        Whitespace
+        RawIdent
+        Whitespace
+        Unknown
+        Identifier
+        Unknown
+    "#]]
+    .assert_eq(&kinds(Dialect::Rust));
+  }
 }
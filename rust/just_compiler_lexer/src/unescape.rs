@@ -72,7 +72,9 @@ where
       // The Chars iterator moved forward.
       callback(0..(literal_text.len() - chars.as_str().len()), result);
     }
-    Mode::Str | Mode::ByteStr => unescape_str_or_byte_str(literal_text, mode, callback),
+    Mode::Str | Mode::ByteStr | Mode::Template => {
+      unescape_str_or_byte_str(literal_text, mode, callback)
+    }
     // NOTE: Raw strings do not perform any explicit character escaping, here we
     // only translate CRLF to LF and produce errors on bare CR.
     Mode::RawStr | Mode::RawByteStr => unescape_raw_str_or_byte_str(literal_text, mode, callback),
@@ -92,6 +94,39 @@ where
   })
 }
 
+/// Takes a contents of a char, string or template literal (without quotes),
+/// and returns the unescaped value.
+/// Invalid characters and escapes are left out of the value,
+/// and returned as errors with their range in `literal_text`.
+pub fn unescape_to_string(
+  literal_text: &str,
+  mode: Mode,
+) -> (String, Vec<(Range<usize>, EscapeError)>) {
+  assert!(!mode.is_bytes());
+  let mut value = String::with_capacity(literal_text.len());
+  let mut errors = Vec::new();
+  unescape_literal(literal_text, mode, &mut |range, result| match result {
+    Ok(c) => value.push(c),
+    Err(err) => errors.push((range, err)),
+  });
+  (value, errors)
+}
+
+/// Takes a contents of a byte, byte string or raw byte string literal (without quotes),
+/// and returns the unescaped bytes, like `unescape_to_string`.
+pub fn unescape_to_bytes(
+  literal_text: &str,
+  mode: Mode,
+) -> (Vec<u8>, Vec<(Range<usize>, EscapeError)>) {
+  let mut value = Vec::with_capacity(literal_text.len());
+  let mut errors = Vec::new();
+  unescape_byte_literal(literal_text, mode, &mut |range, result| match result {
+    Ok(b) => value.push(b),
+    Err(err) => errors.push((range, err)),
+  });
+  (value, errors)
+}
+
 /// Takes a contents of a char literal (without quotes), and returns an
 /// unescaped char or an error
 pub fn unescape_char(literal_text: &str) -> Result<char, (usize, EscapeError)> {
@@ -118,24 +153,34 @@ pub enum Mode {
   ByteStr,
   RawStr,
   RawByteStr,
+  /// Part of a Just template string, between the delimiters (`` ` ``, `${` and `}`).
+  /// Backticks and `$` can be escaped, e.g. `` \` `` and `\$`.
+  Template,
 }
 
 impl Mode {
   pub fn in_single_quotes(self) -> bool {
     match self {
       Mode::Char | Mode::Byte => true,
-      Mode::Str | Mode::ByteStr | Mode::RawStr | Mode::RawByteStr => false,
+      Mode::Str | Mode::ByteStr | Mode::RawStr | Mode::RawByteStr | Mode::Template => false,
     }
   }
 
   pub fn in_double_quotes(self) -> bool {
-    !self.in_single_quotes()
+    match self {
+      Mode::Str | Mode::ByteStr | Mode::RawStr | Mode::RawByteStr => true,
+      Mode::Char | Mode::Byte | Mode::Template => false,
+    }
+  }
+
+  pub fn in_backticks(self) -> bool {
+    matches!(self, Mode::Template)
   }
 
   pub fn is_bytes(self) -> bool {
     match self {
       Mode::Byte | Mode::ByteStr | Mode::RawByteStr => true,
-      Mode::Char | Mode::Str | Mode::RawStr => false,
+      Mode::Char | Mode::Str | Mode::RawStr | Mode::Template => false,
     }
   }
 }
@@ -149,6 +194,7 @@ fn scan_escape(first_char: char, chars: &mut Chars<'_>, mode: Mode) -> Result<ch
       '\r' => Err(EscapeError::BareCarriageReturn),
       '\'' if mode.in_single_quotes() => Err(EscapeError::EscapeOnlyChar),
       '"' if mode.in_double_quotes() => Err(EscapeError::EscapeOnlyChar),
+      '`' if mode.in_backticks() => Err(EscapeError::EscapeOnlyChar),
       _ => {
         if mode.is_bytes() && !first_char.is_ascii() {
           // Byte literal can't be a non-ascii character.
@@ -171,6 +217,8 @@ fn scan_escape(first_char: char, chars: &mut Chars<'_>, mode: Mode) -> Result<ch
     '\\' => '\\',
     '\'' => '\'',
     '0' => '\0',
+    '`' if mode.in_backticks() => '`',
+    '$' if mode.in_backticks() => '$',
 
     'x' => {
       // Parse hexadecimal character code.
@@ -226,12 +274,10 @@ fn scan_escape(first_char: char, chars: &mut Chars<'_>, mode: Mode) -> Result<ch
               return Err(EscapeError::UnicodeEscapeInByte);
             }
 
-            break std::char::from_u32(value).ok_or_else(|| {
-              if value > 0x10FFFF {
-                EscapeError::OutOfRangeUnicodeEscape
-              } else {
-                EscapeError::LoneSurrogateUnicodeEscape
-              }
+            break std::char::from_u32(value).ok_or(if value > 0x10FFFF {
+              EscapeError::OutOfRangeUnicodeEscape
+            } else {
+              EscapeError::LoneSurrogateUnicodeEscape
             })?;
           }
           Some(c) => {
@@ -243,7 +289,6 @@ fn scan_escape(first_char: char, chars: &mut Chars<'_>, mode: Mode) -> Result<ch
               // Stop updating value since we're sure that it's is incorrect already.
               continue;
             }
-            let digit = digit as u32;
             value = value * 16 + digit;
           }
         };
@@ -263,13 +308,13 @@ fn unescape_char_or_byte(chars: &mut Chars<'_>, mode: Mode) -> Result<char, Esca
   Ok(res)
 }
 
-/// Takes a contents of a string literal (without quotes) and produces a
+/// Takes a contents of a string or template literal (without quotes) and produces a
 /// sequence of escaped characters or errors.
 fn unescape_str_or_byte_str<F>(src: &str, mode: Mode, callback: &mut F)
 where
   F: FnMut(Range<usize>, Result<char, EscapeError>),
 {
-  assert!(mode.in_double_quotes());
+  assert!(mode.in_double_quotes() || mode.in_backticks());
   let initial_len = src.len();
   let mut chars = src.chars();
   while let Some(first_char) = chars.next() {
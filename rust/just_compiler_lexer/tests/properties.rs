@@ -0,0 +1,84 @@
+//! Properties every tokenization must satisfy, whatever the input.
+
+use justc_lexer::dialect::Dialect;
+use justc_lexer::token_stream::TokenStream;
+use justc_lexer::tokenize::tokenize_with;
+use proptest::prelude::*;
+
+const DIALECTS: [Dialect; 2] = [Dialect::Just, Dialect::Rust];
+
+/// Pieces of source code that are likely to exercise tricky lexer paths
+/// (unterminated literals, nested comments and templates, multi-byte chars...).
+const FRAGMENTS: &[&str] = &[
+  " ", "\n", "\t", "\r\n", "let", "x", "_", "r", "b", "br", "0", "1", "0x", "0b", "0o", "1e", ".",
+  "..", "...", "::", "=>", "->", "==", "!=", "<=", ">=", "&&", "||", "+=", "=", "!", "<", ">", "-",
+  "+", "*", "/", "%", "^", "//", "///", "//!", "/*", "/**", "*/", "{", "}", "(", ")", "[", "]",
+  ";", ",", "'", "\"", "`", "${", "$", "#", "#!", "r#", "r#\"", "\"#", "\\", "\\u{", "ü", "🦀",
+  "№", "\u{200e}", "\0",
+];
+
+/// Strings made of `FRAGMENTS`.
+fn source_like() -> impl Strategy<Value = String> {
+  prop::collection::vec(prop::sample::select(FRAGMENTS), 0..64)
+    .prop_map(|fragments| fragments.concat())
+}
+
+/// Checks all properties on `input` in every dialect.
+fn check(input: &str) -> Result<(), TestCaseError> {
+  for &dialect in DIALECTS.iter() {
+    let mut offset = 0;
+    let mut round_trip = String::with_capacity(input.len());
+    for token in tokenize_with(input, dialect) {
+      prop_assert!(token.len > 0, "empty token {:?} at {}", token, offset);
+      let end = offset + token.len;
+      prop_assert!(end <= input.len(), "token {:?} past the end", token);
+      prop_assert!(
+        input.is_char_boundary(offset) && input.is_char_boundary(end),
+        "token {:?} at {} splits a code point",
+        token,
+        offset
+      );
+      round_trip.push_str(&input[offset..end]);
+      offset = end;
+    }
+    prop_assert_eq!(
+      offset,
+      input.len(),
+      "token lengths don't sum to the input length"
+    );
+    prop_assert_eq!(&round_trip, input);
+
+    // Spans are contiguous and agree with the lengths.
+    let mut offset = 0;
+    for token in TokenStream::new(input)
+      .with_dialect(dialect)
+      .with_keywords()
+    {
+      prop_assert_eq!(token.span.start, offset);
+      offset = token.span.end;
+    }
+    prop_assert_eq!(offset, input.len());
+  }
+  Ok(())
+}
+
+proptest! {
+  #![proptest_config(ProptestConfig::with_cases(2048))]
+
+  #[test]
+  fn any_string(input in any::<String>()) {
+    check(&input)?;
+  }
+
+  #[test]
+  fn source_like_string(input in source_like()) {
+    check(&input)?;
+  }
+}
+
+#[test]
+fn empty_input() {
+  for &dialect in DIALECTS.iter() {
+    assert_eq!(tokenize_with("", dialect).count(), 0);
+  }
+}
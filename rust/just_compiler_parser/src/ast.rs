@@ -0,0 +1,139 @@
+//! Typed layer over the syntax tree.
+//!
+//! Each node kind has a wrapper with accessors for its parts, e.g. `LetDecl::initializer`.
+//! Accessors return `Option` since the code may be incomplete,
+//! so tools can still work on what is there.
+
+#[macro_use]
+mod macros;
+mod nodes;
+mod operators;
+mod types;
+
+pub use nodes::*;
+pub use operators::{BinOp, PrefixOp};
+pub use types::*;
+
+use super::syntax_kind::SyntaxKind;
+use super::syntax_node::SyntaxNode;
+use std::marker::PhantomData;
+
+/// Typed view of a syntax node.
+pub trait AstNode: Sized {
+  fn can_cast(kind: SyntaxKind) -> bool;
+
+  /// Wraps `syntax` if it is of a kind of this node.
+  fn cast(syntax: SyntaxNode) -> Option<Self>;
+
+  fn syntax(&self) -> &SyntaxNode;
+}
+
+/// Iterator over the children of a node that are of type `N`.
+pub struct AstChildren<N> {
+  inner: Box<dyn Iterator<Item = SyntaxNode>>,
+  ph: PhantomData<N>,
+}
+
+impl<N: AstNode> AstChildren<N> {
+  fn new(parent: &SyntaxNode) -> AstChildren<N> {
+    AstChildren {
+      inner: Box::new(parent.children()),
+      ph: PhantomData,
+    }
+  }
+}
+
+impl<N: AstNode> Iterator for AstChildren<N> {
+  type Item = N;
+
+  fn next(&mut self) -> Option<N> {
+    self.inner.by_ref().find_map(N::cast)
+  }
+}
+
+/// Declarations that have a name, e.g. `x` in `let x = 1`.
+pub trait HasName: AstNode {
+  fn name(&self) -> Option<Name> {
+    support::child(self.syntax())
+  }
+}
+
+/// Declarations that can be `pub`.
+pub trait HasVisibility: AstNode {
+  fn visibility(&self) -> Option<Visibility> {
+    support::child(self.syntax())
+  }
+
+  fn is_pub(&self) -> bool {
+    self.visibility().is_some()
+  }
+}
+
+/// Declarations that can be decorated, e.g. with `@nominal`.
+pub trait HasDecorators: AstNode {
+  fn decorators(&self) -> AstChildren<Decorator> {
+    support::children(self.syntax())
+  }
+}
+
+/// Nodes with generic parameters, e.g. `<T>` in `type List<T> = Vec<T>`.
+pub trait HasGenericParams: AstNode {
+  fn generic_param_list(&self) -> Option<GenericParamList> {
+    support::child(self.syntax())
+  }
+}
+
+/// Nodes with an optional type annotation, e.g. `: i32` in `let x: i32 = 1`.
+pub trait HasTypeAnnotation: AstNode {
+  fn type_annotation(&self) -> Option<TypeAnnotation> {
+    support::child(self.syntax())
+  }
+
+  /// The annotated type.
+  fn ty(&self) -> Option<Type> {
+    self.type_annotation()?.ty()
+  }
+}
+
+mod support {
+  use super::{AstChildren, AstNode};
+  use crate::syntax_kind::SyntaxKind;
+  use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+  pub(super) fn child<N: AstNode>(parent: &SyntaxNode) -> Option<N> {
+    parent.children().find_map(N::cast)
+  }
+
+  pub(super) fn children<N: AstNode>(parent: &SyntaxNode) -> AstChildren<N> {
+    AstChildren::new(parent)
+  }
+
+  pub(super) fn token(parent: &SyntaxNode, kind: SyntaxKind) -> Option<SyntaxToken> {
+    parent
+      .children_with_tokens()
+      .filter_map(|element| element.into_token())
+      .find(|token| token.kind() == kind)
+  }
+
+  /// The first child of type `N` after the token of `kind`, e.g. the `else` branch of an `if`.
+  pub(super) fn child_after<N: AstNode>(parent: &SyntaxNode, kind: SyntaxKind) -> Option<N> {
+    parent
+      .children_with_tokens()
+      .skip_while(|element| element.as_token().map(SyntaxToken::kind) != Some(kind))
+      .filter_map(|element| element.into_node())
+      .find_map(N::cast)
+  }
+
+  /// The children of type `N` before the token of `kind`, all of them if there is no such token.
+  pub(super) fn children_before<N: AstNode>(parent: &SyntaxNode, kind: SyntaxKind) -> Vec<N> {
+    parent
+      .children_with_tokens()
+      .take_while(|element| element.as_token().map(SyntaxToken::kind) != Some(kind))
+      .filter_map(|element| element.into_node())
+      .filter_map(N::cast)
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests;
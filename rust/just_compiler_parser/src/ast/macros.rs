@@ -0,0 +1,81 @@
+//! Macros generating the wrappers of `nodes` and `types`.
+//!
+//! They expand to code using `AstNode`, `SyntaxKind`, `SyntaxNode` and `fmt`, which must be in scope.
+
+macro_rules! ast_node {
+  ($(#[$meta:meta])* $name:ident) => {
+    $(#[$meta])*
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct $name {
+      syntax: SyntaxNode,
+    }
+
+    impl AstNode for $name {
+      fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::$name
+      }
+
+      fn cast(syntax: SyntaxNode) -> Option<$name> {
+        if $name::can_cast(syntax.kind()) {
+          Some($name { syntax })
+        } else {
+          None
+        }
+      }
+
+      fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+      }
+    }
+
+    impl fmt::Display for $name {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.syntax, f)
+      }
+    }
+  };
+}
+
+macro_rules! ast_enum {
+  ($(#[$meta:meta])* $name:ident { $($variant:ident,)* }) => {
+    $(#[$meta])*
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub enum $name {
+      $($variant($variant),)*
+    }
+
+    impl AstNode for $name {
+      fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, $(SyntaxKind::$variant)|*)
+      }
+
+      fn cast(syntax: SyntaxNode) -> Option<$name> {
+        let node = match syntax.kind() {
+          $(SyntaxKind::$variant => $name::$variant($variant { syntax }),)*
+          _ => return None,
+        };
+        Some(node)
+      }
+
+      fn syntax(&self) -> &SyntaxNode {
+        match self {
+          $($name::$variant(node) => node.syntax(),)*
+        }
+      }
+    }
+
+    $(
+      impl From<$variant> for $name {
+        fn from(node: $variant) -> $name {
+          $name::$variant(node)
+        }
+      }
+    )*
+
+    impl fmt::Display for $name {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.syntax(), f)
+      }
+    }
+  };
+}
@@ -0,0 +1,1028 @@
+//! Wrappers of the syntax nodes, one per node kind, and enums of the node kinds
+//! that can appear in the same position (`Stmt`, `Expr`). Types are in `types`.
+
+use super::operators::{BinOp, PrefixOp};
+use super::support;
+use super::Type;
+use super::{
+  AstChildren, AstNode, HasDecorators, HasGenericParams, HasName, HasTypeAnnotation, HasVisibility,
+};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+use crate::T;
+use std::fmt;
+
+ast_node!(SourceFile);
+
+impl SourceFile {
+  pub fn statements(&self) -> AstChildren<Stmt> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_enum! {
+  /// Statements and declarations, in a source file or a block.
+  Stmt {
+    LetDecl,
+    TypeAlias,
+    UseDecl,
+    ModDecl,
+    FnDecl,
+    ExprStmt,
+  }
+}
+
+ast_node!(
+  /// `@log`, `@memo(100)`
+  Decorator
+);
+
+impl Decorator {
+  pub fn path(&self) -> Option<Path> {
+    support::child(&self.syntax)
+  }
+
+  pub fn arg_list(&self) -> Option<ArgList> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `pub`
+  Visibility
+);
+
+ast_node!(
+  /// `let x = 1`, `let mut y: i32 = 2`, `const z = 3`
+  LetDecl
+);
+
+impl HasName for LetDecl {}
+impl HasVisibility for LetDecl {}
+impl HasDecorators for LetDecl {}
+impl HasTypeAnnotation for LetDecl {}
+
+impl LetDecl {
+  pub fn let_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![let])
+  }
+
+  pub fn const_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![const])
+  }
+
+  pub fn mut_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![mut])
+  }
+
+  pub fn is_mut(&self) -> bool {
+    self.mut_token().is_some()
+  }
+
+  pub fn is_const(&self) -> bool {
+    self.const_token().is_some()
+  }
+
+  /// The value after `=`.
+  pub fn initializer(&self) -> Option<Expr> {
+    support::child_after(&self.syntax, T![=])
+  }
+}
+
+ast_node!(
+  /// `type Vocaloid = { name: String }`, `nominal type None = Symbol()`
+  TypeAlias
+);
+
+impl HasName for TypeAlias {}
+impl HasVisibility for TypeAlias {}
+impl HasDecorators for TypeAlias {}
+impl HasGenericParams for TypeAlias {}
+
+impl TypeAlias {
+  pub fn nominal_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![nominal])
+  }
+
+  pub fn is_nominal(&self) -> bool {
+    self.nominal_token().is_some()
+  }
+
+  /// The aliased type, after `=`.
+  pub fn ty(&self) -> Option<Type> {
+    support::child_after(&self.syntax, T![=])
+  }
+}
+
+ast_node!(
+  /// `use std::path`
+  UseDecl
+);
+
+impl HasVisibility for UseDecl {}
+impl HasDecorators for UseDecl {}
+
+impl UseDecl {
+  pub fn use_tree(&self) -> Option<UseTree> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `std::path`, `std::{env, fs}`, `a::*`, `a as b`
+  UseTree
+);
+
+impl UseTree {
+  pub fn path(&self) -> Option<Path> {
+    support::child(&self.syntax)
+  }
+
+  pub fn use_tree_list(&self) -> Option<UseTreeList> {
+    support::child(&self.syntax)
+  }
+
+  pub fn star_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![*])
+  }
+
+  pub fn rename(&self) -> Option<Rename> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `{ env, fs }`
+  UseTreeList
+);
+
+impl UseTreeList {
+  pub fn use_trees(&self) -> AstChildren<UseTree> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `as b`
+  Rename
+);
+
+impl HasName for Rename {}
+
+ast_node!(
+  /// `mod tokenize`
+  ModDecl
+);
+
+impl HasName for ModDecl {}
+impl HasVisibility for ModDecl {}
+impl HasDecorators for ModDecl {}
+
+ast_node!(
+  /// `fn main() { ... }`
+  FnDecl
+);
+
+impl HasName for FnDecl {}
+impl HasVisibility for FnDecl {}
+impl HasDecorators for FnDecl {}
+impl HasGenericParams for FnDecl {}
+
+impl FnDecl {
+  pub fn param_list(&self) -> Option<ParamList> {
+    support::child(&self.syntax)
+  }
+
+  pub fn ret_type(&self) -> Option<RetType> {
+    support::child(&self.syntax)
+  }
+
+  pub fn body(&self) -> Option<Block> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `<T, U: Bound>`
+  GenericParamList
+);
+
+impl GenericParamList {
+  pub fn generic_params(&self) -> AstChildren<GenericParam> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `T`, `U: Bound`
+  GenericParam
+);
+
+impl HasName for GenericParam {}
+
+impl GenericParam {
+  /// The constraint of the parameter, after `:`.
+  pub fn bound(&self) -> Option<Type> {
+    support::child::<TypeAnnotation>(&self.syntax)?.ty()
+  }
+}
+
+ast_node!(
+  /// `<i32, &str>`
+  GenericArgList
+);
+
+impl GenericArgList {
+  pub fn types(&self) -> AstChildren<Type> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `(x: i32, mut y = 1)`, `({ v }| x)`, `x` in `x => x * 2`
+  ParamList
+);
+
+impl ParamList {
+  /// The `|` ending the context parameter.
+  pub fn pipe_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![|])
+  }
+
+  /// The parameter before `|`, e.g. `{ v }` in `({ v }| x) => v + x`.
+  pub fn context_param(&self) -> Option<Param> {
+    self.pipe_token()?;
+    support::child(&self.syntax)
+  }
+
+  /// The parameters, without the context parameter.
+  pub fn params(&self) -> impl Iterator<Item = Param> {
+    let skip = self.context_param().is_some() as usize;
+    support::children(&self.syntax).skip(skip)
+  }
+}
+
+ast_node!(
+  /// `x: i32`, `mut y = 1`, `&mut ctx`, `{ v } = { v }`
+  Param
+);
+
+impl HasName for Param {}
+impl HasTypeAnnotation for Param {}
+
+impl Param {
+  /// The `&` of a parameter taken by reference.
+  pub fn amp_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![&])
+  }
+
+  pub fn is_ref(&self) -> bool {
+    self.amp_token().is_some()
+  }
+
+  pub fn mut_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![mut])
+  }
+
+  pub fn is_mut(&self) -> bool {
+    self.mut_token().is_some()
+  }
+
+  pub fn pat(&self) -> Option<Pat> {
+    support::child(&self.syntax)
+  }
+
+  /// The default value, after `=`.
+  pub fn default_value(&self) -> Option<Expr> {
+    support::child_after(&self.syntax, T![=])
+  }
+}
+
+ast_enum! {
+  /// What a parameter binds, a name or the fields of an object.
+  Pat {
+    Name,
+    ObjectPat,
+  }
+}
+
+impl Pat {
+  /// The names bound by the pattern, e.g. `v` and `y` in `{ v, x: y }`.
+  pub fn names(&self) -> impl Iterator<Item = Name> {
+    self.syntax().descendants().filter_map(Name::cast)
+  }
+}
+
+ast_node!(
+  /// `{ v, x: { y } }`
+  ObjectPat
+);
+
+impl ObjectPat {
+  pub fn fields(&self) -> AstChildren<ObjectPatField> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `v`, `x: { y }`
+  ObjectPatField
+);
+
+impl ObjectPatField {
+  /// The field, when it is bound to another pattern, e.g. `x` in `x: { y }`.
+  pub fn name_ref(&self) -> Option<NameRef> {
+    support::child(&self.syntax)
+  }
+
+  /// The pattern the field is bound to, the name of the field for shorthand fields.
+  pub fn pat(&self) -> Option<Pat> {
+    support::child(&self.syntax)
+  }
+
+  pub fn is_shorthand(&self) -> bool {
+    self.name_ref().is_none()
+  }
+
+  /// Name of the field.
+  pub fn field_name(&self) -> Option<String> {
+    match self.name_ref() {
+      Some(name_ref) => Some(name_ref.text()),
+      None => match self.pat()? {
+        Pat::Name(name) => Some(name.text()),
+        Pat::ObjectPat(_) => None,
+      },
+    }
+  }
+}
+
+ast_node!(
+  /// `-> i32`
+  RetType
+);
+
+impl RetType {
+  pub fn ty(&self) -> Option<Type> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `: i32`
+  TypeAnnotation
+);
+
+impl TypeAnnotation {
+  pub fn ty(&self) -> Option<Type> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `{ let a = 1; a }`
+  Block
+);
+
+impl Block {
+  pub fn statements(&self) -> AstChildren<Stmt> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// An expression used as a statement, e.g. `compile(dir);`.
+  ExprStmt
+);
+
+impl ExprStmt {
+  pub fn expr(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+
+  /// The `;` discarding the value of the expression, e.g. the value of a block.
+  pub fn semicolon_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![;])
+  }
+}
+
+ast_enum! {
+  Expr {
+    Literal,
+    PathExpr,
+    ParenExpr,
+    ArrayExpr,
+    ObjectLiteral,
+    TemplateLiteral,
+    ArrowFn,
+    InjectExpr,
+    CallExpr,
+    FieldExpr,
+    IndexExpr,
+    PrefixExpr,
+    RefExpr,
+    BinExpr,
+    CastExpr,
+    IfExpr,
+    MatchExpr,
+    ReturnExpr,
+    BreakExpr,
+    ContinueExpr,
+    WhileExpr,
+    LoopExpr,
+    ForExpr,
+    Block,
+  }
+}
+
+ast_node!(
+  /// `1`, `2.5`, `'a'`, `"abc"`, `true`
+  Literal
+);
+
+/// Kind of a `Literal`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LiteralKind {
+  Int,
+  Float,
+  Char,
+  Byte,
+  String,
+  ByteString,
+  Bool(bool),
+}
+
+impl Literal {
+  pub fn token(&self) -> Option<SyntaxToken> {
+    self.syntax.first_token()
+  }
+
+  pub fn kind(&self) -> Option<LiteralKind> {
+    let kind = match self.token()?.kind() {
+      SyntaxKind::IntNumber => LiteralKind::Int,
+      SyntaxKind::FloatNumber => LiteralKind::Float,
+      SyntaxKind::Char => LiteralKind::Char,
+      SyntaxKind::Byte => LiteralKind::Byte,
+      SyntaxKind::String | SyntaxKind::RawString => LiteralKind::String,
+      SyntaxKind::ByteString | SyntaxKind::RawByteString => LiteralKind::ByteString,
+      T![true] => LiteralKind::Bool(true),
+      T![false] => LiteralKind::Bool(false),
+      _ => return None,
+    };
+    Some(kind)
+  }
+}
+
+ast_node!(
+  /// `x`, `std::env`
+  PathExpr
+);
+
+impl PathExpr {
+  pub fn path(&self) -> Option<Path> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `(a + b)`
+  ParenExpr
+);
+
+impl ParenExpr {
+  pub fn expr(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `[1, 2, ..rest]`
+  ArrayExpr
+);
+
+/// Element of an `ArrayExpr`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ArrayElement {
+  Expr(Expr),
+  Spread(SpreadExpr),
+}
+
+impl ArrayExpr {
+  pub fn elements(&self) -> impl Iterator<Item = ArrayElement> {
+    self.syntax.children().filter_map(|node| {
+      if let Some(spread) = SpreadExpr::cast(node.clone()) {
+        return Some(ArrayElement::Spread(spread));
+      }
+      Expr::cast(node).map(ArrayElement::Expr)
+    })
+  }
+}
+
+ast_node!(
+  /// `..rest`
+  SpreadExpr
+);
+
+impl SpreadExpr {
+  pub fn expr(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `{ name, age: 17 }`, `ParsedSource { ..source, ast: [] }`
+  ObjectLiteral
+);
+
+/// Member of an `ObjectLiteral`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ObjectMember {
+  Field(ObjectField),
+  Spread(SpreadExpr),
+}
+
+impl ObjectLiteral {
+  /// The type of a typed object literal, e.g. `ParsedSource`.
+  pub fn path(&self) -> Option<Path> {
+    support::child(&self.syntax)
+  }
+
+  pub fn members(&self) -> impl Iterator<Item = ObjectMember> {
+    object_members(&self.syntax)
+  }
+
+  pub fn fields(&self) -> AstChildren<ObjectField> {
+    support::children(&self.syntax)
+  }
+}
+
+fn object_members(syntax: &SyntaxNode) -> impl Iterator<Item = ObjectMember> {
+  syntax.children().filter_map(|node| {
+    if let Some(spread) = SpreadExpr::cast(node.clone()) {
+      return Some(ObjectMember::Spread(spread));
+    }
+    ObjectField::cast(node).map(ObjectMember::Field)
+  })
+}
+
+ast_node!(
+  /// `age: 17`, or `name` as a shorthand for `name: name`
+  ObjectField
+);
+
+impl ObjectField {
+  pub fn name_ref(&self) -> Option<NameRef> {
+    support::child(&self.syntax)
+  }
+
+  /// The value of the field, `None` for a shorthand field.
+  pub fn expr(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+
+  pub fn is_shorthand(&self) -> bool {
+    support::token(&self.syntax, T![:]).is_none()
+  }
+}
+
+ast_node!(
+  /// `` `hello` ``, `` `hello ${name}!` ``
+  TemplateLiteral
+);
+
+impl TemplateLiteral {
+  /// The string parts of the template, around the substitutions.
+  pub fn strings(&self) -> impl Iterator<Item = SyntaxToken> {
+    self
+      .syntax
+      .children_with_tokens()
+      .filter_map(|element| element.into_token())
+      .filter(|token| token.kind().is_literal())
+  }
+
+  /// The expressions of the substitutions.
+  pub fn substitutions(&self) -> AstChildren<Expr> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `(v: i32) -> i32 => v + 1`, `move () => name`
+  ArrowFn
+);
+
+impl HasGenericParams for ArrowFn {}
+
+impl ArrowFn {
+  pub fn move_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![move])
+  }
+
+  /// True for `move () => x`, which takes ownership of the values it captures.
+  pub fn is_move(&self) -> bool {
+    self.move_token().is_some()
+  }
+
+  pub fn param_list(&self) -> Option<ParamList> {
+    support::child(&self.syntax)
+  }
+
+  pub fn ret_type(&self) -> Option<RetType> {
+    support::child(&self.syntax)
+  }
+
+  /// The body of the function, after `=>`, either a `Block` or a single expression.
+  pub fn body(&self) -> Option<Expr> {
+    support::child_after(&self.syntax, T![=>])
+  }
+}
+
+ast_node!(
+  /// `inc{ x: 10 }` in `inc{ x: 10 }(2)`, replaces the captured `x` of `inc`
+  InjectExpr
+);
+
+impl InjectExpr {
+  /// The function the values are injected into.
+  pub fn target(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+
+  pub fn members(&self) -> impl Iterator<Item = ObjectMember> {
+    object_members(&self.syntax)
+  }
+
+  pub fn fields(&self) -> AstChildren<ObjectField> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `f(a)`, `Vec.new<T>()`
+  CallExpr
+);
+
+impl CallExpr {
+  pub fn callee(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+
+  pub fn generic_arg_list(&self) -> Option<GenericArgList> {
+    support::child(&self.syntax)
+  }
+
+  pub fn arg_list(&self) -> Option<ArgList> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `(a, b)`
+  ArgList
+);
+
+impl ArgList {
+  pub fn args(&self) -> AstChildren<Expr> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `a.b`
+  FieldExpr
+);
+
+impl FieldExpr {
+  pub fn receiver(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+
+  pub fn name_ref(&self) -> Option<NameRef> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `a[0]`
+  IndexExpr
+);
+
+impl IndexExpr {
+  pub fn base(&self) -> Option<Expr> {
+    support::children_before(&self.syntax, T!['['])
+      .into_iter()
+      .next()
+  }
+
+  pub fn index(&self) -> Option<Expr> {
+    support::child_after(&self.syntax, T!['['])
+  }
+}
+
+ast_node!(
+  /// `-a`, `!a`, `*a`
+  PrefixExpr
+);
+
+impl PrefixExpr {
+  pub fn op_token(&self) -> Option<SyntaxToken> {
+    self.syntax.first_token()
+  }
+
+  pub fn op(&self) -> Option<PrefixOp> {
+    PrefixOp::from_kind(self.op_token()?.kind())
+  }
+
+  pub fn expr(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `&a`, `&mut a`
+  RefExpr
+);
+
+impl RefExpr {
+  pub fn mut_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![mut])
+  }
+
+  pub fn is_mut(&self) -> bool {
+    self.mut_token().is_some()
+  }
+
+  pub fn expr(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `a + b`, `a = b`
+  BinExpr
+);
+
+impl BinExpr {
+  pub fn lhs(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+
+  pub fn op_token(&self) -> Option<SyntaxToken> {
+    self
+      .syntax
+      .children_with_tokens()
+      .filter_map(|element| element.into_token())
+      .find(|token| BinOp::from_kind(token.kind()).is_some())
+  }
+
+  pub fn op(&self) -> Option<BinOp> {
+    BinOp::from_kind(self.op_token()?.kind())
+  }
+
+  pub fn rhs(&self) -> Option<Expr> {
+    let op = self.op_token()?;
+    support::child_after(&self.syntax, op.kind())
+  }
+}
+
+ast_node!(
+  /// `a as i64`, `a as literal`
+  CastExpr
+);
+
+impl CastExpr {
+  pub fn expr(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+
+  pub fn ty(&self) -> Option<Type> {
+    support::child(&self.syntax)
+  }
+
+  /// `literal` in `a as literal`.
+  pub fn literal_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![literal])
+  }
+}
+
+ast_node!(
+  /// `if a { b } else { c }`, `if isEmpty(input) return []`
+  IfExpr
+);
+
+impl IfExpr {
+  pub fn condition(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+
+  /// The branch taken when the condition holds, usually a `Block`.
+  pub fn then_branch(&self) -> Option<Expr> {
+    support::children_before::<Expr>(&self.syntax, T![else])
+      .into_iter()
+      .nth(1)
+  }
+
+  /// The branch after `else`, a `Block` or another `IfExpr` for `else if`.
+  pub fn else_branch(&self) -> Option<Expr> {
+    support::child_after(&self.syntax, T![else])
+  }
+}
+
+ast_node!(
+  /// `match a { 1 => b, _ => c }`
+  MatchExpr
+);
+
+impl MatchExpr {
+  pub fn expr(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+
+  pub fn match_arm_list(&self) -> Option<MatchArmList> {
+    support::child(&self.syntax)
+  }
+
+  pub fn arms(&self) -> AstChildren<MatchArm> {
+    match self.match_arm_list() {
+      Some(list) => list.arms(),
+      None => support::children(&self.syntax),
+    }
+  }
+}
+
+ast_node!(
+  /// `{ 1 => b, _ => c }`
+  MatchArmList
+);
+
+impl MatchArmList {
+  pub fn arms(&self) -> AstChildren<MatchArm> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `x if x > 0 => x`
+  MatchArm
+);
+
+impl MatchArm {
+  /// The pattern of the arm, parsed as an expression, e.g. `_` or `Whitespace.match`.
+  pub fn pattern(&self) -> Option<Expr> {
+    support::children_before(&self.syntax, T![=>])
+      .into_iter()
+      .next()
+  }
+
+  pub fn guard(&self) -> Option<MatchGuard> {
+    support::child(&self.syntax)
+  }
+
+  pub fn body(&self) -> Option<Expr> {
+    support::child_after(&self.syntax, T![=>])
+  }
+}
+
+ast_node!(
+  /// `if x > 0`
+  MatchGuard
+);
+
+impl MatchGuard {
+  pub fn condition(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `return`, `return x`
+  ReturnExpr
+);
+
+impl ReturnExpr {
+  pub fn expr(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `break`
+  BreakExpr
+);
+
+ast_node!(
+  /// `continue`
+  ContinueExpr
+);
+
+ast_node!(
+  /// `while a { b }`
+  WhileExpr
+);
+
+impl WhileExpr {
+  pub fn condition(&self) -> Option<Expr> {
+    support::child::<Expr>(&self.syntax).filter(|expr| !is_body(expr, self.body()))
+  }
+
+  pub fn body(&self) -> Option<Block> {
+    self.syntax.children().filter_map(Block::cast).last()
+  }
+}
+
+ast_node!(
+  /// `loop { a }`
+  LoopExpr
+);
+
+impl LoopExpr {
+  pub fn body(&self) -> Option<Block> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `for x in xs { a }`
+  ForExpr
+);
+
+impl HasName for ForExpr {}
+
+impl ForExpr {
+  /// The iterated expression, after `in`.
+  pub fn iterable(&self) -> Option<Expr> {
+    support::child_after::<Expr>(&self.syntax, T![in]).filter(|expr| !is_body(expr, self.body()))
+  }
+
+  pub fn body(&self) -> Option<Block> {
+    self.syntax.children().filter_map(Block::cast).last()
+  }
+}
+
+/// True if `expr` is the body of a loop, which is parsed as an expression when the condition is missing.
+fn is_body(expr: &Expr, body: Option<Block>) -> bool {
+  match (expr, body) {
+    (Expr::Block(block), Some(body)) => *block == body,
+    _ => false,
+  }
+}
+
+ast_node!(
+  /// `std::path::Path`, `Vec<T>`
+  Path
+);
+
+impl Path {
+  pub fn segments(&self) -> AstChildren<PathSegment> {
+    support::children(&self.syntax)
+  }
+
+  /// The last segment, e.g. `Path` in `std::path::Path`.
+  pub fn last_segment(&self) -> Option<PathSegment> {
+    self.segments().last()
+  }
+}
+
+ast_node!(
+  /// `path`, `Vec<T>`
+  PathSegment
+);
+
+impl PathSegment {
+  pub fn name_ref(&self) -> Option<NameRef> {
+    support::child(&self.syntax)
+  }
+
+  pub fn generic_arg_list(&self) -> Option<GenericArgList> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// Name being defined, e.g. `x` in `let x = 1`.
+  Name
+);
+
+impl Name {
+  pub fn ident_token(&self) -> Option<SyntaxToken> {
+    self.syntax.first_token()
+  }
+
+  pub fn text(&self) -> String {
+    self.syntax.text()
+  }
+}
+
+ast_node!(
+  /// Reference to a name, e.g. `x` in `x + 1`.
+  NameRef
+);
+
+impl NameRef {
+  pub fn ident_token(&self) -> Option<SyntaxToken> {
+    self.syntax.first_token()
+  }
+
+  pub fn text(&self) -> String {
+    self.syntax.text()
+  }
+}
@@ -0,0 +1,107 @@
+use crate::syntax_kind::SyntaxKind;
+use crate::T;
+
+/// Operator of a `BinExpr`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BinOp {
+  Assign,
+  AddAssign,
+  SubAssign,
+  MulAssign,
+  DivAssign,
+  RemAssign,
+  /// "||"
+  Or,
+  /// "&&"
+  And,
+  Eq,
+  NotEq,
+  Lt,
+  Gt,
+  LtEq,
+  GtEq,
+  BitOr,
+  BitXor,
+  BitAnd,
+  Add,
+  Sub,
+  Mul,
+  Div,
+  Rem,
+}
+
+impl BinOp {
+  pub fn from_kind(kind: SyntaxKind) -> Option<BinOp> {
+    let op = match kind {
+      T![=] => BinOp::Assign,
+      SyntaxKind::PlusEq => BinOp::AddAssign,
+      SyntaxKind::MinusEq => BinOp::SubAssign,
+      SyntaxKind::StarEq => BinOp::MulAssign,
+      SyntaxKind::SlashEq => BinOp::DivAssign,
+      SyntaxKind::PercentEq => BinOp::RemAssign,
+      T![||] => BinOp::Or,
+      T![&&] => BinOp::And,
+      T![==] => BinOp::Eq,
+      T![!=] => BinOp::NotEq,
+      T![<] => BinOp::Lt,
+      T![>] => BinOp::Gt,
+      T![<=] => BinOp::LtEq,
+      T![>=] => BinOp::GtEq,
+      T![|] => BinOp::BitOr,
+      T![^] => BinOp::BitXor,
+      T![&] => BinOp::BitAnd,
+      T![+] => BinOp::Add,
+      T![-] => BinOp::Sub,
+      T![*] => BinOp::Mul,
+      T![/] => BinOp::Div,
+      T![%] => BinOp::Rem,
+      _ => return None,
+    };
+    Some(op)
+  }
+
+  pub fn is_assignment(self) -> bool {
+    matches!(
+      self,
+      BinOp::Assign
+        | BinOp::AddAssign
+        | BinOp::SubAssign
+        | BinOp::MulAssign
+        | BinOp::DivAssign
+        | BinOp::RemAssign
+    )
+  }
+
+  pub fn is_comparison(self) -> bool {
+    matches!(
+      self,
+      BinOp::Eq | BinOp::NotEq | BinOp::Lt | BinOp::Gt | BinOp::LtEq | BinOp::GtEq
+    )
+  }
+
+  pub fn is_logical(self) -> bool {
+    matches!(self, BinOp::Or | BinOp::And)
+  }
+}
+
+/// Operator of a `PrefixExpr`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PrefixOp {
+  /// "-"
+  Neg,
+  /// "!"
+  Not,
+  /// "*"
+  Deref,
+}
+
+impl PrefixOp {
+  pub fn from_kind(kind: SyntaxKind) -> Option<PrefixOp> {
+    match kind {
+      T![-] => Some(PrefixOp::Neg),
+      T![!] => Some(PrefixOp::Not),
+      T![*] => Some(PrefixOp::Deref),
+      _ => None,
+    }
+  }
+}
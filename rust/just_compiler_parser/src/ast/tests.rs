@@ -0,0 +1,501 @@
+use super::*;
+use crate::parse;
+
+fn stmts(text: &str) -> Vec<Stmt> {
+  parse(text).tree().statements().collect()
+}
+
+fn only_stmt(text: &str) -> Stmt {
+  let mut stmts = stmts(text);
+  assert_eq!(stmts.len(), 1, "{:?}", stmts);
+  stmts.remove(0)
+}
+
+fn only_expr(text: &str) -> Expr {
+  match only_stmt(text) {
+    Stmt::ExprStmt(stmt) => stmt.expr().unwrap(),
+    stmt => panic!("not an expression statement: {:?}", stmt),
+  }
+}
+
+fn text<N: AstNode>(node: Option<N>) -> Option<String> {
+  node.map(|node| node.syntax().text())
+}
+
+#[test]
+fn let_decl() {
+  let decl = match only_stmt("@log pub let mut x: i32 = 1 + 2;") {
+    Stmt::LetDecl(decl) => decl,
+    stmt => panic!("{:?}", stmt),
+  };
+  assert!(decl.is_pub());
+  assert!(decl.is_mut());
+  assert!(!decl.is_const());
+  assert_eq!(decl.decorators().count(), 1);
+  assert_eq!(decl.name().unwrap().text(), "x");
+  assert_eq!(text(decl.ty()), Some("i32".to_string()));
+  assert_eq!(text(decl.initializer()), Some("1 + 2".to_string()));
+}
+
+#[test]
+fn partial_let_decl() {
+  let decl = match only_stmt("let = ") {
+    Stmt::LetDecl(decl) => decl,
+    stmt => panic!("{:?}", stmt),
+  };
+  assert_eq!(decl.name(), None);
+  assert_eq!(decl.ty(), None);
+  assert_eq!(decl.initializer(), None);
+  assert!(!decl.is_pub());
+}
+
+#[test]
+fn type_alias() {
+  let alias = match only_stmt("pub nominal type Some<T: Copy> = Vec<T>") {
+    Stmt::TypeAlias(alias) => alias,
+    stmt => panic!("{:?}", stmt),
+  };
+  assert!(alias.is_nominal());
+  assert_eq!(alias.name().unwrap().text(), "Some");
+  let param = alias
+    .generic_param_list()
+    .unwrap()
+    .generic_params()
+    .next()
+    .unwrap();
+  assert_eq!(param.name().unwrap().text(), "T");
+  assert_eq!(text(param.bound()), Some("Copy".to_string()));
+  let ty = match alias.ty() {
+    Some(Type::PathType(ty)) => ty,
+    ty => panic!("{:?}", ty),
+  };
+  let segment = ty.path().unwrap().last_segment().unwrap();
+  assert_eq!(segment.name_ref().unwrap().text(), "Vec");
+  assert_eq!(segment.generic_arg_list().unwrap().types().count(), 1);
+}
+
+fn only_type(text: &str) -> Type {
+  match only_stmt(text) {
+    Stmt::TypeAlias(alias) => alias.ty().unwrap(),
+    stmt => panic!("not a type alias: {:?}", stmt),
+  }
+}
+
+#[test]
+fn type_exprs() {
+  let union = match only_type("type T = A & { id: u64, name?: str } | [i32; 3] | []") {
+    Type::UnionType(union) => union,
+    ty => panic!("{:?}", ty),
+  };
+  let types: Vec<_> = union.types().collect();
+  assert_eq!(types.len(), 3);
+  let object = match &types[0] {
+    Type::IntersectionType(intersection) => match intersection.types().nth(1) {
+      Some(Type::ObjectType(object)) => object,
+      ty => panic!("{:?}", ty),
+    },
+    ty => panic!("{:?}", ty),
+  };
+  let fields: Vec<_> = object
+    .fields()
+    .map(|field| {
+      (
+        field.name().unwrap().text(),
+        field.is_optional(),
+        text(field.ty()).unwrap(),
+      )
+    })
+    .collect();
+  assert_eq!(
+    fields,
+    vec![
+      ("id".to_string(), false, "u64".to_string()),
+      ("name".to_string(), true, "str".to_string())
+    ]
+  );
+  match &types[1] {
+    Type::ArrayType(array) => {
+      assert_eq!(text(array.ty()), Some("i32".to_string()));
+      assert_eq!(text(array.len()), Some("3".to_string()));
+    }
+    ty => panic!("{:?}", ty),
+  }
+  match &types[2] {
+    Type::TupleType(tuple) => assert_eq!(tuple.types().count(), 0),
+    ty => panic!("{:?}", ty),
+  }
+
+  let fn_type = match only_type("type F = <T>(entry: T) -> bool") {
+    Type::FnType(fn_type) => fn_type,
+    ty => panic!("{:?}", ty),
+  };
+  assert!(fn_type.generic_param_list().is_some());
+  assert_eq!(fn_type.param_list().unwrap().params().count(), 1);
+  assert_eq!(
+    text(fn_type.ret_type().unwrap().ty()),
+    Some("bool".to_string())
+  );
+
+  match only_type("nominal type Some<T> = Symbol<T>()") {
+    Type::SymbolType(symbol) => assert_eq!(text(symbol.ty()), Some("T".to_string())),
+    ty => panic!("{:?}", ty),
+  }
+  match only_type("type C = 'a'") {
+    Type::LiteralType(literal) => {
+      assert_eq!(literal.literal().unwrap().kind(), Some(LiteralKind::Char))
+    }
+    ty => panic!("{:?}", ty),
+  }
+}
+
+#[test]
+fn type_level_types() {
+  let conditional = match only_type("type F<T> = T extends str | char ? keyof T : T[0]") {
+    Type::ConditionalType(conditional) => conditional,
+    ty => panic!("{:?}", ty),
+  };
+  assert_eq!(text(conditional.check_type()), Some("T".to_string()));
+  assert_eq!(
+    text(conditional.extends_type()),
+    Some("str | char".to_string())
+  );
+  match conditional.true_type() {
+    Some(Type::KeyofType(keyof)) => assert_eq!(text(keyof.ty()), Some("T".to_string())),
+    ty => panic!("{:?}", ty),
+  }
+  match conditional.false_type() {
+    Some(Type::IndexedAccessType(indexed)) => {
+      assert_eq!(text(indexed.ty()), Some("T".to_string()));
+      assert_eq!(text(indexed.index()), Some("0".to_string()));
+    }
+    ty => panic!("{:?}", ty),
+  }
+
+  let mapped = match only_type("type P<T> = { [K in keyof T]?: T[K] }") {
+    Type::MappedType(mapped) => mapped,
+    ty => panic!("{:?}", ty),
+  };
+  assert_eq!(
+    text(mapped.generic_param().and_then(|param| param.name())),
+    Some("K".to_string())
+  );
+  assert_eq!(text(mapped.keys()), Some("keyof T".to_string()));
+  assert!(mapped.is_optional());
+  assert_eq!(text(mapped.ty()), Some("T[K]".to_string()));
+}
+
+#[test]
+fn use_decl() {
+  let decl = match only_stmt("use std::{env, fs as f, path::*}") {
+    Stmt::UseDecl(decl) => decl,
+    stmt => panic!("{:?}", stmt),
+  };
+  let tree = decl.use_tree().unwrap();
+  assert_eq!(text(tree.path()), Some("std".to_string()));
+  let trees: Vec<_> = tree.use_tree_list().unwrap().use_trees().collect();
+  assert_eq!(trees.len(), 3);
+  assert_eq!(
+    trees[1].rename().unwrap().name().unwrap().text(),
+    "f".to_string()
+  );
+  assert!(trees[2].star_token().is_some());
+  assert_eq!(trees[0].rename(), None);
+}
+
+#[test]
+fn fn_decl_and_block() {
+  let decl = match only_stmt("fn compile(dir: &str) -> bool {\n  let p = dir\n  check(p)\n}") {
+    Stmt::FnDecl(decl) => decl,
+    stmt => panic!("{:?}", stmt),
+  };
+  assert_eq!(decl.name().unwrap().text(), "compile");
+  let param = decl.param_list().unwrap().params().next().unwrap();
+  match param.ty() {
+    Some(Type::RefType(ty)) => {
+      assert!(!ty.is_mut());
+      assert_eq!(text(ty.ty()), Some("str".to_string()));
+    }
+    ty => panic!("{:?}", ty),
+  }
+  assert_eq!(
+    text(decl.ret_type().unwrap().ty()),
+    Some("bool".to_string())
+  );
+  assert_eq!(decl.body().unwrap().statements().count(), 2);
+}
+
+#[test]
+fn arrow_fn() {
+  let arrow = match only_expr("(v: i32, mut w = 1) -> i32 => { return v + w }") {
+    Expr::ArrowFn(arrow) => arrow,
+    expr => panic!("{:?}", expr),
+  };
+  let params: Vec<_> = arrow.param_list().unwrap().params().collect();
+  assert_eq!(params.len(), 2);
+  assert!(!params[0].is_mut());
+  assert!(params[1].is_mut());
+  assert_eq!(text(params[1].default_value()), Some("1".to_string()));
+  assert_eq!(text(arrow.ret_type()), Some("-> i32".to_string()));
+  let body = match arrow.body() {
+    Some(Expr::Block(block)) => block,
+    body => panic!("{:?}", body),
+  };
+  match body.statements().next() {
+    Some(Stmt::ExprStmt(stmt)) => match stmt.expr() {
+      Some(Expr::ReturnExpr(ret)) => assert_eq!(text(ret.expr()), Some("v + w".to_string())),
+      expr => panic!("{:?}", expr),
+    },
+    stmt => panic!("{:?}", stmt),
+  }
+}
+
+#[test]
+fn arrow_fn_context() {
+  let arrow = match only_expr("<T>({ v, w: { x } } = { v }| &mut y, z) => v + y") {
+    Expr::ArrowFn(arrow) => arrow,
+    expr => panic!("{:?}", expr),
+  };
+  assert_eq!(
+    arrow.generic_param_list().unwrap().generic_params().count(),
+    1
+  );
+  let param_list = arrow.param_list().unwrap();
+  let context = param_list.context_param().unwrap();
+  assert_eq!(context.name(), None);
+  assert_eq!(text(context.default_value()), Some("{ v }".to_string()));
+  let pat = match context.pat() {
+    Some(Pat::ObjectPat(pat)) => pat,
+    pat => panic!("{:?}", pat),
+  };
+  let fields: Vec<_> = pat.fields().collect();
+  assert!(fields[0].is_shorthand());
+  assert_eq!(fields[1].field_name(), Some("w".to_string()));
+  let names: Vec<_> = Pat::from(pat).names().map(|name| name.text()).collect();
+  assert_eq!(names, ["v", "x"]);
+
+  let params: Vec<_> = param_list.params().collect();
+  assert_eq!(params.len(), 2);
+  assert!(params[0].is_ref() && params[0].is_mut());
+  assert_eq!(params[0].name().unwrap().text(), "y");
+  assert!(!params[1].is_ref());
+
+  let arrow = match only_expr("(a, b) => a") {
+    Expr::ArrowFn(arrow) => arrow,
+    expr => panic!("{:?}", expr),
+  };
+  let param_list = arrow.param_list().unwrap();
+  assert_eq!(param_list.context_param(), None);
+  assert_eq!(param_list.params().count(), 2);
+}
+
+#[test]
+fn binary_and_prefix_exprs() {
+  let bin = match only_expr("a += -b * c") {
+    Expr::BinExpr(bin) => bin,
+    expr => panic!("{:?}", expr),
+  };
+  assert_eq!(bin.op(), Some(BinOp::AddAssign));
+  assert!(bin.op().unwrap().is_assignment());
+  assert_eq!(text(bin.lhs()), Some("a".to_string()));
+  let rhs = match bin.rhs() {
+    Some(Expr::BinExpr(rhs)) => rhs,
+    rhs => panic!("{:?}", rhs),
+  };
+  assert_eq!(rhs.op(), Some(BinOp::Mul));
+  match rhs.lhs() {
+    Some(Expr::PrefixExpr(prefix)) => {
+      assert_eq!(prefix.op(), Some(PrefixOp::Neg));
+      assert_eq!(text(prefix.expr()), Some("b".to_string()));
+    }
+    lhs => panic!("{:?}", lhs),
+  }
+
+  let bin = match only_expr("a ==") {
+    Expr::BinExpr(bin) => bin,
+    expr => panic!("{:?}", expr),
+  };
+  assert_eq!(bin.op(), Some(BinOp::Eq));
+  assert_eq!(bin.rhs(), None);
+}
+
+#[test]
+fn postfix_exprs() {
+  let call = match only_expr("Vec.new<ASTNode>(a, b[0])") {
+    Expr::CallExpr(call) => call,
+    expr => panic!("{:?}", expr),
+  };
+  let callee = match call.callee() {
+    Some(Expr::FieldExpr(field)) => field,
+    callee => panic!("{:?}", callee),
+  };
+  assert_eq!(text(callee.receiver()), Some("Vec".to_string()));
+  assert_eq!(callee.name_ref().unwrap().text(), "new");
+  assert_eq!(text(call.generic_arg_list()), Some("<ASTNode>".to_string()));
+  let args: Vec<_> = call.arg_list().unwrap().args().collect();
+  assert_eq!(args.len(), 2);
+  match &args[1] {
+    Expr::IndexExpr(index) => {
+      assert_eq!(text(index.base()), Some("b".to_string()));
+      assert_eq!(text(index.index()), Some("0".to_string()));
+    }
+    arg => panic!("{:?}", arg),
+  }
+
+  match only_expr("x as literal") {
+    Expr::CastExpr(cast) => {
+      assert_eq!(cast.ty(), None);
+      assert!(cast.literal_token().is_some());
+    }
+    expr => panic!("{:?}", expr),
+  }
+  match only_expr("&mut x") {
+    Expr::RefExpr(r) => assert!(r.is_mut()),
+    expr => panic!("{:?}", expr),
+  }
+}
+
+#[test]
+fn literals() {
+  let array = match only_expr("[1, 2.5, 'a', \"s\", false, ..rest]") {
+    Expr::ArrayExpr(array) => array,
+    expr => panic!("{:?}", expr),
+  };
+  let kinds: Vec<_> = array
+    .elements()
+    .map(|element| match element {
+      ArrayElement::Expr(Expr::Literal(literal)) => literal.kind(),
+      ArrayElement::Expr(expr) => panic!("{:?}", expr),
+      ArrayElement::Spread(spread) => {
+        assert_eq!(text(spread.expr()), Some("rest".to_string()));
+        None
+      }
+    })
+    .collect();
+  assert_eq!(
+    kinds,
+    [
+      Some(LiteralKind::Int),
+      Some(LiteralKind::Float),
+      Some(LiteralKind::Char),
+      Some(LiteralKind::String),
+      Some(LiteralKind::Bool(false)),
+      None
+    ]
+  );
+}
+
+#[test]
+fn object_literal() {
+  let object = match only_expr("ParsedSource { name, ast: [], ..source }") {
+    Expr::ObjectLiteral(object) => object,
+    expr => panic!("{:?}", expr),
+  };
+  assert_eq!(text(object.path()), Some("ParsedSource".to_string()));
+  let members: Vec<_> = object.members().collect();
+  assert_eq!(members.len(), 3);
+  assert!(matches!(members[2], ObjectMember::Spread(_)));
+  let fields: Vec<_> = object.fields().collect();
+  assert!(fields[0].is_shorthand());
+  assert_eq!(fields[0].expr(), None);
+  assert!(!fields[1].is_shorthand());
+  assert_eq!(fields[1].name_ref().unwrap().text(), "ast");
+  assert_eq!(text(fields[1].expr()), Some("[]".to_string()));
+}
+
+#[test]
+fn template_literal() {
+  let template = match only_expr("`a${b}c${d + 1}e`") {
+    Expr::TemplateLiteral(template) => template,
+    expr => panic!("{:?}", expr),
+  };
+  let strings: Vec<_> = template
+    .strings()
+    .map(|token| token.text().to_string())
+    .collect();
+  assert_eq!(strings, ["`a${", "}c${", "}e`"]);
+  let substitutions: Vec<_> = template
+    .substitutions()
+    .map(|expr| expr.to_string())
+    .collect();
+  assert_eq!(substitutions, ["b", "d + 1"]);
+}
+
+#[test]
+fn if_expr() {
+  let if_expr = match only_expr("if a { b } else if c { d } else { e }") {
+    Expr::IfExpr(if_expr) => if_expr,
+    expr => panic!("{:?}", expr),
+  };
+  assert_eq!(text(if_expr.condition()), Some("a".to_string()));
+  assert_eq!(text(if_expr.then_branch()), Some("{ b }".to_string()));
+  match if_expr.else_branch() {
+    Some(Expr::IfExpr(else_if)) => {
+      assert_eq!(text(else_if.condition()), Some("c".to_string()));
+      assert_eq!(text(else_if.else_branch()), Some("{ e }".to_string()));
+    }
+    branch => panic!("{:?}", branch),
+  }
+
+  let if_expr = match only_expr("if isEmpty(input) return []") {
+    Expr::IfExpr(if_expr) => if_expr,
+    expr => panic!("{:?}", expr),
+  };
+  assert!(matches!(if_expr.then_branch(), Some(Expr::ReturnExpr(_))));
+  assert_eq!(if_expr.else_branch(), None);
+}
+
+#[test]
+fn match_expr() {
+  let match_expr = match only_expr("match c {\n  x if x > 0 => 1,\n  _ => { 2 }\n}") {
+    Expr::MatchExpr(match_expr) => match_expr,
+    expr => panic!("{:?}", expr),
+  };
+  assert_eq!(text(match_expr.expr()), Some("c".to_string()));
+  let arms: Vec<_> = match_expr.arms().collect();
+  assert_eq!(arms.len(), 2);
+  assert_eq!(text(arms[0].pattern()), Some("x".to_string()));
+  assert_eq!(
+    text(arms[0].guard().unwrap().condition()),
+    Some("x > 0".to_string())
+  );
+  assert_eq!(text(arms[0].body()), Some("1".to_string()));
+  assert_eq!(arms[1].guard(), None);
+  assert_eq!(text(arms[1].body()), Some("{ 2 }".to_string()));
+}
+
+#[test]
+fn loops() {
+  match only_expr("for x in xs { f(x) }") {
+    Expr::ForExpr(for_expr) => {
+      assert_eq!(for_expr.name().unwrap().text(), "x");
+      assert_eq!(text(for_expr.iterable()), Some("xs".to_string()));
+      assert_eq!(text(for_expr.body()), Some("{ f(x) }".to_string()));
+    }
+    expr => panic!("{:?}", expr),
+  }
+  match only_expr("while { a }") {
+    Expr::WhileExpr(while_expr) => {
+      assert_eq!(while_expr.condition(), None);
+      assert_eq!(text(while_expr.body()), Some("{ a }".to_string()));
+    }
+    expr => panic!("{:?}", expr),
+  }
+}
+
+#[test]
+fn repository_sources_have_typed_statements() {
+  let source = include_str!("../../../../just/justc1/src/main.just");
+  let names: Vec<_> = parse(source)
+    .tree()
+    .statements()
+    .filter_map(|stmt| match stmt {
+      Stmt::FnDecl(decl) => decl.name().map(|name| name.text()),
+      Stmt::UseDecl(decl) => decl.use_tree().map(|tree| tree.to_string()),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(
+    names,
+    ["std::path", "std::env", "std::fs", "main", "compile"]
+  );
+}
@@ -0,0 +1,269 @@
+//! Wrappers of the type expressions, e.g. `ParsedSource & { symbols: Vec<Symbol> }`.
+
+use super::support;
+use super::{AstChildren, AstNode, HasGenericParams, HasName, HasTypeAnnotation};
+use super::{Expr, GenericArgList, GenericParam, Literal, ParamList, Path, RetType};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+use crate::T;
+use std::fmt;
+
+ast_enum! {
+  Type {
+    PathType,
+    RefType,
+    ParenType,
+    UnionType,
+    IntersectionType,
+    ObjectType,
+    TupleType,
+    ArrayType,
+    FnType,
+    LiteralType,
+    SymbolType,
+    KeyofType,
+    IndexedAccessType,
+    MappedType,
+    ConditionalType,
+  }
+}
+
+ast_node!(
+  /// `i32`, `Vec<T>`
+  PathType
+);
+
+impl PathType {
+  pub fn path(&self) -> Option<Path> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `&T`, `&mut T`
+  RefType
+);
+
+impl RefType {
+  pub fn mut_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![mut])
+  }
+
+  pub fn is_mut(&self) -> bool {
+    self.mut_token().is_some()
+  }
+
+  pub fn ty(&self) -> Option<Type> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `(int | char)`
+  ParenType
+);
+
+impl ParenType {
+  pub fn ty(&self) -> Option<Type> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `int | char`, `Some<T> | None`
+  UnionType
+);
+
+impl UnionType {
+  pub fn types(&self) -> AstChildren<Type> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `ParsedSource & { symbols: Vec<Symbol> }`
+  IntersectionType
+);
+
+impl IntersectionType {
+  pub fn types(&self) -> AstChildren<Type> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `{ name: String, age?: u8 }`
+  ObjectType
+);
+
+impl ObjectType {
+  pub fn fields(&self) -> AstChildren<ObjectTypeField> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `name: String`, `age?: u8`
+  ObjectTypeField
+);
+
+impl HasName for ObjectTypeField {}
+impl HasTypeAnnotation for ObjectTypeField {}
+
+impl ObjectTypeField {
+  pub fn question_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![?])
+  }
+
+  pub fn is_optional(&self) -> bool {
+    self.question_token().is_some()
+  }
+}
+
+ast_node!(
+  /// `[i32, u32, char]`, `[]`
+  TupleType
+);
+
+impl TupleType {
+  pub fn types(&self) -> AstChildren<Type> {
+    support::children(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `[i32; 3]`, `[u8]`
+  ArrayType
+);
+
+impl ArrayType {
+  pub fn ty(&self) -> Option<Type> {
+    support::child(&self.syntax)
+  }
+
+  /// The length after `;`, none for arrays of any length.
+  pub fn len(&self) -> Option<Expr> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `(entry: T) -> bool`, `<T>(x: T) -> T`
+  FnType
+);
+
+impl HasGenericParams for FnType {}
+
+impl FnType {
+  pub fn param_list(&self) -> Option<ParamList> {
+    support::child(&self.syntax)
+  }
+
+  pub fn ret_type(&self) -> Option<RetType> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `true`, `1`, `'a'`, `"foo"`
+  LiteralType
+);
+
+impl LiteralType {
+  pub fn literal(&self) -> Option<Literal> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `Symbol()`, `Symbol<T>()`, a value distinct from any other, used to build nominal types.
+  SymbolType
+);
+
+impl SymbolType {
+  pub fn generic_arg_list(&self) -> Option<GenericArgList> {
+    support::child(&self.syntax)
+  }
+
+  /// The type of the value carried by the symbol, e.g. `T` in `Symbol<T>()`.
+  pub fn ty(&self) -> Option<Type> {
+    self.generic_arg_list()?.types().next()
+  }
+}
+
+ast_node!(
+  /// `keyof T`, the union of the names of the fields of `T`.
+  KeyofType
+);
+
+impl KeyofType {
+  pub fn ty(&self) -> Option<Type> {
+    support::child(&self.syntax)
+  }
+}
+
+ast_node!(
+  /// `T["name"]`, `T[K]`
+  IndexedAccessType
+);
+
+impl IndexedAccessType {
+  pub fn ty(&self) -> Option<Type> {
+    support::children(&self.syntax).next()
+  }
+
+  pub fn index(&self) -> Option<Type> {
+    support::children(&self.syntax).nth(1)
+  }
+}
+
+ast_node!(
+  /// `{ [K in keyof T]?: T[K] }`
+  MappedType
+);
+
+impl HasTypeAnnotation for MappedType {}
+
+impl MappedType {
+  /// `K`, bound to each key in the type of the fields.
+  pub fn generic_param(&self) -> Option<GenericParam> {
+    support::child(&self.syntax)
+  }
+
+  /// The union of the keys after `in`.
+  pub fn keys(&self) -> Option<Type> {
+    support::child(&self.syntax)
+  }
+
+  pub fn question_token(&self) -> Option<SyntaxToken> {
+    support::token(&self.syntax, T![?])
+  }
+
+  pub fn is_optional(&self) -> bool {
+    self.question_token().is_some()
+  }
+}
+
+ast_node!(
+  /// `T extends str ? true : false`
+  ConditionalType
+);
+
+impl ConditionalType {
+  /// The type tested, `T`.
+  pub fn check_type(&self) -> Option<Type> {
+    support::children(&self.syntax).next()
+  }
+
+  pub fn extends_type(&self) -> Option<Type> {
+    support::children(&self.syntax).nth(1)
+  }
+
+  pub fn true_type(&self) -> Option<Type> {
+    support::children(&self.syntax).nth(2)
+  }
+
+  pub fn false_type(&self) -> Option<Type> {
+    support::children(&self.syntax).nth(3)
+  }
+}
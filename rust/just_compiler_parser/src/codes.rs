@@ -0,0 +1,12 @@
+//! Codes of the parser diagnostics.
+//!
+//! Lexer diagnostics keep their own codes, see `justc_lexer::diagnose`.
+
+use justc_lexer::diagnostic::Code;
+
+/// A specific token or construct is expected, e.g. "expected `)`, found `;`".
+pub const EXPECTED: Code = Code("P0001");
+/// A token that cannot start a statement.
+pub const EXPECTED_STATEMENT: Code = Code("P0002");
+/// Two statements on the same line without `;` between them.
+pub const MISSING_SEPARATOR: Code = Code("P0003");
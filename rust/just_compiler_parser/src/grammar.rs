@@ -0,0 +1,143 @@
+//! Grammar of *Just*.
+//!
+//! See `docs/just-programming-language/language-reference` for the constructs,
+//! the functions of each module are named after the node they parse.
+
+mod expressions;
+mod params;
+mod statements;
+mod types;
+
+use super::parser::{CompletedMarker, Parser};
+use super::syntax_kind::SyntaxKind;
+use crate::T;
+
+/// Tokens that end a statement or start a declaration.
+/// After an error, the parser skips tokens until one of them or a line break.
+const STMT_RECOVERY: &[SyntaxKind] = &[
+  T![;],
+  T!['}'],
+  T![let],
+  T![const],
+  T![type],
+  T![fn],
+  T![pub],
+  T![@],
+];
+
+/// Tokens that cannot be part of a list, e.g. the `let` in `f(a,\nlet b = 1`,
+/// `delimited` stops at them instead of skipping the rest of the file.
+const LIST_RECOVERY: &[SyntaxKind] = &[
+  T![;],
+  T![')'],
+  T![']'],
+  T!['}'],
+  T![let],
+  T![const],
+  T![type],
+  T![fn],
+  T![pub],
+  T![@],
+];
+
+pub(crate) fn source_file(p: &mut Parser) {
+  let m = p.start();
+  while !p.at_eof() {
+    statements::statement(p);
+  }
+  m.complete(p, SyntaxKind::SourceFile);
+}
+
+/// Name being defined, e.g. `x` in `let x = 1`.
+fn name(p: &mut Parser) -> Option<CompletedMarker> {
+  if !p.at(SyntaxKind::Ident) {
+    p.error_expected("a name");
+    p.missing();
+    return None;
+  }
+  let m = p.start();
+  p.bump();
+  Some(m.complete(p, SyntaxKind::Name))
+}
+
+/// Reference to a name, e.g. `x` in `x + 1`.
+fn name_ref(p: &mut Parser) -> Option<CompletedMarker> {
+  if !p.at_any(&[SyntaxKind::Ident, T![self]]) {
+    p.error_expected("a name");
+    p.missing();
+    return None;
+  }
+  let m = p.start();
+  p.bump();
+  Some(m.complete(p, SyntaxKind::NameRef))
+}
+
+/// Path made of segments separated by `separators`, e.g. `std::path::Path`.
+/// `segment` parses what follows the name of each segment, e.g. generic arguments in types.
+fn path(
+  p: &mut Parser,
+  separators: &[SyntaxKind],
+  mut segment: impl FnMut(&mut Parser),
+) -> CompletedMarker {
+  let m = p.start();
+  loop {
+    let s = p.start();
+    name_ref(p);
+    segment(p);
+    s.complete(p, SyntaxKind::PathSegment);
+    // A trailing separator is left to the caller, e.g. in `use std::` followed by a line break.
+    if !(p.at_any(separators) && p.nth_at(1, SyntaxKind::Ident) && !p.nth_newline_before(1)) {
+      break;
+    }
+    p.bump();
+  }
+  m.complete(p, SyntaxKind::Path)
+}
+
+/// Kind of the token after the bracket closing the one `n` tokens ahead,
+/// e.g. `=>` for the `(` of `(x) => x`.
+fn nth_after_closing(p: &Parser, mut n: usize) -> SyntaxKind {
+  let mut depth = 0;
+  loop {
+    match p.peek(n) {
+      T!['('] | T!['['] | T!['{'] => depth += 1,
+      T![')'] | T![']'] | T!['}'] => {
+        depth -= 1;
+        if depth == 0 {
+          return p.peek(n + 1);
+        }
+      }
+      SyntaxKind::Eof => return SyntaxKind::Eof,
+      _ => (),
+    }
+    n += 1;
+  }
+}
+
+/// Comma separated list between `open` and `close`, e.g. arguments.
+/// `element` parses one element and returns false if there is none.
+/// The list ends early at tokens of `LIST_RECOVERY`, or when a `,` is missing at the end of a line,
+/// so an unclosed list doesn't swallow the code after it.
+fn delimited(
+  p: &mut Parser,
+  open: SyntaxKind,
+  close: SyntaxKind,
+  what: &str,
+  mut element: impl FnMut(&mut Parser) -> bool,
+) {
+  p.expect(open);
+  while !p.at(close) && !p.at_eof() && !p.at_any(LIST_RECOVERY) {
+    if !element(p) {
+      p.error_expected(what);
+      p.bump_error();
+      continue;
+    }
+    if !p.at(close) && !p.expect(T![,]) && p.newline_before() {
+      break;
+    }
+  }
+  p.expect(close);
+}
+
+#[cfg(test)]
+mod tests;
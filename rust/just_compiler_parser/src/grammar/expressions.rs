@@ -0,0 +1,603 @@
+//! Expressions, parsed by precedence climbing.
+//!
+//! Calls, indexing and object literals must start on the line of the expression they apply to,
+//! so a statement starting with `(` or `[` is not mistaken for the continuation of the previous one.
+
+use super::params;
+use super::statements::block;
+use super::types;
+use super::{delimited, name, name_ref, nth_after_closing, path};
+use crate::parser::{CompletedMarker, Parser};
+use crate::syntax_kind::SyntaxKind;
+use crate::T;
+use justc_lexer::keyword::ContextualKeyword;
+
+/// Constructs that are not allowed in some positions.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct Restrictions {
+  /// `{` starts the body of the enclosing construct, not an object literal, e.g. in `if a {`.
+  pub(super) no_object_literal: bool,
+  /// `|` ends the context parameter, it is not a bitwise or, e.g. in `(ctx = { v }| x) => v + x`.
+  pub(super) no_bit_or: bool,
+  /// `=>` follows a match pattern, the pattern is not an arrow function, e.g. in `x => x + 1,`.
+  pub(super) no_arrow_fn: bool,
+}
+
+impl Restrictions {
+  fn condition() -> Restrictions {
+    Restrictions {
+      no_object_literal: true,
+      ..Restrictions::default()
+    }
+  }
+
+  /// Patterns and guards of match arms, which are followed by `=>`.
+  fn match_arm() -> Restrictions {
+    Restrictions {
+      no_object_literal: true,
+      no_arrow_fn: true,
+      ..Restrictions::default()
+    }
+  }
+
+  pub(super) fn context_param() -> Restrictions {
+    Restrictions {
+      no_bit_or: true,
+      ..Restrictions::default()
+    }
+  }
+}
+
+const CAST_BP: u8 = 10;
+const PREFIX_BP: u8 = 11;
+
+/// Binding power of binary operators, and whether they are right associative.
+fn infix_bp(kind: SyntaxKind) -> Option<(u8, bool)> {
+  let bp = match kind {
+    T![=] | SyntaxKind::PlusEq | SyntaxKind::MinusEq => (1, true),
+    SyntaxKind::StarEq | SyntaxKind::SlashEq | SyntaxKind::PercentEq => (1, true),
+    T![||] => (2, false),
+    T![&&] => (3, false),
+    T![==] | T![!=] | T![<] | T![>] | T![<=] | T![>=] => (4, false),
+    T![|] => (5, false),
+    T![^] => (6, false),
+    T![&] => (7, false),
+    T![+] | T![-] => (8, false),
+    T![*] | T![/] | T![%] => (9, false),
+    _ => return None,
+  };
+  Some(bp)
+}
+
+pub(super) fn expr(p: &mut Parser) -> Option<CompletedMarker> {
+  expr_bp(p, 1, Restrictions::default())
+}
+
+pub(super) fn expr_with(p: &mut Parser, r: Restrictions) -> Option<CompletedMarker> {
+  expr_bp(p, 1, r)
+}
+
+/// Parses an expression, reports an error and adds a `Missing` node if there is none.
+pub(super) fn expr_or_error(p: &mut Parser, r: Restrictions) {
+  expr_bp_or_error(p, 1, r);
+}
+
+fn expr_bp_or_error(p: &mut Parser, min_bp: u8, r: Restrictions) {
+  if expr_bp(p, min_bp, r).is_none() {
+    p.error_expected("an expression");
+    p.missing();
+  }
+}
+
+pub(super) fn at_expr_start(p: &Parser) -> bool {
+  nth_at_expr_start(p, 0)
+}
+
+fn nth_at_expr_start(p: &Parser, n: usize) -> bool {
+  let kind = p.nth(n);
+  match kind {
+    SyntaxKind::TemplateMiddle | SyntaxKind::TemplateTail => false,
+    _ if kind.is_literal() => true,
+    SyntaxKind::Ident
+    | T![self]
+    | T!['(']
+    | T!['[']
+    | T!['{']
+    | T![-]
+    | T![!]
+    | T![*]
+    | T![&]
+    | T![move]
+    | T![if]
+    | T![while]
+    | T![loop]
+    | T![for]
+    | T![return]
+    | T![break]
+    | T![continue] => true,
+    T![<] => generic_arrow_fn_ahead(p, n),
+    _ => false,
+  }
+}
+
+fn expr_bp(p: &mut Parser, min_bp: u8, r: Restrictions) -> Option<CompletedMarker> {
+  let mut lhs = lhs(p, r)?;
+  loop {
+    if p.at(T![as]) {
+      if CAST_BP < min_bp {
+        break;
+      }
+      let m = lhs.precede(p);
+      p.bump();
+      if !p.eat(T![literal]) {
+        types::cast_type(p);
+      }
+      lhs = m.complete(p, SyntaxKind::CastExpr);
+      continue;
+    }
+    if p.at(T![|]) && r.no_bit_or {
+      break;
+    }
+    let (bp, right_assoc) = match infix_bp(p.current()) {
+      Some(bp) => bp,
+      None => break,
+    };
+    if bp < min_bp {
+      break;
+    }
+    let m = lhs.precede(p);
+    p.bump();
+    let rhs_bp = if right_assoc { bp } else { bp + 1 };
+    expr_bp_or_error(p, rhs_bp, r);
+    lhs = m.complete(p, SyntaxKind::BinExpr);
+  }
+  Some(lhs)
+}
+
+fn lhs(p: &mut Parser, r: Restrictions) -> Option<CompletedMarker> {
+  if p.at(T![&]) && !r.no_arrow_fn && ref_arrow_fn_ahead(p) {
+    return Some(arrow_fn(p));
+  }
+  if p.at(T![move]) {
+    return Some(arrow_fn(p));
+  }
+  let kind = match p.current() {
+    T![-] | T![!] | T![*] => SyntaxKind::PrefixExpr,
+    T![&] => SyntaxKind::RefExpr,
+    _ => {
+      let atom = atom(p, r)?;
+      return Some(postfix(p, atom, r));
+    }
+  };
+  let m = p.start();
+  p.bump();
+  if kind == SyntaxKind::RefExpr
+    && p.at_contextual_kw(ContextualKeyword::Mut)
+    && nth_at_expr_start(p, 1)
+  {
+    p.bump_remap(T![mut]);
+  }
+  expr_bp_or_error(p, PREFIX_BP, r);
+  Some(m.complete(p, kind))
+}
+
+/// True at a literal token, including `true` and `false`.
+pub(super) fn at_literal(p: &Parser) -> bool {
+  p.current().is_literal()
+    || p.at_contextual_kw(ContextualKeyword::True)
+    || p.at_contextual_kw(ContextualKeyword::False)
+}
+
+/// `1`, `"foo"`, `true`
+pub(super) fn literal(p: &mut Parser) -> CompletedMarker {
+  let m = p.start();
+  if p.at_contextual_kw(ContextualKeyword::True) {
+    p.bump_remap(T![true]);
+  } else if p.at_contextual_kw(ContextualKeyword::False) {
+    p.bump_remap(T![false]);
+  } else {
+    p.bump();
+  }
+  m.complete(p, SyntaxKind::Literal)
+}
+
+fn atom(p: &mut Parser, r: Restrictions) -> Option<CompletedMarker> {
+  let done = match p.current() {
+    SyntaxKind::TemplateString | SyntaxKind::TemplateHead => template_literal(p),
+    SyntaxKind::TemplateMiddle | SyntaxKind::TemplateTail => return None,
+    _ if at_literal(p) => literal(p),
+    SyntaxKind::Ident
+      if p.at_contextual_kw(ContextualKeyword::Match)
+        && nth_at_expr_start(p, 1)
+        && !p.nth_at(1, T!['{'])
+        && !p.nth_newline_before(1) =>
+    {
+      match_expr(p)
+    }
+    SyntaxKind::Ident if !r.no_arrow_fn && p.nth_at(1, T![=>]) && !p.nth_newline_before(1) => {
+      arrow_fn(p)
+    }
+    SyntaxKind::Ident | T![self] => path_expr_or_object_literal(p, r),
+    T!['('] if !r.no_arrow_fn && arrow_fn_ahead(p, 0) => arrow_fn(p),
+    T![<] if !r.no_arrow_fn && generic_arrow_fn_ahead(p, 0) => arrow_fn(p),
+    T!['('] => {
+      let m = p.start();
+      p.bump();
+      expr_or_error(p, Restrictions::default());
+      p.expect(T![')']);
+      m.complete(p, SyntaxKind::ParenExpr)
+    }
+    T!['['] => array_expr(p),
+    T!['{'] if !r.no_object_literal && object_literal_ahead(p) => {
+      let m = p.start();
+      object_literal_body(p);
+      m.complete(p, SyntaxKind::ObjectLiteral)
+    }
+    T!['{'] => block(p),
+    T![if] => if_expr(p),
+    T![while] => {
+      let m = p.start();
+      p.bump();
+      expr_or_error(p, Restrictions::condition());
+      loop_body(p);
+      m.complete(p, SyntaxKind::WhileExpr)
+    }
+    T![loop] => {
+      let m = p.start();
+      p.bump();
+      loop_body(p);
+      m.complete(p, SyntaxKind::LoopExpr)
+    }
+    T![for] => {
+      let m = p.start();
+      p.bump();
+      name(p);
+      p.expect(T![in]);
+      expr_or_error(p, Restrictions::condition());
+      loop_body(p);
+      m.complete(p, SyntaxKind::ForExpr)
+    }
+    T![return] => {
+      let m = p.start();
+      p.bump();
+      if !p.newline_before() && at_expr_start(p) {
+        expr_with(p, r);
+      }
+      m.complete(p, SyntaxKind::ReturnExpr)
+    }
+    T![break] => {
+      let m = p.start();
+      p.bump();
+      m.complete(p, SyntaxKind::BreakExpr)
+    }
+    T![continue] => {
+      let m = p.start();
+      p.bump();
+      m.complete(p, SyntaxKind::ContinueExpr)
+    }
+    _ => return None,
+  };
+  Some(done)
+}
+
+/// Calls, field accesses, indexing and injections following `lhs`.
+fn postfix(p: &mut Parser, mut lhs: CompletedMarker, r: Restrictions) -> CompletedMarker {
+  loop {
+    lhs = match p.current() {
+      T!['{'] if !r.no_object_literal && !p.newline_before() && injection_ahead(p) => {
+        let m = lhs.precede(p);
+        object_literal_body(p);
+        m.complete(p, SyntaxKind::InjectExpr)
+      }
+      T!['('] if !p.newline_before() => {
+        let m = lhs.precede(p);
+        arg_list(p);
+        m.complete(p, SyntaxKind::CallExpr)
+      }
+      T![<] if generic_call_ahead(p) => {
+        let m = lhs.precede(p);
+        types::generic_arg_list(p);
+        arg_list(p);
+        m.complete(p, SyntaxKind::CallExpr)
+      }
+      T!['['] if !p.newline_before() => {
+        let m = lhs.precede(p);
+        p.bump();
+        expr_or_error(p, Restrictions::default());
+        p.expect(T![']']);
+        m.complete(p, SyntaxKind::IndexExpr)
+      }
+      T![.] => {
+        let m = lhs.precede(p);
+        p.bump();
+        name_ref(p);
+        m.complete(p, SyntaxKind::FieldExpr)
+      }
+      _ => return lhs,
+    };
+  }
+}
+
+/// `(a, b)`
+pub(super) fn arg_list(p: &mut Parser) {
+  let m = p.start();
+  delimited(p, T!['('], T![')'], "an argument", |p| {
+    expr_with(p, Restrictions::default()).is_some()
+  });
+  m.complete(p, SyntaxKind::ArgList);
+}
+
+/// `a`, `std::env`, and typed object literals such as `ParsedSource { name }`.
+/// `inc{ x: 10 }(2)` is an injection, see `injection_ahead`.
+fn path_expr_or_object_literal(p: &mut Parser, r: Restrictions) -> CompletedMarker {
+  let path = path(p, &[T![::]], |_| ());
+  if p.at(T!['{'])
+    && !r.no_object_literal
+    && !p.newline_before()
+    && object_literal_ahead(p)
+    && !injection_ahead(p)
+  {
+    let m = path.precede(p);
+    object_literal_body(p);
+    m.complete(p, SyntaxKind::ObjectLiteral)
+  } else {
+    let m = path.precede(p);
+    m.complete(p, SyntaxKind::PathExpr)
+  }
+}
+
+/// True if the `{` at the current position starts an object literal rather than a block:
+/// `{}`, `{ ..a }`, `{ a }`, `{ a, b }` or `{ a: b }`.
+fn object_literal_ahead(p: &Parser) -> bool {
+  match p.nth(1) {
+    T!['}'] | T![..] => true,
+    SyntaxKind::Ident => p.nth_at_any(2, &[T![:], T![,], T!['}']]),
+    _ => false,
+  }
+}
+
+/// True if the `{` at the current position starts the injection of a call, e.g. `inc{ x: 10 }(2)`,
+/// i.e. it looks like an object literal and the matching `}` is followed by `(` on the same line.
+/// Without the call, `Path { x }` is a typed object literal.
+fn injection_ahead(p: &Parser) -> bool {
+  if !object_literal_ahead(p) {
+    return false;
+  }
+  let mut depth = 0;
+  let mut n = 0;
+  loop {
+    match p.peek(n) {
+      T!['('] | T!['['] | T!['{'] => depth += 1,
+      T![')'] | T![']'] | T!['}'] => {
+        depth -= 1;
+        if depth == 0 {
+          return p.peek(n + 1) == T!['('] && !p.nth_newline_before(n + 1);
+        }
+      }
+      SyntaxKind::Eof => return false,
+      _ => (),
+    }
+    n += 1;
+  }
+}
+
+/// `{ name, ast: [], ..rest }`
+fn object_literal_body(p: &mut Parser) {
+  delimited(p, T!['{'], T!['}'], "a field", |p| {
+    if p.at(T![..]) {
+      spread_expr(p);
+    } else if p.at(SyntaxKind::Ident) {
+      let m = p.start();
+      name_ref(p);
+      if p.eat(T![:]) {
+        expr_or_error(p, Restrictions::default());
+      }
+      m.complete(p, SyntaxKind::ObjectField);
+    } else {
+      return false;
+    }
+    true
+  });
+}
+
+/// `..rest`
+fn spread_expr(p: &mut Parser) {
+  let m = p.start();
+  p.bump();
+  expr_or_error(p, Restrictions::default());
+  m.complete(p, SyntaxKind::SpreadExpr);
+}
+
+/// `[1, 2, ..rest]`
+fn array_expr(p: &mut Parser) -> CompletedMarker {
+  let m = p.start();
+  delimited(p, T!['['], T![']'], "an element", |p| {
+    if p.at(T![..]) {
+      spread_expr(p);
+      return true;
+    }
+    expr_with(p, Restrictions::default()).is_some()
+  });
+  m.complete(p, SyntaxKind::ArrayExpr)
+}
+
+/// `` `hello ${name}!` ``
+fn template_literal(p: &mut Parser) -> CompletedMarker {
+  let m = p.start();
+  if !p.eat(SyntaxKind::TemplateString) {
+    p.bump();
+    loop {
+      expr_or_error(p, Restrictions::default());
+      if !p.eat(SyntaxKind::TemplateMiddle) {
+        break;
+      }
+    }
+    if !p.eat(SyntaxKind::TemplateTail) {
+      p.error_expected("`}` closing the substitution");
+    }
+  }
+  m.complete(p, SyntaxKind::TemplateLiteral)
+}
+
+/// True if the `(` `n` tokens ahead starts the parameters of an arrow function,
+/// i.e. the matching `)` is followed by `=>` or `->`, or there is none yet and the first parameter is typed,
+/// e.g. `(a: ` being written, which can't be an expression.
+fn arrow_fn_ahead(p: &Parser, n: usize) -> bool {
+  match nth_after_closing(p, n) {
+    T![=>] | T![->] => true,
+    SyntaxKind::Eof => p.nth_at(n + 1, SyntaxKind::Ident) && p.nth_at(n + 2, T![:]),
+    _ => false,
+  }
+}
+
+/// True if the `<` `n` tokens ahead starts the generic parameters of an arrow function,
+/// e.g. `<T>(array: &Array<T>) => array.len()`.
+fn generic_arrow_fn_ahead(p: &Parser, mut n: usize) -> bool {
+  let mut depth = 0;
+  loop {
+    match p.peek(n) {
+      T![<] => depth += 1,
+      T![>] => {
+        depth -= 1;
+        if depth == 0 {
+          return p.peek(n + 1) == T!['('] && arrow_fn_ahead(p, n + 1);
+        }
+      }
+      SyntaxKind::Ident | T![,] | T![:] | T![::] | T![.] | T![&] | T!['['] | T![']'] | T![;] => (),
+      SyntaxKind::IntNumber => (),
+      _ => return false,
+    }
+    n += 1;
+  }
+}
+
+/// True if the `&` at the current position starts the parameter of an arrow function
+/// without parentheses, e.g. `&ctx => ctx.x + 1`.
+fn ref_arrow_fn_ahead(p: &Parser) -> bool {
+  let n = if p.nth_at_contextual_kw(1, ContextualKeyword::Mut) && p.nth_at(2, SyntaxKind::Ident) {
+    2
+  } else {
+    1
+  };
+  p.nth_at(n, SyntaxKind::Ident) && p.nth_at(n + 1, T![=>]) && !p.nth_newline_before(n + 1)
+}
+
+/// `(x: i32) -> i32 => x + 1`, `<T>(array: &Array<T>) => array.len()`, `({ v }| x) => v + x`,
+/// `x => x * 2`, `move () => name`
+fn arrow_fn(p: &mut Parser) -> CompletedMarker {
+  let m = p.start();
+  p.eat(T![move]);
+  if p.at(T![<]) {
+    params::generic_param_list(p);
+  }
+  if p.at(T!['(']) {
+    params::param_list(p);
+  } else {
+    params::param_list_without_parens(p);
+  }
+  if p.at(T![->]) {
+    types::ret_type(p);
+  }
+  p.expect(T![=>]);
+  if p.at(T!['{']) {
+    block(p);
+  } else {
+    expr_or_error(p, Restrictions::default());
+  }
+  m.complete(p, SyntaxKind::ArrowFn)
+}
+
+/// True if the `<` at the current position starts generic arguments of a call, e.g. `new<T>()`,
+/// rather than a comparison.
+fn generic_call_ahead(p: &Parser) -> bool {
+  let mut depth = 0;
+  for n in 0..64 {
+    match p.nth(n) {
+      T![<] => depth += 1,
+      T![>] => {
+        depth -= 1;
+        if depth == 0 {
+          return p.nth_at(n + 1, T!['(']) && !p.nth_newline_before(n + 1);
+        }
+      }
+      SyntaxKind::Ident | T![,] | T![::] | T![.] | T![&] | T!['['] | T![']'] | T![;] => (),
+      SyntaxKind::IntNumber => (),
+      _ => return false,
+    }
+  }
+  false
+}
+
+/// `if a { b } else { c }`, `if isEmpty(input) return []`
+fn if_expr(p: &mut Parser) -> CompletedMarker {
+  let m = p.start();
+  p.bump();
+  expr_or_error(p, Restrictions::condition());
+  branch(p);
+  if p.eat(T![else]) {
+    if p.at(T![if]) {
+      if_expr(p);
+    } else {
+      branch(p);
+    }
+  }
+  m.complete(p, SyntaxKind::IfExpr)
+}
+
+fn branch(p: &mut Parser) {
+  if p.at(T!['{']) {
+    block(p);
+  } else {
+    expr_or_error(p, Restrictions::default());
+  }
+}
+
+fn loop_body(p: &mut Parser) {
+  if p.at(T!['{']) {
+    block(p);
+  } else {
+    p.error_expected("a block");
+    p.missing();
+  }
+}
+
+/// `match a { 1 => b, _ => c }`
+fn match_expr(p: &mut Parser) -> CompletedMarker {
+  let m = p.start();
+  p.bump_remap(T![match]);
+  expr_or_error(p, Restrictions::condition());
+  let arms = p.start();
+  p.expect(T!['{']);
+  while !p.at(T!['}']) && !p.at_eof() {
+    if !at_expr_start(p) {
+      p.error_expected("a match arm");
+      p.bump_error();
+      continue;
+    }
+    match_arm(p);
+  }
+  p.expect(T!['}']);
+  arms.complete(p, SyntaxKind::MatchArmList);
+  m.complete(p, SyntaxKind::MatchExpr)
+}
+
+fn match_arm(p: &mut Parser) {
+  let m = p.start();
+  expr_with(p, Restrictions::match_arm());
+  if p.at(T![if]) {
+    let guard = p.start();
+    p.bump();
+    expr_or_error(p, Restrictions::match_arm());
+    guard.complete(p, SyntaxKind::MatchGuard);
+  }
+  p.expect(T![=>]);
+  if p.at(T!['{']) {
+    block(p);
+  } else {
+    expr_or_error(p, Restrictions::default());
+  }
+  if !p.eat(T![,]) && !p.at(T!['}']) && !p.newline_before() {
+    p.error_expected("`,`");
+  }
+  m.complete(p, SyntaxKind::MatchArm);
+}
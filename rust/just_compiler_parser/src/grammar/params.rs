@@ -0,0 +1,116 @@
+//! Parameters of functions and generic parameters.
+
+use super::expressions::{self, Restrictions};
+use super::types;
+use super::{delimited, name, name_ref};
+use crate::parser::Parser;
+use crate::syntax_kind::SyntaxKind;
+use crate::T;
+use justc_lexer::keyword::ContextualKeyword;
+
+/// `<T, U: Bound>`
+pub(super) fn generic_param_list(p: &mut Parser) {
+  let m = p.start();
+  delimited(p, T![<], T![>], "a generic parameter", |p| {
+    if !p.at(SyntaxKind::Ident) {
+      return false;
+    }
+    let m = p.start();
+    name(p);
+    if p.at(T![:]) {
+      types::type_annotation(p);
+    }
+    m.complete(p, SyntaxKind::GenericParam);
+    true
+  });
+  m.complete(p, SyntaxKind::GenericParamList);
+}
+
+/// `(x: i32, mut y = 1)`, `(&mut ctx)`, `({ v }| x)`
+///
+/// The first parameter is the context of the function if it is followed by `|`,
+/// so a `|` in its type or default value must be in parentheses, e.g. `(ctx: (A | B) = (a | b)| x)`.
+pub(super) fn param_list(p: &mut Parser) {
+  let m = p.start();
+  let mut first = true;
+  delimited(p, T!['('], T![')'], "a parameter", |p| {
+    if !at_param_start(p) {
+      return false;
+    }
+    if !first {
+      param(p, Restrictions::default());
+      return true;
+    }
+    first = false;
+    param(p, Restrictions::context_param());
+    if p.eat(T![|]) && at_param_start(p) {
+      param(p, Restrictions::default());
+    }
+    true
+  });
+  m.complete(p, SyntaxKind::ParamList);
+}
+
+/// The parameter of `x => x + 1` or `&ctx => ctx.x`, which has no parentheses.
+pub(super) fn param_list_without_parens(p: &mut Parser) {
+  let m = p.start();
+  param(p, Restrictions::default());
+  m.complete(p, SyntaxKind::ParamList);
+}
+
+fn at_param_start(p: &Parser) -> bool {
+  p.at_any(&[SyntaxKind::Ident, T![&], T!['{']])
+}
+
+/// `x: i32`, `mut y = 1`, `&mut ctx`, `{ v } = { v }`
+fn param(p: &mut Parser, r: Restrictions) {
+  let m = p.start();
+  p.eat(T![&]);
+  if p.nth_at_any(1, &[SyntaxKind::Ident, T!['{']]) {
+    p.eat_contextual_kw(ContextualKeyword::Mut);
+  }
+  pat(p);
+  if p.at(T![:]) && r.no_bit_or {
+    types::context_type_annotation(p);
+  } else if p.at(T![:]) {
+    types::type_annotation(p);
+  }
+  if p.eat(T![=]) {
+    expressions::expr_or_error(p, r);
+  }
+  m.complete(p, SyntaxKind::Param);
+}
+
+/// `x`, `{ v, w: { x } }`
+fn pat(p: &mut Parser) {
+  match p.current() {
+    SyntaxKind::Ident => {
+      name(p);
+    }
+    T!['{'] => object_pat(p),
+    _ => {
+      p.error_expected("a parameter name");
+      p.missing();
+    }
+  }
+}
+
+fn object_pat(p: &mut Parser) {
+  let m = p.start();
+  delimited(p, T!['{'], T!['}'], "a field", |p| {
+    if !p.at(SyntaxKind::Ident) {
+      return false;
+    }
+    let field = p.start();
+    if p.nth_at(1, T![:]) {
+      name_ref(p);
+      p.bump();
+      pat(p);
+    } else {
+      name(p);
+    }
+    field.complete(p, SyntaxKind::ObjectPatField);
+    true
+  });
+  m.complete(p, SyntaxKind::ObjectPat);
+}
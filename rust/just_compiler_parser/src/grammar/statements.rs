@@ -0,0 +1,229 @@
+//! Declarations and statements.
+//!
+//! *Just* source files are lists of statements, declarations can appear anywhere a statement can.
+//! Statements are separated by `;` or line breaks.
+
+use super::expressions::{self, Restrictions};
+use super::params;
+use super::types;
+use super::{delimited, name, path, STMT_RECOVERY};
+use crate::codes;
+use crate::parser::{CompletedMarker, Parser};
+use crate::syntax_kind::SyntaxKind;
+use crate::T;
+use justc_lexer::keyword::ContextualKeyword;
+
+pub(super) fn statement(p: &mut Parser) {
+  let m = p.start();
+  let mut has_modifiers = false;
+  while p.at(T![@]) {
+    decorator(p);
+    has_modifiers = true;
+  }
+  if p.at(T![pub]) {
+    let v = p.start();
+    p.bump();
+    v.complete(p, SyntaxKind::Visibility);
+    has_modifiers = true;
+  }
+
+  let kind = match p.current() {
+    T![let] | T![const] => {
+      let_decl(p);
+      SyntaxKind::LetDecl
+    }
+    T![type] => {
+      type_alias(p);
+      SyntaxKind::TypeAlias
+    }
+    T![fn] if p.nth_at(1, SyntaxKind::Ident) => {
+      fn_decl(p);
+      SyntaxKind::FnDecl
+    }
+    SyntaxKind::Ident
+      if p.at_contextual_kw(ContextualKeyword::Nominal) && p.nth_at(1, T![type]) =>
+    {
+      type_alias(p);
+      SyntaxKind::TypeAlias
+    }
+    SyntaxKind::Ident
+      if p.at_contextual_kw(ContextualKeyword::Use)
+        && p.nth_at_any(1, &[SyntaxKind::Ident, T!['{']]) =>
+    {
+      p.bump_remap(T![use]);
+      use_tree(p);
+      SyntaxKind::UseDecl
+    }
+    SyntaxKind::Ident
+      if p.at_contextual_kw(ContextualKeyword::Mod) && p.nth_at(1, SyntaxKind::Ident) =>
+    {
+      p.bump_remap(T![mod]);
+      name(p);
+      SyntaxKind::ModDecl
+    }
+    _ if has_modifiers => {
+      p.error_expected("a declaration");
+      p.missing();
+      m.complete(p, SyntaxKind::Error);
+      return;
+    }
+    T!['{'] => {
+      block(p);
+      SyntaxKind::ExprStmt
+    }
+    _ if expressions::at_expr_start(p) => {
+      expressions::expr(p);
+      SyntaxKind::ExprStmt
+    }
+    _ => {
+      m.abandon(p);
+      p.error(
+        codes::EXPECTED_STATEMENT,
+        format!("expected a statement, found `{}`", p.nth_text(0)),
+      );
+      p.bump_error_until(STMT_RECOVERY);
+      return;
+    }
+  };
+  statement_end(p);
+  m.complete(p, kind);
+}
+
+/// Consumes the `;` ending a statement, if any.
+/// Without `;`, the next statement must start on another line.
+/// Tokens left on the line are skipped in an `Error` node, e.g. `: b)` in `let a = (a: b)`.
+fn statement_end(p: &mut Parser) {
+  if p.eat(T![;]) || p.at_any(&[T!['}'], SyntaxKind::Eof]) || p.newline_before() {
+    return;
+  }
+  // A block closes the statement, e.g. `if a { b } c`.
+  if p.prev() == T!['}'] {
+    return;
+  }
+  p.error(
+    codes::MISSING_SEPARATOR,
+    "expected `;` or a line break after the statement",
+  );
+  if !p.at_any(STMT_RECOVERY) {
+    p.bump_error_until(STMT_RECOVERY);
+    p.eat(T![;]);
+  }
+}
+
+/// `{ statement* }`
+pub(super) fn block(p: &mut Parser) -> CompletedMarker {
+  let m = p.start();
+  p.expect(T!['{']);
+  while !p.at(T!['}']) && !p.at_eof() {
+    statement(p);
+  }
+  p.expect(T!['}']);
+  m.complete(p, SyntaxKind::Block)
+}
+
+/// `@log`, `@nominal`, `@memo(100)`
+fn decorator(p: &mut Parser) {
+  let m = p.start();
+  p.bump();
+  if p.at(SyntaxKind::Ident) {
+    path(p, &[T![.], T![::]], |_| ());
+  } else {
+    p.error_expected("a decorator name");
+    p.missing();
+  }
+  if p.at(T!['(']) && !p.newline_before() {
+    expressions::arg_list(p);
+  }
+  m.complete(p, SyntaxKind::Decorator);
+}
+
+/// `let mut x: i32 = 1`, `const y = 2`
+fn let_decl(p: &mut Parser) {
+  p.bump();
+  p.eat_contextual_kw(ContextualKeyword::Mut);
+  name(p);
+  if p.at(T![:]) {
+    types::type_annotation(p);
+  }
+  if p.eat(T![=]) {
+    expressions::expr_or_error(p, Restrictions::default());
+  }
+}
+
+/// `type Vocaloid = { name: String }`, `nominal type None = Symbol()`
+fn type_alias(p: &mut Parser) {
+  p.eat_contextual_kw(ContextualKeyword::Nominal);
+  p.expect(T![type]);
+  name(p);
+  if p.at(T![<]) {
+    params::generic_param_list(p);
+  }
+  p.expect(T![=]);
+  types::type_(p);
+}
+
+/// `fn compile(dir: &str) -> bool { ... }`
+fn fn_decl(p: &mut Parser) {
+  p.bump();
+  name(p);
+  if p.at(T![<]) {
+    params::generic_param_list(p);
+  }
+  if p.at(T!['(']) {
+    params::param_list(p);
+  } else {
+    p.error_expected("`(`");
+    p.missing();
+  }
+  if p.at(T![->]) {
+    types::fn_decl_ret_type(p);
+  }
+  if p.at(T!['{']) {
+    block(p);
+  } else {
+    p.error_expected("a function body");
+    p.missing();
+  }
+}
+
+/// `std::path`, `std.str.Chars`, `std::{env, fs as f}`
+fn use_tree(p: &mut Parser) {
+  let m = p.start();
+  if p.at(T!['{']) {
+    use_tree_list(p);
+  } else if p.at(SyntaxKind::Ident) {
+    path(p, &[T![::], T![.]], |_| ());
+    if p.at_any(&[T![::], T![.]]) {
+      p.bump();
+      if p.at(T!['{']) {
+        use_tree_list(p);
+      } else if !p.eat(T![*]) {
+        // `use std::` while typing.
+        p.error_expected("a name");
+        p.missing();
+      }
+    }
+    if p.at(T![as]) {
+      let r = p.start();
+      p.bump();
+      name(p);
+      r.complete(p, SyntaxKind::Rename);
+    }
+  } else {
+    p.error_expected("a path");
+    p.missing();
+  }
+  m.complete(p, SyntaxKind::UseTree);
+}
+
+fn use_tree_list(p: &mut Parser) {
+  let m = p.start();
+  delimited(p, T!['{'], T!['}'], "a path", |p| {
+    if !p.at_any(&[SyntaxKind::Ident, T!['{']]) {
+      return false;
+    }
+    use_tree(p);
+    true
+  });
+  m.complete(p, SyntaxKind::UseTreeList);
+}
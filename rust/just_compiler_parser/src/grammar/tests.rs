@@ -0,0 +1,2245 @@
+use crate::parse;
+use expect_test::{expect, Expect};
+
+fn check(text: &str, expect: Expect) {
+  let parse = parse(text);
+  assert_eq!(parse.syntax().text(), text, "the tree must be lossless");
+  expect.assert_eq(&parse.debug_dump());
+}
+
+#[test]
+fn empty() {
+  check(
+    "",
+    expect![[r#"
+      SourceFile@0..0
+  "#]],
+  );
+}
+
+#[test]
+fn trivia_is_kept() {
+  check(
+    "// leading\nlet x = 1 /* inline */ // trailing\n",
+    expect![[r#"
+        SourceFile@0..46
+          LineComment@0..10 "// leading"
+          Whitespace@10..11 "\n"
+          LetDecl@11..20
+            LetKw@11..14 "let"
+            Whitespace@14..15 " "
+            Name@15..16
+              Ident@15..16 "x"
+            Whitespace@16..17 " "
+            Eq@17..18 "="
+            Whitespace@18..19 " "
+            Literal@19..20
+              IntNumber@19..20 "1"
+          Whitespace@20..21 " "
+          BlockComment@21..33 "/* inline */"
+          Whitespace@33..34 " "
+          LineComment@34..45 "// trailing"
+          Whitespace@45..46 "\n"
+    "#]],
+  );
+}
+
+#[test]
+fn let_decls() {
+  check(
+    "let a: Vec<i32> = [1, 2, 3];\nlet mut f1 = 1_000.123_456\nconst t: bool = true",
+    expect![[r#"
+        SourceFile@0..76
+          LetDecl@0..28
+            LetKw@0..3 "let"
+            Whitespace@3..4 " "
+            Name@4..5
+              Ident@4..5 "a"
+            TypeAnnotation@5..15
+              Colon@5..6 ":"
+              Whitespace@6..7 " "
+              PathType@7..15
+                Path@7..15
+                  PathSegment@7..15
+                    NameRef@7..10
+                      Ident@7..10 "Vec"
+                    GenericArgList@10..15
+                      Lt@10..11 "<"
+                      PathType@11..14
+                        Path@11..14
+                          PathSegment@11..14
+                            NameRef@11..14
+                              Ident@11..14 "i32"
+                      Gt@14..15 ">"
+            Whitespace@15..16 " "
+            Eq@16..17 "="
+            Whitespace@17..18 " "
+            ArrayExpr@18..27
+              OpenBracket@18..19 "["
+              Literal@19..20
+                IntNumber@19..20 "1"
+              Comma@20..21 ","
+              Whitespace@21..22 " "
+              Literal@22..23
+                IntNumber@22..23 "2"
+              Comma@23..24 ","
+              Whitespace@24..25 " "
+              Literal@25..26
+                IntNumber@25..26 "3"
+              CloseBracket@26..27 "]"
+            Semi@27..28 ";"
+          Whitespace@28..29 "\n"
+          LetDecl@29..55
+            LetKw@29..32 "let"
+            Whitespace@32..33 " "
+            MutKw@33..36 "mut"
+            Whitespace@36..37 " "
+            Name@37..39
+              Ident@37..39 "f1"
+            Whitespace@39..40 " "
+            Eq@40..41 "="
+            Whitespace@41..42 " "
+            Literal@42..55
+              FloatNumber@42..55 "1_000.123_456"
+          Whitespace@55..56 "\n"
+          LetDecl@56..76
+            ConstKw@56..61 "const"
+            Whitespace@61..62 " "
+            Name@62..63
+              Ident@62..63 "t"
+            TypeAnnotation@63..69
+              Colon@63..64 ":"
+              Whitespace@64..65 " "
+              PathType@65..69
+                Path@65..69
+                  PathSegment@65..69
+                    NameRef@65..69
+                      Ident@65..69 "bool"
+            Whitespace@69..70 " "
+            Eq@70..71 "="
+            Whitespace@71..72 " "
+            Literal@72..76
+              TrueKw@72..76 "true"
+    "#]],
+  );
+}
+
+#[test]
+fn use_decls() {
+  check(
+    "use std::path;\nuse std.str.Chars\npub use a::{b, c as d, e::*}",
+    expect![[r#"
+        SourceFile@0..61
+          UseDecl@0..14
+            UseKw@0..3 "use"
+            Whitespace@3..4 " "
+            UseTree@4..13
+              Path@4..13
+                PathSegment@4..7
+                  NameRef@4..7
+                    Ident@4..7 "std"
+                ColonColon@7..9 "::"
+                PathSegment@9..13
+                  NameRef@9..13
+                    Ident@9..13 "path"
+            Semi@13..14 ";"
+          Whitespace@14..15 "\n"
+          UseDecl@15..32
+            UseKw@15..18 "use"
+            Whitespace@18..19 " "
+            UseTree@19..32
+              Path@19..32
+                PathSegment@19..22
+                  NameRef@19..22
+                    Ident@19..22 "std"
+                Dot@22..23 "."
+                PathSegment@23..26
+                  NameRef@23..26
+                    Ident@23..26 "str"
+                Dot@26..27 "."
+                PathSegment@27..32
+                  NameRef@27..32
+                    Ident@27..32 "Chars"
+          Whitespace@32..33 "\n"
+          UseDecl@33..61
+            Visibility@33..36
+              PubKw@33..36 "pub"
+            Whitespace@36..37 " "
+            UseKw@37..40 "use"
+            Whitespace@40..41 " "
+            UseTree@41..61
+              Path@41..42
+                PathSegment@41..42
+                  NameRef@41..42
+                    Ident@41..42 "a"
+              ColonColon@42..44 "::"
+              UseTreeList@44..61
+                OpenBrace@44..45 "{"
+                UseTree@45..46
+                  Path@45..46
+                    PathSegment@45..46
+                      NameRef@45..46
+                        Ident@45..46 "b"
+                Comma@46..47 ","
+                Whitespace@47..48 " "
+                UseTree@48..54
+                  Path@48..49
+                    PathSegment@48..49
+                      NameRef@48..49
+                        Ident@48..49 "c"
+                  Whitespace@49..50 " "
+                  Rename@50..54
+                    AsKw@50..52 "as"
+                    Whitespace@52..53 " "
+                    Name@53..54
+                      Ident@53..54 "d"
+                Comma@54..55 ","
+                Whitespace@55..56 " "
+                UseTree@56..60
+                  Path@56..57
+                    PathSegment@56..57
+                      NameRef@56..57
+                        Ident@56..57 "e"
+                  ColonColon@57..59 "::"
+                  Star@59..60 "*"
+                CloseBrace@60..61 "}"
+    "#]],
+  );
+}
+
+#[test]
+fn type_aliases() {
+  check(
+    "pub type Parsed<T> = Vec<&T>\nnominal type Id = u64",
+    expect![[r#"
+        SourceFile@0..50
+          TypeAlias@0..28
+            Visibility@0..3
+              PubKw@0..3 "pub"
+            Whitespace@3..4 " "
+            TypeKw@4..8 "type"
+            Whitespace@8..9 " "
+            Name@9..15
+              Ident@9..15 "Parsed"
+            GenericParamList@15..18
+              Lt@15..16 "<"
+              GenericParam@16..17
+                Name@16..17
+                  Ident@16..17 "T"
+              Gt@17..18 ">"
+            Whitespace@18..19 " "
+            Eq@19..20 "="
+            Whitespace@20..21 " "
+            PathType@21..28
+              Path@21..28
+                PathSegment@21..28
+                  NameRef@21..24
+                    Ident@21..24 "Vec"
+                  GenericArgList@24..28
+                    Lt@24..25 "<"
+                    RefType@25..27
+                      And@25..26 "&"
+                      PathType@26..27
+                        Path@26..27
+                          PathSegment@26..27
+                            NameRef@26..27
+                              Ident@26..27 "T"
+                    Gt@27..28 ">"
+          Whitespace@28..29 "\n"
+          TypeAlias@29..50
+            NominalKw@29..36 "nominal"
+            Whitespace@36..37 " "
+            TypeKw@37..41 "type"
+            Whitespace@41..42 " "
+            Name@42..44
+              Ident@42..44 "Id"
+            Whitespace@44..45 " "
+            Eq@45..46 "="
+            Whitespace@46..47 " "
+            PathType@47..50
+              Path@47..50
+                PathSegment@47..50
+                  NameRef@47..50
+                    Ident@47..50 "u64"
+    "#]],
+  );
+}
+
+#[test]
+fn union_and_intersection_types() {
+  check(
+    "type SourceFile = ParsedSource & { symbols: Vec<Symbol>, path?: &str }\ntype C = int | char & Ch\ntype O<T> =\n  | Some<T>\n  | None",
+    expect![[r#"
+        SourceFile@0..128
+          TypeAlias@0..70
+            TypeKw@0..4 "type"
+            Whitespace@4..5 " "
+            Name@5..15
+              Ident@5..15 "SourceFile"
+            Whitespace@15..16 " "
+            Eq@16..17 "="
+            Whitespace@17..18 " "
+            IntersectionType@18..70
+              PathType@18..30
+                Path@18..30
+                  PathSegment@18..30
+                    NameRef@18..30
+                      Ident@18..30 "ParsedSource"
+              Whitespace@30..31 " "
+              And@31..32 "&"
+              Whitespace@32..33 " "
+              ObjectType@33..70
+                OpenBrace@33..34 "{"
+                Whitespace@34..35 " "
+                ObjectTypeField@35..55
+                  Name@35..42
+                    Ident@35..42 "symbols"
+                  TypeAnnotation@42..55
+                    Colon@42..43 ":"
+                    Whitespace@43..44 " "
+                    PathType@44..55
+                      Path@44..55
+                        PathSegment@44..55
+                          NameRef@44..47
+                            Ident@44..47 "Vec"
+                          GenericArgList@47..55
+                            Lt@47..48 "<"
+                            PathType@48..54
+                              Path@48..54
+                                PathSegment@48..54
+                                  NameRef@48..54
+                                    Ident@48..54 "Symbol"
+                            Gt@54..55 ">"
+                Comma@55..56 ","
+                Whitespace@56..57 " "
+                ObjectTypeField@57..68
+                  Name@57..61
+                    Ident@57..61 "path"
+                  Question@61..62 "?"
+                  TypeAnnotation@62..68
+                    Colon@62..63 ":"
+                    Whitespace@63..64 " "
+                    RefType@64..68
+                      And@64..65 "&"
+                      PathType@65..68
+                        Path@65..68
+                          PathSegment@65..68
+                            NameRef@65..68
+                              Ident@65..68 "str"
+                Whitespace@68..69 " "
+                CloseBrace@69..70 "}"
+          Whitespace@70..71 "\n"
+          TypeAlias@71..95
+            TypeKw@71..75 "type"
+            Whitespace@75..76 " "
+            Name@76..77
+              Ident@76..77 "C"
+            Whitespace@77..78 " "
+            Eq@78..79 "="
+            Whitespace@79..80 " "
+            UnionType@80..95
+              PathType@80..83
+                Path@80..83
+                  PathSegment@80..83
+                    NameRef@80..83
+                      Ident@80..83 "int"
+              Whitespace@83..84 " "
+              Or@84..85 "|"
+              Whitespace@85..86 " "
+              IntersectionType@86..95
+                PathType@86..90
+                  Path@86..90
+                    PathSegment@86..90
+                      NameRef@86..90
+                        Ident@86..90 "char"
+                Whitespace@90..91 " "
+                And@91..92 "&"
+                Whitespace@92..93 " "
+                PathType@93..95
+                  Path@93..95
+                    PathSegment@93..95
+                      NameRef@93..95
+                        Ident@93..95 "Ch"
+          Whitespace@95..96 "\n"
+          TypeAlias@96..128
+            TypeKw@96..100 "type"
+            Whitespace@100..101 " "
+            Name@101..102
+              Ident@101..102 "O"
+            GenericParamList@102..105
+              Lt@102..103 "<"
+              GenericParam@103..104
+                Name@103..104
+                  Ident@103..104 "T"
+              Gt@104..105 ">"
+            Whitespace@105..106 " "
+            Eq@106..107 "="
+            Whitespace@107..110 "\n  "
+            UnionType@110..128
+              Or@110..111 "|"
+              Whitespace@111..112 " "
+              PathType@112..119
+                Path@112..119
+                  PathSegment@112..119
+                    NameRef@112..116
+                      Ident@112..116 "Some"
+                    GenericArgList@116..119
+                      Lt@116..117 "<"
+                      PathType@117..118
+                        Path@117..118
+                          PathSegment@117..118
+                            NameRef@117..118
+                              Ident@117..118 "T"
+                      Gt@118..119 ">"
+              Whitespace@119..122 "\n  "
+              Or@122..123 "|"
+              Whitespace@123..124 " "
+              PathType@124..128
+                Path@124..128
+                  PathSegment@124..128
+                    NameRef@124..128
+                      Ident@124..128 "None"
+    "#]],
+  );
+}
+
+#[test]
+fn tuple_array_and_fn_types() {
+  check(
+    "let a: [i32; 3]\nlet t: [i32, u32, char] | []\nlet f: <T>(entry: T, i: usize) -> bool\nlet p: ((x: u8) -> u8) | [u8]",
+    expect![[r#"
+        SourceFile@0..113
+          LetDecl@0..15
+            LetKw@0..3 "let"
+            Whitespace@3..4 " "
+            Name@4..5
+              Ident@4..5 "a"
+            TypeAnnotation@5..15
+              Colon@5..6 ":"
+              Whitespace@6..7 " "
+              ArrayType@7..15
+                OpenBracket@7..8 "["
+                PathType@8..11
+                  Path@8..11
+                    PathSegment@8..11
+                      NameRef@8..11
+                        Ident@8..11 "i32"
+                Semi@11..12 ";"
+                Whitespace@12..13 " "
+                Literal@13..14
+                  IntNumber@13..14 "3"
+                CloseBracket@14..15 "]"
+          Whitespace@15..16 "\n"
+          LetDecl@16..44
+            LetKw@16..19 "let"
+            Whitespace@19..20 " "
+            Name@20..21
+              Ident@20..21 "t"
+            TypeAnnotation@21..44
+              Colon@21..22 ":"
+              Whitespace@22..23 " "
+              UnionType@23..44
+                TupleType@23..39
+                  OpenBracket@23..24 "["
+                  PathType@24..27
+                    Path@24..27
+                      PathSegment@24..27
+                        NameRef@24..27
+                          Ident@24..27 "i32"
+                  Comma@27..28 ","
+                  Whitespace@28..29 " "
+                  PathType@29..32
+                    Path@29..32
+                      PathSegment@29..32
+                        NameRef@29..32
+                          Ident@29..32 "u32"
+                  Comma@32..33 ","
+                  Whitespace@33..34 " "
+                  PathType@34..38
+                    Path@34..38
+                      PathSegment@34..38
+                        NameRef@34..38
+                          Ident@34..38 "char"
+                  CloseBracket@38..39 "]"
+                Whitespace@39..40 " "
+                Or@40..41 "|"
+                Whitespace@41..42 " "
+                TupleType@42..44
+                  OpenBracket@42..43 "["
+                  CloseBracket@43..44 "]"
+          Whitespace@44..45 "\n"
+          LetDecl@45..83
+            LetKw@45..48 "let"
+            Whitespace@48..49 " "
+            Name@49..50
+              Ident@49..50 "f"
+            TypeAnnotation@50..83
+              Colon@50..51 ":"
+              Whitespace@51..52 " "
+              FnType@52..83
+                GenericParamList@52..55
+                  Lt@52..53 "<"
+                  GenericParam@53..54
+                    Name@53..54
+                      Ident@53..54 "T"
+                  Gt@54..55 ">"
+                ParamList@55..75
+                  OpenParen@55..56 "("
+                  Param@56..64
+                    Name@56..61
+                      Ident@56..61 "entry"
+                    TypeAnnotation@61..64
+                      Colon@61..62 ":"
+                      Whitespace@62..63 " "
+                      PathType@63..64
+                        Path@63..64
+                          PathSegment@63..64
+                            NameRef@63..64
+                              Ident@63..64 "T"
+                  Comma@64..65 ","
+                  Whitespace@65..66 " "
+                  Param@66..74
+                    Name@66..67
+                      Ident@66..67 "i"
+                    TypeAnnotation@67..74
+                      Colon@67..68 ":"
+                      Whitespace@68..69 " "
+                      PathType@69..74
+                        Path@69..74
+                          PathSegment@69..74
+                            NameRef@69..74
+                              Ident@69..74 "usize"
+                  CloseParen@74..75 ")"
+                Whitespace@75..76 " "
+                RetType@76..83
+                  ThinArrow@76..78 "->"
+                  Whitespace@78..79 " "
+                  PathType@79..83
+                    Path@79..83
+                      PathSegment@79..83
+                        NameRef@79..83
+                          Ident@79..83 "bool"
+          Whitespace@83..84 "\n"
+          LetDecl@84..113
+            LetKw@84..87 "let"
+            Whitespace@87..88 " "
+            Name@88..89
+              Ident@88..89 "p"
+            TypeAnnotation@89..113
+              Colon@89..90 ":"
+              Whitespace@90..91 " "
+              UnionType@91..113
+                ParenType@91..106
+                  OpenParen@91..92 "("
+                  FnType@92..105
+                    ParamList@92..99
+                      OpenParen@92..93 "("
+                      Param@93..98
+                        Name@93..94
+                          Ident@93..94 "x"
+                        TypeAnnotation@94..98
+                          Colon@94..95 ":"
+                          Whitespace@95..96 " "
+                          PathType@96..98
+                            Path@96..98
+                              PathSegment@96..98
+                                NameRef@96..98
+                                  Ident@96..98 "u8"
+                      CloseParen@98..99 ")"
+                    Whitespace@99..100 " "
+                    RetType@100..105
+                      ThinArrow@100..102 "->"
+                      Whitespace@102..103 " "
+                      PathType@103..105
+                        Path@103..105
+                          PathSegment@103..105
+                            NameRef@103..105
+                              Ident@103..105 "u8"
+                  CloseParen@105..106 ")"
+                Whitespace@106..107 " "
+                Or@107..108 "|"
+                Whitespace@108..109 " "
+                ArrayType@109..113
+                  OpenBracket@109..110 "["
+                  PathType@110..112
+                    Path@110..112
+                      PathSegment@110..112
+                        NameRef@110..112
+                          Ident@110..112 "u8"
+                  CloseBracket@112..113 "]"
+    "#]],
+  );
+}
+
+#[test]
+fn type_level_types() {
+  check(
+    "type P<T> = { [K in keyof T]?: T[K] }\ntype F<T> = T extends str ? keyof T : T[0]",
+    expect![[r#"
+        SourceFile@0..80
+          TypeAlias@0..37
+            TypeKw@0..4 "type"
+            Whitespace@4..5 " "
+            Name@5..6
+              Ident@5..6 "P"
+            GenericParamList@6..9
+              Lt@6..7 "<"
+              GenericParam@7..8
+                Name@7..8
+                  Ident@7..8 "T"
+              Gt@8..9 ">"
+            Whitespace@9..10 " "
+            Eq@10..11 "="
+            Whitespace@11..12 " "
+            MappedType@12..37
+              OpenBrace@12..13 "{"
+              Whitespace@13..14 " "
+              OpenBracket@14..15 "["
+              GenericParam@15..16
+                Name@15..16
+                  Ident@15..16 "K"
+              Whitespace@16..17 " "
+              InKw@17..19 "in"
+              Whitespace@19..20 " "
+              KeyofType@20..27
+                KeyofKw@20..25 "keyof"
+                Whitespace@25..26 " "
+                PathType@26..27
+                  Path@26..27
+                    PathSegment@26..27
+                      NameRef@26..27
+                        Ident@26..27 "T"
+              CloseBracket@27..28 "]"
+              Question@28..29 "?"
+              TypeAnnotation@29..35
+                Colon@29..30 ":"
+                Whitespace@30..31 " "
+                IndexedAccessType@31..35
+                  PathType@31..32
+                    Path@31..32
+                      PathSegment@31..32
+                        NameRef@31..32
+                          Ident@31..32 "T"
+                  OpenBracket@32..33 "["
+                  PathType@33..34
+                    Path@33..34
+                      PathSegment@33..34
+                        NameRef@33..34
+                          Ident@33..34 "K"
+                  CloseBracket@34..35 "]"
+              Whitespace@35..36 " "
+              CloseBrace@36..37 "}"
+          Whitespace@37..38 "\n"
+          TypeAlias@38..80
+            TypeKw@38..42 "type"
+            Whitespace@42..43 " "
+            Name@43..44
+              Ident@43..44 "F"
+            GenericParamList@44..47
+              Lt@44..45 "<"
+              GenericParam@45..46
+                Name@45..46
+                  Ident@45..46 "T"
+              Gt@46..47 ">"
+            Whitespace@47..48 " "
+            Eq@48..49 "="
+            Whitespace@49..50 " "
+            ConditionalType@50..80
+              PathType@50..51
+                Path@50..51
+                  PathSegment@50..51
+                    NameRef@50..51
+                      Ident@50..51 "T"
+              Whitespace@51..52 " "
+              ExtendsKw@52..59 "extends"
+              Whitespace@59..60 " "
+              PathType@60..63
+                Path@60..63
+                  PathSegment@60..63
+                    NameRef@60..63
+                      Ident@60..63 "str"
+              Whitespace@63..64 " "
+              Question@64..65 "?"
+              Whitespace@65..66 " "
+              KeyofType@66..73
+                KeyofKw@66..71 "keyof"
+                Whitespace@71..72 " "
+                PathType@72..73
+                  Path@72..73
+                    PathSegment@72..73
+                      NameRef@72..73
+                        Ident@72..73 "T"
+              Whitespace@73..74 " "
+              Colon@74..75 ":"
+              Whitespace@75..76 " "
+              IndexedAccessType@76..80
+                PathType@76..77
+                  Path@76..77
+                    PathSegment@76..77
+                      NameRef@76..77
+                        Ident@76..77 "T"
+                OpenBracket@77..78 "["
+                LiteralType@78..79
+                  Literal@78..79
+                    IntNumber@78..79 "0"
+                CloseBracket@79..80 "]"
+    "#]],
+  );
+}
+
+#[test]
+fn keyof_as_a_name() {
+  check(
+    "type K = keyof<i32>\nlet keyof = 1",
+    expect![[r#"
+        SourceFile@0..33
+          TypeAlias@0..19
+            TypeKw@0..4 "type"
+            Whitespace@4..5 " "
+            Name@5..6
+              Ident@5..6 "K"
+            Whitespace@6..7 " "
+            Eq@7..8 "="
+            Whitespace@8..9 " "
+            PathType@9..19
+              Path@9..19
+                PathSegment@9..19
+                  NameRef@9..14
+                    Ident@9..14 "keyof"
+                  GenericArgList@14..19
+                    Lt@14..15 "<"
+                    PathType@15..18
+                      Path@15..18
+                        PathSegment@15..18
+                          NameRef@15..18
+                            Ident@15..18 "i32"
+                    Gt@18..19 ">"
+          Whitespace@19..20 "\n"
+          LetDecl@20..33
+            LetKw@20..23 "let"
+            Whitespace@23..24 " "
+            Name@24..29
+              Ident@24..29 "keyof"
+            Whitespace@29..30 " "
+            Eq@30..31 "="
+            Whitespace@31..32 " "
+            Literal@32..33
+              IntNumber@32..33 "1"
+    "#]],
+  );
+}
+
+#[test]
+fn literal_and_symbol_types() {
+  check(
+    "nominal type Some<T> = Symbol<T>()\nnominal type None = Symbol()\ntype L = true | 1 | 'a' | \"foo\"",
+    expect![[r#"
+        SourceFile@0..95
+          TypeAlias@0..34
+            NominalKw@0..7 "nominal"
+            Whitespace@7..8 " "
+            TypeKw@8..12 "type"
+            Whitespace@12..13 " "
+            Name@13..17
+              Ident@13..17 "Some"
+            GenericParamList@17..20
+              Lt@17..18 "<"
+              GenericParam@18..19
+                Name@18..19
+                  Ident@18..19 "T"
+              Gt@19..20 ">"
+            Whitespace@20..21 " "
+            Eq@21..22 "="
+            Whitespace@22..23 " "
+            SymbolType@23..34
+              SymbolKw@23..29 "Symbol"
+              GenericArgList@29..32
+                Lt@29..30 "<"
+                PathType@30..31
+                  Path@30..31
+                    PathSegment@30..31
+                      NameRef@30..31
+                        Ident@30..31 "T"
+                Gt@31..32 ">"
+              OpenParen@32..33 "("
+              CloseParen@33..34 ")"
+          Whitespace@34..35 "\n"
+          TypeAlias@35..63
+            NominalKw@35..42 "nominal"
+            Whitespace@42..43 " "
+            TypeKw@43..47 "type"
+            Whitespace@47..48 " "
+            Name@48..52
+              Ident@48..52 "None"
+            Whitespace@52..53 " "
+            Eq@53..54 "="
+            Whitespace@54..55 " "
+            SymbolType@55..63
+              SymbolKw@55..61 "Symbol"
+              OpenParen@61..62 "("
+              CloseParen@62..63 ")"
+          Whitespace@63..64 "\n"
+          TypeAlias@64..95
+            TypeKw@64..68 "type"
+            Whitespace@68..69 " "
+            Name@69..70
+              Ident@69..70 "L"
+            Whitespace@70..71 " "
+            Eq@71..72 "="
+            Whitespace@72..73 " "
+            UnionType@73..95
+              LiteralType@73..77
+                Literal@73..77
+                  TrueKw@73..77 "true"
+              Whitespace@77..78 " "
+              Or@78..79 "|"
+              Whitespace@79..80 " "
+              LiteralType@80..81
+                Literal@80..81
+                  IntNumber@80..81 "1"
+              Whitespace@81..82 " "
+              Or@82..83 "|"
+              Whitespace@83..84 " "
+              LiteralType@84..87
+                Literal@84..87
+                  Char@84..87 "'a'"
+              Whitespace@87..88 " "
+              Or@88..89 "|"
+              Whitespace@89..90 " "
+              LiteralType@90..95
+                Literal@90..95
+                  String@90..95 "\"foo\""
+    "#]],
+  );
+}
+
+#[test]
+fn operators_after_types() {
+  check(
+    "let m = x as u8 | mask & 1\nlet f = (ctx: Ctx | x: i32) => x",
+    expect![[r#"
+        SourceFile@0..59
+          LetDecl@0..26
+            LetKw@0..3 "let"
+            Whitespace@3..4 " "
+            Name@4..5
+              Ident@4..5 "m"
+            Whitespace@5..6 " "
+            Eq@6..7 "="
+            Whitespace@7..8 " "
+            BinExpr@8..26
+              CastExpr@8..15
+                PathExpr@8..9
+                  Path@8..9
+                    PathSegment@8..9
+                      NameRef@8..9
+                        Ident@8..9 "x"
+                Whitespace@9..10 " "
+                AsKw@10..12 "as"
+                Whitespace@12..13 " "
+                PathType@13..15
+                  Path@13..15
+                    PathSegment@13..15
+                      NameRef@13..15
+                        Ident@13..15 "u8"
+              Whitespace@15..16 " "
+              Or@16..17 "|"
+              Whitespace@17..18 " "
+              BinExpr@18..26
+                PathExpr@18..22
+                  Path@18..22
+                    PathSegment@18..22
+                      NameRef@18..22
+                        Ident@18..22 "mask"
+                Whitespace@22..23 " "
+                And@23..24 "&"
+                Whitespace@24..25 " "
+                Literal@25..26
+                  IntNumber@25..26 "1"
+          Whitespace@26..27 "\n"
+          LetDecl@27..59
+            LetKw@27..30 "let"
+            Whitespace@30..31 " "
+            Name@31..32
+              Ident@31..32 "f"
+            Whitespace@32..33 " "
+            Eq@33..34 "="
+            Whitespace@34..35 " "
+            ArrowFn@35..59
+              ParamList@35..54
+                OpenParen@35..36 "("
+                Param@36..44
+                  Name@36..39
+                    Ident@36..39 "ctx"
+                  TypeAnnotation@39..44
+                    Colon@39..40 ":"
+                    Whitespace@40..41 " "
+                    PathType@41..44
+                      Path@41..44
+                        PathSegment@41..44
+                          NameRef@41..44
+                            Ident@41..44 "Ctx"
+                Whitespace@44..45 " "
+                Or@45..46 "|"
+                Whitespace@46..47 " "
+                Param@47..53
+                  Name@47..48
+                    Ident@47..48 "x"
+                  TypeAnnotation@48..53
+                    Colon@48..49 ":"
+                    Whitespace@49..50 " "
+                    PathType@50..53
+                      Path@50..53
+                        PathSegment@50..53
+                          NameRef@50..53
+                            Ident@50..53 "i32"
+                CloseParen@53..54 ")"
+              Whitespace@54..55 " "
+              FatArrow@55..57 "=>"
+              Whitespace@57..58 " "
+              PathExpr@58..59
+                Path@58..59
+                  PathSegment@58..59
+                    NameRef@58..59
+                      Ident@58..59 "x"
+    "#]],
+  );
+}
+
+#[test]
+fn fn_decl() {
+  check(
+    "pub fn main() {\n  let args = env::args().collect();\n  compile(&args[1]);\n}",
+    expect![[r#"
+        SourceFile@0..74
+          FnDecl@0..74
+            Visibility@0..3
+              PubKw@0..3 "pub"
+            Whitespace@3..4 " "
+            FnKw@4..6 "fn"
+            Whitespace@6..7 " "
+            Name@7..11
+              Ident@7..11 "main"
+            ParamList@11..13
+              OpenParen@11..12 "("
+              CloseParen@12..13 ")"
+            Whitespace@13..14 " "
+            Block@14..74
+              OpenBrace@14..15 "{"
+              Whitespace@15..18 "\n  "
+              LetDecl@18..51
+                LetKw@18..21 "let"
+                Whitespace@21..22 " "
+                Name@22..26
+                  Ident@22..26 "args"
+                Whitespace@26..27 " "
+                Eq@27..28 "="
+                Whitespace@28..29 " "
+                CallExpr@29..50
+                  FieldExpr@29..48
+                    CallExpr@29..40
+                      PathExpr@29..38
+                        Path@29..38
+                          PathSegment@29..32
+                            NameRef@29..32
+                              Ident@29..32 "env"
+                          ColonColon@32..34 "::"
+                          PathSegment@34..38
+                            NameRef@34..38
+                              Ident@34..38 "args"
+                      ArgList@38..40
+                        OpenParen@38..39 "("
+                        CloseParen@39..40 ")"
+                    Dot@40..41 "."
+                    NameRef@41..48
+                      Ident@41..48 "collect"
+                  ArgList@48..50
+                    OpenParen@48..49 "("
+                    CloseParen@49..50 ")"
+                Semi@50..51 ";"
+              Whitespace@51..54 "\n  "
+              ExprStmt@54..72
+                CallExpr@54..71
+                  PathExpr@54..61
+                    Path@54..61
+                      PathSegment@54..61
+                        NameRef@54..61
+                          Ident@54..61 "compile"
+                  ArgList@61..71
+                    OpenParen@61..62 "("
+                    RefExpr@62..70
+                      And@62..63 "&"
+                      IndexExpr@63..70
+                        PathExpr@63..67
+                          Path@63..67
+                            PathSegment@63..67
+                              NameRef@63..67
+                                Ident@63..67 "args"
+                        OpenBracket@67..68 "["
+                        Literal@68..69
+                          IntNumber@68..69 "1"
+                        CloseBracket@69..70 "]"
+                    CloseParen@70..71 ")"
+                Semi@71..72 ";"
+              Whitespace@72..73 "\n"
+              CloseBrace@73..74 "}"
+    "#]],
+  );
+}
+
+#[test]
+fn arrow_fns() {
+  check(
+    "let inc = (v: i32) -> i32 => v + 1\nlet foo = (x = 1) => {\n  return x\n}",
+    expect![[r#"
+        SourceFile@0..70
+          LetDecl@0..34
+            LetKw@0..3 "let"
+            Whitespace@3..4 " "
+            Name@4..7
+              Ident@4..7 "inc"
+            Whitespace@7..8 " "
+            Eq@8..9 "="
+            Whitespace@9..10 " "
+            ArrowFn@10..34
+              ParamList@10..18
+                OpenParen@10..11 "("
+                Param@11..17
+                  Name@11..12
+                    Ident@11..12 "v"
+                  TypeAnnotation@12..17
+                    Colon@12..13 ":"
+                    Whitespace@13..14 " "
+                    PathType@14..17
+                      Path@14..17
+                        PathSegment@14..17
+                          NameRef@14..17
+                            Ident@14..17 "i32"
+                CloseParen@17..18 ")"
+              Whitespace@18..19 " "
+              RetType@19..25
+                ThinArrow@19..21 "->"
+                Whitespace@21..22 " "
+                PathType@22..25
+                  Path@22..25
+                    PathSegment@22..25
+                      NameRef@22..25
+                        Ident@22..25 "i32"
+              Whitespace@25..26 " "
+              FatArrow@26..28 "=>"
+              Whitespace@28..29 " "
+              BinExpr@29..34
+                PathExpr@29..30
+                  Path@29..30
+                    PathSegment@29..30
+                      NameRef@29..30
+                        Ident@29..30 "v"
+                Whitespace@30..31 " "
+                Plus@31..32 "+"
+                Whitespace@32..33 " "
+                Literal@33..34
+                  IntNumber@33..34 "1"
+          Whitespace@34..35 "\n"
+          LetDecl@35..70
+            LetKw@35..38 "let"
+            Whitespace@38..39 " "
+            Name@39..42
+              Ident@39..42 "foo"
+            Whitespace@42..43 " "
+            Eq@43..44 "="
+            Whitespace@44..45 " "
+            ArrowFn@45..70
+              ParamList@45..52
+                OpenParen@45..46 "("
+                Param@46..51
+                  Name@46..47
+                    Ident@46..47 "x"
+                  Whitespace@47..48 " "
+                  Eq@48..49 "="
+                  Whitespace@49..50 " "
+                  Literal@50..51
+                    IntNumber@50..51 "1"
+                CloseParen@51..52 ")"
+              Whitespace@52..53 " "
+              FatArrow@53..55 "=>"
+              Whitespace@55..56 " "
+              Block@56..70
+                OpenBrace@56..57 "{"
+                Whitespace@57..60 "\n  "
+                ExprStmt@60..68
+                  ReturnExpr@60..68
+                    ReturnKw@60..66 "return"
+                    Whitespace@66..67 " "
+                    PathExpr@67..68
+                      Path@67..68
+                        PathSegment@67..68
+                          NameRef@67..68
+                            Ident@67..68 "x"
+                Whitespace@68..69 "\n"
+                CloseBrace@69..70 "}"
+    "#]],
+  );
+}
+
+#[test]
+fn move_arrow_fns() {
+  check(
+    "move () => name\nmove x => x",
+    expect![[r#"
+        SourceFile@0..27
+          ExprStmt@0..15
+            ArrowFn@0..15
+              MoveKw@0..4 "move"
+              Whitespace@4..5 " "
+              ParamList@5..7
+                OpenParen@5..6 "("
+                CloseParen@6..7 ")"
+              Whitespace@7..8 " "
+              FatArrow@8..10 "=>"
+              Whitespace@10..11 " "
+              PathExpr@11..15
+                Path@11..15
+                  PathSegment@11..15
+                    NameRef@11..15
+                      Ident@11..15 "name"
+          Whitespace@15..16 "\n"
+          ExprStmt@16..27
+            ArrowFn@16..27
+              MoveKw@16..20 "move"
+              Whitespace@20..21 " "
+              ParamList@21..22
+                Param@21..22
+                  Name@21..22
+                    Ident@21..22 "x"
+              Whitespace@22..23 " "
+              FatArrow@23..25 "=>"
+              Whitespace@25..26 " "
+              PathExpr@26..27
+                Path@26..27
+                  PathSegment@26..27
+                    NameRef@26..27
+                      Ident@26..27 "x"
+    "#]],
+  );
+}
+
+#[test]
+fn arrow_fn_context() {
+  check(
+    "({ v }| x) => v + x\n({ v } = { v }| &mut x, y: i32) => v | x",
+    expect![[r#"
+        SourceFile@0..60
+          ExprStmt@0..19
+            ArrowFn@0..19
+              ParamList@0..10
+                OpenParen@0..1 "("
+                Param@1..6
+                  ObjectPat@1..6
+                    OpenBrace@1..2 "{"
+                    Whitespace@2..3 " "
+                    ObjectPatField@3..4
+                      Name@3..4
+                        Ident@3..4 "v"
+                    Whitespace@4..5 " "
+                    CloseBrace@5..6 "}"
+                Or@6..7 "|"
+                Whitespace@7..8 " "
+                Param@8..9
+                  Name@8..9
+                    Ident@8..9 "x"
+                CloseParen@9..10 ")"
+              Whitespace@10..11 " "
+              FatArrow@11..13 "=>"
+              Whitespace@13..14 " "
+              BinExpr@14..19
+                PathExpr@14..15
+                  Path@14..15
+                    PathSegment@14..15
+                      NameRef@14..15
+                        Ident@14..15 "v"
+                Whitespace@15..16 " "
+                Plus@16..17 "+"
+                Whitespace@17..18 " "
+                PathExpr@18..19
+                  Path@18..19
+                    PathSegment@18..19
+                      NameRef@18..19
+                        Ident@18..19 "x"
+          Whitespace@19..20 "\n"
+          ExprStmt@20..60
+            ArrowFn@20..60
+              ParamList@20..51
+                OpenParen@20..21 "("
+                Param@21..34
+                  ObjectPat@21..26
+                    OpenBrace@21..22 "{"
+                    Whitespace@22..23 " "
+                    ObjectPatField@23..24
+                      Name@23..24
+                        Ident@23..24 "v"
+                    Whitespace@24..25 " "
+                    CloseBrace@25..26 "}"
+                  Whitespace@26..27 " "
+                  Eq@27..28 "="
+                  Whitespace@28..29 " "
+                  ObjectLiteral@29..34
+                    OpenBrace@29..30 "{"
+                    Whitespace@30..31 " "
+                    ObjectField@31..32
+                      NameRef@31..32
+                        Ident@31..32 "v"
+                    Whitespace@32..33 " "
+                    CloseBrace@33..34 "}"
+                Or@34..35 "|"
+                Whitespace@35..36 " "
+                Param@36..42
+                  And@36..37 "&"
+                  MutKw@37..40 "mut"
+                  Whitespace@40..41 " "
+                  Name@41..42
+                    Ident@41..42 "x"
+                Comma@42..43 ","
+                Whitespace@43..44 " "
+                Param@44..50
+                  Name@44..45
+                    Ident@44..45 "y"
+                  TypeAnnotation@45..50
+                    Colon@45..46 ":"
+                    Whitespace@46..47 " "
+                    PathType@47..50
+                      Path@47..50
+                        PathSegment@47..50
+                          NameRef@47..50
+                            Ident@47..50 "i32"
+                CloseParen@50..51 ")"
+              Whitespace@51..52 " "
+              FatArrow@52..54 "=>"
+              Whitespace@54..55 " "
+              BinExpr@55..60
+                PathExpr@55..56
+                  Path@55..56
+                    PathSegment@55..56
+                      NameRef@55..56
+                        Ident@55..56 "v"
+                Whitespace@56..57 " "
+                Or@57..58 "|"
+                Whitespace@58..59 " "
+                PathExpr@59..60
+                  Path@59..60
+                    PathSegment@59..60
+                      NameRef@59..60
+                        Ident@59..60 "x"
+    "#]],
+  );
+}
+
+#[test]
+fn arrow_fn_params() {
+  check(
+    "<T>(array: &Array<T>, mut n = 0) -> T => array[n]\n(&mut ctx: Counter) => ctx.count += 1\nxs.map(x => x * 2)\nconst inc = &ctx => ctx.v",
+    expect![[r#"
+        SourceFile@0..132
+          ExprStmt@0..49
+            ArrowFn@0..49
+              GenericParamList@0..3
+                Lt@0..1 "<"
+                GenericParam@1..2
+                  Name@1..2
+                    Ident@1..2 "T"
+                Gt@2..3 ">"
+              ParamList@3..32
+                OpenParen@3..4 "("
+                Param@4..20
+                  Name@4..9
+                    Ident@4..9 "array"
+                  TypeAnnotation@9..20
+                    Colon@9..10 ":"
+                    Whitespace@10..11 " "
+                    RefType@11..20
+                      And@11..12 "&"
+                      PathType@12..20
+                        Path@12..20
+                          PathSegment@12..20
+                            NameRef@12..17
+                              Ident@12..17 "Array"
+                            GenericArgList@17..20
+                              Lt@17..18 "<"
+                              PathType@18..19
+                                Path@18..19
+                                  PathSegment@18..19
+                                    NameRef@18..19
+                                      Ident@18..19 "T"
+                              Gt@19..20 ">"
+                Comma@20..21 ","
+                Whitespace@21..22 " "
+                Param@22..31
+                  MutKw@22..25 "mut"
+                  Whitespace@25..26 " "
+                  Name@26..27
+                    Ident@26..27 "n"
+                  Whitespace@27..28 " "
+                  Eq@28..29 "="
+                  Whitespace@29..30 " "
+                  Literal@30..31
+                    IntNumber@30..31 "0"
+                CloseParen@31..32 ")"
+              Whitespace@32..33 " "
+              RetType@33..37
+                ThinArrow@33..35 "->"
+                Whitespace@35..36 " "
+                PathType@36..37
+                  Path@36..37
+                    PathSegment@36..37
+                      NameRef@36..37
+                        Ident@36..37 "T"
+              Whitespace@37..38 " "
+              FatArrow@38..40 "=>"
+              Whitespace@40..41 " "
+              IndexExpr@41..49
+                PathExpr@41..46
+                  Path@41..46
+                    PathSegment@41..46
+                      NameRef@41..46
+                        Ident@41..46 "array"
+                OpenBracket@46..47 "["
+                PathExpr@47..48
+                  Path@47..48
+                    PathSegment@47..48
+                      NameRef@47..48
+                        Ident@47..48 "n"
+                CloseBracket@48..49 "]"
+          Whitespace@49..50 "\n"
+          ExprStmt@50..87
+            ArrowFn@50..87
+              ParamList@50..69
+                OpenParen@50..51 "("
+                Param@51..68
+                  And@51..52 "&"
+                  MutKw@52..55 "mut"
+                  Whitespace@55..56 " "
+                  Name@56..59
+                    Ident@56..59 "ctx"
+                  TypeAnnotation@59..68
+                    Colon@59..60 ":"
+                    Whitespace@60..61 " "
+                    PathType@61..68
+                      Path@61..68
+                        PathSegment@61..68
+                          NameRef@61..68
+                            Ident@61..68 "Counter"
+                CloseParen@68..69 ")"
+              Whitespace@69..70 " "
+              FatArrow@70..72 "=>"
+              Whitespace@72..73 " "
+              BinExpr@73..87
+                FieldExpr@73..82
+                  PathExpr@73..76
+                    Path@73..76
+                      PathSegment@73..76
+                        NameRef@73..76
+                          Ident@73..76 "ctx"
+                  Dot@76..77 "."
+                  NameRef@77..82
+                    Ident@77..82 "count"
+                Whitespace@82..83 " "
+                PlusEq@83..85 "+="
+                Whitespace@85..86 " "
+                Literal@86..87
+                  IntNumber@86..87 "1"
+          Whitespace@87..88 "\n"
+          ExprStmt@88..106
+            CallExpr@88..106
+              FieldExpr@88..94
+                PathExpr@88..90
+                  Path@88..90
+                    PathSegment@88..90
+                      NameRef@88..90
+                        Ident@88..90 "xs"
+                Dot@90..91 "."
+                NameRef@91..94
+                  Ident@91..94 "map"
+              ArgList@94..106
+                OpenParen@94..95 "("
+                ArrowFn@95..105
+                  ParamList@95..96
+                    Param@95..96
+                      Name@95..96
+                        Ident@95..96 "x"
+                  Whitespace@96..97 " "
+                  FatArrow@97..99 "=>"
+                  Whitespace@99..100 " "
+                  BinExpr@100..105
+                    PathExpr@100..101
+                      Path@100..101
+                        PathSegment@100..101
+                          NameRef@100..101
+                            Ident@100..101 "x"
+                    Whitespace@101..102 " "
+                    Star@102..103 "*"
+                    Whitespace@103..104 " "
+                    Literal@104..105
+                      IntNumber@104..105 "2"
+                CloseParen@105..106 ")"
+          Whitespace@106..107 "\n"
+          LetDecl@107..132
+            ConstKw@107..112 "const"
+            Whitespace@112..113 " "
+            Name@113..116
+              Ident@113..116 "inc"
+            Whitespace@116..117 " "
+            Eq@117..118 "="
+            Whitespace@118..119 " "
+            ArrowFn@119..132
+              ParamList@119..123
+                Param@119..123
+                  And@119..120 "&"
+                  Name@120..123
+                    Ident@120..123 "ctx"
+              Whitespace@123..124 " "
+              FatArrow@124..126 "=>"
+              Whitespace@126..127 " "
+              FieldExpr@127..132
+                PathExpr@127..130
+                  Path@127..130
+                    PathSegment@127..130
+                      NameRef@127..130
+                        Ident@127..130 "ctx"
+                Dot@130..131 "."
+                NameRef@131..132
+                  Ident@131..132 "v"
+    "#]],
+  );
+}
+
+#[test]
+fn match_patterns_are_not_arrow_fns() {
+  check(
+    "match a {\n  x if y => 1,\n  (z) => 2\n}",
+    expect![[r#"
+        SourceFile@0..37
+          ExprStmt@0..37
+            MatchExpr@0..37
+              MatchKw@0..5 "match"
+              Whitespace@5..6 " "
+              PathExpr@6..7
+                Path@6..7
+                  PathSegment@6..7
+                    NameRef@6..7
+                      Ident@6..7 "a"
+              Whitespace@7..8 " "
+              MatchArmList@8..37
+                OpenBrace@8..9 "{"
+                Whitespace@9..12 "\n  "
+                MatchArm@12..24
+                  PathExpr@12..13
+                    Path@12..13
+                      PathSegment@12..13
+                        NameRef@12..13
+                          Ident@12..13 "x"
+                  Whitespace@13..14 " "
+                  MatchGuard@14..18
+                    IfKw@14..16 "if"
+                    Whitespace@16..17 " "
+                    PathExpr@17..18
+                      Path@17..18
+                        PathSegment@17..18
+                          NameRef@17..18
+                            Ident@17..18 "y"
+                  Whitespace@18..19 " "
+                  FatArrow@19..21 "=>"
+                  Whitespace@21..22 " "
+                  Literal@22..23
+                    IntNumber@22..23 "1"
+                  Comma@23..24 ","
+                Whitespace@24..27 "\n  "
+                MatchArm@27..35
+                  ParenExpr@27..30
+                    OpenParen@27..28 "("
+                    PathExpr@28..29
+                      Path@28..29
+                        PathSegment@28..29
+                          NameRef@28..29
+                            Ident@28..29 "z"
+                    CloseParen@29..30 ")"
+                  Whitespace@30..31 " "
+                  FatArrow@31..33 "=>"
+                  Whitespace@33..34 " "
+                  Literal@34..35
+                    IntNumber@34..35 "2"
+                Whitespace@35..36 "\n"
+                CloseBrace@36..37 "}"
+    "#]],
+  );
+}
+
+#[test]
+fn decorators() {
+  check(
+    "@log\n@memo(100)\npub let copy = () => 1",
+    expect![[r#"
+      SourceFile@0..38
+        LetDecl@0..38
+          Decorator@0..4
+            At@0..1 "@"
+            Path@1..4
+              PathSegment@1..4
+                NameRef@1..4
+                  Ident@1..4 "log"
+          Whitespace@4..5 "\n"
+          Decorator@5..15
+            At@5..6 "@"
+            Path@6..10
+              PathSegment@6..10
+                NameRef@6..10
+                  Ident@6..10 "memo"
+            ArgList@10..15
+              OpenParen@10..11 "("
+              Literal@11..14
+                IntNumber@11..14 "100"
+              CloseParen@14..15 ")"
+          Whitespace@15..16 "\n"
+          Visibility@16..19
+            PubKw@16..19 "pub"
+          Whitespace@19..20 " "
+          LetKw@20..23 "let"
+          Whitespace@23..24 " "
+          Name@24..28
+            Ident@24..28 "copy"
+          Whitespace@28..29 " "
+          Eq@29..30 "="
+          Whitespace@30..31 " "
+          ArrowFn@31..38
+            ParamList@31..33
+              OpenParen@31..32 "("
+              CloseParen@32..33 ")"
+            Whitespace@33..34 " "
+            FatArrow@34..36 "=>"
+            Whitespace@36..37 " "
+            Literal@37..38
+              IntNumber@37..38 "1"
+  "#]],
+  );
+}
+
+#[test]
+fn precedence() {
+  check(
+    "a = b + c * -d as i64 == e || f",
+    expect![[r#"
+      SourceFile@0..31
+        ExprStmt@0..31
+          BinExpr@0..31
+            PathExpr@0..1
+              Path@0..1
+                PathSegment@0..1
+                  NameRef@0..1
+                    Ident@0..1 "a"
+            Whitespace@1..2 " "
+            Eq@2..3 "="
+            Whitespace@3..4 " "
+            BinExpr@4..31
+              BinExpr@4..26
+                BinExpr@4..21
+                  PathExpr@4..5
+                    Path@4..5
+                      PathSegment@4..5
+                        NameRef@4..5
+                          Ident@4..5 "b"
+                  Whitespace@5..6 " "
+                  Plus@6..7 "+"
+                  Whitespace@7..8 " "
+                  BinExpr@8..21
+                    PathExpr@8..9
+                      Path@8..9
+                        PathSegment@8..9
+                          NameRef@8..9
+                            Ident@8..9 "c"
+                    Whitespace@9..10 " "
+                    Star@10..11 "*"
+                    Whitespace@11..12 " "
+                    CastExpr@12..21
+                      PrefixExpr@12..14
+                        Minus@12..13 "-"
+                        PathExpr@13..14
+                          Path@13..14
+                            PathSegment@13..14
+                              NameRef@13..14
+                                Ident@13..14 "d"
+                      Whitespace@14..15 " "
+                      AsKw@15..17 "as"
+                      Whitespace@17..18 " "
+                      PathType@18..21
+                        Path@18..21
+                          PathSegment@18..21
+                            NameRef@18..21
+                              Ident@18..21 "i64"
+                Whitespace@21..22 " "
+                EqEq@22..24 "=="
+                Whitespace@24..25 " "
+                PathExpr@25..26
+                  Path@25..26
+                    PathSegment@25..26
+                      NameRef@25..26
+                        Ident@25..26 "e"
+              Whitespace@26..27 " "
+              OrOr@27..29 "||"
+              Whitespace@29..30 " "
+              PathExpr@30..31
+                Path@30..31
+                  PathSegment@30..31
+                    NameRef@30..31
+                      Ident@30..31 "f"
+  "#]],
+  );
+}
+
+#[test]
+fn postfix() {
+  check(
+    "Vec.new<ASTNode>()\nitems[0].len\nf\n(g)",
+    expect![[r#"
+      SourceFile@0..37
+        ExprStmt@0..18
+          CallExpr@0..18
+            FieldExpr@0..7
+              PathExpr@0..3
+                Path@0..3
+                  PathSegment@0..3
+                    NameRef@0..3
+                      Ident@0..3 "Vec"
+              Dot@3..4 "."
+              NameRef@4..7
+                Ident@4..7 "new"
+            GenericArgList@7..16
+              Lt@7..8 "<"
+              PathType@8..15
+                Path@8..15
+                  PathSegment@8..15
+                    NameRef@8..15
+                      Ident@8..15 "ASTNode"
+              Gt@15..16 ">"
+            ArgList@16..18
+              OpenParen@16..17 "("
+              CloseParen@17..18 ")"
+        Whitespace@18..19 "\n"
+        ExprStmt@19..31
+          FieldExpr@19..31
+            IndexExpr@19..27
+              PathExpr@19..24
+                Path@19..24
+                  PathSegment@19..24
+                    NameRef@19..24
+                      Ident@19..24 "items"
+              OpenBracket@24..25 "["
+              Literal@25..26
+                IntNumber@25..26 "0"
+              CloseBracket@26..27 "]"
+            Dot@27..28 "."
+            NameRef@28..31
+              Ident@28..31 "len"
+        Whitespace@31..32 "\n"
+        ExprStmt@32..33
+          PathExpr@32..33
+            Path@32..33
+              PathSegment@32..33
+                NameRef@32..33
+                  Ident@32..33 "f"
+        Whitespace@33..34 "\n"
+        ExprStmt@34..37
+          ParenExpr@34..37
+            OpenParen@34..35 "("
+            PathExpr@35..36
+              Path@35..36
+                PathSegment@35..36
+                  NameRef@35..36
+                    Ident@35..36 "g"
+            CloseParen@36..37 ")"
+  "#]],
+  );
+}
+
+#[test]
+fn injections() {
+  check(
+    "inc{ x: 10 }(2)\nlet p = Point { x }\nobj.f{ ..deps }()",
+    expect![[r#"
+        SourceFile@0..53
+          ExprStmt@0..15
+            CallExpr@0..15
+              InjectExpr@0..12
+                PathExpr@0..3
+                  Path@0..3
+                    PathSegment@0..3
+                      NameRef@0..3
+                        Ident@0..3 "inc"
+                OpenBrace@3..4 "{"
+                Whitespace@4..5 " "
+                ObjectField@5..10
+                  NameRef@5..6
+                    Ident@5..6 "x"
+                  Colon@6..7 ":"
+                  Whitespace@7..8 " "
+                  Literal@8..10
+                    IntNumber@8..10 "10"
+                Whitespace@10..11 " "
+                CloseBrace@11..12 "}"
+              ArgList@12..15
+                OpenParen@12..13 "("
+                Literal@13..14
+                  IntNumber@13..14 "2"
+                CloseParen@14..15 ")"
+          Whitespace@15..16 "\n"
+          LetDecl@16..35
+            LetKw@16..19 "let"
+            Whitespace@19..20 " "
+            Name@20..21
+              Ident@20..21 "p"
+            Whitespace@21..22 " "
+            Eq@22..23 "="
+            Whitespace@23..24 " "
+            ObjectLiteral@24..35
+              Path@24..29
+                PathSegment@24..29
+                  NameRef@24..29
+                    Ident@24..29 "Point"
+              Whitespace@29..30 " "
+              OpenBrace@30..31 "{"
+              Whitespace@31..32 " "
+              ObjectField@32..33
+                NameRef@32..33
+                  Ident@32..33 "x"
+              Whitespace@33..34 " "
+              CloseBrace@34..35 "}"
+          Whitespace@35..36 "\n"
+          ExprStmt@36..53
+            CallExpr@36..53
+              InjectExpr@36..51
+                FieldExpr@36..41
+                  PathExpr@36..39
+                    Path@36..39
+                      PathSegment@36..39
+                        NameRef@36..39
+                          Ident@36..39 "obj"
+                  Dot@39..40 "."
+                  NameRef@40..41
+                    Ident@40..41 "f"
+                OpenBrace@41..42 "{"
+                Whitespace@42..43 " "
+                SpreadExpr@43..49
+                  DotDot@43..45 ".."
+                  PathExpr@45..49
+                    Path@45..49
+                      PathSegment@45..49
+                        NameRef@45..49
+                          Ident@45..49 "deps"
+                Whitespace@49..50 " "
+                CloseBrace@50..51 "}"
+              ArgList@51..53
+                OpenParen@51..52 "("
+                CloseParen@52..53 ")"
+    "#]],
+  );
+}
+
+#[test]
+fn literals() {
+  check(
+    "[true, 'a', \"s\", `t`, `a${b}c${d}e`, ..rest]",
+    expect![[r#"
+        SourceFile@0..44
+          ExprStmt@0..44
+            ArrayExpr@0..44
+              OpenBracket@0..1 "["
+              Literal@1..5
+                TrueKw@1..5 "true"
+              Comma@5..6 ","
+              Whitespace@6..7 " "
+              Literal@7..10
+                Char@7..10 "'a'"
+              Comma@10..11 ","
+              Whitespace@11..12 " "
+              Literal@12..15
+                String@12..15 "\"s\""
+              Comma@15..16 ","
+              Whitespace@16..17 " "
+              TemplateLiteral@17..20
+                TemplateString@17..20 "`t`"
+              Comma@20..21 ","
+              Whitespace@21..22 " "
+              TemplateLiteral@22..35
+                TemplateHead@22..26 "`a${"
+                PathExpr@26..27
+                  Path@26..27
+                    PathSegment@26..27
+                      NameRef@26..27
+                        Ident@26..27 "b"
+                TemplateMiddle@27..31 "}c${"
+                PathExpr@31..32
+                  Path@31..32
+                    PathSegment@31..32
+                      NameRef@31..32
+                        Ident@31..32 "d"
+                TemplateTail@32..35 "}e`"
+              Comma@35..36 ","
+              Whitespace@36..37 " "
+              SpreadExpr@37..43
+                DotDot@37..39 ".."
+                PathExpr@39..43
+                  Path@39..43
+                    PathSegment@39..43
+                      NameRef@39..43
+                        Ident@39..43 "rest"
+              CloseBracket@43..44 "]"
+    "#]],
+  );
+}
+
+#[test]
+fn object_literals() {
+  check(
+    "let a = { name, age: 17 }\nlet b = ParsedSource { ..a, ast: [] }\nlet c = {}",
+    expect![[r#"
+        SourceFile@0..74
+          LetDecl@0..25
+            LetKw@0..3 "let"
+            Whitespace@3..4 " "
+            Name@4..5
+              Ident@4..5 "a"
+            Whitespace@5..6 " "
+            Eq@6..7 "="
+            Whitespace@7..8 " "
+            ObjectLiteral@8..25
+              OpenBrace@8..9 "{"
+              Whitespace@9..10 " "
+              ObjectField@10..14
+                NameRef@10..14
+                  Ident@10..14 "name"
+              Comma@14..15 ","
+              Whitespace@15..16 " "
+              ObjectField@16..23
+                NameRef@16..19
+                  Ident@16..19 "age"
+                Colon@19..20 ":"
+                Whitespace@20..21 " "
+                Literal@21..23
+                  IntNumber@21..23 "17"
+              Whitespace@23..24 " "
+              CloseBrace@24..25 "}"
+          Whitespace@25..26 "\n"
+          LetDecl@26..63
+            LetKw@26..29 "let"
+            Whitespace@29..30 " "
+            Name@30..31
+              Ident@30..31 "b"
+            Whitespace@31..32 " "
+            Eq@32..33 "="
+            Whitespace@33..34 " "
+            ObjectLiteral@34..63
+              Path@34..46
+                PathSegment@34..46
+                  NameRef@34..46
+                    Ident@34..46 "ParsedSource"
+              Whitespace@46..47 " "
+              OpenBrace@47..48 "{"
+              Whitespace@48..49 " "
+              SpreadExpr@49..52
+                DotDot@49..51 ".."
+                PathExpr@51..52
+                  Path@51..52
+                    PathSegment@51..52
+                      NameRef@51..52
+                        Ident@51..52 "a"
+              Comma@52..53 ","
+              Whitespace@53..54 " "
+              ObjectField@54..61
+                NameRef@54..57
+                  Ident@54..57 "ast"
+                Colon@57..58 ":"
+                Whitespace@58..59 " "
+                ArrayExpr@59..61
+                  OpenBracket@59..60 "["
+                  CloseBracket@60..61 "]"
+              Whitespace@61..62 " "
+              CloseBrace@62..63 "}"
+          Whitespace@63..64 "\n"
+          LetDecl@64..74
+            LetKw@64..67 "let"
+            Whitespace@67..68 " "
+            Name@68..69
+              Ident@68..69 "c"
+            Whitespace@69..70 " "
+            Eq@70..71 "="
+            Whitespace@71..72 " "
+            ObjectLiteral@72..74
+              OpenBrace@72..73 "{"
+              CloseBrace@73..74 "}"
+    "#]],
+  );
+}
+
+#[test]
+fn control_flow() {
+  check(
+    "if isEmpty(input) return []\nif (v % 2) { v } else if v { 1 } else { v + 1 }\nwhile a { break }\nfor x in xs { continue }",
+    expect![[r#"
+        SourceFile@0..118
+          ExprStmt@0..27
+            IfExpr@0..27
+              IfKw@0..2 "if"
+              Whitespace@2..3 " "
+              CallExpr@3..17
+                PathExpr@3..10
+                  Path@3..10
+                    PathSegment@3..10
+                      NameRef@3..10
+                        Ident@3..10 "isEmpty"
+                ArgList@10..17
+                  OpenParen@10..11 "("
+                  PathExpr@11..16
+                    Path@11..16
+                      PathSegment@11..16
+                        NameRef@11..16
+                          Ident@11..16 "input"
+                  CloseParen@16..17 ")"
+              Whitespace@17..18 " "
+              ReturnExpr@18..27
+                ReturnKw@18..24 "return"
+                Whitespace@24..25 " "
+                ArrayExpr@25..27
+                  OpenBracket@25..26 "["
+                  CloseBracket@26..27 "]"
+          Whitespace@27..28 "\n"
+          ExprStmt@28..75
+            IfExpr@28..75
+              IfKw@28..30 "if"
+              Whitespace@30..31 " "
+              ParenExpr@31..38
+                OpenParen@31..32 "("
+                BinExpr@32..37
+                  PathExpr@32..33
+                    Path@32..33
+                      PathSegment@32..33
+                        NameRef@32..33
+                          Ident@32..33 "v"
+                  Whitespace@33..34 " "
+                  Percent@34..35 "%"
+                  Whitespace@35..36 " "
+                  Literal@36..37
+                    IntNumber@36..37 "2"
+                CloseParen@37..38 ")"
+              Whitespace@38..39 " "
+              Block@39..44
+                OpenBrace@39..40 "{"
+                Whitespace@40..41 " "
+                ExprStmt@41..42
+                  PathExpr@41..42
+                    Path@41..42
+                      PathSegment@41..42
+                        NameRef@41..42
+                          Ident@41..42 "v"
+                Whitespace@42..43 " "
+                CloseBrace@43..44 "}"
+              Whitespace@44..45 " "
+              ElseKw@45..49 "else"
+              Whitespace@49..50 " "
+              IfExpr@50..75
+                IfKw@50..52 "if"
+                Whitespace@52..53 " "
+                PathExpr@53..54
+                  Path@53..54
+                    PathSegment@53..54
+                      NameRef@53..54
+                        Ident@53..54 "v"
+                Whitespace@54..55 " "
+                Block@55..60
+                  OpenBrace@55..56 "{"
+                  Whitespace@56..57 " "
+                  ExprStmt@57..58
+                    Literal@57..58
+                      IntNumber@57..58 "1"
+                  Whitespace@58..59 " "
+                  CloseBrace@59..60 "}"
+                Whitespace@60..61 " "
+                ElseKw@61..65 "else"
+                Whitespace@65..66 " "
+                Block@66..75
+                  OpenBrace@66..67 "{"
+                  Whitespace@67..68 " "
+                  ExprStmt@68..73
+                    BinExpr@68..73
+                      PathExpr@68..69
+                        Path@68..69
+                          PathSegment@68..69
+                            NameRef@68..69
+                              Ident@68..69 "v"
+                      Whitespace@69..70 " "
+                      Plus@70..71 "+"
+                      Whitespace@71..72 " "
+                      Literal@72..73
+                        IntNumber@72..73 "1"
+                  Whitespace@73..74 " "
+                  CloseBrace@74..75 "}"
+          Whitespace@75..76 "\n"
+          ExprStmt@76..93
+            WhileExpr@76..93
+              WhileKw@76..81 "while"
+              Whitespace@81..82 " "
+              PathExpr@82..83
+                Path@82..83
+                  PathSegment@82..83
+                    NameRef@82..83
+                      Ident@82..83 "a"
+              Whitespace@83..84 " "
+              Block@84..93
+                OpenBrace@84..85 "{"
+                Whitespace@85..86 " "
+                ExprStmt@86..91
+                  BreakExpr@86..91
+                    BreakKw@86..91 "break"
+                Whitespace@91..92 " "
+                CloseBrace@92..93 "}"
+          Whitespace@93..94 "\n"
+          ExprStmt@94..118
+            ForExpr@94..118
+              ForKw@94..97 "for"
+              Whitespace@97..98 " "
+              Name@98..99
+                Ident@98..99 "x"
+              Whitespace@99..100 " "
+              InKw@100..102 "in"
+              Whitespace@102..103 " "
+              PathExpr@103..105
+                Path@103..105
+                  PathSegment@103..105
+                    NameRef@103..105
+                      Ident@103..105 "xs"
+              Whitespace@105..106 " "
+              Block@106..118
+                OpenBrace@106..107 "{"
+                Whitespace@107..108 " "
+                ExprStmt@108..116
+                  ContinueExpr@108..116
+                    ContinueKw@108..116 "continue"
+                Whitespace@116..117 " "
+                CloseBrace@117..118 "}"
+    "#]],
+  );
+}
+
+#[test]
+fn match_expr() {
+  check(
+    "match firstChar {\n  Whitespace.match => 1,\n  x if x > 0 => { 2 }\n  _ => \"unknown\"\n}",
+    expect![[r#"
+        SourceFile@0..83
+          ExprStmt@0..83
+            MatchExpr@0..83
+              MatchKw@0..5 "match"
+              Whitespace@5..6 " "
+              PathExpr@6..15
+                Path@6..15
+                  PathSegment@6..15
+                    NameRef@6..15
+                      Ident@6..15 "firstChar"
+              Whitespace@15..16 " "
+              MatchArmList@16..83
+                OpenBrace@16..17 "{"
+                Whitespace@17..20 "\n  "
+                MatchArm@20..42
+                  FieldExpr@20..36
+                    PathExpr@20..30
+                      Path@20..30
+                        PathSegment@20..30
+                          NameRef@20..30
+                            Ident@20..30 "Whitespace"
+                    Dot@30..31 "."
+                    NameRef@31..36
+                      Ident@31..36 "match"
+                  Whitespace@36..37 " "
+                  FatArrow@37..39 "=>"
+                  Whitespace@39..40 " "
+                  Literal@40..41
+                    IntNumber@40..41 "1"
+                  Comma@41..42 ","
+                Whitespace@42..45 "\n  "
+                MatchArm@45..64
+                  PathExpr@45..46
+                    Path@45..46
+                      PathSegment@45..46
+                        NameRef@45..46
+                          Ident@45..46 "x"
+                  Whitespace@46..47 " "
+                  MatchGuard@47..55
+                    IfKw@47..49 "if"
+                    Whitespace@49..50 " "
+                    BinExpr@50..55
+                      PathExpr@50..51
+                        Path@50..51
+                          PathSegment@50..51
+                            NameRef@50..51
+                              Ident@50..51 "x"
+                      Whitespace@51..52 " "
+                      Gt@52..53 ">"
+                      Whitespace@53..54 " "
+                      Literal@54..55
+                        IntNumber@54..55 "0"
+                  Whitespace@55..56 " "
+                  FatArrow@56..58 "=>"
+                  Whitespace@58..59 " "
+                  Block@59..64
+                    OpenBrace@59..60 "{"
+                    Whitespace@60..61 " "
+                    ExprStmt@61..62
+                      Literal@61..62
+                        IntNumber@61..62 "2"
+                    Whitespace@62..63 " "
+                    CloseBrace@63..64 "}"
+                Whitespace@64..67 "\n  "
+                MatchArm@67..81
+                  PathExpr@67..68
+                    Path@67..68
+                      PathSegment@67..68
+                        NameRef@67..68
+                          Ident@67..68 "_"
+                  Whitespace@68..69 " "
+                  FatArrow@69..71 "=>"
+                  Whitespace@71..72 " "
+                  Literal@72..81
+                    String@72..81 "\"unknown\""
+                Whitespace@81..82 "\n"
+                CloseBrace@82..83 "}"
+    "#]],
+  );
+}
+
+#[test]
+fn contextual_keywords_as_names() {
+  check(
+    "let match = mut.use\nlet nominal = match",
+    expect![[r#"
+      SourceFile@0..39
+        LetDecl@0..19
+          LetKw@0..3 "let"
+          Whitespace@3..4 " "
+          Name@4..9
+            Ident@4..9 "match"
+          Whitespace@9..10 " "
+          Eq@10..11 "="
+          Whitespace@11..12 " "
+          FieldExpr@12..19
+            PathExpr@12..15
+              Path@12..15
+                PathSegment@12..15
+                  NameRef@12..15
+                    Ident@12..15 "mut"
+            Dot@15..16 "."
+            NameRef@16..19
+              Ident@16..19 "use"
+        Whitespace@19..20 "\n"
+        LetDecl@20..39
+          LetKw@20..23 "let"
+          Whitespace@23..24 " "
+          Name@24..31
+            Ident@24..31 "nominal"
+          Whitespace@31..32 " "
+          Eq@32..33 "="
+          Whitespace@33..34 " "
+          PathExpr@34..39
+            Path@34..39
+              PathSegment@34..39
+                NameRef@34..39
+                  Ident@34..39 "match"
+  "#]],
+  );
+}
+
+#[test]
+fn missing_separator() {
+  check(
+    "let a = 1 let b = 2",
+    expect![[r#"
+      SourceFile@0..19
+        LetDecl@0..9
+          LetKw@0..3 "let"
+          Whitespace@3..4 " "
+          Name@4..5
+            Ident@4..5 "a"
+          Whitespace@5..6 " "
+          Eq@6..7 "="
+          Whitespace@7..8 " "
+          Literal@8..9
+            IntNumber@8..9 "1"
+        Whitespace@9..10 " "
+        LetDecl@10..19
+          LetKw@10..13 "let"
+          Whitespace@13..14 " "
+          Name@14..15
+            Ident@14..15 "b"
+          Whitespace@15..16 " "
+          Eq@16..17 "="
+          Whitespace@17..18 " "
+          Literal@18..19
+            IntNumber@18..19 "2"
+      error[P0003]@10..13: expected `;` or a line break after the statement
+  "#]],
+  );
+}
+
+#[test]
+fn unexpected_tokens() {
+  check(
+    "let = ; ) pub",
+    expect![[r#"
+        SourceFile@0..13
+          LetDecl@0..7
+            LetKw@0..3 "let"
+            Missing@3..3
+            Whitespace@3..4 " "
+            Eq@4..5 "="
+            Missing@5..5
+            Whitespace@5..6 " "
+            Semi@6..7 ";"
+          Whitespace@7..8 " "
+          Error@8..9
+            CloseParen@8..9 ")"
+          Whitespace@9..10 " "
+          Error@10..13
+            Visibility@10..13
+              PubKw@10..13 "pub"
+            Missing@13..13
+        error[P0001]@4..5: expected a name, found `=`
+        error[P0001]@6..7: expected an expression, found `;`
+        error[P0002]@8..9: expected a statement, found `)`
+        error[P0001]@13..13: expected a declaration, found end of file
+    "#]],
+  );
+}
+
+#[test]
+fn lexer_errors_are_reported() {
+  check(
+    "let s = \"abc",
+    expect![[r#"
+      SourceFile@0..12
+        LetDecl@0..12
+          LetKw@0..3 "let"
+          Whitespace@3..4 " "
+          Name@4..5
+            Ident@4..5 "s"
+          Whitespace@5..6 " "
+          Eq@6..7 "="
+          Whitespace@7..8 " "
+          Literal@8..12
+            String@8..12 "\"abc"
+      error[L0003]@8..12: unterminated string
+  "#]],
+  );
+}
+
+#[test]
+fn long_lookahead() {
+  // Looks for the `)` closing the parameters of an arrow function.
+  let text = format!("({}", "a, ".repeat(20_000));
+  let parse = parse(&text);
+  assert_eq!(parse.syntax().text(), text);
+}
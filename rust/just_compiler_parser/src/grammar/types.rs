@@ -0,0 +1,305 @@
+//! Type expressions.
+//!
+//! `|` binds looser than `&`, e.g. `A & B | C` is a union of `A & B` and `C`,
+//! and the return type of a function type extends as far as possible, e.g. `() -> A | B` returns `A | B`.
+//! A conditional type binds looser than `|`, e.g. `T extends A | B ? X : Y` tests `T` against `A | B`,
+//! and `keyof` and indexing bind tighter than `&`, e.g. `keyof T[K]` is the keys of `T[K]`.
+
+use super::expressions::{self, at_literal, literal, Restrictions};
+use super::params;
+use super::{delimited, name, nth_after_closing, path};
+use crate::parser::{CompletedMarker, Parser};
+use crate::syntax_kind::SyntaxKind;
+use crate::T;
+use justc_lexer::keyword::ContextualKeyword;
+
+/// `: i32`
+pub(super) fn type_annotation(p: &mut Parser) {
+  let m = p.start();
+  p.expect(T![:]);
+  type_(p);
+  m.complete(p, SyntaxKind::TypeAnnotation);
+}
+
+/// `: Ctx` of a context parameter, which ends at `|`, e.g. in `(ctx: Ctx | x: i32)`.
+pub(super) fn context_type_annotation(p: &mut Parser) {
+  let m = p.start();
+  p.expect(T![:]);
+  intersection_type(p);
+  m.complete(p, SyntaxKind::TypeAnnotation);
+}
+
+/// `-> i32`
+pub(super) fn ret_type(p: &mut Parser) {
+  let m = p.start();
+  p.expect(T![->]);
+  type_(p);
+  m.complete(p, SyntaxKind::RetType);
+}
+
+/// `-> i32` of a function declaration, where a `{` right after `->` is the body,
+/// so an object type must be in parentheses, e.g. `fn f() -> ({ x: i32 }) { ... }`.
+pub(super) fn fn_decl_ret_type(p: &mut Parser) {
+  let m = p.start();
+  p.expect(T![->]);
+  if p.at(T!['{']) {
+    p.error_expected("a type");
+    p.missing();
+  } else {
+    type_(p);
+  }
+  m.complete(p, SyntaxKind::RetType);
+}
+
+/// The type of `x as i32`, where `|` and `&` are binary operators, e.g. in `x as u8 | mask`.
+pub(super) fn cast_type(p: &mut Parser) {
+  primary_type(p);
+}
+
+/// `int | char`, `| Some<T>\n| None`, `T extends str ? true : false`
+pub(super) fn type_(p: &mut Parser) {
+  let lhs = match union_type(p) {
+    Some(lhs) => lhs,
+    None => return,
+  };
+  if p.at_contextual_kw(ContextualKeyword::Extends) {
+    conditional_type(p, lhs);
+  }
+}
+
+fn union_type(p: &mut Parser) -> Option<CompletedMarker> {
+  let m = p.start();
+  let leading = p.eat(T![|]);
+  let first = intersection_type(p);
+  if !leading && !p.at(T![|]) {
+    m.abandon(p);
+    return first;
+  }
+  while p.eat(T![|]) {
+    intersection_type(p);
+  }
+  Some(m.complete(p, SyntaxKind::UnionType))
+}
+
+/// `T extends str ? true : false`, where the type after `extends` can't be conditional without parentheses.
+fn conditional_type(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
+  let m = lhs.precede(p);
+  p.bump_remap(T![extends]);
+  union_type(p);
+  p.expect(T![?]);
+  type_(p);
+  p.expect(T![:]);
+  type_(p);
+  m.complete(p, SyntaxKind::ConditionalType)
+}
+
+/// `ParsedSource & { symbols: Vec<Symbol> }`
+///
+/// The `&` must be on the line of the previous type, a line starting with `&` is a reference expression.
+fn intersection_type(p: &mut Parser) -> Option<CompletedMarker> {
+  let lhs = postfix_type(p)?;
+  if !at_intersection(p) {
+    return Some(lhs);
+  }
+  let m = lhs.precede(p);
+  while at_intersection(p) {
+    p.bump();
+    postfix_type(p);
+  }
+  Some(m.complete(p, SyntaxKind::IntersectionType))
+}
+
+fn at_intersection(p: &Parser) -> bool {
+  p.at(T![&]) && !p.newline_before()
+}
+
+/// `T["name"]`, `keyof T`
+///
+/// The `[` must be on the line of the indexed type, a line starting with `[` is an array expression.
+fn postfix_type(p: &mut Parser) -> Option<CompletedMarker> {
+  if p.at_contextual_kw(ContextualKeyword::Keyof) && at_keyof_operand(p) {
+    let m = p.start();
+    p.bump_remap(T![keyof]);
+    postfix_type(p);
+    return Some(m.complete(p, SyntaxKind::KeyofType));
+  }
+  let mut lhs = primary_type(p)?;
+  while p.at(T!['[']) && !p.newline_before() {
+    let m = lhs.precede(p);
+    p.bump();
+    type_(p);
+    p.expect(T![']']);
+    lhs = m.complete(p, SyntaxKind::IndexedAccessType);
+  }
+  Some(lhs)
+}
+
+/// True after `keyof` when it is an operator rather than the name of a type.
+fn at_keyof_operand(p: &Parser) -> bool {
+  p.nth_at_any(1, &[SyntaxKind::Ident, T!['{'], T!['['], T!['(']])
+}
+
+fn primary_type(p: &mut Parser) -> Option<CompletedMarker> {
+  let done = match p.current() {
+    T![&] => {
+      let m = p.start();
+      p.bump();
+      p.eat_contextual_kw(ContextualKeyword::Mut);
+      primary_type(p);
+      m.complete(p, SyntaxKind::RefType)
+    }
+    _ if at_literal(p) => {
+      let m = p.start();
+      literal(p);
+      m.complete(p, SyntaxKind::LiteralType)
+    }
+    SyntaxKind::Ident
+      if p.at_contextual_kw(ContextualKeyword::Symbol) && p.nth_at_any(1, &[T!['('], T![<]]) =>
+    {
+      symbol_type(p)
+    }
+    SyntaxKind::Ident => {
+      let m = p.start();
+      path(p, &[T![::], T![.]], |p| {
+        if p.at(T![<]) {
+          generic_arg_list(p);
+        }
+      });
+      m.complete(p, SyntaxKind::PathType)
+    }
+    T!['{'] => object_type(p),
+    T!['['] => array_or_tuple_type(p),
+    T!['('] if nth_after_closing(p, 0) == T![->] => fn_type(p),
+    T![<] => fn_type(p),
+    T!['('] => {
+      let m = p.start();
+      p.bump();
+      type_(p);
+      p.expect(T![')']);
+      m.complete(p, SyntaxKind::ParenType)
+    }
+    _ => {
+      p.error_expected("a type");
+      p.missing();
+      return None;
+    }
+  };
+  Some(done)
+}
+
+/// `{ name: String, age?: u8 }`, `{ [K in keyof T]?: T[K] }`
+fn object_type(p: &mut Parser) -> CompletedMarker {
+  if p.nth(1) == T!['['] && p.nth(2) == SyntaxKind::Ident && p.nth(3) == T![in] {
+    return mapped_type(p);
+  }
+  let m = p.start();
+  delimited(p, T!['{'], T!['}'], "a field", |p| {
+    if !p.at(SyntaxKind::Ident) {
+      return false;
+    }
+    let m = p.start();
+    name(p);
+    p.eat(T![?]);
+    type_annotation(p);
+    m.complete(p, SyntaxKind::ObjectTypeField);
+    true
+  });
+  m.complete(p, SyntaxKind::ObjectType)
+}
+
+/// `{ [K in keyof T]?: T[K] }`, an object type with a field for each key of a union of string literals.
+fn mapped_type(p: &mut Parser) -> CompletedMarker {
+  let m = p.start();
+  p.bump();
+  p.bump();
+  let param = p.start();
+  name(p);
+  param.complete(p, SyntaxKind::GenericParam);
+  p.bump();
+  type_(p);
+  p.expect(T![']']);
+  p.eat(T![?]);
+  type_annotation(p);
+  p.eat(T![,]);
+  p.expect(T!['}']);
+  m.complete(p, SyntaxKind::MappedType)
+}
+
+/// `[i32; 3]`, `[u8]`, `[i32, u32, char]`, `[]`
+fn array_or_tuple_type(p: &mut Parser) -> CompletedMarker {
+  let m = p.start();
+  p.bump();
+  if p.eat(T![']']) {
+    return m.complete(p, SyntaxKind::TupleType);
+  }
+  type_(p);
+  if p.eat(T![;]) {
+    expressions::expr_or_error(p, Restrictions::default());
+    p.expect(T![']']);
+    return m.complete(p, SyntaxKind::ArrayType);
+  }
+  if p.eat(T![']']) {
+    return m.complete(p, SyntaxKind::ArrayType);
+  }
+  while p.eat(T![,]) && !p.at(T![']']) {
+    if !at_type_start(p) {
+      break;
+    }
+    type_(p);
+  }
+  p.expect(T![']']);
+  m.complete(p, SyntaxKind::TupleType)
+}
+
+/// `(entry: T) -> bool`, `<T>(x: T) -> T`
+fn fn_type(p: &mut Parser) -> CompletedMarker {
+  let m = p.start();
+  if p.at(T![<]) {
+    params::generic_param_list(p);
+  }
+  if p.at(T!['(']) {
+    params::param_list(p);
+  } else {
+    p.error_expected("`(`");
+    p.missing();
+  }
+  ret_type(p);
+  m.complete(p, SyntaxKind::FnType)
+}
+
+/// `Symbol()`, `Symbol<T>()`
+fn symbol_type(p: &mut Parser) -> CompletedMarker {
+  let m = p.start();
+  p.bump_remap(T![Symbol]);
+  if p.at(T![<]) {
+    generic_arg_list(p);
+  }
+  p.expect(T!['(']);
+  p.expect(T![')']);
+  m.complete(p, SyntaxKind::SymbolType)
+}
+
+/// `<i32, &str>`
+pub(super) fn generic_arg_list(p: &mut Parser) {
+  let m = p.start();
+  delimited(p, T![<], T![>], "a type", |p| {
+    if !at_type_start(p) {
+      return false;
+    }
+    type_(p);
+    true
+  });
+  m.complete(p, SyntaxKind::GenericArgList);
+}
+
+pub(super) fn at_type_start(p: &Parser) -> bool {
+  p.at_any(&[
+    T![&],
+    T![|],
+    T!['{'],
+    T!['['],
+    T!['('],
+    T![<],
+    SyntaxKind::Ident,
+  ]) || at_literal(p)
+}
@@ -0,0 +1,256 @@
+//! Green tree: immutable, position independent syntax tree.
+//!
+//! A green node only knows its kind, its children and the length of its text,
+//! so identical subtrees can be shared and edits only rebuild the path to the root.
+//! Navigation with parents and offsets is provided by `syntax_node`, built on top of it.
+
+use super::syntax_kind::SyntaxKind;
+use std::fmt;
+use std::sync::Arc;
+
+/// Either a node or a token, in the green or in the syntax tree.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NodeOrToken<N, T> {
+  Node(N),
+  Token(T),
+}
+
+impl<N, T> NodeOrToken<N, T> {
+  pub fn into_node(self) -> Option<N> {
+    match self {
+      NodeOrToken::Node(node) => Some(node),
+      NodeOrToken::Token(_) => None,
+    }
+  }
+
+  pub fn into_token(self) -> Option<T> {
+    match self {
+      NodeOrToken::Node(_) => None,
+      NodeOrToken::Token(token) => Some(token),
+    }
+  }
+
+  pub fn as_node(&self) -> Option<&N> {
+    match self {
+      NodeOrToken::Node(node) => Some(node),
+      NodeOrToken::Token(_) => None,
+    }
+  }
+
+  pub fn as_token(&self) -> Option<&T> {
+    match self {
+      NodeOrToken::Node(_) => None,
+      NodeOrToken::Token(token) => Some(token),
+    }
+  }
+}
+
+pub type GreenElement = NodeOrToken<GreenNode, GreenToken>;
+
+impl GreenElement {
+  pub fn kind(&self) -> SyntaxKind {
+    match self {
+      NodeOrToken::Node(node) => node.kind(),
+      NodeOrToken::Token(token) => token.kind(),
+    }
+  }
+
+  pub fn text_len(&self) -> usize {
+    match self {
+      NodeOrToken::Node(node) => node.text_len(),
+      NodeOrToken::Token(token) => token.text_len(),
+    }
+  }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct GreenToken(Arc<GreenTokenData>);
+
+#[derive(PartialEq, Eq, Hash)]
+struct GreenTokenData {
+  kind: SyntaxKind,
+  text: String,
+}
+
+impl GreenToken {
+  pub fn new(kind: SyntaxKind, text: &str) -> GreenToken {
+    GreenToken(Arc::new(GreenTokenData {
+      kind,
+      text: text.to_string(),
+    }))
+  }
+
+  pub fn kind(&self) -> SyntaxKind {
+    self.0.kind
+  }
+
+  pub fn text(&self) -> &str {
+    &self.0.text
+  }
+
+  pub fn text_len(&self) -> usize {
+    self.0.text.len()
+  }
+}
+
+impl fmt::Debug for GreenToken {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{:?} {:?}", self.kind(), self.text())
+  }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct GreenNode(Arc<GreenNodeData>);
+
+#[derive(PartialEq, Eq, Hash)]
+struct GreenNodeData {
+  kind: SyntaxKind,
+  text_len: usize,
+  children: Vec<GreenElement>,
+}
+
+impl GreenNode {
+  pub fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> GreenNode {
+    let text_len = children.iter().map(GreenElement::text_len).sum();
+    GreenNode(Arc::new(GreenNodeData {
+      kind,
+      text_len,
+      children,
+    }))
+  }
+
+  pub fn kind(&self) -> SyntaxKind {
+    self.0.kind
+  }
+
+  pub fn text_len(&self) -> usize {
+    self.0.text_len
+  }
+
+  pub fn children(&self) -> &[GreenElement] {
+    &self.0.children
+  }
+
+  /// True if both nodes are the same allocation, not only equal trees.
+  pub fn ptr_eq(&self, other: &GreenNode) -> bool {
+    Arc::ptr_eq(&self.0, &other.0)
+  }
+
+  /// Copy of the node with the child at `index` replaced by `child`.
+  pub fn replace_child(&self, index: usize, child: GreenElement) -> GreenNode {
+    let mut children = self.children().to_vec();
+    children[index] = child;
+    GreenNode::new(self.kind(), children)
+  }
+
+  fn write_text(&self, out: &mut String) {
+    for child in self.children() {
+      match child {
+        NodeOrToken::Node(node) => node.write_text(out),
+        NodeOrToken::Token(token) => out.push_str(token.text()),
+      }
+    }
+  }
+}
+
+impl fmt::Display for GreenNode {
+  /// The source code of the node.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mut text = String::with_capacity(self.text_len());
+    self.write_text(&mut text);
+    f.write_str(&text)
+  }
+}
+
+impl fmt::Debug for GreenNode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("GreenNode")
+      .field("kind", &self.kind())
+      .field("text_len", &self.text_len())
+      .field("children", &self.children())
+      .finish()
+  }
+}
+
+/// Builds a green tree top-down, e.g. from parser events.
+#[derive(Debug, Default)]
+pub struct GreenNodeBuilder {
+  /// Kinds of the open nodes, with the index of their first child in `children`.
+  parents: Vec<(SyntaxKind, usize)>,
+  children: Vec<GreenElement>,
+}
+
+impl GreenNodeBuilder {
+  pub fn new() -> GreenNodeBuilder {
+    GreenNodeBuilder::default()
+  }
+
+  /// Starts a node, the following tokens and nodes are its children until `finish_node`.
+  pub fn start_node(&mut self, kind: SyntaxKind) {
+    self.parents.push((kind, self.children.len()));
+  }
+
+  pub fn finish_node(&mut self) {
+    let (kind, first_child) = self.parents.pop().expect("no node to finish");
+    let children = self.children.split_off(first_child);
+    self
+      .children
+      .push(NodeOrToken::Node(GreenNode::new(kind, children)));
+  }
+
+  pub fn token(&mut self, kind: SyntaxKind, text: &str) {
+    self
+      .children
+      .push(NodeOrToken::Token(GreenToken::new(kind, text)));
+  }
+
+  /// Number of nodes started and not finished yet.
+  pub fn depth(&self) -> usize {
+    self.parents.len()
+  }
+
+  /// Returns the root node, all nodes must be finished.
+  pub fn finish(mut self) -> GreenNode {
+    assert!(self.parents.is_empty(), "unfinished nodes");
+    assert_eq!(self.children.len(), 1, "the tree must have a single root");
+    match self.children.pop() {
+      Some(NodeOrToken::Node(node)) => node,
+      _ => panic!("the root of the tree must be a node"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn build_tree() {
+    let mut builder = GreenNodeBuilder::new();
+    builder.start_node(SyntaxKind::SourceFile);
+    builder.start_node(SyntaxKind::PathExpr);
+    builder.token(SyntaxKind::Ident, "abc");
+    builder.finish_node();
+    builder.token(SyntaxKind::Whitespace, "\n");
+    builder.finish_node();
+    let root = builder.finish();
+
+    assert_eq!(root.kind(), SyntaxKind::SourceFile);
+    assert_eq!(root.text_len(), 4);
+    assert_eq!(root.to_string(), "abc\n");
+    assert_eq!(root.children().len(), 2);
+    assert_eq!(root.children()[0].kind(), SyntaxKind::PathExpr);
+
+    let replaced = root.replace_child(
+      1,
+      NodeOrToken::Token(GreenToken::new(SyntaxKind::Semi, ";")),
+    );
+    assert_eq!(replaced.to_string(), "abc;");
+    assert_eq!(root.to_string(), "abc\n");
+    // Unchanged children are shared.
+    assert!(replaced.children()[0]
+      .as_node()
+      .unwrap()
+      .ptr_eq(root.children()[0].as_node().unwrap()));
+  }
+}
@@ -0,0 +1,80 @@
+//! Just compiler parser.
+//!
+//! Parses the tokens of `justc_lexer` into a lossless syntax tree:
+//! every byte of the source code, whitespace and comments included,
+//! is in exactly one token of the tree, so the source code can be printed back from it.
+//! The parser never fails, invalid code is reported as diagnostics and still produces a tree.
+
+pub mod ast;
+pub mod codes;
+mod grammar;
+pub mod green;
+mod parser;
+pub mod syntax_kind;
+pub mod syntax_node;
+mod tree_builder;
+
+pub use ast::AstNode;
+pub use green::{GreenNode, NodeOrToken};
+pub use syntax_kind::SyntaxKind;
+pub use syntax_node::{SyntaxElement, SyntaxNode, SyntaxToken};
+
+use justc_lexer::diagnose::diagnose_tokens;
+use justc_lexer::diagnostic::Diagnostic;
+use justc_lexer::token_stream::{tokenize_with_spans, SpannedToken};
+use parser::{Input, Parser};
+
+/// Result of parsing a source file: the syntax tree and the lexer and parser diagnostics.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Parse {
+  green: GreenNode,
+  errors: Vec<Diagnostic>,
+}
+
+impl Parse {
+  pub fn green(&self) -> &GreenNode {
+    &self.green
+  }
+
+  /// Root of the syntax tree, a `SourceFile` node.
+  pub fn syntax(&self) -> SyntaxNode {
+    SyntaxNode::new_root(self.green.clone())
+  }
+
+  /// Typed root of the syntax tree.
+  pub fn tree(&self) -> ast::SourceFile {
+    ast::SourceFile::cast(self.syntax()).expect("the root is a source file")
+  }
+
+  /// Diagnostics ordered by position.
+  pub fn errors(&self) -> &[Diagnostic] {
+    &self.errors
+  }
+
+  /// Dump of the tree followed by the diagnostics, for tests and debugging.
+  pub fn debug_dump(&self) -> String {
+    let mut out = self.syntax().debug_dump();
+    for error in &self.errors {
+      out.push_str(&format!(
+        "{}[{}]@{:?}: {}\n",
+        error.severity, error.code, error.span, error.message
+      ));
+    }
+    out
+  }
+}
+
+pub fn parse(text: &str) -> Parse {
+  let tokens: Vec<SpannedToken> = tokenize_with_spans(text).with_keywords().collect();
+  let input = Input::new(text, &tokens);
+  let mut p = Parser::new(&input);
+  grammar::source_file(&mut p);
+  let (events, parse_errors) = p.finish();
+  let events = parser::resolve_forward_parents(events);
+  let green = tree_builder::build_tree(text, &tokens, events);
+
+  let mut errors = diagnose_tokens(text, tokens.iter().copied());
+  errors.extend(parse_errors);
+  errors.sort_by_key(|error| error.span.start);
+  Parse { green, errors }
+}
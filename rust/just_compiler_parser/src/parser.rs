@@ -0,0 +1,385 @@
+//! Parser engine.
+//!
+//! The grammar only sees significant tokens (no trivia) and produces a flat list of events
+//! (start node, token, finish node), `tree_builder` turns them into a green tree
+//! with the trivia put back in.
+
+use super::codes;
+use super::syntax_kind::SyntaxKind;
+use justc_lexer::diagnostic::{Code, Diagnostic};
+use justc_lexer::keyword::ContextualKeyword;
+use justc_lexer::span::Span;
+use justc_lexer::token_stream::SpannedToken;
+use std::cell::Cell;
+
+/// Significant tokens of the source code.
+pub(crate) struct Input<'t> {
+  text: &'t str,
+  kinds: Vec<SyntaxKind>,
+  spans: Vec<Span>,
+  /// Whether there is a line break between a token and the previous one.
+  newline_before: Vec<bool>,
+}
+
+impl<'t> Input<'t> {
+  pub(crate) fn new(text: &'t str, tokens: &[SpannedToken]) -> Input<'t> {
+    let mut input = Input {
+      text,
+      kinds: Vec::new(),
+      spans: Vec::new(),
+      newline_before: Vec::new(),
+    };
+    let mut newline = false;
+    for token in tokens {
+      let kind = SyntaxKind::from_token(token.kind);
+      if kind.is_trivia() {
+        newline |= token.text(text).contains('\n');
+        continue;
+      }
+      input.kinds.push(kind);
+      input.spans.push(token.span);
+      input.newline_before.push(newline);
+      newline = false;
+    }
+    input
+  }
+}
+
+pub(crate) enum Event {
+  /// Starts a node, `forward_parent` is the distance to the `Start` of a node
+  /// that must wrap this one, see `CompletedMarker::precede`.
+  Start {
+    kind: SyntaxKind,
+    forward_parent: Option<usize>,
+  },
+  Finish,
+  /// Consumes the next significant token, with the given kind.
+  Token {
+    kind: SyntaxKind,
+  },
+}
+
+impl Event {
+  fn tombstone() -> Event {
+    Event::Start {
+      kind: SyntaxKind::Tombstone,
+      forward_parent: None,
+    }
+  }
+}
+
+/// Number of lookups without consuming a token after which the parser is considered stuck.
+const STEP_LIMIT: u32 = 10_000;
+
+pub(crate) struct Parser<'t> {
+  input: &'t Input<'t>,
+  pos: usize,
+  events: Vec<Event>,
+  errors: Vec<Diagnostic>,
+  steps: Cell<u32>,
+}
+
+impl<'t> Parser<'t> {
+  pub(crate) fn new(input: &'t Input<'t>) -> Parser<'t> {
+    Parser {
+      input,
+      pos: 0,
+      events: Vec::new(),
+      errors: Vec::new(),
+      steps: Cell::new(0),
+    }
+  }
+
+  pub(crate) fn finish(self) -> (Vec<Event>, Vec<Diagnostic>) {
+    (self.events, self.errors)
+  }
+
+  /// Kind of the token `n` tokens ahead, `Eof` past the end.
+  pub(crate) fn nth(&self, n: usize) -> SyntaxKind {
+    let steps = self.steps.get();
+    assert!(steps < STEP_LIMIT, "the parser seems stuck");
+    self.steps.set(steps + 1);
+    self
+      .input
+      .kinds
+      .get(self.pos + n)
+      .copied()
+      .unwrap_or(SyntaxKind::Eof)
+  }
+
+  /// Like `nth`, for lookahead scans that may go arbitrarily far without consuming tokens.
+  pub(crate) fn peek(&self, n: usize) -> SyntaxKind {
+    self
+      .input
+      .kinds
+      .get(self.pos + n)
+      .copied()
+      .unwrap_or(SyntaxKind::Eof)
+  }
+
+  pub(crate) fn current(&self) -> SyntaxKind {
+    self.nth(0)
+  }
+
+  pub(crate) fn at(&self, kind: SyntaxKind) -> bool {
+    self.nth_at(0, kind)
+  }
+
+  pub(crate) fn nth_at(&self, n: usize, kind: SyntaxKind) -> bool {
+    self.nth(n) == kind
+  }
+
+  pub(crate) fn at_any(&self, kinds: &[SyntaxKind]) -> bool {
+    kinds.contains(&self.current())
+  }
+
+  pub(crate) fn nth_at_any(&self, n: usize, kinds: &[SyntaxKind]) -> bool {
+    kinds.contains(&self.nth(n))
+  }
+
+  pub(crate) fn at_eof(&self) -> bool {
+    self.at(SyntaxKind::Eof)
+  }
+
+  /// True if the token `n` tokens ahead is the identifier `keyword`.
+  pub(crate) fn nth_at_contextual_kw(&self, n: usize, keyword: ContextualKeyword) -> bool {
+    self.nth_at(n, SyntaxKind::Ident) && self.nth_text(n) == keyword.as_str()
+  }
+
+  pub(crate) fn at_contextual_kw(&self, keyword: ContextualKeyword) -> bool {
+    self.nth_at_contextual_kw(0, keyword)
+  }
+
+  /// Source code of the token `n` tokens ahead, empty past the end.
+  pub(crate) fn nth_text(&self, n: usize) -> &'t str {
+    match self.input.spans.get(self.pos + n) {
+      Some(span) => &self.input.text[span.range()],
+      None => "",
+    }
+  }
+
+  /// True if the token `n` tokens ahead is on a different line than the token before it.
+  pub(crate) fn nth_newline_before(&self, n: usize) -> bool {
+    self
+      .input
+      .newline_before
+      .get(self.pos + n)
+      .copied()
+      .unwrap_or(true)
+  }
+
+  pub(crate) fn newline_before(&self) -> bool {
+    self.nth_newline_before(0)
+  }
+
+  /// Kind of the last consumed token.
+  pub(crate) fn prev(&self) -> SyntaxKind {
+    match self.pos.checked_sub(1) {
+      Some(pos) => self.input.kinds[pos],
+      None => SyntaxKind::Eof,
+    }
+  }
+
+  /// Span of the current token, or an empty span at the end of the text.
+  pub(crate) fn current_span(&self) -> Span {
+    match self.input.spans.get(self.pos) {
+      Some(span) => *span,
+      None => Span::empty(self.input.text.len()),
+    }
+  }
+
+  pub(crate) fn start(&mut self) -> Marker {
+    let pos = self.events.len();
+    self.events.push(Event::tombstone());
+    Marker { pos }
+  }
+
+  /// Consumes the current token.
+  pub(crate) fn bump(&mut self) {
+    let kind = self.current();
+    self.bump_remap(kind);
+  }
+
+  /// Consumes the current token as a token of `kind`, e.g. an identifier as a contextual keyword.
+  pub(crate) fn bump_remap(&mut self, kind: SyntaxKind) {
+    if self.at_eof() {
+      return;
+    }
+    self.pos += 1;
+    self.steps.set(0);
+    self.events.push(Event::Token { kind });
+  }
+
+  /// Consumes the current token if it is of `kind`.
+  pub(crate) fn eat(&mut self, kind: SyntaxKind) -> bool {
+    if !self.at(kind) {
+      return false;
+    }
+    self.bump();
+    true
+  }
+
+  /// Consumes the current token if it is the identifier `keyword`, as the keyword.
+  pub(crate) fn eat_contextual_kw(&mut self, keyword: ContextualKeyword) -> bool {
+    if !self.at_contextual_kw(keyword) {
+      return false;
+    }
+    self.bump_remap(SyntaxKind::from_contextual_keyword(keyword));
+    true
+  }
+
+  /// Consumes a token of `kind`, reports an error if the current token is of another kind.
+  pub(crate) fn expect(&mut self, kind: SyntaxKind) -> bool {
+    if self.eat(kind) {
+      return true;
+    }
+    self.error_expected(&describe(kind));
+    false
+  }
+
+  /// Reports "expected `what`, found ..." at the current token.
+  pub(crate) fn error_expected(&mut self, what: &str) {
+    let found = match self.current() {
+      SyntaxKind::Eof => "end of file".to_string(),
+      _ => format!("`{}`", self.nth_text(0)),
+    };
+    let message = format!("expected {}, found {}", what, found);
+    self.error(codes::EXPECTED, message);
+  }
+
+  /// Reports an error at the current token.
+  pub(crate) fn error(&mut self, code: Code, message: impl Into<String>) {
+    let span = self.current_span();
+    self.error_at(code, message, span);
+  }
+
+  /// Only the first error at a position is kept, the following ones are usually caused by it,
+  /// e.g. the missing `)` after the missing argument in `f(a,`.
+  pub(crate) fn error_at(&mut self, code: Code, message: impl Into<String>, span: Span) {
+    if let Some(last) = self.errors.last() {
+      if last.span.start == span.start {
+        return;
+      }
+    }
+    self.errors.push(Diagnostic::error(code, message, span));
+  }
+
+  /// Consumes the current token in an `Error` node.
+  pub(crate) fn bump_error(&mut self) {
+    let m = self.start();
+    self.bump();
+    m.complete(self, SyntaxKind::Error);
+  }
+
+  /// Consumes tokens in an `Error` node until one of `recovery` or a line break,
+  /// always consuming the current token.
+  pub(crate) fn bump_error_until(&mut self, recovery: &[SyntaxKind]) {
+    let m = self.start();
+    self.bump();
+    while !self.at_eof() && !self.newline_before() && !self.at_any(recovery) {
+      self.bump();
+    }
+    m.complete(self, SyntaxKind::Error);
+  }
+
+  /// Adds an empty `Missing` node, in place of a node that should be at the current position.
+  pub(crate) fn missing(&mut self) {
+    let m = self.start();
+    m.complete(self, SyntaxKind::Missing);
+  }
+}
+
+/// How a token kind is named in error messages.
+pub(crate) fn describe(kind: SyntaxKind) -> String {
+  match kind.fixed_text() {
+    Some(text) => format!("`{}`", text),
+    None => match kind {
+      SyntaxKind::Ident => "an identifier".to_string(),
+      SyntaxKind::Eof => "end of file".to_string(),
+      _ if kind.is_literal() => "a literal".to_string(),
+      _ => format!("{:?}", kind),
+    },
+  }
+}
+
+/// Start of a node that is not finished yet.
+#[must_use]
+pub(crate) struct Marker {
+  pos: usize,
+}
+
+impl Marker {
+  /// Finishes the node, all events since `start` are its children.
+  pub(crate) fn complete(self, p: &mut Parser, kind: SyntaxKind) -> CompletedMarker {
+    match &mut p.events[self.pos] {
+      Event::Start { kind: slot, .. } => *slot = kind,
+      _ => unreachable!(),
+    }
+    p.events.push(Event::Finish);
+    CompletedMarker { pos: self.pos }
+  }
+
+  /// Drops the node, its children are attached to its parent.
+  pub(crate) fn abandon(self, p: &mut Parser) {
+    if self.pos == p.events.len() - 1 {
+      p.events.pop();
+    }
+  }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct CompletedMarker {
+  pos: usize,
+}
+
+impl CompletedMarker {
+  /// Starts a node that wraps this one, e.g. the binary expression of which it is the left operand.
+  pub(crate) fn precede(self, p: &mut Parser) -> Marker {
+    let m = p.start();
+    match &mut p.events[self.pos] {
+      Event::Start { forward_parent, .. } => *forward_parent = Some(m.pos - self.pos),
+      _ => unreachable!(),
+    }
+    m
+  }
+}
+
+/// Events with forward parents resolved, see `Event::Start`.
+pub(crate) fn resolve_forward_parents(mut events: Vec<Event>) -> Vec<Event> {
+  let mut resolved = Vec::with_capacity(events.len());
+  for i in 0..events.len() {
+    match std::mem::replace(&mut events[i], Event::tombstone()) {
+      Event::Start {
+        kind,
+        forward_parent,
+      } => {
+        let mut kinds = vec![kind];
+        let mut idx = i;
+        let mut forward_parent = forward_parent;
+        while let Some(distance) = forward_parent {
+          idx += distance;
+          forward_parent = match std::mem::replace(&mut events[idx], Event::tombstone()) {
+            Event::Start {
+              kind,
+              forward_parent,
+            } => {
+              kinds.push(kind);
+              forward_parent
+            }
+            _ => unreachable!(),
+          };
+        }
+        for kind in kinds.into_iter().rev() {
+          if kind != SyntaxKind::Tombstone {
+            resolved.push(Event::Start {
+              kind,
+              forward_parent: None,
+            });
+          }
+        }
+      }
+      event => resolved.push(event),
+    }
+  }
+  resolved
+}
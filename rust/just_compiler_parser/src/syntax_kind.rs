@@ -0,0 +1,512 @@
+//! Kinds of the tokens and nodes of the syntax tree.
+
+use justc_lexer::keyword::{ContextualKeyword, Keyword};
+use justc_lexer::tokenize::{LiteralKind, TokenKind};
+
+/// Kind of a token or a node of the syntax tree.
+///
+/// Token kinds mirror `justc_lexer::tokenize::TokenKind`, without the details kept by the lexer
+/// (e.g. whether a string is terminated), which are reported as diagnostics instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u16)]
+pub enum SyntaxKind {
+  // Trivia:
+  Whitespace,
+  LineComment,
+  BlockComment,
+
+  Ident,
+  /// Token the lexer did not expect, e.g. "№".
+  Unknown,
+
+  // Literals:
+  IntNumber,
+  FloatNumber,
+  Char,
+  Byte,
+  String,
+  ByteString,
+  RawString,
+  RawByteString,
+  /// "`hello`", template string without substitutions.
+  TemplateString,
+  TemplateHead,
+  TemplateMiddle,
+  TemplateTail,
+
+  // Punctuation:
+  FatArrow,
+  ThinArrow,
+  DotDot,
+  DotDotDot,
+  ColonColon,
+  EqEq,
+  NotEq,
+  LtEq,
+  GtEq,
+  AndAnd,
+  OrOr,
+  PlusEq,
+  MinusEq,
+  StarEq,
+  SlashEq,
+  PercentEq,
+  Semi,
+  Comma,
+  Dot,
+  OpenParen,
+  CloseParen,
+  OpenBrace,
+  CloseBrace,
+  OpenBracket,
+  CloseBracket,
+  At,
+  Pound,
+  Tilde,
+  Question,
+  Colon,
+  Dollar,
+  Eq,
+  Bang,
+  Lt,
+  Gt,
+  Minus,
+  And,
+  Or,
+  Plus,
+  Star,
+  Slash,
+  Caret,
+  Percent,
+
+  // Keywords:
+  AsKw,
+  AsyncKw,
+  AwaitKw,
+  BreakKw,
+  ConstKw,
+  ContinueKw,
+  ElseKw,
+  ExportKw,
+  FnKw,
+  ForKw,
+  IfKw,
+  ImportKw,
+  InKw,
+  LetKw,
+  LiteralKw,
+  LoopKw,
+  MoveKw,
+  NsKw,
+  PubKw,
+  ReturnKw,
+  SelfKw,
+  StaticKw,
+  TypeKw,
+  UnsafeKw,
+  WhileKw,
+  YieldKw,
+
+  // Contextual keywords, identifiers turned into keywords by the parser:
+  MutKw,
+  UseKw,
+  ModKw,
+  MatchKw,
+  NominalKw,
+  TrueKw,
+  FalseKw,
+  SymbolKw,
+  KeyofKw,
+  ExtendsKw,
+
+  // Nodes:
+  SourceFile,
+  /// Tokens the parser could not make sense of.
+  Error,
+  /// Empty node where a required one is absent, e.g. the initializer of `let x =`.
+  Missing,
+
+  Decorator,
+  Visibility,
+  LetDecl,
+  TypeAlias,
+  UseDecl,
+  UseTree,
+  UseTreeList,
+  Rename,
+  ModDecl,
+  FnDecl,
+
+  GenericParamList,
+  GenericParam,
+  GenericArgList,
+  ParamList,
+  Param,
+  ObjectPat,
+  ObjectPatField,
+  RetType,
+  TypeAnnotation,
+
+  Block,
+  ExprStmt,
+
+  Literal,
+  PathExpr,
+  ParenExpr,
+  ArrayExpr,
+  SpreadExpr,
+  ObjectLiteral,
+  ObjectField,
+  TemplateLiteral,
+  ArrowFn,
+  InjectExpr,
+  CallExpr,
+  ArgList,
+  FieldExpr,
+  IndexExpr,
+  PrefixExpr,
+  RefExpr,
+  BinExpr,
+  CastExpr,
+  IfExpr,
+  MatchExpr,
+  MatchArmList,
+  MatchArm,
+  MatchGuard,
+  ReturnExpr,
+  BreakExpr,
+  ContinueExpr,
+  WhileExpr,
+  LoopExpr,
+  ForExpr,
+
+  PathType,
+  RefType,
+  ParenType,
+  UnionType,
+  IntersectionType,
+  ObjectType,
+  ObjectTypeField,
+  TupleType,
+  ArrayType,
+  FnType,
+  LiteralType,
+  SymbolType,
+  KeyofType,
+  IndexedAccessType,
+  MappedType,
+  ConditionalType,
+
+  Path,
+  PathSegment,
+  Name,
+  NameRef,
+
+  /// End of the input, only seen by the parser.
+  Eof,
+  /// Placeholder of an abandoned node, only seen by the parser.
+  Tombstone,
+}
+
+impl SyntaxKind {
+  /// Whitespace and comments.
+  pub fn is_trivia(self) -> bool {
+    matches!(
+      self,
+      SyntaxKind::Whitespace | SyntaxKind::LineComment | SyntaxKind::BlockComment
+    )
+  }
+
+  /// Reserved and contextual keywords.
+  pub fn is_keyword(self) -> bool {
+    (SyntaxKind::AsKw..=SyntaxKind::ExtendsKw).contains(&self)
+  }
+
+  pub fn is_literal(self) -> bool {
+    (SyntaxKind::IntNumber..=SyntaxKind::TemplateTail).contains(&self)
+  }
+
+  pub fn is_punct(self) -> bool {
+    (SyntaxKind::FatArrow..=SyntaxKind::Percent).contains(&self)
+  }
+
+  /// Kinds of nodes, as opposed to tokens.
+  pub fn is_node(self) -> bool {
+    (SyntaxKind::SourceFile..=SyntaxKind::NameRef).contains(&self)
+  }
+
+  pub fn from_token(kind: TokenKind) -> SyntaxKind {
+    match kind {
+      TokenKind::LineComment { .. } => SyntaxKind::LineComment,
+      TokenKind::BlockComment { .. } => SyntaxKind::BlockComment,
+      TokenKind::Whitespace => SyntaxKind::Whitespace,
+      TokenKind::Identifier | TokenKind::RawIdent => SyntaxKind::Ident,
+      TokenKind::Keyword(keyword) => SyntaxKind::from_keyword(keyword),
+      TokenKind::Literal { kind, .. } => match kind {
+        LiteralKind::Int { .. } => SyntaxKind::IntNumber,
+        LiteralKind::Float { .. } => SyntaxKind::FloatNumber,
+        LiteralKind::Char { .. } => SyntaxKind::Char,
+        LiteralKind::Byte { .. } => SyntaxKind::Byte,
+        LiteralKind::Str { .. } => SyntaxKind::String,
+        LiteralKind::ByteStr { .. } => SyntaxKind::ByteString,
+        LiteralKind::RawStr { .. } => SyntaxKind::RawString,
+        LiteralKind::RawByteStr { .. } => SyntaxKind::RawByteString,
+        LiteralKind::Template { .. } => SyntaxKind::TemplateString,
+        LiteralKind::TemplateHead => SyntaxKind::TemplateHead,
+        LiteralKind::TemplateMiddle => SyntaxKind::TemplateMiddle,
+        LiteralKind::TemplateTail { .. } => SyntaxKind::TemplateTail,
+      },
+      TokenKind::Lifetime { .. } | TokenKind::Unknown => SyntaxKind::Unknown,
+      TokenKind::FatArrow => SyntaxKind::FatArrow,
+      TokenKind::ThinArrow => SyntaxKind::ThinArrow,
+      TokenKind::DotDot => SyntaxKind::DotDot,
+      TokenKind::DotDotDot => SyntaxKind::DotDotDot,
+      TokenKind::ColonColon => SyntaxKind::ColonColon,
+      TokenKind::EqEq => SyntaxKind::EqEq,
+      TokenKind::NotEq => SyntaxKind::NotEq,
+      TokenKind::LtEq => SyntaxKind::LtEq,
+      TokenKind::GtEq => SyntaxKind::GtEq,
+      TokenKind::AndAnd => SyntaxKind::AndAnd,
+      TokenKind::OrOr => SyntaxKind::OrOr,
+      TokenKind::PlusEq => SyntaxKind::PlusEq,
+      TokenKind::MinusEq => SyntaxKind::MinusEq,
+      TokenKind::StarEq => SyntaxKind::StarEq,
+      TokenKind::SlashEq => SyntaxKind::SlashEq,
+      TokenKind::PercentEq => SyntaxKind::PercentEq,
+      TokenKind::Semi => SyntaxKind::Semi,
+      TokenKind::Comma => SyntaxKind::Comma,
+      TokenKind::Dot => SyntaxKind::Dot,
+      TokenKind::OpenParen => SyntaxKind::OpenParen,
+      TokenKind::CloseParen => SyntaxKind::CloseParen,
+      TokenKind::OpenBrace => SyntaxKind::OpenBrace,
+      TokenKind::CloseBrace => SyntaxKind::CloseBrace,
+      TokenKind::OpenBracket => SyntaxKind::OpenBracket,
+      TokenKind::CloseBracket => SyntaxKind::CloseBracket,
+      TokenKind::At => SyntaxKind::At,
+      TokenKind::Pound => SyntaxKind::Pound,
+      TokenKind::Tilde => SyntaxKind::Tilde,
+      TokenKind::Question => SyntaxKind::Question,
+      TokenKind::Colon => SyntaxKind::Colon,
+      TokenKind::Dollar => SyntaxKind::Dollar,
+      TokenKind::Eq => SyntaxKind::Eq,
+      TokenKind::Bang => SyntaxKind::Bang,
+      TokenKind::Lt => SyntaxKind::Lt,
+      TokenKind::Gt => SyntaxKind::Gt,
+      TokenKind::Minus => SyntaxKind::Minus,
+      TokenKind::And => SyntaxKind::And,
+      TokenKind::Or => SyntaxKind::Or,
+      TokenKind::Plus => SyntaxKind::Plus,
+      TokenKind::Star => SyntaxKind::Star,
+      TokenKind::Slash => SyntaxKind::Slash,
+      TokenKind::Caret => SyntaxKind::Caret,
+      TokenKind::Percent => SyntaxKind::Percent,
+    }
+  }
+
+  pub fn from_keyword(keyword: Keyword) -> SyntaxKind {
+    match keyword {
+      Keyword::As => SyntaxKind::AsKw,
+      Keyword::Async => SyntaxKind::AsyncKw,
+      Keyword::Await => SyntaxKind::AwaitKw,
+      Keyword::Break => SyntaxKind::BreakKw,
+      Keyword::Const => SyntaxKind::ConstKw,
+      Keyword::Continue => SyntaxKind::ContinueKw,
+      Keyword::Else => SyntaxKind::ElseKw,
+      Keyword::Export => SyntaxKind::ExportKw,
+      Keyword::Fn => SyntaxKind::FnKw,
+      Keyword::For => SyntaxKind::ForKw,
+      Keyword::If => SyntaxKind::IfKw,
+      Keyword::Import => SyntaxKind::ImportKw,
+      Keyword::In => SyntaxKind::InKw,
+      Keyword::Let => SyntaxKind::LetKw,
+      Keyword::Literal => SyntaxKind::LiteralKw,
+      Keyword::Loop => SyntaxKind::LoopKw,
+      Keyword::Move => SyntaxKind::MoveKw,
+      Keyword::Ns => SyntaxKind::NsKw,
+      Keyword::Pub => SyntaxKind::PubKw,
+      Keyword::Return => SyntaxKind::ReturnKw,
+      Keyword::SelfValue => SyntaxKind::SelfKw,
+      Keyword::Static => SyntaxKind::StaticKw,
+      Keyword::Type => SyntaxKind::TypeKw,
+      Keyword::Unsafe => SyntaxKind::UnsafeKw,
+      Keyword::While => SyntaxKind::WhileKw,
+      Keyword::Yield => SyntaxKind::YieldKw,
+    }
+  }
+
+  pub fn from_contextual_keyword(keyword: ContextualKeyword) -> SyntaxKind {
+    match keyword {
+      ContextualKeyword::Mut => SyntaxKind::MutKw,
+      ContextualKeyword::Use => SyntaxKind::UseKw,
+      ContextualKeyword::Mod => SyntaxKind::ModKw,
+      ContextualKeyword::Match => SyntaxKind::MatchKw,
+      ContextualKeyword::Nominal => SyntaxKind::NominalKw,
+      ContextualKeyword::True => SyntaxKind::TrueKw,
+      ContextualKeyword::False => SyntaxKind::FalseKw,
+      ContextualKeyword::Symbol => SyntaxKind::SymbolKw,
+      ContextualKeyword::Keyof => SyntaxKind::KeyofKw,
+      ContextualKeyword::Extends => SyntaxKind::ExtendsKw,
+    }
+  }
+
+  /// Text of the tokens that are always spelled the same, i.e. punctuation and keywords.
+  pub fn fixed_text(self) -> Option<&'static str> {
+    let text = match self {
+      SyntaxKind::FatArrow => "=>",
+      SyntaxKind::ThinArrow => "->",
+      SyntaxKind::DotDot => "..",
+      SyntaxKind::DotDotDot => "...",
+      SyntaxKind::ColonColon => "::",
+      SyntaxKind::EqEq => "==",
+      SyntaxKind::NotEq => "!=",
+      SyntaxKind::LtEq => "<=",
+      SyntaxKind::GtEq => ">=",
+      SyntaxKind::AndAnd => "&&",
+      SyntaxKind::OrOr => "||",
+      SyntaxKind::PlusEq => "+=",
+      SyntaxKind::MinusEq => "-=",
+      SyntaxKind::StarEq => "*=",
+      SyntaxKind::SlashEq => "/=",
+      SyntaxKind::PercentEq => "%=",
+      SyntaxKind::Semi => ";",
+      SyntaxKind::Comma => ",",
+      SyntaxKind::Dot => ".",
+      SyntaxKind::OpenParen => "(",
+      SyntaxKind::CloseParen => ")",
+      SyntaxKind::OpenBrace => "{",
+      SyntaxKind::CloseBrace => "}",
+      SyntaxKind::OpenBracket => "[",
+      SyntaxKind::CloseBracket => "]",
+      SyntaxKind::At => "@",
+      SyntaxKind::Pound => "#",
+      SyntaxKind::Tilde => "~",
+      SyntaxKind::Question => "?",
+      SyntaxKind::Colon => ":",
+      SyntaxKind::Dollar => "$",
+      SyntaxKind::Eq => "=",
+      SyntaxKind::Bang => "!",
+      SyntaxKind::Lt => "<",
+      SyntaxKind::Gt => ">",
+      SyntaxKind::Minus => "-",
+      SyntaxKind::And => "&",
+      SyntaxKind::Or => "|",
+      SyntaxKind::Plus => "+",
+      SyntaxKind::Star => "*",
+      SyntaxKind::Slash => "/",
+      SyntaxKind::Caret => "^",
+      SyntaxKind::Percent => "%",
+      SyntaxKind::MutKw => "mut",
+      SyntaxKind::UseKw => "use",
+      SyntaxKind::ModKw => "mod",
+      SyntaxKind::MatchKw => "match",
+      SyntaxKind::NominalKw => "nominal",
+      SyntaxKind::TrueKw => "true",
+      SyntaxKind::FalseKw => "false",
+      SyntaxKind::SymbolKw => "Symbol",
+      SyntaxKind::KeyofKw => "keyof",
+      SyntaxKind::ExtendsKw => "extends",
+      _ if self.is_keyword() => Keyword::ALL[self as usize - SyntaxKind::AsKw as usize].as_str(),
+      _ => return None,
+    };
+    Some(text)
+  }
+}
+
+/// Kind of a punctuation or keyword token from its text, e.g. `T![;]` or `T![let]`.
+#[macro_export]
+macro_rules! T {
+  [=>] => { $crate::SyntaxKind::FatArrow };
+  [->] => { $crate::SyntaxKind::ThinArrow };
+  [..] => { $crate::SyntaxKind::DotDot };
+  [::] => { $crate::SyntaxKind::ColonColon };
+  [==] => { $crate::SyntaxKind::EqEq };
+  [!=] => { $crate::SyntaxKind::NotEq };
+  [<=] => { $crate::SyntaxKind::LtEq };
+  [>=] => { $crate::SyntaxKind::GtEq };
+  [&&] => { $crate::SyntaxKind::AndAnd };
+  [||] => { $crate::SyntaxKind::OrOr };
+  [;] => { $crate::SyntaxKind::Semi };
+  [,] => { $crate::SyntaxKind::Comma };
+  [.] => { $crate::SyntaxKind::Dot };
+  ['('] => { $crate::SyntaxKind::OpenParen };
+  [')'] => { $crate::SyntaxKind::CloseParen };
+  ['{'] => { $crate::SyntaxKind::OpenBrace };
+  ['}'] => { $crate::SyntaxKind::CloseBrace };
+  ['['] => { $crate::SyntaxKind::OpenBracket };
+  [']'] => { $crate::SyntaxKind::CloseBracket };
+  [@] => { $crate::SyntaxKind::At };
+  [?] => { $crate::SyntaxKind::Question };
+  [:] => { $crate::SyntaxKind::Colon };
+  [=] => { $crate::SyntaxKind::Eq };
+  [!] => { $crate::SyntaxKind::Bang };
+  [<] => { $crate::SyntaxKind::Lt };
+  [>] => { $crate::SyntaxKind::Gt };
+  [-] => { $crate::SyntaxKind::Minus };
+  [&] => { $crate::SyntaxKind::And };
+  [|] => { $crate::SyntaxKind::Or };
+  [+] => { $crate::SyntaxKind::Plus };
+  [*] => { $crate::SyntaxKind::Star };
+  [/] => { $crate::SyntaxKind::Slash };
+  [^] => { $crate::SyntaxKind::Caret };
+  [%] => { $crate::SyntaxKind::Percent };
+  [as] => { $crate::SyntaxKind::AsKw };
+  [break] => { $crate::SyntaxKind::BreakKw };
+  [const] => { $crate::SyntaxKind::ConstKw };
+  [continue] => { $crate::SyntaxKind::ContinueKw };
+  [else] => { $crate::SyntaxKind::ElseKw };
+  [fn] => { $crate::SyntaxKind::FnKw };
+  [for] => { $crate::SyntaxKind::ForKw };
+  [if] => { $crate::SyntaxKind::IfKw };
+  [in] => { $crate::SyntaxKind::InKw };
+  [let] => { $crate::SyntaxKind::LetKw };
+  [literal] => { $crate::SyntaxKind::LiteralKw };
+  [loop] => { $crate::SyntaxKind::LoopKw };
+  [pub] => { $crate::SyntaxKind::PubKw };
+  [return] => { $crate::SyntaxKind::ReturnKw };
+  [self] => { $crate::SyntaxKind::SelfKw };
+  [type] => { $crate::SyntaxKind::TypeKw };
+  [while] => { $crate::SyntaxKind::WhileKw };
+  [mut] => { $crate::SyntaxKind::MutKw };
+  [use] => { $crate::SyntaxKind::UseKw };
+  [mod] => { $crate::SyntaxKind::ModKw };
+  [match] => { $crate::SyntaxKind::MatchKw };
+  [move] => { $crate::SyntaxKind::MoveKw };
+  [nominal] => { $crate::SyntaxKind::NominalKw };
+  [true] => { $crate::SyntaxKind::TrueKw };
+  [false] => { $crate::SyntaxKind::FalseKw };
+  [Symbol] => { $crate::SyntaxKind::SymbolKw };
+  [keyof] => { $crate::SyntaxKind::KeyofKw };
+  [extends] => { $crate::SyntaxKind::ExtendsKw };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn keyword_kinds_follow_keyword_table() {
+    for &keyword in Keyword::ALL {
+      let kind = SyntaxKind::from_keyword(keyword);
+      assert!(kind.is_keyword());
+      assert_eq!(kind.fixed_text(), Some(keyword.as_str()));
+    }
+    for &keyword in ContextualKeyword::ALL {
+      let kind = SyntaxKind::from_contextual_keyword(keyword);
+      assert!(kind.is_keyword());
+      assert_eq!(kind.fixed_text(), Some(keyword.as_str()));
+    }
+  }
+
+  #[test]
+  fn token_classes() {
+    assert!(SyntaxKind::BlockComment.is_trivia());
+    assert!(!SyntaxKind::Ident.is_trivia());
+    assert!(SyntaxKind::TemplateTail.is_literal());
+    assert!(T![%].is_punct());
+    assert!(!T![%].is_node());
+    assert!(SyntaxKind::NameRef.is_node());
+    assert!(!SyntaxKind::Eof.is_node());
+    assert_eq!(T![::].fixed_text(), Some("::"));
+    assert_eq!(SyntaxKind::Ident.fixed_text(), None);
+  }
+}
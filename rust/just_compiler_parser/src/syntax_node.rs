@@ -0,0 +1,493 @@
+//! Syntax tree: green tree with parent pointers and offsets.
+//!
+//! `SyntaxNode`s are created on demand while navigating the tree,
+//! they are cheap to clone and are equal when they point at the same green node at the same offset.
+
+use super::green::{GreenElement, GreenNode, GreenToken, NodeOrToken};
+use super::syntax_kind::SyntaxKind;
+use justc_lexer::span::Span;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter;
+use std::rc::Rc;
+
+pub type SyntaxElement = NodeOrToken<SyntaxNode, SyntaxToken>;
+
+#[derive(Clone)]
+pub struct SyntaxNode(Rc<NodeData>);
+
+struct NodeData {
+  green: GreenNode,
+  parent: Option<SyntaxNode>,
+  /// Index in the children of the parent.
+  index: usize,
+  offset: usize,
+}
+
+impl SyntaxNode {
+  pub fn new_root(green: GreenNode) -> SyntaxNode {
+    SyntaxNode(Rc::new(NodeData {
+      green,
+      parent: None,
+      index: 0,
+      offset: 0,
+    }))
+  }
+
+  fn new_child(&self, index: usize, offset: usize, green: &GreenElement) -> SyntaxElement {
+    match green {
+      NodeOrToken::Node(node) => NodeOrToken::Node(SyntaxNode(Rc::new(NodeData {
+        green: node.clone(),
+        parent: Some(self.clone()),
+        index,
+        offset,
+      }))),
+      NodeOrToken::Token(token) => NodeOrToken::Token(SyntaxToken {
+        green: token.clone(),
+        parent: self.clone(),
+        index,
+        offset,
+      }),
+    }
+  }
+
+  pub fn kind(&self) -> SyntaxKind {
+    self.0.green.kind()
+  }
+
+  pub fn green(&self) -> &GreenNode {
+    &self.0.green
+  }
+
+  pub fn text_range(&self) -> Span {
+    Span::new(self.0.offset, self.0.offset + self.0.green.text_len())
+  }
+
+  /// The source code of the node, trivia included.
+  pub fn text(&self) -> String {
+    self.0.green.to_string()
+  }
+
+  pub fn parent(&self) -> Option<SyntaxNode> {
+    self.0.parent.clone()
+  }
+
+  /// The node and its parents, up to the root.
+  pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> {
+    iter::successors(Some(self.clone()), SyntaxNode::parent)
+  }
+
+  pub fn children_with_tokens(&self) -> SyntaxElementChildren {
+    SyntaxElementChildren {
+      parent: self.clone(),
+      index: 0,
+      offset: self.0.offset,
+    }
+  }
+
+  pub fn children(&self) -> impl Iterator<Item = SyntaxNode> {
+    self
+      .children_with_tokens()
+      .filter_map(SyntaxElement::into_node)
+  }
+
+  pub fn first_child_or_token(&self) -> Option<SyntaxElement> {
+    self.children_with_tokens().next()
+  }
+
+  pub fn first_child(&self) -> Option<SyntaxNode> {
+    self.children().next()
+  }
+
+  pub fn last_child_or_token(&self) -> Option<SyntaxElement> {
+    let children = self.green().children();
+    let last = children.last()?;
+    let offset = self.text_range().end - last.text_len();
+    Some(self.new_child(children.len() - 1, offset, last))
+  }
+
+  pub fn next_sibling_or_token(&self) -> Option<SyntaxElement> {
+    let parent = self.parent()?;
+    let index = self.0.index + 1;
+    let green = parent.green().children().get(index)?;
+    Some(parent.new_child(index, self.text_range().end, green))
+  }
+
+  pub fn prev_sibling_or_token(&self) -> Option<SyntaxElement> {
+    let parent = self.parent()?;
+    let index = self.0.index.checked_sub(1)?;
+    let green = &parent.green().children()[index];
+    Some(parent.new_child(index, self.0.offset - green.text_len(), green))
+  }
+
+  pub fn next_sibling(&self) -> Option<SyntaxNode> {
+    iter::successors(
+      self.next_sibling_or_token(),
+      SyntaxElement::next_sibling_or_token,
+    )
+    .find_map(SyntaxElement::into_node)
+  }
+
+  pub fn prev_sibling(&self) -> Option<SyntaxNode> {
+    iter::successors(
+      self.prev_sibling_or_token(),
+      SyntaxElement::prev_sibling_or_token,
+    )
+    .find_map(SyntaxElement::into_node)
+  }
+
+  pub fn first_token(&self) -> Option<SyntaxToken> {
+    self
+      .descendants_with_tokens()
+      .find_map(SyntaxElement::into_token)
+  }
+
+  pub fn last_token(&self) -> Option<SyntaxToken> {
+    let children: Vec<_> = self.children_with_tokens().collect();
+    children.into_iter().rev().find_map(|child| match child {
+      NodeOrToken::Node(node) => node.last_token(),
+      NodeOrToken::Token(token) => Some(token),
+    })
+  }
+
+  /// The node and all the nodes below it, in preorder.
+  pub fn descendants(&self) -> impl Iterator<Item = SyntaxNode> {
+    self
+      .descendants_with_tokens()
+      .filter_map(SyntaxElement::into_node)
+  }
+
+  /// The node and all the nodes and tokens below it, in preorder.
+  pub fn descendants_with_tokens(&self) -> impl Iterator<Item = SyntaxElement> {
+    let root = self.clone();
+    iter::successors(Some(NodeOrToken::Node(self.clone())), move |element| {
+      if let NodeOrToken::Node(node) = element {
+        if let Some(child) = node.first_child_or_token() {
+          return Some(child);
+        }
+      }
+      let mut element = element.clone();
+      loop {
+        if element.as_node() == Some(&root) {
+          return None;
+        }
+        if let Some(sibling) = element.next_sibling_or_token() {
+          return Some(sibling);
+        }
+        element = NodeOrToken::Node(element.parent()?);
+      }
+    })
+  }
+
+  /// The token containing `offset`.
+  /// At the boundary between two tokens, the one starting at `offset` is returned.
+  pub fn token_at_offset(&self, offset: usize) -> Option<SyntaxToken> {
+    let range = self.text_range();
+    if offset < range.start || offset > range.end {
+      return None;
+    }
+    let mut node = self.clone();
+    loop {
+      let child = node
+        .children_with_tokens()
+        .find(|child| {
+          let range = child.text_range();
+          range.start <= offset && offset < range.end
+        })
+        .or_else(|| node.last_child_or_token())?;
+      match child {
+        NodeOrToken::Node(child) => node = child,
+        NodeOrToken::Token(token) => return Some(token),
+      }
+    }
+  }
+
+  /// The smallest node or token whose range contains `span`.
+  pub fn covering_element(&self, span: Span) -> SyntaxElement {
+    let mut node = self.clone();
+    loop {
+      let child = node.children_with_tokens().find(|child| {
+        let range = child.text_range();
+        range.contains_span(span) && !(span.is_empty() && range.is_empty())
+      });
+      match child {
+        Some(NodeOrToken::Node(child)) => node = child,
+        Some(NodeOrToken::Token(token)) => return NodeOrToken::Token(token),
+        None => return NodeOrToken::Node(node),
+      }
+    }
+  }
+
+  /// Indented dump of the tree, with the kind and range of every element and the text of tokens.
+  pub fn debug_dump(&self) -> String {
+    let mut out = String::new();
+    let base = self.ancestors().count();
+    for element in self.descendants_with_tokens() {
+      let depth = element
+        .parent()
+        .map_or(0, |parent| parent.ancestors().count() + 1 - base);
+      let indent = "  ".repeat(depth);
+      match element {
+        NodeOrToken::Node(node) => out.push_str(&format!("{}{:?}\n", indent, node)),
+        NodeOrToken::Token(token) => out.push_str(&format!("{}{:?}\n", indent, token)),
+      }
+    }
+    out
+  }
+}
+
+impl PartialEq for SyntaxNode {
+  fn eq(&self, other: &SyntaxNode) -> bool {
+    self.0.green.ptr_eq(&other.0.green) && self.0.offset == other.0.offset
+  }
+}
+
+impl Eq for SyntaxNode {}
+
+impl Hash for SyntaxNode {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.0.offset.hash(state);
+    self.kind().hash(state);
+  }
+}
+
+impl fmt::Debug for SyntaxNode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{:?}@{:?}", self.kind(), self.text_range())
+  }
+}
+
+impl fmt::Display for SyntaxNode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(self.green(), f)
+  }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SyntaxToken {
+  green: GreenToken,
+  parent: SyntaxNode,
+  index: usize,
+  offset: usize,
+}
+
+impl SyntaxToken {
+  pub fn kind(&self) -> SyntaxKind {
+    self.green.kind()
+  }
+
+  pub fn green(&self) -> &GreenToken {
+    &self.green
+  }
+
+  pub fn text(&self) -> &str {
+    self.green.text()
+  }
+
+  pub fn text_range(&self) -> Span {
+    Span::new(self.offset, self.offset + self.green.text_len())
+  }
+
+  pub fn parent(&self) -> SyntaxNode {
+    self.parent.clone()
+  }
+
+  pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> {
+    self.parent.ancestors()
+  }
+
+  pub fn next_sibling_or_token(&self) -> Option<SyntaxElement> {
+    let index = self.index + 1;
+    let green = self.parent.green().children().get(index)?;
+    Some(self.parent.new_child(index, self.text_range().end, green))
+  }
+
+  pub fn prev_sibling_or_token(&self) -> Option<SyntaxElement> {
+    let index = self.index.checked_sub(1)?;
+    let green = &self.parent.green().children()[index];
+    Some(
+      self
+        .parent
+        .new_child(index, self.offset - green.text_len(), green),
+    )
+  }
+
+  /// The next token in the whole tree, not only among the siblings.
+  pub fn next_token(&self) -> Option<SyntaxToken> {
+    let mut element = NodeOrToken::Token(self.clone());
+    loop {
+      match element.next_sibling_or_token() {
+        Some(NodeOrToken::Token(token)) => return Some(token),
+        Some(NodeOrToken::Node(node)) => match node.first_token() {
+          Some(token) => return Some(token),
+          None => element = NodeOrToken::Node(node),
+        },
+        None => element = NodeOrToken::Node(element.parent()?),
+      }
+    }
+  }
+
+  /// The previous token in the whole tree, not only among the siblings.
+  pub fn prev_token(&self) -> Option<SyntaxToken> {
+    let mut element = NodeOrToken::Token(self.clone());
+    loop {
+      match element.prev_sibling_or_token() {
+        Some(NodeOrToken::Token(token)) => return Some(token),
+        Some(NodeOrToken::Node(node)) => match node.last_token() {
+          Some(token) => return Some(token),
+          None => element = NodeOrToken::Node(node),
+        },
+        None => element = NodeOrToken::Node(element.parent()?),
+      }
+    }
+  }
+}
+
+impl fmt::Debug for SyntaxToken {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{:?}@{:?} {:?}",
+      self.kind(),
+      self.text_range(),
+      self.text()
+    )
+  }
+}
+
+impl fmt::Display for SyntaxToken {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.text())
+  }
+}
+
+impl SyntaxElement {
+  pub fn kind(&self) -> SyntaxKind {
+    match self {
+      NodeOrToken::Node(node) => node.kind(),
+      NodeOrToken::Token(token) => token.kind(),
+    }
+  }
+
+  pub fn text_range(&self) -> Span {
+    match self {
+      NodeOrToken::Node(node) => node.text_range(),
+      NodeOrToken::Token(token) => token.text_range(),
+    }
+  }
+
+  pub fn parent(&self) -> Option<SyntaxNode> {
+    match self {
+      NodeOrToken::Node(node) => node.parent(),
+      NodeOrToken::Token(token) => Some(token.parent()),
+    }
+  }
+
+  pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> {
+    let first = match self {
+      NodeOrToken::Node(node) => Some(node.clone()),
+      NodeOrToken::Token(token) => Some(token.parent()),
+    };
+    iter::successors(first, SyntaxNode::parent)
+  }
+
+  pub fn next_sibling_or_token(&self) -> Option<SyntaxElement> {
+    match self {
+      NodeOrToken::Node(node) => node.next_sibling_or_token(),
+      NodeOrToken::Token(token) => token.next_sibling_or_token(),
+    }
+  }
+
+  pub fn prev_sibling_or_token(&self) -> Option<SyntaxElement> {
+    match self {
+      NodeOrToken::Node(node) => node.prev_sibling_or_token(),
+      NodeOrToken::Token(token) => token.prev_sibling_or_token(),
+    }
+  }
+}
+
+/// Iterator over the children of a node, see `SyntaxNode::children_with_tokens`.
+pub struct SyntaxElementChildren {
+  parent: SyntaxNode,
+  index: usize,
+  offset: usize,
+}
+
+impl Iterator for SyntaxElementChildren {
+  type Item = SyntaxElement;
+
+  fn next(&mut self) -> Option<SyntaxElement> {
+    let green = self.parent.green().children().get(self.index)?;
+    let child = self.parent.new_child(self.index, self.offset, green);
+    self.index += 1;
+    self.offset += green.text_len();
+    Some(child)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parse;
+
+  #[test]
+  fn navigation() {
+    let root = parse("let a = b + 1 // c\nf()").syntax();
+    let let_decl = root.first_child().unwrap();
+    assert_eq!(let_decl.kind(), SyntaxKind::LetDecl);
+    assert_eq!(let_decl.text(), "let a = b + 1");
+    assert_eq!(let_decl.parent(), Some(root.clone()));
+
+    let stmt = let_decl.next_sibling().unwrap();
+    assert_eq!(stmt.kind(), SyntaxKind::ExprStmt);
+    assert_eq!(stmt.prev_sibling(), Some(let_decl.clone()));
+    assert_eq!(stmt.next_sibling(), None);
+
+    let kinds: Vec<_> = let_decl.children().map(|node| node.kind()).collect();
+    assert_eq!(kinds, [SyntaxKind::Name, SyntaxKind::BinExpr]);
+    assert_eq!(
+      let_decl.descendants().map(|node| node.kind()).last(),
+      Some(SyntaxKind::Literal)
+    );
+
+    let first = let_decl.first_token().unwrap();
+    let last = let_decl.last_token().unwrap();
+    assert_eq!((first.text(), last.text()), ("let", "1"));
+    assert_eq!(last.next_token().unwrap().kind(), SyntaxKind::Whitespace);
+    assert_eq!(
+      last.next_token().unwrap().next_token().unwrap().text(),
+      "// c"
+    );
+    assert_eq!(last.prev_token().unwrap().prev_token().unwrap().text(), "+");
+    assert_eq!(first.prev_token(), None);
+    assert_eq!(root.last_token().unwrap().text(), ")");
+  }
+
+  #[test]
+  fn lookup_by_offset() {
+    let root = parse("let a = bc + 1").syntax();
+    assert_eq!(root.token_at_offset(8).unwrap().text(), "bc");
+    assert_eq!(root.token_at_offset(9).unwrap().text(), "bc");
+    assert_eq!(root.token_at_offset(10).unwrap().text(), " ");
+    assert_eq!(root.token_at_offset(14).unwrap().text(), "1");
+    assert_eq!(root.token_at_offset(15), None);
+
+    let covering = root.covering_element(Span::new(8, 14));
+    assert_eq!(covering.kind(), SyntaxKind::BinExpr);
+    let covering = root.covering_element(Span::new(8, 9));
+    assert_eq!(covering.kind(), SyntaxKind::Ident);
+    let covering = root.covering_element(Span::new(2, 6));
+    assert_eq!(covering.kind(), SyntaxKind::LetDecl);
+  }
+
+  #[test]
+  fn nodes_are_equal_by_position() {
+    let root = parse("a\na").syntax();
+    let first = root.first_child().unwrap();
+    let second = first.next_sibling().unwrap();
+    assert_eq!(first, root.first_child().unwrap());
+    assert_ne!(first, second);
+    assert_eq!(format!("{:?}", second), "ExprStmt@2..3");
+    assert_eq!(second.to_string(), "a");
+  }
+}
@@ -0,0 +1,97 @@
+//! Turns parser events into a green tree, putting the trivia back in.
+//!
+//! Trivia between two tokens is attached to the innermost node containing both,
+//! so nodes start and end with a significant token (or are empty).
+//! Trivia at the start and at the end of the file belongs to the root.
+
+use super::green::{GreenNode, GreenNodeBuilder};
+use super::parser::Event;
+use super::syntax_kind::SyntaxKind;
+use justc_lexer::token_stream::SpannedToken;
+
+pub(crate) fn build_tree(text: &str, tokens: &[SpannedToken], events: Vec<Event>) -> GreenNode {
+  let mut builder = TreeBuilder {
+    text,
+    tokens,
+    pos: 0,
+    inner: GreenNodeBuilder::new(),
+  };
+  let has_tokens = nodes_with_tokens(&events);
+  let len = events.len();
+  for (i, event) in events.into_iter().enumerate() {
+    match event {
+      Event::Start { kind, .. } => {
+        // The root is started before any trivia, other nodes after the trivia preceding them.
+        // Empty nodes are left before the trivia, so they don't end their parent with trivia.
+        if builder.inner.depth() > 0 && has_tokens[i] {
+          builder.trivia();
+        }
+        builder.inner.start_node(kind);
+      }
+      Event::Finish => {
+        if i == len - 1 {
+          builder.trivia();
+        }
+        builder.inner.finish_node();
+      }
+      Event::Token { kind } => {
+        builder.trivia();
+        builder.token(kind);
+      }
+    }
+  }
+  debug_assert_eq!(builder.pos, tokens.len(), "tokens left out of the tree");
+  builder.inner.finish()
+}
+
+/// For each `Start` event, whether the node contains at least one token.
+fn nodes_with_tokens(events: &[Event]) -> Vec<bool> {
+  let mut has_tokens = vec![false; events.len()];
+  let mut open = Vec::new();
+  for (i, event) in events.iter().enumerate() {
+    match event {
+      Event::Start { .. } => open.push(i),
+      Event::Finish => {
+        let start = open.pop().expect("unbalanced events");
+        if has_tokens[start] {
+          if let Some(&parent) = open.last() {
+            has_tokens[parent] = true;
+          }
+        }
+      }
+      Event::Token { .. } => {
+        if let Some(&parent) = open.last() {
+          has_tokens[parent] = true;
+        }
+      }
+    }
+  }
+  has_tokens
+}
+
+struct TreeBuilder<'a> {
+  text: &'a str,
+  tokens: &'a [SpannedToken],
+  /// Index of the next token of `tokens`.
+  pos: usize,
+  inner: GreenNodeBuilder,
+}
+
+impl TreeBuilder<'_> {
+  fn token(&mut self, kind: SyntaxKind) {
+    let token = self.tokens[self.pos];
+    self.inner.token(kind, token.text(self.text));
+    self.pos += 1;
+  }
+
+  /// Adds the trivia tokens before the next significant token.
+  fn trivia(&mut self) {
+    while let Some(token) = self.tokens.get(self.pos) {
+      let kind = SyntaxKind::from_token(token.kind);
+      if !kind.is_trivia() {
+        break;
+      }
+      self.token(kind);
+    }
+  }
+}
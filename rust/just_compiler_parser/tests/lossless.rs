@@ -0,0 +1,85 @@
+//! The syntax tree holds every byte of the source code, whatever the input.
+
+use justc_parser::{parse, NodeOrToken, SyntaxKind};
+use proptest::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Pieces of source code likely to confuse the parser (unbalanced delimiters, keywords...).
+const FRAGMENTS: &[&str] = &[
+  " ", "\n", "let", "mut", "const", "type", "nominal", "use", "mod", "fn", "pub", "if", "else",
+  "match", "return", "while", "for", "in", "as", "keyof", "extends", "?", "x", "_", "1", "2.5",
+  "\"s\"", "'c'", "`t`", "`${", "}`", "(", ")", "{", "}", "[", "]", "<", ">", "=>", "->", "=", ":",
+  "::", ".", "..", ",", ";", "@", "&", "|", "+", "-", "*", "/", "//", "/*", "*/", "#", "№",
+];
+
+fn source_like() -> impl Strategy<Value = String> {
+  prop::collection::vec(prop::sample::select(FRAGMENTS), 0..64)
+    .prop_map(|fragments| fragments.concat())
+}
+
+fn check(text: &str) -> Result<(), TestCaseError> {
+  let parse = parse(text);
+  let root = parse.syntax();
+  prop_assert_eq!(root.kind(), SyntaxKind::SourceFile);
+  prop_assert_eq!(root.text(), text);
+
+  // Tokens are contiguous and nodes start and end with a significant token.
+  let mut offset = 0;
+  for element in root.descendants_with_tokens() {
+    match element {
+      NodeOrToken::Token(token) => {
+        prop_assert_eq!(token.text_range().start, offset);
+        prop_assert!(!token.text().is_empty());
+        offset = token.text_range().end;
+      }
+      NodeOrToken::Node(node) if node != root => {
+        for token in node.first_token().into_iter().chain(node.last_token()) {
+          prop_assert!(!token.kind().is_trivia(), "{:?} in {:?}", token, node);
+        }
+      }
+      NodeOrToken::Node(_) => (),
+    }
+  }
+  prop_assert_eq!(offset, text.len());
+  Ok(())
+}
+
+proptest! {
+  #![proptest_config(ProptestConfig::with_cases(1024))]
+
+  #[test]
+  fn any_string(text in any::<String>()) {
+    check(&text)?;
+  }
+
+  #[test]
+  fn source_like_string(text in source_like()) {
+    check(&text)?;
+  }
+}
+
+fn just_files(dir: &Path, files: &mut Vec<PathBuf>) {
+  for entry in fs::read_dir(dir).unwrap() {
+    let path = entry.unwrap().path();
+    if path.is_dir() {
+      just_files(&path, files);
+    } else if path.extension() == Some("just".as_ref()) {
+      files.push(path);
+    }
+  }
+}
+
+#[test]
+fn repository_sources() {
+  let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+  let mut files = Vec::new();
+  for dir in &["just", "docs", "rust/just_compiler/fixtures"] {
+    just_files(&root.join(dir), &mut files);
+  }
+  assert!(!files.is_empty());
+  for file in files {
+    let text = fs::read_to_string(&file).unwrap();
+    check(&text).unwrap_or_else(|err| panic!("{}: {}", file.display(), err));
+  }
+}
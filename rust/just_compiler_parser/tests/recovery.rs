@@ -0,0 +1,32 @@
+//! Partial code, as written while typing, must still give a usable tree.
+//!
+//! Each `test_data/recovery/*.just` file is parsed and its tree compared with the `.txt` file next to it,
+//! run with `UPDATE_EXPECT=1` to update them.
+
+use expect_test::expect_file;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn fixtures() -> Vec<PathBuf> {
+  let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_data/recovery");
+  let mut files: Vec<_> = fs::read_dir(dir)
+    .unwrap()
+    .map(|entry| entry.unwrap().path())
+    .filter(|path| path.extension() == Some("just".as_ref()))
+    .collect();
+  files.sort();
+  files
+}
+
+#[test]
+fn recovery() {
+  let files = fixtures();
+  assert!(!files.is_empty());
+  for file in files {
+    let text = fs::read_to_string(&file).unwrap();
+    let parse = justc_parser::parse(&text);
+    assert_eq!(parse.syntax().text(), text, "{}", file.display());
+    assert!(!parse.errors().is_empty(), "{}", file.display());
+    expect_file![file.with_extension("txt")].assert_eq(&parse.debug_dump());
+  }
+}